@@ -0,0 +1,101 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+/* Generated with cbindgen from the anise-capi crate: do not edit manually. */
+
+#ifndef ANISE_H
+#define ANISE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * The call succeeded
+ */
+#define ANISE_OK 0
+
+/**
+ * One of the pointers provided is null
+ */
+#define ANISE_ERR_NULL_POINTER 1
+
+/**
+ * One of the strings provided is not valid UTF-8
+ */
+#define ANISE_ERR_INVALID_STRING 2
+
+/**
+ * The kernel could not be loaded
+ */
+#define ANISE_ERR_LOAD 3
+
+/**
+ * The aberration correction is not one of the SPICE flags (e.g. `NONE` or `LT+S`)
+ */
+#define ANISE_ERR_ABERRATION 4
+
+/**
+ * No kernel is loaded
+ */
+#define ANISE_ERR_NOT_LOADED 5
+
+/**
+ * The loaded kernels do not allow computing the requested state
+ */
+#define ANISE_ERR_QUERY 6
+
+/**
+ * A previous call panicked while holding the loaded kernels
+ */
+#define ANISE_ERR_POISONED 7
+
+/**
+ * Returns the static, null-terminated description of the provided return code.
+ */
+const char *anise_error_message(int code);
+
+/**
+ * Loads the kernel at the provided path, like `furnsh_c`.
+ *
+ * # Safety
+ * `path` must be null or point to a null-terminated string.
+ */
+int anise_furnsh(const char *path);
+
+/**
+ * Unloads all of the kernels, like `kclear_c`.
+ */
+int anise_kclear(void);
+
+/**
+ * Computes the state (km and km/s) of the target relative to the observer in the provided frame, and the one-way
+ * light time (s) between them, like `spkezr_c` but with NAIF IDs instead of names.
+ *
+ * The epoch is in seconds past J2000 TDB, and the aberration correction is one of the SPICE flags, e.g. `NONE` or `LT+S`.
+ * On success, the six components of the state are written to `state_out` and the light time to `lt_out`.
+ *
+ * # Safety
+ * `abcorr` must be null or point to a null-terminated string, `state_out` must be null or point to an array of six doubles,
+ * and `lt_out` must be null or point to a double.
+ */
+int anise_spkezr(int target,
+                 double epoch_et,
+                 int frame,
+                 const char *abcorr,
+                 int observer,
+                 double *state_out,
+                 double *lt_out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif
+
+#endif  /* ANISE_H */
@@ -0,0 +1,239 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+//! C API of ANISE mirroring the CSPICE functions, to ease the migration of C and C++ code bases.
+//!
+//! The header of this API is `include/anise.h`, and can be regenerated with `cbindgen --config cbindgen.toml --output include/anise.h`.
+//! Like CSPICE, the loaded kernels are shared by all of the calls: load them with [anise_furnsh] and unload them with [anise_kclear].
+//! Every function returns [ANISE_OK] on success, or one of the non-zero `ANISE_ERR_*` codes, which [anise_error_message] describes.
+
+use core::ffi::{c_char, c_double, c_int, CStr};
+use std::sync::RwLock;
+
+use log::error;
+
+use anise::almanac::Almanac;
+use anise::astro::Aberration;
+use anise::time::Epoch;
+
+/// The call succeeded
+pub const ANISE_OK: c_int = 0;
+/// One of the pointers provided is null
+pub const ANISE_ERR_NULL_POINTER: c_int = 1;
+/// One of the strings provided is not valid UTF-8
+pub const ANISE_ERR_INVALID_STRING: c_int = 2;
+/// The kernel could not be loaded
+pub const ANISE_ERR_LOAD: c_int = 3;
+/// The aberration correction is not one of the SPICE flags (e.g. `NONE` or `LT+S`)
+pub const ANISE_ERR_ABERRATION: c_int = 4;
+/// No kernel is loaded
+pub const ANISE_ERR_NOT_LOADED: c_int = 5;
+/// The loaded kernels do not allow computing the requested state
+pub const ANISE_ERR_QUERY: c_int = 6;
+/// A previous call panicked while holding the loaded kernels
+pub const ANISE_ERR_POISONED: c_int = 7;
+
+static ALMANAC: RwLock<Option<Almanac>> = RwLock::new(None);
+
+/// Returns the static, null-terminated description of the provided return code.
+#[no_mangle]
+pub extern "C" fn anise_error_message(code: c_int) -> *const c_char {
+    let msg: &'static CStr = match code {
+        ANISE_OK => c"success",
+        ANISE_ERR_NULL_POINTER => c"null pointer provided",
+        ANISE_ERR_INVALID_STRING => c"string is not valid UTF-8",
+        ANISE_ERR_LOAD => c"kernel could not be loaded",
+        ANISE_ERR_ABERRATION => c"unknown aberration correction",
+        ANISE_ERR_NOT_LOADED => c"no kernel loaded",
+        ANISE_ERR_QUERY => c"state could not be computed from the loaded kernels",
+        ANISE_ERR_POISONED => c"loaded kernels poisoned by a previous panic",
+        _ => c"unknown return code",
+    };
+    msg.as_ptr()
+}
+
+/// Loads the kernel at the provided path, like `furnsh_c`.
+///
+/// # Safety
+/// `path` must be null or point to a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn anise_furnsh(path: *const c_char) -> c_int {
+    let path = match str_from_ptr(path) {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+
+    let mut almanac = match ALMANAC.write() {
+        Ok(almanac) => almanac,
+        Err(_) => return ANISE_ERR_POISONED,
+    };
+
+    match almanac.as_ref().unwrap_or(&Almanac::default()).load(path) {
+        Ok(loaded) => {
+            *almanac = Some(loaded);
+            ANISE_OK
+        }
+        Err(e) => {
+            error!("could not load {path}: {e}");
+            ANISE_ERR_LOAD
+        }
+    }
+}
+
+/// Unloads all of the kernels, like `kclear_c`.
+#[no_mangle]
+pub extern "C" fn anise_kclear() -> c_int {
+    match ALMANAC.write() {
+        Ok(mut almanac) => {
+            *almanac = None;
+            ANISE_OK
+        }
+        Err(_) => ANISE_ERR_POISONED,
+    }
+}
+
+/// Computes the state (km and km/s) of the target relative to the observer in the provided frame, and the one-way
+/// light time (s) between them, like `spkezr_c` but with NAIF IDs instead of names.
+///
+/// The epoch is in seconds past J2000 TDB, and the aberration correction is one of the SPICE flags, e.g. `NONE` or `LT+S`.
+/// On success, the six components of the state are written to `state_out` and the light time to `lt_out`.
+///
+/// # Safety
+/// `abcorr` must be null or point to a null-terminated string, `state_out` must be null or point to an array of six doubles,
+/// and `lt_out` must be null or point to a double.
+#[no_mangle]
+pub unsafe extern "C" fn anise_spkezr(
+    target: c_int,
+    epoch_et: c_double,
+    frame: c_int,
+    abcorr: *const c_char,
+    observer: c_int,
+    state_out: *mut c_double,
+    lt_out: *mut c_double,
+) -> c_int {
+    if state_out.is_null() || lt_out.is_null() {
+        return ANISE_ERR_NULL_POINTER;
+    }
+
+    let ab_corr = match str_from_ptr(abcorr) {
+        Ok(abcorr) => match Aberration::new(abcorr) {
+            Ok(ab_corr) => ab_corr,
+            Err(_) => return ANISE_ERR_ABERRATION,
+        },
+        Err(code) => return code,
+    };
+
+    let almanac = match ALMANAC.read() {
+        Ok(almanac) => almanac,
+        Err(_) => return ANISE_ERR_POISONED,
+    };
+    let almanac = match almanac.as_ref() {
+        Some(almanac) => almanac,
+        None => return ANISE_ERR_NOT_LOADED,
+    };
+
+    match almanac.spk_ezr(
+        target,
+        Epoch::from_et_seconds(epoch_et),
+        frame,
+        observer,
+        ab_corr,
+    ) {
+        Ok(state) => {
+            let state_out = core::slice::from_raw_parts_mut(state_out, 6);
+            state_out[..3].copy_from_slice(state.radius_km.as_slice());
+            state_out[3..].copy_from_slice(state.velocity_km_s.as_slice());
+            *lt_out = state.light_time().to_seconds();
+            ANISE_OK
+        }
+        Err(e) => {
+            error!("could not compute the state of {target} relative to {observer}: {e}");
+            ANISE_ERR_QUERY
+        }
+    }
+}
+
+/// Reads the provided null-terminated string, returning the error code to report if it's invalid.
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> Result<&'a str, c_int> {
+    if ptr.is_null() {
+        return Err(ANISE_ERR_NULL_POINTER);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| ANISE_ERR_INVALID_STRING)
+}
+
+#[cfg(test)]
+mod capi_ut {
+    use core::ffi::c_char;
+    use core::ptr::null;
+
+    use super::*;
+
+    #[test]
+    fn error_codes() {
+        unsafe {
+            let mut state = [0.0; 6];
+            let mut lt = 0.0;
+            assert_eq!(
+                anise_spkezr(
+                    301,
+                    0.0,
+                    1,
+                    c"NONE".as_ptr(),
+                    399,
+                    state.as_mut_ptr(),
+                    &mut lt
+                ),
+                ANISE_ERR_NOT_LOADED
+            );
+            assert_eq!(
+                anise_spkezr(301, 0.0, 1, null(), 399, state.as_mut_ptr(), &mut lt),
+                ANISE_ERR_NULL_POINTER
+            );
+            assert_eq!(
+                anise_spkezr(
+                    301,
+                    0.0,
+                    1,
+                    c"LT+X".as_ptr(),
+                    399,
+                    state.as_mut_ptr(),
+                    &mut lt
+                ),
+                ANISE_ERR_ABERRATION
+            );
+            assert_eq!(
+                anise_spkezr(
+                    301,
+                    0.0,
+                    1,
+                    [0xc3_u8 as c_char, 0].as_ptr(),
+                    399,
+                    state.as_mut_ptr(),
+                    &mut lt
+                ),
+                ANISE_ERR_INVALID_STRING
+            );
+
+            assert_eq!(anise_furnsh(null()), ANISE_ERR_NULL_POINTER);
+            assert_eq!(
+                anise_furnsh(c"../data/not_a_file.bsp".as_ptr()),
+                ANISE_ERR_LOAD
+            );
+        }
+        assert_eq!(anise_kclear(), ANISE_OK);
+
+        for code in 0..=ANISE_ERR_POISONED + 1 {
+            let msg = unsafe { CStr::from_ptr(anise_error_message(code)) };
+            assert!(!msg.is_empty());
+        }
+    }
+}
@@ -0,0 +1,132 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::ffi::{c_char, c_double, c_int};
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::PathBuf;
+
+use anise::constants::orientations::J2000;
+use anise::prelude::*;
+use anise_capi::{ANISE_ERR_NOT_LOADED, ANISE_ERR_QUERY, ANISE_OK};
+use libloading::{Library, Symbol};
+
+type Furnsh = unsafe extern "C" fn(*const c_char) -> c_int;
+type Kclear = unsafe extern "C" fn() -> c_int;
+type Spkezr = unsafe extern "C" fn(
+    c_int,
+    c_double,
+    c_int,
+    *const c_char,
+    c_int,
+    *mut c_double,
+    *mut c_double,
+) -> c_int;
+
+/// Returns the path of the C library built for this test, i.e. next to the test executable, or in its parent folder
+/// if the library was built with `cargo build`.
+fn library_path() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let name = format!("{DLL_PREFIX}anise_capi{DLL_SUFFIX}");
+    exe.ancestors()
+        .skip(1)
+        .take(2)
+        .map(|dir| dir.join(&name))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| panic!("{name} not found next to {}", exe.display()))
+}
+
+#[test]
+fn capi_spkezr() {
+    let path = library_path();
+    let lib = unsafe { Library::new(&path) }
+        .unwrap_or_else(|e| panic!("could not open {}: {e}", path.display()));
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 2, 29);
+
+    unsafe {
+        let furnsh: Symbol<Furnsh> = lib.get(b"anise_furnsh").unwrap();
+        let kclear: Symbol<Kclear> = lib.get(b"anise_kclear").unwrap();
+        let spkezr: Symbol<Spkezr> = lib.get(b"anise_spkezr").unwrap();
+
+        let mut state = [0.0; 6];
+        let mut lt_s = 0.0;
+        assert_eq!(
+            spkezr(
+                301,
+                epoch.to_et_seconds(),
+                J2000,
+                c"NONE".as_ptr(),
+                399,
+                state.as_mut_ptr(),
+                &mut lt_s
+            ),
+            ANISE_ERR_NOT_LOADED
+        );
+
+        assert_eq!(furnsh(c"../data/de440s.bsp".as_ptr()), ANISE_OK);
+        let almanac = Almanac::new("../data/de440s.bsp").unwrap();
+
+        for (abcorr, ab_corr) in [
+            (c"NONE", Aberration::NONE),
+            (c"LT+S", Aberration::LT_S),
+            (c"XCN", Aberration::XCN),
+        ] {
+            for (target, observer) in [(301, 399), (399, 10), (10, 301)] {
+                assert_eq!(
+                    spkezr(
+                        target,
+                        epoch.to_et_seconds(),
+                        J2000,
+                        abcorr.as_ptr(),
+                        observer,
+                        state.as_mut_ptr(),
+                        &mut lt_s
+                    ),
+                    ANISE_OK
+                );
+
+                let expected = almanac
+                    .spk_ezr(target, epoch, J2000, observer, ab_corr)
+                    .unwrap();
+                assert_eq!(state[..3], *expected.radius_km.as_slice());
+                assert_eq!(state[3..], *expected.velocity_km_s.as_slice());
+                assert_eq!(lt_s, expected.light_time().to_seconds());
+            }
+        }
+
+        // Jupiter is not in this kernel
+        assert_eq!(
+            spkezr(
+                599,
+                epoch.to_et_seconds(),
+                J2000,
+                c"NONE".as_ptr(),
+                399,
+                state.as_mut_ptr(),
+                &mut lt_s
+            ),
+            ANISE_ERR_QUERY
+        );
+
+        assert_eq!(kclear(), ANISE_OK);
+        assert_eq!(
+            spkezr(
+                301,
+                epoch.to_et_seconds(),
+                J2000,
+                c"NONE".as_ptr(),
+                399,
+                state.as_mut_ptr(),
+                &mut lt_s
+            ),
+            ANISE_ERR_NOT_LOADED
+        );
+    }
+}
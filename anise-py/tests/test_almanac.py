@@ -1,6 +1,8 @@
 from pathlib import Path
 import pickle
 
+import pytest
+
 from anise import Almanac, MetaAlmanac
 from anise.astro import *
 from anise.astro.constants import Frames
@@ -107,6 +109,34 @@ def test_state_transformation():
     # cf. https://github.com/nyx-space/hifitime/issues/270
 
 
+def test_state_of_vectors():
+    """
+    Compares the position and velocity vectors of the Moon as seen from the Earth with SpiceyPy.
+    """
+    np = pytest.importorskip("numpy")
+    spice = pytest.importorskip("spiceypy")
+
+    data_path = Path(__file__).parent.joinpath("..", "..", "data", "de440s.bsp")
+    ctx = Almanac(str(data_path))
+    spice.furnsh(str(data_path))
+
+    for epoch in [Epoch("2021-10-29 12:34:56 TDB"), "2021-10-29T12:34:56 TDB"]:
+        pos_km, vel_km_s = ctx.state_of_vectors(301, Frames.EARTH_J2000, epoch)
+        pos_km = np.array(pos_km)
+        vel_km_s = np.array(vel_km_s)
+
+        et = Epoch("2021-10-29 12:34:56 TDB").to_et_seconds()
+        spice_state, _ = spice.spkez(301, et, "J2000", "NONE", 399)
+
+        assert np.linalg.norm(pos_km - spice_state[:3]) < 1e-7
+        assert np.linalg.norm(vel_km_s - spice_state[3:]) < 1e-10
+
+    spice.unload(str(data_path))
+
+    with pytest.raises(Exception, match="invalid epoch"):
+        ctx.state_of_vectors(301, Frames.EARTH_J2000, "not an epoch")
+
+
 def test_meta_load():
     data_path = Path(__file__).parent.joinpath("..", "..", "data", "local.dhall")
     meta = MetaAlmanac(str(data_path))
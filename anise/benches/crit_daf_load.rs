@@ -0,0 +1,51 @@
+use anise::{
+    constants::frames::{EARTH_J2000, MOON_J2000},
+    prelude::*,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const DE440: &str = "../data/de440.bsp";
+
+/// Resident memory of this process in bytes, if it can be read (Linux only).
+fn resident() -> Option<usize> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages = statm.split_whitespace().nth(1)?.parse::<usize>().ok()?;
+    Some(pages * 4096)
+}
+
+/// Reports the growth of the resident memory after loading DE440 and querying a state from it.
+fn report_resident(name: &str, load: fn(&str) -> SPK) {
+    let before = resident();
+    let spk = load(DE440);
+    let almanac = Almanac::from_spk(spk).unwrap();
+    let epoch = Epoch::from_gregorian_at_noon(2025, 1, 1, TimeScale::ET);
+    black_box(
+        almanac
+            .translate_geometric(EARTH_J2000, MOON_J2000, epoch)
+            .unwrap(),
+    );
+    if let (Some(before), Some(after)) = (before, resident()) {
+        println!(
+            "{name}: resident memory grew by {:.1} MB",
+            after.saturating_sub(before) as f64 / 1e6
+        );
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    report_resident("ANISE DAF/SPK load", |path| SPK::load(path).unwrap());
+    report_resident("ANISE DAF/SPK load_mmap", |path| {
+        SPK::load_mmap(path).unwrap()
+    });
+
+    c.bench_function("ANISE DAF/SPK load DE440", |b| {
+        b.iter(|| black_box(SPK::load(DE440).unwrap()))
+    });
+
+    c.bench_function("ANISE DAF/SPK load_mmap DE440", |b| {
+        b.iter(|| black_box(SPK::load_mmap(DE440).unwrap()))
+    });
+}
+
+criterion_group!(daf_load, criterion_benchmark);
+criterion_main!(daf_load);
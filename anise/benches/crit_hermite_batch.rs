@@ -0,0 +1,89 @@
+use anise::{
+    math::{cartesian::CartesianState, Vector3},
+    naif::daf::{datatypes::HermiteSetType13, NAIFDataSet},
+    naif::spk::summary::SPKSummaryRecord,
+    prelude::*,
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const NUM_RECORDS: usize = 1_440;
+const NUM_EPOCHS: usize = 100_000;
+const STEP_S: f64 = 60.0;
+
+/// Builds a Type 13 segment of one day of a 7000 km circular orbit sampled every minute, with the window size of
+/// the spacecraft ephemerides produced by most orbit determination tools.
+fn build_type13_data() -> Vec<f64> {
+    let mean_motion_rad_s = (398_600.4418_f64 / 7000.0_f64.powi(3)).sqrt();
+    let epochs: Vec<f64> = (0..NUM_RECORDS).map(|n| STEP_S * n as f64).collect();
+    let mut data = Vec::with_capacity(NUM_RECORDS * 7 + NUM_RECORDS / 100 + 2);
+    for t in &epochs {
+        let (sin_t, cos_t) = (t * mean_motion_rad_s).sin_cos();
+        let v_km_s = 7000.0 * mean_motion_rad_s;
+        data.extend([
+            7000.0 * cos_t,
+            7000.0 * sin_t * 0.9,
+            7000.0 * sin_t * 0.4,
+            -v_km_s * sin_t,
+            v_km_s * cos_t * 0.9,
+            v_km_s * cos_t * 0.4,
+        ]);
+    }
+    data.extend(&epochs);
+    data.extend((1..=(NUM_RECORDS - 1) / 100).map(|k| epochs[100 * k - 1]));
+    data.extend([7.0, NUM_RECORDS as f64]);
+    data
+}
+
+/// Epochs spread over the whole segment in a pseudo-random order, e.g. the measurement epochs of several stations.
+fn unordered_epochs() -> Vec<Epoch> {
+    let span_s = STEP_S * (NUM_RECORDS - 1) as f64;
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..NUM_EPOCHS)
+        .map(|_| {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            Epoch::from_et_seconds((seed >> 11) as f64 / (1u64 << 53) as f64 * span_s)
+        })
+        .collect()
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let data = build_type13_data();
+    let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+    let summary = SPKSummaryRecord::default();
+    let epochs = unordered_epochs();
+
+    let mut group = c.benchmark_group("Type 13 unordered epochs");
+    group.sample_size(10);
+
+    group.bench_function("per-epoch evaluation", |b| {
+        b.iter(|| {
+            let states: Vec<CartesianState> = epochs
+                .iter()
+                .map(|epoch| dataset.to_cartesian_state(*epoch, &summary, None).unwrap())
+                .collect();
+            black_box(states)
+        })
+    });
+
+    group.bench_function("per-epoch position only evaluation", |b| {
+        b.iter(|| {
+            let positions: Vec<Vector3> = epochs
+                .iter()
+                .map(|epoch| dataset.evaluate_position(*epoch, &summary).unwrap())
+                .collect();
+            black_box(positions)
+        })
+    });
+
+    group.bench_function("batch evaluation", |b| {
+        b.iter(|| black_box(dataset.evaluate_batch(&epochs, &summary, None).unwrap()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(hermite_batch, criterion_benchmark);
+criterion_main!(hermite_batch);
@@ -0,0 +1,60 @@
+use anise::{
+    naif::daf::{datatypes::HermiteSetType13, NAIFDataSet},
+    naif::spk::summary::SPKSummaryRecord,
+    prelude::*,
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const NUM_RECORDS: usize = 500_000;
+const NUM_QUERIES: usize = 1_000;
+
+/// Builds a large synthetic Type 13 segment, mimicking long spacecraft ephemerides.
+fn build_type13_data() -> Vec<f64> {
+    let epochs: Vec<f64> = (0..NUM_RECORDS).map(|n| 60.0 * n as f64).collect();
+    let mut data = Vec::with_capacity(NUM_RECORDS * 7 + NUM_RECORDS / 100 + 2);
+    for t in &epochs {
+        let (sin_t, cos_t) = (t * 1e-3).sin_cos();
+        data.extend([
+            7000.0 * cos_t,
+            7000.0 * sin_t,
+            0.0,
+            -7.0 * sin_t,
+            7.0 * cos_t,
+            0.0,
+        ]);
+    }
+    data.extend(&epochs);
+    data.extend((1..=(NUM_RECORDS - 1) / 100).map(|k| epochs[100 * k - 1]));
+    data.extend([7.0, NUM_RECORDS as f64]);
+    data
+}
+
+fn benchmark_type13_queries(dataset: &HermiteSetType13, summary: &SPKSummaryRecord) {
+    let step_s = 60.0 * (NUM_RECORDS - 1) as f64 / NUM_QUERIES as f64;
+    for n in 0..NUM_QUERIES {
+        let epoch = Epoch::from_et_seconds(n as f64 * step_s + 0.5);
+        black_box(dataset.evaluate(epoch, summary).unwrap());
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let data = build_type13_data();
+    let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+    let without_registry = HermiteSetType13 {
+        epoch_registry: &[],
+        ..dataset
+    };
+    let summary = SPKSummaryRecord::default();
+
+    c.bench_function("Type 13 with epoch registry", |b| {
+        b.iter(|| benchmark_type13_queries(&dataset, &summary))
+    });
+
+    c.bench_function("Type 13 without epoch registry", |b| {
+        b.iter(|| benchmark_type13_queries(&without_registry, &summary))
+    });
+}
+
+criterion_group!(hermite_registry, criterion_benchmark);
+criterion_main!(hermite_registry);
@@ -0,0 +1,75 @@
+use anise::{
+    naif::daf::{
+        datatypes::{HermiteSetType13, HermiteWindowCache},
+        NAIFDataSet,
+    },
+    naif::spk::summary::SPKSummaryRecord,
+    prelude::*,
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const NUM_RECORDS: usize = 10_000;
+const NUM_QUERIES: usize = 1_000_000;
+
+/// Builds a synthetic Type 13 segment of a circular orbit sampled every minute.
+fn build_type13_data() -> Vec<f64> {
+    let epochs: Vec<f64> = (0..NUM_RECORDS).map(|n| 60.0 * n as f64).collect();
+    let mut data = Vec::with_capacity(NUM_RECORDS * 7 + NUM_RECORDS / 100 + 2);
+    for t in &epochs {
+        let (sin_t, cos_t) = (t * 1e-3).sin_cos();
+        data.extend([
+            7000.0 * cos_t,
+            7000.0 * sin_t,
+            0.0,
+            -7.0 * sin_t,
+            7.0 * cos_t,
+            0.0,
+        ]);
+    }
+    data.extend(&epochs);
+    data.extend((1..=(NUM_RECORDS - 1) / 100).map(|k| epochs[100 * k - 1]));
+    data.extend([7.0, NUM_RECORDS as f64]);
+    data
+}
+
+/// Sequential sweep of the whole segment, i.e. about a hundred queries between two records.
+fn sweep_epochs() -> impl Iterator<Item = Epoch> {
+    let step_s = 60.0 * (NUM_RECORDS - 1) as f64 / NUM_QUERIES as f64;
+    (0..NUM_QUERIES).map(move |n| Epoch::from_et_seconds(n as f64 * step_s + 0.5))
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let data = build_type13_data();
+    let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+    let summary = SPKSummaryRecord::default();
+
+    let mut group = c.benchmark_group("Type 13 sequential sweep");
+    group.sample_size(10);
+
+    group.bench_function("without window cache", |b| {
+        b.iter(|| {
+            for epoch in sweep_epochs() {
+                black_box(dataset.evaluate(epoch, &summary).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("with window cache", |b| {
+        b.iter(|| {
+            let mut cache = HermiteWindowCache::default();
+            for epoch in sweep_epochs() {
+                black_box(
+                    dataset
+                        .evaluate_cached(epoch, &summary, &mut cache)
+                        .unwrap(),
+                );
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(hermite_window_cache, criterion_benchmark);
+criterion_main!(hermite_window_cache);
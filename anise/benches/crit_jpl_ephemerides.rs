@@ -28,6 +28,12 @@ fn benchmark_anise_single_hop_type2_cheby(ctx: &Almanac, time_it: TimeSeries) {
     }
 }
 
+fn benchmark_anise_single_hop_type2_cheby_position(ctx: &Almanac, time_it: TimeSeries) {
+    for epoch in time_it {
+        black_box(ctx.position_at(EARTH_J2000, MOON_J2000, epoch).unwrap());
+    }
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     let start_epoch = Epoch::from_gregorian_at_noon(1900, 1, 1, TimeScale::ET);
     let end_epoch = Epoch::from_gregorian_at_noon(2099, 1, 1, TimeScale::ET);
@@ -47,6 +53,10 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| benchmark_anise_single_hop_type2_cheby(&ctx, time_it.clone()))
     });
 
+    c.bench_function("ANISE ephemerides single hop position only", |b| {
+        b.iter(|| benchmark_anise_single_hop_type2_cheby_position(&ctx, time_it.clone()))
+    });
+
     c.bench_function("SPICE ephemerides single hop", |b| {
         b.iter(|| benchmark_spice_single_hop_type2_cheby(time_it.clone()))
     });
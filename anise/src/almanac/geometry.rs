@@ -0,0 +1,238 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use crate::{
+    astro::Aberration,
+    ephemerides::EphemerisPhysicsSnafu,
+    errors::{AlmanacError, EphemerisSnafu},
+    frames::Frame,
+    math::cartesian::CartesianState,
+    prelude::Orbit,
+};
+
+use super::Almanac;
+use crate::errors::AlmanacResult;
+
+use hifitime::Epoch;
+use snafu::ResultExt;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+#[cfg_attr(feature = "python", pymethods)]
+impl Almanac {
+    /// Returns the angular separation (between 0 and π radians) of the two targets as seen from the observer, like SPICE's `vsep` of the `spkpos` of each target.
+    ///
+    /// # Algorithm
+    /// 1. Compute the state of each target as seen from the observer, with the provided aberration correction.
+    /// 2. Return the angle between these two position vectors.
+    pub fn angular_separation_rad(
+        &self,
+        target1: Frame,
+        target2: Frame,
+        observer: Frame,
+        epoch: Epoch,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<f64> {
+        let obs_to_target1 = self.transform(target1, observer, epoch, ab_corr)?;
+        let obs_to_target2 = self.transform(target2, observer, epoch, ab_corr)?;
+
+        CartesianState::zero_at_epoch(epoch, obs_to_target1.frame)
+            .angular_separation_rad(&obs_to_target1, &obs_to_target2)
+            .context(EphemerisPhysicsSnafu {
+                action: "computing angular separation",
+            })
+            .context(EphemerisSnafu {
+                action: "computing angular separation",
+            })
+    }
+
+    /// Returns the planetocentric longitude and latitude (in radians) of the sub-observer point of the observer on the
+    /// body whose body fixed frame is provided (e.g. IAU_MOON), and the altitude (in km) of the observer above that point.
+    ///
+    /// The sub-observer point is where the line from the center of the body to the observer intercepts the ellipsoid of the body,
+    /// i.e. the `INTERCEPT/ELLIPSOID` method of SPICE's `subpnt`. If the body fixed frame does not include its shape, it is fetched from the loaded planetary data.
+    ///
+    /// # Algorithm
+    /// 1. Compute the state of the observer with respect to the body in its body fixed frame, with the provided aberration correction.
+    ///    The orientation of the body is that at the epoch of the observer.
+    /// 2. Return the planetocentric coordinates of that state, and its distance to the ellipsoid along that same direction.
+    pub fn sub_observer_point_rad(
+        &self,
+        mut body_fixed: Frame,
+        observer: Orbit,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<(f64, f64, f64)> {
+        if body_fixed.shape.is_none() {
+            body_fixed =
+                self.frame_from_uid(body_fixed)
+                    .map_err(|e| AlmanacError::GenericError {
+                        err: format!("{e} when fetching {body_fixed:e} frame data"),
+                    })?;
+        }
+
+        let observer_bf = CartesianState {
+            frame: body_fixed,
+            ..self.transform_to(observer, body_fixed, ab_corr)?
+        };
+
+        observer_bf
+            .sub_observer_point_rad()
+            .context(EphemerisPhysicsSnafu {
+                action: "computing sub-observer point",
+            })
+            .context(EphemerisSnafu {
+                action: "computing sub-observer point",
+            })
+    }
+}
+
+#[cfg(test)]
+mod ut_geometry {
+    use core::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use crate::{
+        constants::frames::{EARTH_J2000, IAU_EARTH_FRAME},
+        math::{cartesian::CartesianState, Vector3},
+        prelude::*,
+        structure::planetocentric::ellipsoid::Ellipsoid,
+    };
+
+    #[test]
+    fn angular_separation() {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let observer = CartesianState::from_position(1.0, 1.0, 0.0, epoch, EARTH_J2000);
+        let at = |x_km: f64, y_km: f64, z_km: f64| {
+            CartesianState::from_position(x_km, y_km, z_km, epoch, EARTH_J2000)
+        };
+
+        for (target1, target2, expected_rad) in [
+            (at(2.0, 1.0, 0.0), at(1.0, 2.0, 0.0), FRAC_PI_2),
+            (at(2.0, 1.0, 0.0), at(0.0, 1.0, 0.0), PI),
+            (at(2.0, 1.0, 0.0), at(3.0, 1.0, 0.0), 0.0),
+            (at(2.0, 1.0, 0.0), at(2.0, 2.0, 0.0), FRAC_PI_4),
+        ] {
+            let sep_rad = observer.angular_separation_rad(&target1, &target2).unwrap();
+            assert!((sep_rad - expected_rad).abs() < 1e-15, "{sep_rad}");
+            assert_eq!(
+                sep_rad,
+                observer.angular_separation_rad(&target2, &target1).unwrap()
+            );
+        }
+
+        // Nearly aligned targets: the arccosine of the dot product would round this to zero.
+        let sep_rad = observer
+            .angular_separation_rad(&at(1e8 + 1.0, 1.0, 0.0), &at(1e8 + 1.0, 1.0, 1e-3))
+            .unwrap();
+        assert!((sep_rad - 1e-11).abs() < 1e-24, "{sep_rad}");
+
+        let later =
+            CartesianState::from_position(1.0, 2.0, 0.0, epoch + 1.0.seconds(), EARTH_J2000);
+        assert!(observer
+            .angular_separation_rad(&at(2.0, 1.0, 0.0), &later)
+            .is_err());
+        let mut other_frame = at(1.0, 2.0, 0.0);
+        other_frame.frame = IAU_EARTH_FRAME;
+        assert!(observer
+            .angular_separation_rad(&at(2.0, 1.0, 0.0), &other_frame)
+            .is_err());
+    }
+
+    #[test]
+    fn sub_observer_point() {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let body_fixed = IAU_EARTH_FRAME.with_ellipsoid(Ellipsoid::from_spheroid(6378.0, 6357.0));
+
+        for (long_deg, lat_deg) in [
+            (0.0_f64, 0.0_f64),
+            (45.0, 30.0),
+            (-120.0, -60.0),
+            (180.0, 89.0),
+        ] {
+            let (sin_long, cos_long) = long_deg.to_radians().sin_cos();
+            let (sin_lat, cos_lat) = lat_deg.to_radians().sin_cos();
+            let direction = Vector3::new(cos_lat * cos_long, cos_lat * sin_long, sin_lat);
+            let radius_km = 42_164.0 * direction;
+            let observer = CartesianState::from_position(
+                radius_km.x,
+                radius_km.y,
+                radius_km.z,
+                epoch,
+                body_fixed,
+            );
+
+            let (long_rad, lat_rad, alt_km) = observer.sub_observer_point_rad().unwrap();
+            assert!((long_rad - long_deg.to_radians()).abs() < 1e-12);
+            assert!((lat_rad - lat_deg.to_radians()).abs() < 1e-12);
+
+            // The surface point is on the ellipsoid
+            let surface_km = (observer.rmag_km() - alt_km) * direction;
+            let on_ellipsoid = (surface_km.x.powi(2) + surface_km.y.powi(2)) / 6378.0_f64.powi(2)
+                + surface_km.z.powi(2) / 6357.0_f64.powi(2);
+            assert!((on_ellipsoid - 1.0).abs() < 1e-14);
+        }
+
+        // Above the equator and the pole, the altitude is with respect to the equatorial and polar radii.
+        let over_equator = CartesianState::from_position(7000.0, 0.0, 0.0, epoch, body_fixed);
+        assert!((over_equator.sub_observer_point_rad().unwrap().2 - 622.0).abs() < 1e-12);
+        let over_pole = CartesianState::from_position(0.0, 0.0, 7000.0, epoch, body_fixed);
+        let (_, lat_rad, alt_km) = over_pole.sub_observer_point_rad().unwrap();
+        assert_eq!(lat_rad, FRAC_PI_2);
+        assert!((alt_km - 643.0).abs() < 1e-12);
+
+        let no_shape = CartesianState::from_position(7000.0, 0.0, 0.0, epoch, IAU_EARTH_FRAME);
+        assert!(no_shape.sub_observer_point_rad().is_err());
+    }
+
+    #[test]
+    fn almanac_geometry() {
+        use crate::almanac::spk::ut_almanac_spk::{constant_spk, DAY_S};
+        use crate::constants::frames::{MOON_J2000, SUN_J2000};
+
+        // Sun along +X of the SSB, the Earth at 1 km along +Y of the EMB, and the Moon along -Y and +Z of the EMB.
+        let planetary = constant_spk(&[
+            (10, 0, 0.0, 10.0, [1.5e8, 0.0, 0.0]),
+            (3, 0, 0.0, 10.0, [0.0, 0.0, 0.0]),
+            (399, 3, 0.0, 10.0, [0.0, 1.0, 0.0]),
+            (301, 3, 0.0, 10.0, [0.0, -3.8e5 + 1.0, 3.8e5]),
+        ]);
+        let almanac = Almanac::default().load_from_bytes(planetary).unwrap();
+        let epoch = Epoch::from_et_seconds(5.0 * DAY_S);
+
+        let sep_rad = almanac
+            .angular_separation_rad(MOON_J2000, SUN_J2000, EARTH_J2000, epoch, None)
+            .unwrap();
+        let earth_to_sun = Vector3::new(1.5e8, -1.0, 0.0);
+        let earth_to_moon = Vector3::new(0.0, -3.8e5, 3.8e5);
+        let expected_rad = (earth_to_sun.dot(&earth_to_moon)
+            / (earth_to_sun.norm() * earth_to_moon.norm()))
+        .acos();
+        assert!((sep_rad - expected_rad).abs() < 1e-12, "{sep_rad}");
+        assert!(almanac
+            .angular_separation_rad(
+                MOON_J2000,
+                SUN_J2000,
+                EARTH_J2000,
+                epoch + 10.0.days(),
+                None
+            )
+            .is_err());
+
+        // The orientation of the J2000 frame is identical to that of the SSB
+        let earth_shaped = EARTH_J2000.with_ellipsoid(Ellipsoid::from_sphere(6378.0));
+        let moon = almanac.state_of(301, SUN_J2000, epoch, None).unwrap();
+        let (long_rad, lat_rad, alt_km) = almanac
+            .sub_observer_point_rad(earth_shaped, moon, None)
+            .unwrap();
+        assert!((long_rad + FRAC_PI_2).abs() < 1e-12);
+        assert!((lat_rad - FRAC_PI_4).abs() < 1e-12);
+        assert!((alt_km - (earth_to_moon.norm() - 6378.0)).abs() < 1e-9);
+    }
+}
@@ -37,6 +37,7 @@ pub const MAX_PLANETARY_DATA: usize = 64;
 pub mod aer;
 pub mod bpc;
 pub mod eclipse;
+pub mod geometry;
 pub mod planetary;
 pub mod solar;
 pub mod spk;
@@ -116,6 +117,10 @@ impl Almanac {
         me
     }
 
+    /// Loads the provided bytes into a clone of this original Almanac, guessing at the file type.
+    ///
+    /// SPICE DAF kernels are parsed without copying the bytes when possible (cf. `DAF::parse_shared`), such that the Almanac shares
+    /// the provided buffer, e.g. a kernel received over the network.
     pub fn load_from_bytes(&self, bytes: Bytes) -> AlmanacResult<Self> {
         // Try to load as a SPICE DAF first (likely the most typical use case)
 
@@ -126,7 +131,7 @@ impl Almanac {
                 return match fileid {
                     "PCK" => {
                         info!("Loading as DAF/PCK");
-                        let bpc = BPC::parse(bytes)
+                        let bpc = BPC::parse_shared(bytes)
                             .context(BPCSnafu {
                                 action: "parsing bytes",
                             })
@@ -139,7 +144,7 @@ impl Almanac {
                     }
                     "SPK" => {
                         info!("Loading as DAF/SPK");
-                        let spk = SPK::parse(bytes)
+                        let spk = SPK::parse_shared(bytes)
                             .context(SPKSnafu {
                                 action: "parsing bytes",
                             })
@@ -201,6 +206,8 @@ impl Almanac {
 #[cfg_attr(feature = "python", pymethods)]
 impl Almanac {
     /// Generic function that tries to load the provided path guessing to the file type.
+    ///
+    /// As in SPICE, the kernels loaded last take precedence over the ones loaded before them where their coverage overlaps.
     pub fn load(&self, path: &str) -> AlmanacResult<Self> {
         // Load the data onto the heap
         let bytes = file2heap!(path).context(LoadingSnafu {
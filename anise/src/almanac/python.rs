@@ -8,14 +8,37 @@
  * Documentation: https://nyxspace.com/
  */
 
+use core::str::FromStr;
+
 use super::{
     planetary::{PlanetaryDataError, PlanetaryDataSetSnafu},
     Almanac,
 };
-use crate::prelude::Frame;
+use crate::prelude::{Aberration, Epoch, Frame};
+use crate::NaifId;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use snafu::prelude::*;
 
+/// An epoch provided from Python, either as a Hifitime epoch or as a string parsed by Hifitime (e.g. `2021-10-29 12:34:56 TDB`).
+#[derive(FromPyObject)]
+pub enum PyEpoch {
+    Epoch(Epoch),
+    Str(String),
+}
+
+impl TryFrom<PyEpoch> for Epoch {
+    type Error = PyErr;
+
+    fn try_from(epoch: PyEpoch) -> Result<Self, Self::Error> {
+        match epoch {
+            PyEpoch::Epoch(epoch) => Ok(epoch),
+            PyEpoch::Str(repr) => Epoch::from_str(&repr)
+                .map_err(|e| PyValueError::new_err(format!("invalid epoch `{repr}`: {e}"))),
+        }
+    }
+}
+
 #[pymethods]
 impl Almanac {
     pub fn frame_info(&self, uid: Frame) -> Result<Frame, PlanetaryDataError> {
@@ -27,4 +50,20 @@ impl Almanac {
             })?
             .to_frame(uid.into()))
     }
+
+    /// Returns the position (km) and the velocity (km/s) of the object as seen from the observer frame, like `state_of`.
+    ///
+    /// The epoch may be a Hifitime epoch or a string, and both vectors are returned as lists of three floats which can be
+    /// directly converted into numpy arrays, e.g. `np.array(pos_km)`.
+    #[pyo3(signature = (object, observer, epoch, ab_corr=None))]
+    pub fn state_of_vectors(
+        &self,
+        object: NaifId,
+        observer: Frame,
+        epoch: PyEpoch,
+        ab_corr: Option<Aberration>,
+    ) -> PyResult<([f64; 3], [f64; 3])> {
+        let state = self.state_of(object, observer, epoch.try_into()?, ab_corr)?;
+        Ok((state.radius_km.into(), state.velocity_km_s.into()))
+    }
 }
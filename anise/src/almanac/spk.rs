@@ -10,13 +10,13 @@
 
 use std::collections::HashMap;
 
-use hifitime::Epoch;
+use hifitime::{Epoch, TimeUnits};
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
-use snafu::ensure;
+use snafu::{ensure, ResultExt};
 
-use crate::ephemerides::NoEphemerisLoadedSnafu;
+use crate::ephemerides::{NoEphemerisLoadedSnafu, SPKSnafu};
 use crate::naif::daf::DAFError;
 use crate::naif::daf::NAIFSummaryRecord;
 use crate::naif::spk::summary::SPKSummaryRecord;
@@ -26,6 +26,22 @@ use log::error;
 
 use super::{Almanac, MAX_LOADED_SPKS};
 
+/// Segment selected to compute the state of a body at a given epoch, and how its data is interpolated.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SPKSegmentInfo {
+    /// Summary of the selected segment
+    pub summary: SPKSummaryRecord,
+    /// Index of the SPK in the loaded SPKs
+    pub spk_no: usize,
+    /// Index of the segment in that SPK
+    pub idx_in_spk: usize,
+    /// SPK data type number of the segment
+    pub data_type: i32,
+    /// Effective degree of the interpolation at that epoch, if the data type interpolates polynomials.
+    /// For the modified difference arrays (Types 1 and 21), this is the maximum integration order of the record.
+    pub degree: Option<usize>,
+}
+
 impl Almanac {
     pub fn from_spk(spk: SPK) -> Result<Almanac, EphemerisError> {
         let me = Self::default();
@@ -34,6 +50,7 @@ impl Almanac {
 
     /// Loads a new SPK file into a new context.
     /// This new context is needed to satisfy the unloading of files. In fact, to unload a file, simply let the newly loaded context drop out of scope and Rust will clean it up.
+    /// Where its segments overlap those of the SPKs loaded before it, this SPK takes precedence.
     pub fn with_spk(&self, spk: SPK) -> Result<Self, EphemerisError> {
         // This is just a bunch of pointers so it doesn't use much memory.
         let mut me = self.clone();
@@ -132,6 +149,34 @@ impl Almanac {
         })
     }
 
+    /// Returns the segment used to compute the state of the provided body at the requested epoch, along with its data type
+    /// and the effective degree of its interpolation at that epoch.
+    ///
+    /// When several segments overlap, this is the segment that every ephemeris query uses: the first matching segment
+    /// of the most recently loaded SPK.
+    pub fn spk_segment_info_at_epoch(
+        &self,
+        id: NaifId,
+        epoch: Epoch,
+    ) -> Result<SPKSegmentInfo, EphemerisError> {
+        let (summary, spk_no, idx_in_spk) = self.spk_summary_at_epoch(id, epoch)?;
+
+        let spk_data = self.spk_data[spk_no]
+            .as_ref()
+            .ok_or(EphemerisError::Unreachable)?;
+        let data = spk_data.nth_segment_data(idx_in_spk).context(SPKSnafu {
+            action: "fetching data for segment information",
+        })?;
+
+        Ok(SPKSegmentInfo {
+            summary: *summary,
+            spk_no,
+            idx_in_spk,
+            data_type: summary.data_type_i,
+            degree: data.degree_at(epoch, summary),
+        })
+    }
+
     /// Returns the most recently loaded summary by its name, if any with that ID are available
     pub fn spk_summary_from_name(
         &self,
@@ -241,6 +286,40 @@ impl Almanac {
         Ok((start, end))
     }
 
+    /// Returns the coverage of the requested id across all loaded SPKs, as a chronologically sorted list of disjoint (start, end) epochs.
+    ///
+    /// Each segment covers the epochs of both its summary and its data (cf. `SegmentData::coverage`). Overlapping and adjacent segments are
+    /// merged, so any gap between two intervals is a time span without data for that id.
+    ///
+    /// # Warning
+    /// This function performs a memory allocation.
+    pub fn spk_coverage(&self, id: NaifId) -> Result<Vec<(Epoch, Epoch)>, EphemerisError> {
+        // Ensures that there is at least one summary for this ID.
+        self.spk_summaries(id)?;
+
+        let mut intervals = vec![];
+        for maybe_spk in self.spk_data.iter().take(self.num_loaded_spk()).rev() {
+            let spk = maybe_spk.as_ref().unwrap();
+            if let Ok(these_summaries) = spk.data_summaries() {
+                for (idx, summary) in these_summaries.iter().enumerate() {
+                    if summary.id() != id || summary.is_empty() {
+                        continue;
+                    }
+                    // Segments whose data cannot be decoded are reported with the epochs of their summary.
+                    let (start, end) = match spk.nth_segment_data(idx) {
+                        Ok(segment) => segment.coverage(summary),
+                        Err(_) => (summary.start_epoch(), summary.end_epoch()),
+                    };
+                    if start <= end {
+                        intervals.push((start, end));
+                    }
+                }
+            }
+        }
+
+        Ok(merge_coverage(intervals))
+    }
+
     /// Returns a map of each loaded SPK ID to its domain validity.
     ///
     /// # Warning
@@ -273,10 +352,74 @@ impl Almanac {
 
         Ok(domains)
     }
+
+    /// Returns the sorted IDs of the targets of all of the loaded SPKs, without duplicates.
+    ///
+    /// # Warning
+    /// This function performs a memory allocation.
+    pub fn all_bodies(&self) -> Result<Vec<NaifId>, EphemerisError> {
+        self.spk_ids(|summary| summary.target_id)
+    }
+
+    /// Returns the sorted IDs of the centers of all of the loaded SPKs, without duplicates.
+    ///
+    /// # Warning
+    /// This function performs a memory allocation.
+    pub fn all_centers(&self) -> Result<Vec<NaifId>, EphemerisError> {
+        self.spk_ids(|summary| summary.center_id)
+    }
+}
+
+impl Almanac {
+    /// Returns the sorted and deduplicated IDs of the non-empty summaries of all of the loaded SPKs, as selected by `id_of`.
+    fn spk_ids(
+        &self,
+        id_of: impl Fn(&SPKSummaryRecord) -> NaifId,
+    ) -> Result<Vec<NaifId>, EphemerisError> {
+        ensure!(self.num_loaded_spk() > 0, NoEphemerisLoadedSnafu);
+
+        let mut ids = vec![];
+        for maybe_spk in self.spk_data.iter().take(self.num_loaded_spk()) {
+            let spk = maybe_spk.as_ref().unwrap();
+            if let Ok(these_summaries) = spk.data_summaries() {
+                ids.extend(
+                    these_summaries
+                        .iter()
+                        .filter(|summary| !summary.is_empty())
+                        .map(&id_of),
+                );
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+
+        Ok(ids)
+    }
+}
+
+/// Sorts and merges the overlapping or adjacent intervals.
+///
+/// Segment epochs are shrunk by one nanosecond on each side (cf. `SPKSummaryRecord::start_epoch`), so intervals at most two nanoseconds apart are adjacent.
+fn merge_coverage(mut intervals: Vec<(Epoch, Epoch)>) -> Vec<(Epoch, Epoch)> {
+    intervals.sort_by_key(|interval| interval.0);
+
+    let mut merged: Vec<(Epoch, Epoch)> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some((_, cur_end)) if start <= *cur_end + 2_i64.nanoseconds() => {
+                if end > *cur_end {
+                    *cur_end = end;
+                }
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
 }
 
 #[cfg(test)]
-mod ut_almanac_spk {
+pub(crate) mod ut_almanac_spk {
     use crate::{
         constants::frames::{EARTH_J2000, MOON_J2000},
         prelude::{Almanac, Epoch},
@@ -329,4 +472,364 @@ mod ut_almanac_spk {
             "empty Almanac should report an error"
         );
     }
+
+    #[test]
+    fn coverage_merge() {
+        use super::merge_coverage;
+        use hifitime::TimeUnits;
+
+        let t0 = Epoch::from_et_seconds(0.0);
+        let intervals = vec![
+            (t0 + 10.days(), t0 + 12.days()),
+            (t0, t0 + 1.days()),
+            // Overlapping
+            (t0 + 0.5.days(), t0 + 2.days()),
+            // Adjacent, as defined by the summaries
+            (t0 + 2.days() + 2.nanoseconds(), t0 + 3.days()),
+            // Contained
+            (t0 + 10.5.days(), t0 + 11.days()),
+            // Gap of a few nanoseconds
+            (t0 + 12.days() + 3.nanoseconds(), t0 + 13.days()),
+        ];
+
+        assert_eq!(
+            merge_coverage(intervals),
+            vec![
+                (t0, t0 + 3.days()),
+                (t0 + 10.days(), t0 + 12.days()),
+                (t0 + 12.days() + 3.nanoseconds(), t0 + 13.days())
+            ]
+        );
+        assert!(merge_coverage(vec![]).is_empty());
+    }
+
+    #[test]
+    fn coverage_multiple_spks() {
+        use crate::naif::{
+            spk::segment::spk_segment_ut::build_spk, spk::summary::SPKSummaryRecord, SPK,
+        };
+        use hifitime::TimeUnits;
+
+        let summary = |target_id, start_day: f64, end_day: f64| SPKSummaryRecord {
+            start_epoch_et_s: start_day * 86_400.0,
+            end_epoch_et_s: end_day * 86_400.0,
+            target_id,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 13,
+            start_idx: 385,
+            end_idx: 400,
+        };
+
+        let spk1 = SPK::parse(build_spk(
+            false,
+            &[
+                summary(-10, 0.0, 1.0),
+                summary(-20, 0.0, 5.0),
+                summary(-10, 1.0, 2.0),
+            ],
+            &[0.0; 16],
+        ))
+        .unwrap();
+        let spk2 = SPK::parse(build_spk(
+            false,
+            &[summary(-10, 4.0, 6.0), summary(-10, 1.5, 2.5)],
+            &[0.0; 16],
+        ))
+        .unwrap();
+
+        let almanac = Almanac::default()
+            .with_spk(spk1)
+            .unwrap()
+            .with_spk(spk2)
+            .unwrap();
+
+        let t0 = Epoch::from_et_seconds(0.0);
+        let ns = 1.nanoseconds();
+        assert_eq!(
+            almanac.spk_coverage(-10).unwrap(),
+            vec![
+                (t0 + ns, t0 + 2.5.days() - ns),
+                (t0 + 4.days() + ns, t0 + 6.days() - ns)
+            ]
+        );
+        assert_eq!(
+            almanac.spk_coverage(-20).unwrap(),
+            vec![(t0 + ns, t0 + 5.days() - ns)]
+        );
+        assert!(almanac.spk_coverage(-30).is_err());
+        assert!(Almanac::default().spk_coverage(-10).is_err());
+
+        // The bodies are the union of those of both SPKs
+        assert_eq!(almanac.all_bodies().unwrap(), vec![-20, -10]);
+        assert_eq!(almanac.all_centers().unwrap(), vec![399]);
+        assert!(Almanac::default().all_bodies().is_err());
+    }
+
+    #[test]
+    fn coverage_restricted_to_data() {
+        use crate::naif::{
+            spk::segment::spk_segment_ut::build_spk, spk::summary::SPKSummaryRecord, SPK,
+        };
+        use hifitime::TimeUnits;
+
+        // Two Type 13 states at 0 and 80 seconds, but a summary covering a full day.
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([7000.0, 600.0, 0.0, 0.0, 7.5, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 86_400.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 13,
+            start_idx: 385,
+            end_idx: 400,
+        };
+        let spk = SPK::parse(build_spk(false, &[summary], &data)).unwrap();
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        let t0 = Epoch::from_et_seconds(0.0);
+        assert_eq!(
+            almanac.spk_coverage(-10).unwrap(),
+            vec![(t0 + 1.nanoseconds(), t0 + 80.seconds())]
+        );
+        // The domain remains that of the summaries.
+        assert_eq!(
+            almanac.spk_domain(-10).unwrap().1,
+            t0 + 1.days() - 1.nanoseconds()
+        );
+    }
+
+    #[test]
+    fn segment_info_overlapping() {
+        use crate::naif::{
+            spk::segment::spk_segment_ut::build_spk, spk::summary::SPKSummaryRecord, SPK,
+        };
+
+        let day_s = 86_400.0;
+        // Lagrange Type 9 of degree 3 with 5 states over [0, 4] days
+        let mut type9 = vec![0.0; 30];
+        type9.extend((0..5).map(|i| i as f64 * day_s));
+        type9.extend([3.0, 5.0]);
+        // Hermite Type 13 with a window of 8 samples but only 3 states over [2, 6] days
+        let mut type13 = vec![0.0; 18];
+        type13.extend([2.0 * day_s, 4.0 * day_s, 6.0 * day_s]);
+        type13.extend([7.0, 3.0]);
+
+        let summary =
+            |data_type_i, start_day: f64, end_day: f64, start_idx, len| SPKSummaryRecord {
+                start_epoch_et_s: start_day * day_s,
+                end_epoch_et_s: end_day * day_s,
+                target_id: -10,
+                center_id: 399,
+                frame_id: 1,
+                data_type_i,
+                start_idx,
+                end_idx: start_idx + len - 1,
+            };
+        let type9_summary = summary(9, 0.0, 4.0, 385, type9.len() as i32);
+        let type13_summary = summary(13, 2.0, 6.0, 385 + type9.len() as i32, type13.len() as i32);
+
+        let mut data = type9.clone();
+        data.extend(&type13);
+        let spk = SPK::parse(build_spk(false, &[type9_summary, type13_summary], &data)).unwrap();
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        let epoch = |day: f64| Epoch::from_et_seconds(day * day_s);
+
+        // Before the overlap, only the Type 9 segment covers the epoch
+        let info = almanac.spk_segment_info_at_epoch(-10, epoch(1.0)).unwrap();
+        assert_eq!(info.summary, type9_summary);
+        assert_eq!((info.spk_no, info.idx_in_spk), (0, 0));
+        assert_eq!(info.data_type, 9);
+        assert_eq!(info.degree, Some(3));
+
+        // In the overlap, the first segment of the SPK is selected, every time
+        for _ in 0..3 {
+            let info = almanac.spk_segment_info_at_epoch(-10, epoch(3.0)).unwrap();
+            assert_eq!(info.summary, type9_summary);
+            assert_eq!(info.data_type, 9);
+        }
+
+        // After the overlap, the Type 13 segment is used with its effective degree
+        let info = almanac.spk_segment_info_at_epoch(-10, epoch(5.0)).unwrap();
+        assert_eq!(info.summary, type13_summary);
+        assert_eq!((info.spk_no, info.idx_in_spk), (0, 1));
+        assert_eq!(info.data_type, 13);
+        assert_eq!(info.degree, Some(5));
+
+        // A more recently loaded SPK takes precedence in the overlap
+        let spk2 = SPK::parse(build_spk(
+            false,
+            &[summary(13, 2.0, 6.0, 385, type13.len() as i32)],
+            &type13,
+        ))
+        .unwrap();
+        let almanac = almanac.with_spk(spk2).unwrap();
+        let info = almanac.spk_segment_info_at_epoch(-10, epoch(3.0)).unwrap();
+        assert_eq!((info.spk_no, info.idx_in_spk), (1, 0));
+        assert_eq!(info.data_type, 13);
+
+        assert!(almanac.spk_segment_info_at_epoch(-10, epoch(7.0)).is_err());
+        assert!(almanac.spk_segment_info_at_epoch(-20, epoch(3.0)).is_err());
+    }
+
+    pub(crate) const DAY_S: f64 = 86_400.0;
+
+    /// Builds an SPK whose segments, given as (target, center, start day, end day, position), each hold a constant
+    /// position, as a Type 13 of two states.
+    pub(crate) fn constant_spk(segments: &[(i32, i32, f64, f64, [f64; 3])]) -> bytes::Bytes {
+        use crate::naif::spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord};
+
+        let mut summaries = Vec::new();
+        let mut data = Vec::new();
+        for (n, (target_id, center_id, start_day, end_day, pos_km)) in segments.iter().enumerate() {
+            data.extend(
+                pos_km
+                    .iter()
+                    .chain(&[0.0; 3])
+                    .chain(pos_km)
+                    .chain(&[0.0; 3]),
+            );
+            data.extend([start_day * DAY_S, end_day * DAY_S, 1.0, 2.0]);
+            summaries.push(SPKSummaryRecord {
+                start_epoch_et_s: start_day * DAY_S,
+                end_epoch_et_s: end_day * DAY_S,
+                target_id: *target_id,
+                center_id: *center_id,
+                frame_id: 1,
+                data_type_i: 13,
+                start_idx: 385 + 16 * n as i32,
+                end_idx: 400 + 16 * n as i32,
+            });
+        }
+        bytes::Bytes::from(build_spk(false, &summaries, &data))
+    }
+
+    #[test]
+    fn state_of_multiple_spks() {
+        use crate::{
+            constants::frames::{EARTH_MOON_BARYCENTER_J2000, SSB_J2000},
+            math::Vector3,
+        };
+
+        // Planetary ephemerides over ten days, a spacecraft around the Earth over [1, 2] days, and a lunar ephemeris over [4, 6] days.
+        let planetary = constant_spk(&[
+            (3, 0, 0.0, 10.0, [1.5e8, 0.0, 0.0]),
+            (399, 3, 0.0, 10.0, [0.0, 4.6e3, 0.0]),
+            (301, 3, 0.0, 10.0, [0.0, -3.8e5, 0.0]),
+        ]);
+        let spacecraft = constant_spk(&[(-10, 399, 1.0, 2.0, [7e3, 0.0, 0.0])]);
+        let lunar = constant_spk(&[(301, 3, 4.0, 6.0, [0.0, -3.9e5, 0.0])]);
+
+        let almanac = Almanac::default()
+            .load_from_bytes(planetary.clone())
+            .and_then(|almanac| almanac.load_from_bytes(spacecraft))
+            .and_then(|almanac| almanac.load_from_bytes(lunar.clone()))
+            .unwrap();
+        assert_eq!(almanac.num_loaded_spk(), 3);
+
+        let epoch = |day: f64| Epoch::from_et_seconds(day * DAY_S);
+        let pos_km = |object, observer, day| {
+            almanac
+                .state_of(object, observer, epoch(day), None)
+                .map(|state| state.radius_km)
+        };
+
+        // Both the planetary and the spacecraft states are found, and chained across kernels
+        assert_eq!(
+            pos_km(399, SSB_J2000, 1.5),
+            Ok(Vector3::new(1.5e8, 4.6e3, 0.0))
+        );
+        assert_eq!(
+            pos_km(-10, EARTH_J2000, 1.5),
+            Ok(Vector3::new(7e3, 0.0, 0.0))
+        );
+        assert_eq!(
+            pos_km(-10, SSB_J2000, 1.5),
+            Ok(Vector3::new(1.5e8 + 7e3, 4.6e3, 0.0))
+        );
+        assert_eq!(
+            pos_km(-10, MOON_J2000, 1.5),
+            Ok(Vector3::new(7e3, 4.6e3 + 3.8e5, 0.0))
+        );
+        assert!(pos_km(-10, EARTH_J2000, 3.0).is_err());
+
+        // Where the coverage overlaps, the last loaded kernel takes precedence
+        assert_eq!(
+            pos_km(301, EARTH_MOON_BARYCENTER_J2000, 5.0),
+            Ok(Vector3::new(0.0, -3.9e5, 0.0))
+        );
+        assert_eq!(
+            pos_km(301, EARTH_MOON_BARYCENTER_J2000, 8.0),
+            Ok(Vector3::new(0.0, -3.8e5, 0.0))
+        );
+
+        // And loading the planetary ephemerides last restores their lunar states
+        let almanac = Almanac::default()
+            .load_from_bytes(lunar)
+            .and_then(|almanac| almanac.load_from_bytes(planetary))
+            .unwrap();
+        assert_eq!(
+            almanac
+                .state_of(301, EARTH_MOON_BARYCENTER_J2000, epoch(5.0), None)
+                .map(|state| state.radius_km),
+            Ok(Vector3::new(0.0, -3.8e5, 0.0))
+        );
+    }
+
+    #[test]
+    fn disconnected_ephemeris_path() {
+        use crate::{ephemerides::EphemerisError, errors::AlmanacError};
+
+        // The spacecraft is relative to the Earth, which is missing from the planetary ephemerides.
+        let almanac = Almanac::default()
+            .load_from_bytes(constant_spk(&[
+                (3, 0, 0.0, 10.0, [1.5e8, 0.0, 0.0]),
+                (301, 3, 0.0, 10.0, [0.0, -3.8e5, 0.0]),
+            ]))
+            .and_then(|almanac| {
+                almanac.load_from_bytes(constant_spk(&[(-12345, 399, 1.0, 2.0, [7e3, 0.0, 0.0])]))
+            })
+            .unwrap();
+
+        let path_error = |epoch| match almanac.state_of(-12345, MOON_J2000, epoch, None) {
+            Err(AlmanacError::Ephemeris { source, .. }) => *source,
+            other => panic!("{other:?}"),
+        };
+
+        let epoch = Epoch::from_et_seconds(1.5 * DAY_S);
+        let err = path_error(epoch);
+        assert_eq!(
+            err,
+            EphemerisError::NoEphemerisPath {
+                from: 301,
+                to: -12345,
+                epoch,
+                from_reachable: vec![301, 3, 0],
+                to_reachable: vec![-12345, 399],
+            }
+        );
+        assert!(
+            err.to_string()
+                .starts_with("no SPK connects body 301 to body -12345"),
+            "{err}"
+        );
+
+        // Outside of the coverage of the spacecraft, it reaches nothing
+        let epoch = Epoch::from_et_seconds(5.0 * DAY_S);
+        assert_eq!(
+            path_error(epoch),
+            EphemerisError::NoEphemerisPath {
+                from: 301,
+                to: -12345,
+                epoch,
+                from_reachable: vec![301, 3, 0],
+                to_reachable: vec![-12345],
+            }
+        );
+        assert_eq!(almanac.reachable_ephemerides(399, epoch), vec![399]);
+    }
 }
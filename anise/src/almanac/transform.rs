@@ -12,6 +12,7 @@ use hifitime::{Epoch, Unit as TimeUnit};
 use snafu::ResultExt;
 
 use crate::{
+    constants::{frames::SSB_J2000, orientations::J2000},
     errors::{AlmanacResult, EphemerisSnafu, OrientationSnafu},
     math::{cartesian::CartesianState, units::LengthUnit, Vector3},
     orientations::OrientationPhysicsSnafu,
@@ -95,8 +96,29 @@ impl Almanac {
             })
     }
 
+    /// Re-expresses the provided state in the provided body-fixed frame, e.g. a lander relative to Mars in IAU_MARS.
+    ///
+    /// The state is translated to the center of the body-fixed frame with the loaded SPKs, and then rotated with the orientation
+    /// of the body from the loaded BPCs or planetary constants. The rotation of the velocity accounts for the angular velocity
+    /// of the body (the ω × r transport term), so a point fixed on the surface of the body has a zero velocity in the returned state.
+    ///
+    /// # Note
+    /// This is `transform_to` where the observer frame is body-fixed, e.g. `Frame::new(MARS_BARYCENTER, IAU_MARS)`.
+    pub fn reframe_to_body_fixed(
+        &self,
+        state: CartesianState,
+        body_fixed_frame: Frame,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<CartesianState> {
+        self.transform_to(state, body_fixed_frame, ab_corr)
+    }
+
     /// Returns the Cartesian state of the object as seen from the provided observer frame (essentially `spkezr`).
     ///
+    /// The segments of the loaded SPKs are chained through every intermediate center up to the common origin of
+    /// the object and the observer (e.g. the Moon relative to the Sun goes through the Earth Moon barycenter and the SSB).
+    /// If no loaded SPK links the object to the observer, a `NoEphemerisPath` ephemeris error lists the bodies reachable from each of them.
+    ///
     /// # Note
     /// The units will be those of the underlying ephemeris data (typically km and km/s)
     pub fn state_of(
@@ -181,4 +203,52 @@ impl Almanac {
                 action: "transform provided state",
             })
     }
+
+    /// Returns the Cartesian state of the target frame as seen from an observer which is not an ephemeris object, e.g. a ground
+    /// station, and optionally given the aberration correction (essentially `spkcpo`).
+    ///
+    /// The observer may be provided in any frame: it is transformed to the solar system barycenter, so that the light time and
+    /// stellar aberration corrections use its own state, including the rotation of its body if it is fixed in a body-fixed frame.
+    /// The returned state is centered on the observer, in the orientation of the target frame.
+    pub fn transform_from_observer(
+        &self,
+        target_frame: Frame,
+        observer: CartesianState,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<CartesianState> {
+        let obs_ssb = self.transform_to(observer, SSB_J2000, None)?;
+
+        let (radius_km, velocity_km_s) = match ab_corr {
+            Some(ab_corr) => self
+                .aberrated_translation(target_frame, &obs_ssb, observer.epoch, ab_corr)
+                .context(EphemerisSnafu {
+                    action: "transform from observer",
+                })?,
+            None => {
+                let tgt_ssb = self
+                    .translate(target_frame, SSB_J2000, observer.epoch, None)
+                    .context(EphemerisSnafu {
+                        action: "transform from observer",
+                    })?;
+                (
+                    tgt_ssb.radius_km - obs_ssb.radius_km,
+                    tgt_ssb.velocity_km_s - obs_ssb.velocity_km_s,
+                )
+            }
+        };
+
+        let state = CartesianState {
+            radius_km,
+            velocity_km_s,
+            epoch: observer.epoch,
+            frame: observer.frame.with_orient(J2000),
+        };
+        self.rotate_to(
+            state,
+            observer.frame.with_orient(target_frame.orientation_id),
+        )
+        .context(OrientationSnafu {
+            action: "transform from observer",
+        })
+    }
 }
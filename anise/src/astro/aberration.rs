@@ -45,7 +45,7 @@ use crate::errors::PhysicsError;
 #[cfg_attr(feature = "python", pyo3(module = "anise"))]
 #[cfg_attr(feature = "python", pyo3(get_all, set_all))]
 pub struct Aberration {
-    /// Indicates whether the light time calculations should be iterated upon (more precise but three times as many CPU cycles).
+    /// Indicates whether the light time calculations should be iterated upon until convergence (more precise but typically three times as many CPU cycles).
     pub converged: bool,
     /// Flag to denote if stellar aberration correction is applied. Stellar aberration is due to the motion of the observer (caused by Earth's orbit around the Sun).
     pub stellar: bool,
@@ -236,6 +236,16 @@ impl fmt::Display for Aberration {
 /// Rotate r by phi radians about h to obtain the apparent position of the object.
 ///
 ///
+/// Applies the stellar aberration correction to the light-time corrected position of the target, given the
+/// velocity of the observer with respect to the solar system barycenter.
+///
+/// The correction rotates the position towards the observer velocity (or away from it in transmit mode), so the
+/// range is unchanged. This is the first order formula of SPICE's `stelab`, which differs from the relativistic
+/// formula by less than (v/c)^2 radians, i.e. about 1e-8 radians for an observer on Earth.
+///
+/// # Errors
+/// + The aberration correction does not request stellar aberration;
+/// + The observer is moving at or faster than the speed of light.
 pub fn stellar_aberration(
     target_pos_km: Vector3,
     obs_wrt_ssb_vel_km_s: Vector3,
@@ -287,6 +297,43 @@ pub fn stellar_aberration(
 
 #[cfg(test)]
 mod ut_aberration {
+    use crate::{constants::SPEED_OF_LIGHT_KM_S, math::Vector3};
+
+    #[test]
+    fn test_stellar_aberration() {
+        use super::{stellar_aberration, Aberration};
+
+        // Observer moving at about the orbital velocity of the Earth along X, target 60 degrees away from X.
+        let vel_km_s = Vector3::new(30.0, 0.0, 0.0);
+        let beta = 30.0 / SPEED_OF_LIGHT_KM_S;
+        let theta = 60.0_f64.to_radians();
+        let tgt_km = Vector3::new(theta.cos(), theta.sin(), 0.0) * 2.25e8;
+
+        let app_km = stellar_aberration(tgt_km, vel_km_s, Aberration::LT_S.unwrap()).unwrap();
+        // Only the direction is corrected
+        assert!((app_km.norm() - tgt_km.norm()).abs() < 1e-6);
+        // The apparent direction moves towards the observer velocity ...
+        let app_theta = app_km.angle(&vel_km_s);
+        assert!(theta - app_theta > 0.5 * beta);
+        // ... and matches the relativistic formula, cos θ' = (cos θ + β) / (1 + β cos θ), to second order.
+        let rel_theta = ((theta.cos() + beta) / (1.0 + beta * theta.cos())).acos();
+        assert!((app_theta - rel_theta).abs() < beta.powi(2));
+
+        // In transmit mode, the correction is applied in the opposite direction
+        let xmit_km = stellar_aberration(tgt_km, vel_km_s, Aberration::XLT_S.unwrap()).unwrap();
+        assert!(xmit_km.angle(&vel_km_s) - theta > 0.5 * beta);
+
+        // Stellar aberration must be requested
+        assert!(stellar_aberration(tgt_km, vel_km_s, Aberration::LT.unwrap()).is_err());
+        // And the observer cannot move faster than light
+        assert!(stellar_aberration(
+            tgt_km,
+            vel_km_s.normalize() * 2.0 * SPEED_OF_LIGHT_KM_S,
+            Aberration::CN_S.unwrap()
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_display() {
         use super::Aberration;
@@ -25,7 +25,7 @@ use pyo3::pyclass::CompareOp;
 pub mod utils;
 
 pub(crate) mod aberration;
-pub use aberration::Aberration;
+pub use aberration::{stellar_aberration, Aberration};
 
 pub(crate) mod occultation;
 pub use occultation::Occultation;
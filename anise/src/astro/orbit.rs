@@ -361,7 +361,7 @@ impl Orbit {
     /// 1. Compute the state data one millisecond before and one millisecond assuming two body dynamics
     /// 2. Compute the DCM for this state, and the pre and post states
     /// 3. Build the c vector as the normalized orbital momentum vector
-    /// 4. Build the i vector as the cross product of \hat{r} and c
+    /// 4. Build the i vector as the cross product of c and \hat{r}
     /// 5. Build the RIC DCM as a 3x3 of the columns [\hat{r}, \hat{i}, \hat{c}], for the post, post, and current states
     /// 6. Compute the difference between the DCMs of the pre and post states, to build the DCM time derivative
     /// 7. Return the DCM structure with a 6x6 state DCM.
@@ -397,13 +397,13 @@ impl Orbit {
     ///
     /// # Algorithm
     /// 1. Build the c vector as the normalized orbital momentum vector
-    /// 2. Build the i vector as the cross product of \hat{r} and c
+    /// 2. Build the i vector as the cross product of c and \hat{r}
     /// 3. Build the RIC DCM as a 3x3 of the columns [\hat{r}, \hat{i}, \hat{c}]
     /// 4. Return the DCM structure **without** accounting for the transport theorem.
     pub fn dcm3x3_from_ric_to_inertial(&self) -> PhysicsResult<DCM> {
         let r_hat = self.r_hat();
         let c_hat = self.hvec()? / self.hmag()?;
-        let i_hat = r_hat.cross(&c_hat);
+        let i_hat = c_hat.cross(&r_hat);
 
         let rot_mat = Matrix3::from_columns(&[r_hat, i_hat, c_hat]);
 
@@ -415,6 +415,16 @@ impl Orbit {
         })
     }
 
+    /// Rotates the provided vector (e.g. a position difference) from this state's inertial frame into this state's RIC frame.
+    /// Refer to dcm3x3_from_ric_to_inertial for details on the RIC frame.
+    ///
+    /// # Note
+    /// The transport theorem is not accounted for, so velocity differences are rotated like any other vector:
+    /// use `ric_difference` to account for the rotation of the RIC frame.
+    pub fn ric_vector(&self, vector: &Vector3) -> PhysicsResult<Vector3> {
+        Ok(self.dcm3x3_from_ric_to_inertial()?.rot_mat.transpose() * vector)
+    }
+
     /// Builds the rotation matrix that rotates from this state's inertial frame to this state's RCN frame (radial, cross, normal)
     ///
     /// # Frame warning
@@ -10,6 +10,7 @@
 
 use super::PhysicsResult;
 use crate::{
+    errors::PhysicsError,
     math::{
         angles::{between_0_360, between_pm_180},
         cartesian::CartesianState,
@@ -243,6 +244,38 @@ impl CartesianState {
         Ok(self.latlongalt()?.0)
     }
 
+    /// Returns the planetocentric longitude and latitude (in radians) of the sub-observer point of this state, and the
+    /// altitude (in km) of this state above that point.
+    ///
+    /// The sub-observer point is where the line from the center of the body to this state intercepts the ellipsoid of
+    /// its frame, so it has the planetocentric coordinates of this state. The longitude is between -π and +π radians,
+    /// as returned by SPICE's `reclat`.
+    ///
+    /// # Frame warning
+    /// This state MUST be in the body fixed frame (e.g. ITRF93) prior to calling this function, or the computation is **invalid**.
+    pub fn sub_observer_point_rad(&self) -> PhysicsResult<(f64, f64, f64)> {
+        let shape = self.frame.shape.ok_or(PhysicsError::MissingFrameData {
+            action: "computing sub-observer point",
+            data: "shape",
+            frame: self.frame.into(),
+        })?;
+
+        let r_hat = self.r_hat();
+        let surface_km = (r_hat.x.powi(2) / shape.semi_major_equatorial_radius_km.powi(2)
+            + r_hat.y.powi(2) / shape.semi_minor_equatorial_radius_km.powi(2)
+            + r_hat.z.powi(2) / shape.polar_radius_km.powi(2))
+        .sqrt()
+        .recip();
+
+        let long_rad = self.radius_km.y.atan2(self.radius_km.x);
+        let lat_rad = self
+            .radius_km
+            .z
+            .atan2(self.radius_km.x.hypot(self.radius_km.y));
+
+        Ok((long_rad, lat_rad, self.rmag_km() - surface_km))
+    }
+
     /// Returns the geodetic height in km.
     ///
     /// Reference: Vallado, 4th Ed., Algorithm 12 page 172.
@@ -37,6 +37,18 @@ pub enum EphemerisError {
         to: FrameUid,
         epoch: Epoch,
     },
+    #[snafu(display(
+        "no SPK connects body {from} to body {to} at epoch {epoch}: {from} only reaches {from_reachable:?} and {to} only reaches {to_reachable:?}"
+    ))]
+    NoEphemerisPath {
+        from: NaifId,
+        to: NaifId,
+        epoch: Epoch,
+        /// Bodies reachable from `from`, starting with `from` itself and followed by each successive center
+        from_reachable: Vec<NaifId>,
+        /// Bodies reachable from `to`, starting with `to` itself and followed by each successive center
+        to_reachable: Vec<NaifId>,
+    },
     #[snafu(display("no ephemeris data loaded (must call load_spk)"))]
     NoEphemerisLoaded,
     #[snafu(display("when {action} caused {source}"))]
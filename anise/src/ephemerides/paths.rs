@@ -98,7 +98,7 @@ impl Almanac {
         })
     }
 
-    /// Returns the ephemeris path between two frames and the common node. This may return a `DisjointRoots` error if the frames do not share a common root, which is considered a file integrity error.
+    /// Returns the ephemeris path between two frames and the common node. This returns a `NoEphemerisPath` error, listing the bodies reachable from each frame, if no loaded SPK links both frames.
     ///
     /// # Example
     ///
@@ -121,7 +121,7 @@ impl Almanac {
     ///
     /// # Note
     /// A proper ANISE file should only have a single root and if two paths are empty, then they should be the same frame.
-    /// If a DisjointRoots error is reported here, it means that the ANISE file is invalid.
+    /// If the frames do not share a common root, it means that the ANISE file is invalid.
     ///
     /// # Time complexity
     /// This can likely be simplified as this as a time complexity of O(n×m) where n, m are the lengths of the paths from
@@ -138,9 +138,20 @@ impl Almanac {
             return Ok((0, [None; MAX_TREE_DEPTH], from_frame.ephemeris_id));
         }
 
-        // Grab the paths
-        let (from_len, from_path) = self.ephemeris_path_to_root(from_frame, epoch)?;
-        let (to_len, to_path) = self.ephemeris_path_to_root(to_frame, epoch)?;
+        // Grab the paths, reporting which bodies can be reached if a segment is missing along the way.
+        let disconnected = |e| match e {
+            EphemerisError::SPK {
+                source: DAFError::SummaryIdAtEpochError { .. },
+                ..
+            } => self.no_ephemeris_path(from_frame, to_frame, epoch),
+            e => e,
+        };
+        let (from_len, from_path) = self
+            .ephemeris_path_to_root(from_frame, epoch)
+            .map_err(disconnected)?;
+        let (to_len, to_path) = self
+            .ephemeris_path_to_root(to_frame, epoch)
+            .map_err(disconnected)?;
 
         // Now that we have the paths, we can find the matching origin.
 
@@ -191,8 +202,41 @@ impl Almanac {
                 }
             }
 
-            // This is weird and I don't think it should happen, so let's raise an error.
-            Err(EphemerisError::Unreachable)
+            // The paths never meet, e.g. if the loaded ephemerides have disjoint roots.
+            Err(self.no_ephemeris_path(from_frame, to_frame, epoch))
+        }
+    }
+
+    /// Returns the bodies reachable from the provided body at the provided epoch: the body itself, followed by its center,
+    /// the center of that center, and so on until no loaded SPK provides the next center or the maximum tree depth is reached.
+    pub fn reachable_ephemerides(&self, id: NaifId, epoch: Epoch) -> Vec<NaifId> {
+        let mut reachable = vec![id];
+        let mut center_id = id;
+        for _ in 0..MAX_TREE_DEPTH {
+            match self.spk_summary_at_epoch(center_id, epoch) {
+                Ok((summary, _, _)) => {
+                    center_id = summary.center_id;
+                    reachable.push(center_id);
+                }
+                Err(_) => break,
+            }
+        }
+        reachable
+    }
+
+    /// Builds the error of a missing link between the provided frames, listing the bodies reachable from each of them.
+    fn no_ephemeris_path(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> EphemerisError {
+        EphemerisError::NoEphemerisPath {
+            from: from_frame.ephemeris_id,
+            to: to_frame.ephemeris_id,
+            epoch,
+            from_reachable: self.reachable_ephemerides(from_frame.ephemeris_id, epoch),
+            to_reachable: self.reachable_ephemerides(to_frame.ephemeris_id, epoch),
         }
     }
 }
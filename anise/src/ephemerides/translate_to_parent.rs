@@ -16,11 +16,10 @@ use crate::almanac::Almanac;
 use crate::ephemerides::EphemInterpolationSnafu;
 use crate::hifitime::Epoch;
 use crate::math::cartesian::CartesianState;
+use crate::math::interpolation::InterpolationError;
 use crate::math::Vector3;
-use crate::naif::daf::datatypes::{
-    HermiteSetType13, LagrangeSetType9, Type2ChebyshevSet, Type3ChebyshevSet,
-};
-use crate::naif::daf::{DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use crate::naif::spk::segment_data::SegmentData;
+use crate::naif::spk::summary::SPKSummaryRecord;
 use crate::prelude::Frame;
 
 #[cfg(feature = "python")]
@@ -54,61 +53,48 @@ impl Almanac {
             .ok_or(EphemerisError::Unreachable)?;
 
         // Now let's simply evaluate the data
-
-        let (pos_km, vel_km_s) = match summary.data_type()? {
-            DafDataType::Type2ChebyshevTriplet => {
-                let data =
-                    spk_data
-                        .nth_data::<Type2ChebyshevSet>(idx_in_spk)
-                        .context(SPKSnafu {
-                            action: "fetching data for interpolation",
-                        })?;
-                data.evaluate(epoch, summary)
-                    .context(EphemInterpolationSnafu)?
-            }
-            DafDataType::Type3ChebyshevSextuplet => {
-                let data =
-                    spk_data
-                        .nth_data::<Type3ChebyshevSet>(idx_in_spk)
-                        .context(SPKSnafu {
-                            action: "fetching data for interpolation",
-                        })?;
-                data.evaluate(epoch, summary)
-                    .context(EphemInterpolationSnafu)?
-            }
-            DafDataType::Type9LagrangeUnequalStep => {
-                let data = spk_data
-                    .nth_data::<LagrangeSetType9>(idx_in_spk)
-                    .context(SPKSnafu {
-                        action: "fetching data for interpolation",
-                    })?;
-                data.evaluate(epoch, summary)
-                    .context(EphemInterpolationSnafu)?
-            }
-            DafDataType::Type13HermiteUnequalStep => {
-                let data = spk_data
-                    .nth_data::<HermiteSetType13>(idx_in_spk)
-                    .context(SPKSnafu {
-                        action: "fetching data for interpolation",
-                    })?;
-                data.evaluate(epoch, summary)
-                    .context(EphemInterpolationSnafu)?
-            }
-            dtype => {
-                return Err(EphemerisError::SPK {
-                    action: "translation to parent",
-                    source: DAFError::UnsupportedDatatype {
-                        dtype,
-                        kind: "SPK computations",
-                    },
-                })
-            }
-        };
+        let data = spk_data.nth_segment_data(idx_in_spk).context(SPKSnafu {
+            action: "fetching data for interpolation",
+        })?;
+        let (pos_km, vel_km_s) = data
+            .evaluate(epoch, summary)
+            .context(EphemInterpolationSnafu)?;
 
         Ok((pos_km, vel_km_s, new_frame))
     }
+
+    /// Returns a single vector of the `source` with respect to its parent in the ephemeris at the provided epoch, as evaluated by `eval`
+    /// (e.g. `SegmentData::evaluate_position`), and the parent frame.
+    pub(crate) fn translation_vector_to_parent(
+        &self,
+        source: Frame,
+        epoch: Epoch,
+        eval: SegmentEval,
+    ) -> Result<(Vector3, Frame), EphemerisError> {
+        let (summary, spk_no, idx_in_spk) =
+            self.spk_summary_at_epoch(source.ephemeris_id, epoch)?;
+
+        let new_frame = source.with_ephem(summary.center_id);
+
+        trace!("translate {source} wrt to {new_frame} @ {epoch:E}");
+
+        let spk_data = self.spk_data[spk_no]
+            .as_ref()
+            .ok_or(EphemerisError::Unreachable)?;
+
+        let data = spk_data.nth_segment_data(idx_in_spk).context(SPKSnafu {
+            action: "fetching data for interpolation",
+        })?;
+        let vector = eval(&data, epoch, summary).context(EphemInterpolationSnafu)?;
+
+        Ok((vector, new_frame))
+    }
 }
 
+/// Evaluates one vector of the data of an SPK segment, e.g. only its position.
+pub(crate) type SegmentEval =
+    fn(&SegmentData, Epoch, &SPKSummaryRecord) -> Result<Vector3, InterpolationError>;
+
 #[cfg_attr(feature = "python", pymethods)]
 impl Almanac {
     /// Performs the GEOMETRIC translation to the parent. Use translate_from_to for aberration.
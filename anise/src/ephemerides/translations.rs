@@ -10,6 +10,7 @@
 
 use snafu::ResultExt;
 
+use super::translate_to_parent::SegmentEval;
 use super::EphemerisError;
 use super::EphemerisPhysicsSnafu;
 use crate::almanac::Almanac;
@@ -26,6 +27,13 @@ use crate::prelude::Frame;
 /// **Limitation:** no translation or rotation may have more than 8 nodes.
 pub const MAX_TREE_DEPTH: usize = 8;
 
+/// Maximum number of iterations of the converged light time correction.
+pub const MAX_LIGHT_TIME_ITERATIONS: usize = 10;
+
+/// The converged light time correction stops iterating once the one-way light time changes by less than
+/// this tolerance in seconds, i.e. the precision of an Epoch.
+pub const LIGHT_TIME_TOLERANCE_S: f64 = 1e-9;
+
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
@@ -113,51 +121,10 @@ impl Almanac {
                 })
             }
             Some(ab_corr) => {
-                // This is a rewrite of NAIF SPICE's `spkapo`
-
                 // Find the geometric position of the observer body with respect to the solar system barycenter.
                 let obs_ssb = self.translate(observer_frame, SSB_J2000, epoch, None)?;
-                let obs_ssb_pos_km = obs_ssb.radius_km;
-                let obs_ssb_vel_km_s = obs_ssb.velocity_km_s;
-
-                // Find the geometric position of the target body with respect to the solar system barycenter.
-                let tgt_ssb = self.translate(target_frame, SSB_J2000, epoch, None)?;
-                let tgt_ssb_pos_km = tgt_ssb.radius_km;
-                let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
-
-                // Subtract the position of the observer to get the relative position.
-                let mut rel_pos_km = tgt_ssb_pos_km - obs_ssb_pos_km;
-                // NOTE: We never correct the velocity, so the geometric velocity is what we're seeking.
-                let mut rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
-
-                // Use this to compute the one-way light time in seconds.
-                let mut one_way_lt_s = rel_pos_km.norm() / SPEED_OF_LIGHT_KM_S;
-
-                // To correct for light time, find the position of the target body at the current epoch
-                // minus the one-way light time. Note that the observer remains where he is.
-
-                let num_it = if ab_corr.converged { 3 } else { 1 };
-                let lt_sign = if ab_corr.transmit_mode { 1.0 } else { -1.0 };
-
-                for _ in 0..num_it {
-                    let epoch_lt = epoch + lt_sign * one_way_lt_s * TimeUnit::Second;
-                    let tgt_ssb = self.translate(target_frame, SSB_J2000, epoch_lt, None)?;
-                    let tgt_ssb_pos_km = tgt_ssb.radius_km;
-                    let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
-
-                    rel_pos_km = tgt_ssb_pos_km - obs_ssb_pos_km;
-                    rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
-                    one_way_lt_s = rel_pos_km.norm() / SPEED_OF_LIGHT_KM_S;
-                }
-
-                // If stellar aberration correction is requested, perform it now.
-                if ab_corr.stellar {
-                    // Modifications based on transmission versus reception case is done in the function directly.
-                    rel_pos_km = stellar_aberration(rel_pos_km, obs_ssb_vel_km_s, ab_corr)
-                        .context(EphemerisPhysicsSnafu {
-                            action: "computing stellar aberration",
-                        })?;
-                }
+                let (rel_pos_km, rel_vel_km_s) =
+                    self.aberrated_translation(target_frame, &obs_ssb, epoch, ab_corr)?;
 
                 Ok(CartesianState {
                     radius_km: rel_pos_km,
@@ -203,6 +170,73 @@ impl Almanac {
 }
 
 impl Almanac {
+    /// Returns the position and velocity of the target frame relative to an observer at the provided epoch, given the geometric
+    /// state of that observer with respect to the solar system barycenter in the J2000 frame, corrected for the provided aberration.
+    pub(crate) fn aberrated_translation(
+        &self,
+        target_frame: Frame,
+        obs_ssb: &CartesianState,
+        epoch: Epoch,
+        ab_corr: Aberration,
+    ) -> Result<(Vector3, Vector3), EphemerisError> {
+        // This is a rewrite of NAIF SPICE's `spkapo`
+        let obs_ssb_pos_km = obs_ssb.radius_km;
+        let obs_ssb_vel_km_s = obs_ssb.velocity_km_s;
+
+        // Find the geometric position of the target body with respect to the solar system barycenter.
+        let tgt_ssb = self.translate(target_frame, SSB_J2000, epoch, None)?;
+        let tgt_ssb_pos_km = tgt_ssb.radius_km;
+        let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
+
+        // Subtract the position of the observer to get the relative position.
+        let mut rel_pos_km = tgt_ssb_pos_km - obs_ssb_pos_km;
+        // NOTE: We never correct the velocity, so the geometric velocity is what we're seeking.
+        let mut rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
+
+        // Use this to compute the one-way light time in seconds.
+        let mut one_way_lt_s = rel_pos_km.norm() / SPEED_OF_LIGHT_KM_S;
+
+        // To correct for light time, find the position of the target body at the current epoch
+        // minus the one-way light time. Note that the observer remains where he is.
+
+        // The converged correction iterates on the emission (or reception) epoch until the
+        // light time no longer changes, whereas the unconverged correction uses a single iteration.
+        let num_it = if ab_corr.converged {
+            MAX_LIGHT_TIME_ITERATIONS
+        } else {
+            1
+        };
+        let lt_sign = if ab_corr.transmit_mode { 1.0 } else { -1.0 };
+
+        for _ in 0..num_it {
+            let epoch_lt = epoch + lt_sign * one_way_lt_s * TimeUnit::Second;
+            let tgt_ssb = self.translate(target_frame, SSB_J2000, epoch_lt, None)?;
+            let tgt_ssb_pos_km = tgt_ssb.radius_km;
+            let tgt_ssb_vel_km_s = tgt_ssb.velocity_km_s;
+
+            rel_pos_km = tgt_ssb_pos_km - obs_ssb_pos_km;
+            rel_vel_km_s = tgt_ssb_vel_km_s - obs_ssb_vel_km_s;
+            let prev_one_way_lt_s = one_way_lt_s;
+            one_way_lt_s = rel_pos_km.norm() / SPEED_OF_LIGHT_KM_S;
+
+            if (one_way_lt_s - prev_one_way_lt_s).abs() < LIGHT_TIME_TOLERANCE_S {
+                break;
+            }
+        }
+
+        // If stellar aberration correction is requested, perform it now.
+        if ab_corr.stellar {
+            // Modifications based on transmission versus reception case is done in the function directly.
+            rel_pos_km = stellar_aberration(rel_pos_km, obs_ssb_vel_km_s, ab_corr).context(
+                EphemerisPhysicsSnafu {
+                    action: "computing stellar aberration",
+                },
+            )?;
+        }
+
+        Ok((rel_pos_km, rel_vel_km_s))
+    }
+
     /// Translates a state with its origin (`to_frame`) and given its units (distance_unit, time_unit), returns that state with respect to the requested frame
     ///
     /// **WARNING:** This function only performs the translation and no rotation _whatsoever_. Use the `transform_state_to` function instead to include rotations.
@@ -235,4 +269,221 @@ impl Almanac {
             action: "translating states (likely a bug!)",
         })
     }
+
+    /// Returns the GEOMETRIC position vector in km of the target frame as seen from the observer frame at the provided epoch.
+    ///
+    /// This is the position of `translate_geometric`, but the Chebyshev (Types 2 and 3) and Hermite (Types 12 and 13) data skip the
+    /// computations only needed for the velocity, so this is faster when the velocity is not needed. The position is identical to
+    /// that of `translate_geometric`.
+    ///
+    /// # Warning
+    /// This function only performs the translation and no rotation whatsoever.
+    pub fn position_at(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<Vector3, EphemerisError> {
+        self.translate_vector(
+            target_frame,
+            observer_frame,
+            epoch,
+            |data, epoch, summary| data.evaluate_position(epoch, summary),
+        )
+    }
+
+    /// Returns the GEOMETRIC velocity vector in km/s of the target frame as seen from the observer frame at the provided epoch.
+    ///
+    /// This is the velocity of `translate_geometric`, but the Chebyshev Type 3 data skip the evaluation of the position. All other
+    /// data types compute the position along with the velocity, so this is equivalent to `translate_geometric` for those.
+    ///
+    /// # Warning
+    /// This function only performs the translation and no rotation whatsoever.
+    pub fn velocity_at(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+    ) -> Result<Vector3, EphemerisError> {
+        self.translate_vector(
+            target_frame,
+            observer_frame,
+            epoch,
+            |data, epoch, summary| data.evaluate_velocity(epoch, summary),
+        )
+    }
+
+    /// Sums the vectors evaluated by `eval` along the ephemeris path from the observer to the target, like the geometric `translate`.
+    fn translate_vector(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epoch: Epoch,
+        eval: SegmentEval,
+    ) -> Result<Vector3, EphemerisError> {
+        if observer_frame == target_frame {
+            return Ok(Vector3::zeros());
+        }
+
+        if let Ok(obs_frame_info) = self.frame_from_uid(observer_frame) {
+            observer_frame = obs_frame_info;
+        }
+
+        let (node_count, _path, common_node) =
+            self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
+
+        let (mut vec_fwrd, mut frame_fwrd) = if observer_frame.ephem_origin_id_match(common_node) {
+            (Vector3::zeros(), observer_frame)
+        } else {
+            self.translation_vector_to_parent(observer_frame, epoch, eval)?
+        };
+
+        let (mut vec_bwrd, mut frame_bwrd) = if target_frame.ephem_origin_id_match(common_node) {
+            (Vector3::zeros(), target_frame)
+        } else {
+            self.translation_vector_to_parent(target_frame, epoch, eval)?
+        };
+
+        for _ in 0..node_count {
+            if !frame_fwrd.ephem_origin_id_match(common_node) {
+                let (cur_vec_fwrd, cur_frame_fwrd) =
+                    self.translation_vector_to_parent(frame_fwrd, epoch, eval)?;
+                vec_fwrd += cur_vec_fwrd;
+                frame_fwrd = cur_frame_fwrd;
+            }
+
+            if !frame_bwrd.ephem_origin_id_match(common_node) {
+                let (cur_vec_bwrd, cur_frame_bwrd) =
+                    self.translation_vector_to_parent(frame_bwrd, epoch, eval)?;
+                vec_bwrd += cur_vec_bwrd;
+                frame_bwrd = cur_frame_bwrd;
+            }
+        }
+
+        Ok(vec_bwrd - vec_fwrd)
+    }
+}
+
+#[cfg(test)]
+mod translation_ut {
+    use crate::{
+        almanac::Almanac,
+        astro::Aberration,
+        constants::{frames::SSB_J2000, SPEED_OF_LIGHT_KM_S},
+        naif::{spk::segment::spk_segment_ut::build_spk, spk::summary::SPKSummaryRecord, SPK},
+        prelude::{Epoch, Frame},
+    };
+
+    #[test]
+    fn converged_light_time() {
+        // A target moving away from the SSB along X at constant speed, over a Lagrange Type 9 segment of degree one.
+        let (x0_km, v_km_s, end_s) = (1e9, 100.0, 1e6);
+        let mut data = vec![x0_km, 0.0, 0.0, v_km_s, 0.0, 0.0];
+        data.extend([x0_km + v_km_s * end_s, 0.0, 0.0, v_km_s, 0.0, 0.0]);
+        data.extend([0.0, end_s, 1.0, 2.0]);
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: end_s,
+            target_id: -10,
+            center_id: 0,
+            frame_id: 1,
+            data_type_i: 9,
+            start_idx: 385,
+            end_idx: 385 + data.len() as i32 - 1,
+        };
+        let spk = SPK::parse(build_spk(false, &[summary], &data)).unwrap();
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        let target = Frame::from_ephem_j2000(-10);
+        let epoch = Epoch::from_et_seconds(5e5);
+        let x_km = x0_km + v_km_s * epoch.to_et_seconds();
+
+        // In reception, the light time solves lt = x(t - lt) / c exactly for linear motion.
+        let lt_s = x_km / (SPEED_OF_LIGHT_KM_S + v_km_s);
+        let state = almanac
+            .translate(target, SSB_J2000, epoch, Aberration::CN)
+            .unwrap();
+        assert!(
+            (state.radius_km.x - (x_km - v_km_s * lt_s)).abs() < 1e-6,
+            "converged error: {:.3e} km",
+            state.radius_km.x - (x_km - v_km_s * lt_s)
+        );
+        assert!((state.velocity_km_s.x - v_km_s).abs() < 1e-9);
+
+        // The unconverged correction only uses the light time of the geometric position.
+        let state = almanac
+            .translate(target, SSB_J2000, epoch, Aberration::LT)
+            .unwrap();
+        let unconverged_x_km = x_km - v_km_s * x_km / SPEED_OF_LIGHT_KM_S;
+        assert!((state.radius_km.x - unconverged_x_km).abs() < 1e-6);
+        assert!((state.radius_km.x - (x_km - v_km_s * lt_s)).abs() > 1e-3);
+
+        // In transmission, the light time solves lt = x(t + lt) / c.
+        let lt_s = x_km / (SPEED_OF_LIGHT_KM_S - v_km_s);
+        let state = almanac
+            .translate(target, SSB_J2000, epoch, Aberration::XCN)
+            .unwrap();
+        assert!((state.radius_km.x - (x_km + v_km_s * lt_s)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn position_and_velocity_only() {
+        // A spacecraft around the Earth over a Hermite Type 13 segment, and the Earth around the SSB over a Lagrange Type 9 segment.
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([6990.0, 600.0, 10.0, -0.2, 7.4, 0.1]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        data.extend([1.5e8, 0.0, 0.0, 0.0, 30.0, 0.0]);
+        data.extend([1.5e8, 2400.0, 0.0, 0.0, 30.0, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        let summaries = [
+            SPKSummaryRecord {
+                start_epoch_et_s: 0.0,
+                end_epoch_et_s: 80.0,
+                target_id: -10,
+                center_id: 399,
+                frame_id: 1,
+                data_type_i: 13,
+                start_idx: 385,
+                end_idx: 400,
+            },
+            SPKSummaryRecord {
+                start_epoch_et_s: 0.0,
+                end_epoch_et_s: 80.0,
+                target_id: 399,
+                center_id: 0,
+                frame_id: 1,
+                data_type_i: 9,
+                start_idx: 401,
+                end_idx: 416,
+            },
+        ];
+        let spk = SPK::parse(build_spk(false, &summaries, &data)).unwrap();
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        let target = Frame::from_ephem_j2000(-10);
+        let earth = Frame::from_ephem_j2000(399);
+        for t in [1.0, 12.5, 40.0, 79.0] {
+            let epoch = Epoch::from_et_seconds(t);
+            for (target, observer) in [(target, SSB_J2000), (SSB_J2000, target), (target, earth)] {
+                let state = almanac.translate(target, observer, epoch, None).unwrap();
+                assert_eq!(
+                    almanac.position_at(target, observer, epoch).unwrap(),
+                    state.radius_km
+                );
+                assert_eq!(
+                    almanac.velocity_at(target, observer, epoch).unwrap(),
+                    state.velocity_km_s
+                );
+            }
+        }
+        assert_eq!(
+            almanac
+                .position_at(target, target, Epoch::from_et_seconds(1.0))
+                .unwrap(),
+            crate::math::Vector3::zeros()
+        );
+        assert!(almanac
+            .position_at(target, SSB_J2000, Epoch::from_et_seconds(100.0))
+            .is_err());
+    }
 }
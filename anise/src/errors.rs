@@ -8,17 +8,30 @@
  * Documentation: https://nyxspace.com/
  */
 
-use hifitime::Epoch;
+// The code derived for the deprecated IntegrityError::SubNormal variant uses it.
+#![allow(deprecated)]
+
 use snafu::prelude::*;
 
+#[cfg(feature = "std")]
 use crate::ephemerides::EphemerisError;
+#[cfg(feature = "std")]
 use crate::orientations::OrientationError;
+#[cfg(feature = "std")]
 use crate::prelude::FrameUid;
+#[cfg(feature = "std")]
 use crate::structure::dataset::DataSetError;
+#[cfg(feature = "std")]
 use crate::structure::semver::Semver;
+#[cfg(feature = "std")]
 use crate::NaifId;
+#[cfg(feature = "std")]
 use core::convert::From;
+#[cfg(feature = "std")]
 use der::Error as DerError;
+#[cfg(feature = "std")]
+use hifitime::Epoch;
+#[cfg(feature = "std")]
 use std::io::ErrorKind as IOErrorKind;
 
 #[cfg(feature = "metaload")]
@@ -26,6 +39,7 @@ use crate::almanac::metaload::MetaAlmanacError;
 #[cfg(feature = "metaload")]
 use crate::almanac::metaload::MetaFile;
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq, Snafu)]
 #[snafu(visibility(pub))]
 pub enum AlmanacError {
@@ -62,8 +76,10 @@ pub enum AlmanacError {
     },
 }
 
+#[cfg(feature = "std")]
 pub type AlmanacResult<T> = Result<T, AlmanacError>;
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq, Snafu)]
 #[snafu(visibility(pub(crate)))]
 pub enum InputOutputError {
@@ -73,6 +89,7 @@ pub enum InputOutputError {
     IOUnknownError,
 }
 
+#[cfg(feature = "std")]
 #[derive(Copy, Clone, Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 pub enum DecodingError {
@@ -105,6 +122,7 @@ pub enum DecodingError {
     Obscure { kind: &'static str },
 }
 
+#[cfg(feature = "std")]
 #[derive(Copy, Clone, PartialEq, Debug, Snafu)]
 #[snafu(visibility(pub(crate)))]
 pub enum IntegrityError {
@@ -121,6 +139,10 @@ pub enum IntegrityError {
         from_frame: FrameUid,
         to_frame: FrameUid,
     },
+    /// Never returned: non-finite values are reported as either [IntegrityError::NaNValue] or [IntegrityError::InfiniteValue].
+    #[deprecated(
+        note = "use NaNValue or InfiniteValue instead, which are returned for non-finite values"
+    )]
     #[snafu(display(
         "data for {variable} in {dataset} decoded as subnormal double (data malformed?)"
     ))]
@@ -128,6 +150,24 @@ pub enum IntegrityError {
         dataset: &'static str,
         variable: &'static str,
     },
+    #[snafu(display(
+        "data for {variable} in {dataset} decoded as NaN at offset {offset} (data malformed?)"
+    ))]
+    NaNValue {
+        dataset: &'static str,
+        variable: &'static str,
+        offset: usize,
+    },
+    #[snafu(display(
+        "data for {variable} in {dataset} decoded as infinite at offset {offset} (data malformed?)"
+    ))]
+    // PhysicsError::InfiniteValue already uses the default selector name.
+    #[snafu(context(name(InfiniteDataSnafu)))]
+    InfiniteValue {
+        dataset: &'static str,
+        variable: &'static str,
+        offset: usize,
+    },
     #[snafu(display("data for {variable}={value} in {dataset} is invalid {reason}"))]
     InvalidValue {
         dataset: &'static str,
@@ -135,6 +175,30 @@ pub enum IntegrityError {
         value: f64,
         reason: &'static str,
     },
+    #[snafu(display(
+        "interpolation window of {window_size} states in {dataset} exceeds its {num_records} records"
+    ))]
+    WindowExceedsRecords {
+        dataset: &'static str,
+        window_size: usize,
+        num_records: usize,
+    },
+    #[snafu(display("epochs in {dataset} are out of chronological order at index {index}"))]
+    NonMonotonicEpochs { dataset: &'static str, index: usize },
+    #[snafu(display(
+        "epoch registry of {dataset} does not match the epoch data at registry index {index}"
+    ))]
+    RegistryMismatch { dataset: &'static str, index: usize },
+    #[snafu(display(
+        "summary of {dataset} covers {summary_start_et_s} to {summary_end_et_s} ET seconds, beyond its data covering {data_start_et_s} to {data_end_et_s} ET seconds"
+    ))]
+    SummaryCoverageMismatch {
+        dataset: &'static str,
+        summary_start_et_s: f64,
+        summary_end_et_s: f64,
+        data_start_et_s: f64,
+        data_end_et_s: f64,
+    },
 }
 
 #[derive(Copy, Clone, PartialEq, Debug, Snafu)]
@@ -148,6 +212,7 @@ pub enum MathError {
     MaxIterationsReached { iter: usize, action: &'static str },
 }
 
+#[cfg(feature = "std")]
 #[derive(Copy, Clone, Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 pub enum PhysicsError {
@@ -209,6 +274,7 @@ pub enum PhysicsError {
     AberrationError { action: &'static str },
 }
 
+#[cfg(feature = "std")]
 impl From<IOErrorKind> for InputOutputError {
     fn from(kind: IOErrorKind) -> Self {
         Self::IOError { kind }
@@ -0,0 +1,198 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::Frame;
+use crate::constants::orientations::{ECLIPJ2000, J2000, J2000_TO_ECLIPJ2000_ANGLE_RAD};
+use crate::errors::{InvalidStateRotationSnafu, PhysicsError};
+use crate::math::cartesian::CartesianState;
+use crate::math::rotation::{r1, r2, r3, DCM};
+use crate::math::Matrix3;
+
+const ARCSEC_TO_RAD: f64 = core::f64::consts::PI / (180.0 * 3600.0);
+
+/// Mean obliquity of the ecliptic at J2000 (IAU 1976), in arcseconds, which defines the [ECLIPJ2000] frame.
+pub const J2000_OBLIQUITY_ARCSEC: f64 = 84381.448;
+
+/// Returns the frame bias matrix, which rotates vectors from the ICRF to the J2000 mean equator and equinox.
+///
+/// This is the IAU 2000 frame bias of the IERS Conventions (2003), computed like the SOFA `iauBi00` and `iauBp00` routines.
+///
+/// # Note
+/// Like SPICE, ANISE identifies the ICRF with the J2000 frame: this bias is at the level of 20 milliarcseconds
+/// and is only needed when comparing with data which explicitly distinguishes both frames.
+pub fn frame_bias() -> Matrix3 {
+    // Offsets of the ICRS pole in longitude and obliquity, and the ICRS right ascension of the J2000 mean equinox.
+    let dpsi_bias_rad = -0.041775 * ARCSEC_TO_RAD;
+    let deps_bias_rad = -0.0068192 * ARCSEC_TO_RAD;
+    let dra0_rad = -0.0146 * ARCSEC_TO_RAD;
+
+    r1(-deps_bias_rad)
+        * r2(dpsi_bias_rad * (J2000_OBLIQUITY_ARCSEC * ARCSEC_TO_RAD).sin())
+        * r3(dra0_rad)
+}
+
+/// Rotates the provided state expressed in the ICRF into the J2000 mean equator and equinox by applying the [frame_bias].
+///
+/// Both frames share the J2000 orientation ID, so the state must be in a J2000 frame and the returned state remains in that frame.
+pub fn icrf_to_j2000(state: CartesianState) -> Result<CartesianState, PhysicsError> {
+    bias_dcm(frame_bias()) * state
+}
+
+/// Rotates the provided state expressed in J2000 into the ICRF, i.e. the inverse of [icrf_to_j2000].
+pub fn j2000_to_icrf(state: CartesianState) -> Result<CartesianState, PhysicsError> {
+    bias_dcm(frame_bias().transpose()) * state
+}
+
+fn bias_dcm(rot_mat: Matrix3) -> DCM {
+    DCM {
+        rot_mat,
+        rot_mat_dt: None,
+        from: J2000,
+        to: J2000,
+    }
+}
+
+/// Rotates the provided state into the orientation of `to_frame` using the constant rotations between the inertial frames,
+/// i.e. without requiring any loaded orientation data.
+///
+/// The returned state keeps its center and epoch, and is tagged with the orientation of `to_frame`.
+/// Only the [J2000] and [ECLIPJ2000] orientations are supported: use [crate::almanac::Almanac::rotate_to] for any other orientation.
+///
+/// # Errors
+/// + Either the state or the destination frame is not in one of the supported inertial orientations.
+pub fn rotate(state: CartesianState, to_frame: Frame) -> Result<CartesianState, PhysicsError> {
+    let from = state.frame.orientation_id;
+    let to = to_frame.orientation_id;
+
+    let rot_mat = match (from, to) {
+        (J2000, J2000) | (ECLIPJ2000, ECLIPJ2000) => Matrix3::identity(),
+        (J2000, ECLIPJ2000) => r1(J2000_TO_ECLIPJ2000_ANGLE_RAD),
+        (ECLIPJ2000, J2000) => r1(J2000_TO_ECLIPJ2000_ANGLE_RAD).transpose(),
+        _ => {
+            return InvalidStateRotationSnafu {
+                from,
+                to,
+                state_frame: state.frame,
+            }
+            .fail()
+        }
+    };
+
+    DCM {
+        rot_mat,
+        rot_mat_dt: None,
+        from,
+        to,
+    } * state
+}
+
+#[cfg(test)]
+mod inertial_ut {
+    use hifitime::Epoch;
+
+    use super::{
+        frame_bias, icrf_to_j2000, j2000_to_icrf, rotate, ARCSEC_TO_RAD, J2000_OBLIQUITY_ARCSEC,
+    };
+    use crate::constants::frames::{EARTH_ECLIPJ2000, EARTH_ITRF93, EARTH_J2000};
+    use crate::constants::orientations::J2000_TO_ECLIPJ2000_ANGLE_RAD;
+    use crate::errors::PhysicsError;
+    use crate::math::cartesian::CartesianState;
+    use crate::math::Matrix3;
+
+    #[test]
+    fn obliquity() {
+        assert!(
+            (J2000_OBLIQUITY_ARCSEC * ARCSEC_TO_RAD - J2000_TO_ECLIPJ2000_ANGLE_RAD).abs()
+                < f64::EPSILON
+        );
+
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let state = CartesianState::new(
+            -2436.45,
+            -2436.45,
+            6891.037,
+            5.088611,
+            -5.088611,
+            0.0,
+            epoch,
+            EARTH_J2000,
+        );
+
+        let ecliptic = rotate(state, EARTH_ECLIPJ2000).unwrap();
+        assert_eq!(ecliptic.frame, EARTH_ECLIPJ2000);
+        assert_eq!(ecliptic.epoch, epoch);
+        assert!((ecliptic.rmag_km() - state.rmag_km()).abs() < 1e-9);
+
+        // The north pole of the ecliptic is at a declination of 90 degrees minus the obliquity in J2000.
+        let pole = CartesianState::new(0.0, 0.0, 1.0, 0.0, 0.0, 0.0, epoch, EARTH_ECLIPJ2000);
+        let pole_j2000 = rotate(pole, EARTH_J2000).unwrap();
+        assert!(pole_j2000.radius_km.x.abs() < f64::EPSILON);
+        assert!(
+            (pole_j2000.radius_km.z.acos() - J2000_OBLIQUITY_ARCSEC * ARCSEC_TO_RAD).abs() < 1e-15
+        );
+
+        let back = rotate(ecliptic, EARTH_J2000).unwrap();
+        assert!((back.radius_km - state.radius_km).norm() < 1e-9);
+        assert!((back.velocity_km_s - state.velocity_km_s).norm() < 1e-12);
+        assert_eq!(rotate(state, EARTH_J2000).unwrap(), state);
+
+        assert_eq!(
+            rotate(state, EARTH_ITRF93),
+            Err(PhysicsError::InvalidStateRotation {
+                from: EARTH_J2000.orientation_id,
+                to: EARTH_ITRF93.orientation_id,
+                state_frame: EARTH_J2000.into(),
+            })
+        );
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn frame_bias_matrix() {
+        // Reference values from the SOFA test suite for iauBp00
+        let expected = Matrix3::new(
+            0.9999999999999942498,
+            -0.7078279744199196626e-7,
+            0.8056217146976134152e-7,
+            0.7078279477857337206e-7,
+            0.9999999999999969484,
+            0.3306041454222147847e-7,
+            -0.8056217380986972157e-7,
+            -0.3306040883980552500e-7,
+            0.9999999999999962084,
+        );
+        let bias = frame_bias();
+        assert!((bias - expected).amax() < 1e-15);
+        assert!((bias * bias.transpose() - Matrix3::identity()).amax() < 1e-15);
+
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let state = CartesianState::new(
+            -2436.45,
+            -2436.45,
+            6891.037,
+            5.088611,
+            -5.088611,
+            0.0,
+            epoch,
+            EARTH_J2000,
+        );
+        let icrf = j2000_to_icrf(state).unwrap();
+        assert_eq!(icrf.frame, EARTH_J2000);
+        // About 23 milliarcseconds, i.e. less than a meter at this altitude
+        let offset_m = (icrf.radius_km - state.radius_km).norm() * 1e3;
+        assert!(offset_m > 0.5 && offset_m < 1.0, "{offset_m}");
+
+        let back = icrf_to_j2000(icrf).unwrap();
+        assert!((back.radius_km - state.radius_km).norm() < 1e-9);
+        assert!((back.velocity_km_s - state.velocity_km_s).norm() < 1e-12);
+
+        assert!(j2000_to_icrf(rotate(state, EARTH_ECLIPJ2000).unwrap()).is_err());
+    }
+}
@@ -10,6 +10,8 @@
 
 mod frame;
 mod frameuid;
+mod inertial;
 
 pub use frame::Frame;
 pub use frameuid::FrameUid;
+pub use inertial::{frame_bias, icrf_to_j2000, j2000_to_icrf, rotate, J2000_OBLIQUITY_ARCSEC};
@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 /*
  * ANISE Toolkit
  * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
@@ -10,19 +11,28 @@
  * Documentation: https://nyxspace.com/
  */
 
+#[cfg(feature = "std")]
 extern crate const_format;
 extern crate hifitime;
 extern crate log;
 
+#[cfg(feature = "std")]
 pub mod almanac;
+#[cfg(feature = "std")]
 pub mod astro;
+#[cfg(feature = "std")]
 pub mod constants;
+#[cfg(feature = "std")]
 pub mod ephemerides;
 pub mod errors;
+#[cfg(feature = "std")]
 pub mod frames;
 pub mod math;
+#[cfg(feature = "std")]
 pub mod naif;
+#[cfg(feature = "std")]
 pub mod orientations;
+#[cfg(feature = "std")]
 pub mod structure;
 
 /// Re-export of hifitime
@@ -31,6 +41,7 @@ pub mod time {
     pub use hifitime::*;
 
     // Stupid but safe algo to find a new frame ID that only collides on the same microsecond
+    #[cfg(feature = "std")]
     pub(crate) fn uuid_from_epoch(id: i32, epoch: Epoch) -> i32 {
         let wrapped_days = epoch
             .to_tdb_duration()
@@ -42,6 +53,7 @@ pub mod time {
     }
 }
 
+#[cfg(feature = "std")]
 pub mod prelude {
     #[cfg(feature = "metaload")]
     pub use crate::almanac::metaload::MetaAlmanac;
@@ -61,12 +73,15 @@ pub mod prelude {
 mod py_errors;
 
 /// Defines the number of bytes in a double (prevents magic numbers)
+#[cfg(feature = "std")]
 pub(crate) const DBL_SIZE: usize = 8;
 
 /// Defines the hash used to identify parents.
+#[cfg(feature = "std")]
 pub(crate) type NaifId = i32;
 
 /// Memory maps a file and **copies** the data on the heap prior to returning a pointer to this heap data.
+#[cfg(feature = "std")]
 #[macro_export]
 macro_rules! file2heap {
     ($filename:tt) => {
@@ -88,6 +103,7 @@ macro_rules! file2heap {
 }
 
 /// Memory maps a file and **copies** the data on the heap prior to returning a pointer to this heap data.
+#[cfg(feature = "std")]
 #[macro_export]
 macro_rules! file_mmap {
     ($filename:tt) => {
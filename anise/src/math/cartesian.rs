@@ -10,12 +10,16 @@
 
 use super::{perp_vector, root_mean_squared, root_sum_squared, Vector3};
 use crate::{
-    astro::PhysicsResult,
+    astro::{orbit::ECC_EPSILON, PhysicsResult},
     constants::SPEED_OF_LIGHT_KM_S,
-    errors::{EpochMismatchSnafu, FrameMismatchSnafu, MathError, PhysicsError},
+    errors::{
+        EpochMismatchSnafu, FrameMismatchSnafu, MathError, ParabolicEccentricitySnafu,
+        PhysicsError, RadiusSnafu, VelocitySnafu,
+    },
     prelude::Frame,
 };
 
+use core::f64::consts::TAU;
 use core::fmt;
 use core::ops::{Add, Neg, Sub};
 use hifitime::{Duration, Epoch, TimeUnits};
@@ -38,7 +42,8 @@ pub struct CartesianState {
     pub radius_km: Vector3,
     /// Velocity in kilometers per second
     pub velocity_km_s: Vector3,
-    /// Epoch with time scale at which this is valid.
+    /// Epoch with time scale at which this is valid, serialized as a TDB string (e.g. `2024-01-01T00:01:09.183927704 TDB`).
+    #[serde(with = "epoch_tdb")]
     pub epoch: Epoch,
     /// Frame in which this Cartesian state lives.
     pub frame: Frame,
@@ -242,6 +247,39 @@ impl CartesianState {
         Ok(self.distance_to_point_km(&other.radius_km))
     }
 
+    /// Returns the angle (between 0 and π radians) between the two targets as seen from this state, if all three states are in the same frame at the same epoch.
+    ///
+    /// The angle is computed from the cross and dot products of the observer to target vectors, which remains accurate for nearly aligned targets.
+    pub fn angular_separation_rad(&self, target1: &Self, target2: &Self) -> PhysicsResult<f64> {
+        for target in [target1, target2] {
+            ensure!(
+                self.frame.ephem_origin_match(target.frame)
+                    && self.frame.orient_origin_match(target.frame),
+                FrameMismatchSnafu {
+                    action: "computing angular separation",
+                    frame1: self.frame,
+                    frame2: target.frame
+                }
+            );
+            ensure!(
+                self.epoch == target.epoch,
+                EpochMismatchSnafu {
+                    action: "computing angular separation",
+                    epoch1: self.epoch,
+                    epoch2: target.epoch
+                }
+            );
+        }
+
+        let obs_to_target1 = target1.radius_km - self.radius_km;
+        let obs_to_target2 = target2.radius_km - self.radius_km;
+
+        Ok(obs_to_target1
+            .cross(&obs_to_target2)
+            .norm()
+            .atan2(obs_to_target1.dot(&obs_to_target2)))
+    }
+
     /// Returns the root mean squared (RSS) radius difference between this state and another state, if both frames match (epoch does not need to match)
     pub fn rss_radius_km(&self, other: &Self) -> PhysicsResult<f64> {
         ensure!(
@@ -390,6 +428,151 @@ impl CartesianState {
     pub fn rel_difference(&self, other: &Self) -> PhysicsResult<(f64, f64)> {
         Ok((self.rel_pos_diff(other)?, self.rel_vel_diff(other)?))
     }
+
+    /// Returns the classical Keplerian orbital elements of this state about a central body of the provided gravitational parameter.
+    ///
+    /// Contrary to the element accessors of `Orbit`, this does not use the GM of the frame of this state.
+    ///
+    /// # Singular orbits
+    /// + If the orbit is circular (eccentricity below `ECC_EPSILON`), the argument of periapsis is set to zero and the true anomaly is the argument of latitude.
+    /// + If the orbit is equatorial, the RAAN is set to zero and the argument of periapsis is the longitude of periapsis.
+    /// + If the orbit is both circular and equatorial, the true anomaly is the true longitude.
+    pub fn keplerian_elements(&self, gm_km3_s2: f64) -> PhysicsResult<KeplerianElements> {
+        if !gm_km3_s2.is_finite() || gm_km3_s2 <= 0.0 {
+            return Err(PhysicsError::AppliedMath {
+                source: MathError::DomainError {
+                    value: gm_km3_s2,
+                    msg: "gravitational parameter must be strictly positive",
+                },
+            });
+        }
+
+        let r = self.radius_km;
+        let v = self.velocity_km_s;
+        let rmag = r.norm();
+        ensure!(
+            rmag > f64::EPSILON,
+            RadiusSnafu {
+                action: "cannot compute Keplerian elements with zero radius"
+            }
+        );
+
+        let h = r.cross(&v);
+        let hmag = h.norm();
+        ensure!(
+            hmag > f64::EPSILON,
+            VelocitySnafu {
+                action: "cannot compute Keplerian elements of a rectilinear orbit"
+            }
+        );
+        let h_hat = h / hmag;
+
+        let evec = ((v.norm_squared() - gm_km3_s2 / rmag) * r - r.dot(&v) * v) / gm_km3_s2;
+        let ecc = evec.norm();
+        ensure!(
+            (1.0 - ecc).abs() >= ECC_EPSILON,
+            ParabolicEccentricitySnafu { limit: ECC_EPSILON }
+        );
+
+        let energy = v.norm_squared() / 2.0 - gm_km3_s2 / rmag;
+        let sma_km = -gm_km3_s2 / (2.0 * energy);
+
+        let inc_rad = h_hat.z.clamp(-1.0, 1.0).acos();
+
+        // Angle from `from` to `to` measured positively in the direction of motion, in [0; 2 pi).
+        let angle = |from: &Vector3, to: &Vector3| -> f64 {
+            h_hat
+                .dot(&from.cross(to))
+                .atan2(from.dot(to))
+                .rem_euclid(TAU)
+        };
+
+        let node = Vector3::z().cross(&h);
+        let equatorial = node.norm() < ECC_EPSILON * hmag;
+        let circular = ecc < ECC_EPSILON;
+
+        // In the equatorial case, angles are measured from the X axis instead of the ascending node.
+        let (raan_rad, reference) = if equatorial {
+            (0.0, Vector3::x())
+        } else {
+            (node.y.atan2(node.x).rem_euclid(TAU), node)
+        };
+
+        let (aop_rad, ta_rad) = if circular {
+            (0.0, angle(&reference, &r))
+        } else {
+            (angle(&reference, &evec), angle(&evec, &r))
+        };
+
+        Ok(KeplerianElements {
+            sma_km,
+            ecc,
+            inc_rad,
+            raan_rad,
+            aop_rad,
+            ta_rad,
+        })
+    }
+}
+
+/// Classical Keplerian orbital elements, as computed by [CartesianState::keplerian_elements].
+///
+/// All angles are stored in radians, and the degree accessors are provided for convenience.
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(get_all))]
+#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeplerianElements {
+    /// Semi-major axis in kilometers, negative for hyperbolic orbits
+    pub sma_km: f64,
+    /// Eccentricity (no unit)
+    pub ecc: f64,
+    /// Inclination in radians
+    pub inc_rad: f64,
+    /// Right ascension of the ascending node in radians
+    pub raan_rad: f64,
+    /// Argument of periapsis in radians
+    pub aop_rad: f64,
+    /// True anomaly in radians
+    pub ta_rad: f64,
+}
+
+#[cfg_attr(feature = "python", pymethods)]
+impl KeplerianElements {
+    /// Returns the inclination in degrees
+    pub fn inc_deg(&self) -> f64 {
+        self.inc_rad.to_degrees()
+    }
+
+    /// Returns the right ascension of the ascending node in degrees
+    pub fn raan_deg(&self) -> f64 {
+        self.raan_rad.to_degrees()
+    }
+
+    /// Returns the argument of periapsis in degrees
+    pub fn aop_deg(&self) -> f64 {
+        self.aop_rad.to_degrees()
+    }
+
+    /// Returns the true anomaly in degrees
+    pub fn ta_deg(&self) -> f64 {
+        self.ta_rad.to_degrees()
+    }
+}
+
+impl fmt::Display for KeplerianElements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sma = {:.6} km\tecc = {:.6}\tinc = {:.6} deg\traan = {:.6} deg\taop = {:.6} deg\tta = {:.6} deg",
+            self.sma_km,
+            self.ecc,
+            self.inc_deg(),
+            self.raan_deg(),
+            self.aop_deg(),
+            self.ta_deg()
+        )
+    }
 }
 
 impl Add for CartesianState {
@@ -419,6 +602,23 @@ impl Add for CartesianState {
     }
 }
 
+/// Serializes epochs as Hifitime strings in TDB, which are parsed back to the nanosecond.
+mod epoch_tdb {
+    use core::str::FromStr;
+
+    use hifitime::{Epoch, TimeScale};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(epoch: &Epoch, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&epoch.to_time_scale(TimeScale::TDB))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Epoch, D::Error> {
+        let repr = String::deserialize(deserializer)?;
+        Epoch::from_str(&repr).map_err(D::Error::custom)
+    }
+}
+
 impl PartialEq for CartesianState {
     /// Two states are equal if their position are equal within one centimeter and their velocities within one centimeter per second.
     fn eq(&self, other: &Self) -> bool {
@@ -466,6 +666,13 @@ impl Neg for CartesianState {
     }
 }
 
+/// Drops the epoch and frame of the state, returning its position (km) and velocity (km/s).
+impl From<CartesianState> for (Vector3, Vector3) {
+    fn from(state: CartesianState) -> Self {
+        (state.radius_km, state.velocity_km_s)
+    }
+}
+
 #[allow(clippy::format_in_format_args)]
 impl fmt::Display for CartesianState {
     // Prints as Cartesian in floating point with units
@@ -600,6 +807,116 @@ mod cartesian_state_ut {
         assert_eq!(s.light_time(), Duration::ZERO);
     }
 
+    #[test]
+    fn keplerian_elements() {
+        // Validated against GMAT, cf. the orbit integration tests.
+        let gm = 398_600.441_5;
+        let e = Epoch::from_mjd_tai(21_545.0);
+        let frame = EARTH_J2000;
+
+        // Angles are compared modulo 360 degrees to avoid wrapping issues near zero.
+        let ang_eq = |got: f64, want: f64, msg: &str| {
+            let diff = (got - want + 180.0).rem_euclid(360.0) - 180.0;
+            assert!(diff.abs() < 1e-9, "{msg}: got {got} want {want}");
+        };
+
+        let cart = CartesianState::new(
+            -2436.45, -2436.45, 6891.037, 5.088_611, -5.088_611, 0.0, e, frame,
+        );
+        let kep = cart.keplerian_elements(gm).unwrap();
+        assert!((kep.sma_km - 7_712.186_117_895_043).abs() < 1e-8);
+        assert!((kep.ecc - 0.000_999_582_831_432_052_5).abs() < 1e-12);
+        ang_eq(kep.inc_deg(), 63.434_003_407_751_14, "inc");
+        ang_eq(kep.raan_deg(), 135.0, "raan");
+        ang_eq(kep.aop_deg(), 90.0, "aop");
+        ang_eq(kep.ta_deg(), 0.0, "ta");
+        assert!((kep.inc_rad - kep.inc_deg().to_radians()).abs() < f64::EPSILON);
+
+        let cart = CartesianState::new(
+            8_057.976_452_202_976,
+            -0.196_740_370_290_888_9,
+            1_475.383_214_274_138,
+            -0.166_470_488_584_076_31,
+            6.913_868_638_275_646_5,
+            0.910_157_981_443_279_1,
+            e,
+            frame,
+        );
+        let kep = cart.keplerian_elements(gm).unwrap();
+        assert!((kep.sma_km - 8_191.93).abs() < 1e-8);
+        assert!((kep.ecc - 1e-6).abs() < 1e-12);
+        ang_eq(kep.inc_deg(), 12.85, "inc");
+        ang_eq(kep.raan_deg(), 306.614, "raan");
+        ang_eq(kep.aop_deg(), 314.189_999_994_618_1, "aop");
+        ang_eq(kep.ta_deg(), 99.887_700_005_381_9, "ta");
+
+        // Circular and equatorial: the true anomaly is the true longitude.
+        let r = 7_000.0;
+        let vc = (gm / r).sqrt();
+        let theta = 30.0_f64.to_radians();
+        let cart = CartesianState::new(
+            r * theta.cos(),
+            r * theta.sin(),
+            0.0,
+            -vc * theta.sin(),
+            vc * theta.cos(),
+            0.0,
+            e,
+            frame,
+        );
+        let kep = cart.keplerian_elements(gm).unwrap();
+        assert!((kep.sma_km - r).abs() < 1e-8);
+        assert!(kep.ecc < 1e-12);
+        ang_eq(kep.inc_deg(), 0.0, "inc");
+        ang_eq(kep.raan_deg(), 0.0, "raan");
+        ang_eq(kep.aop_deg(), 0.0, "aop");
+        ang_eq(kep.ta_deg(), 30.0, "tlong");
+
+        // Circular and inclined: the true anomaly is the argument of latitude.
+        let cart = CartesianState::new(0.0, 0.0, r, -vc, 0.0, 0.0, e, frame);
+        let kep = cart.keplerian_elements(gm).unwrap();
+        ang_eq(kep.inc_deg(), 90.0, "inc");
+        ang_eq(kep.raan_deg(), 0.0, "raan");
+        ang_eq(kep.aop_deg(), 0.0, "aop");
+        ang_eq(kep.ta_deg(), 90.0, "arg lat");
+
+        // Eccentric and retrograde equatorial: the AOP is the longitude of periapsis.
+        let vp = (gm * 1.5 / r).sqrt();
+        let cart = CartesianState::new(0.0, r, 0.0, vp, 0.0, 0.0, e, frame);
+        let kep = cart.keplerian_elements(gm).unwrap();
+        assert!((kep.ecc - 0.5).abs() < 1e-12);
+        ang_eq(kep.inc_deg(), 180.0, "inc");
+        ang_eq(kep.raan_deg(), 0.0, "raan");
+        ang_eq(kep.aop_deg(), 270.0, "aop");
+        ang_eq(kep.ta_deg(), 0.0, "ta");
+
+        // Hyperbolic orbits have a negative SMA.
+        let cart = CartesianState::new(r, 0.0, 0.0, 0.0, 2.0 * vc, 0.0, e, frame);
+        let kep = cart.keplerian_elements(gm).unwrap();
+        assert!(kep.sma_km < 0.0);
+        assert!((kep.ecc - 3.0).abs() < 1e-12);
+
+        // Singular and invalid cases
+        assert!(matches!(
+            cart.keplerian_elements(0.0),
+            Err(PhysicsError::AppliedMath { .. })
+        ));
+        assert!(matches!(
+            CartesianState::zero(frame).keplerian_elements(gm),
+            Err(PhysicsError::RadiusError { .. })
+        ));
+        let rectilinear = CartesianState::new(r, 0.0, 0.0, 1.0, 0.0, 0.0, e, frame);
+        assert!(matches!(
+            rectilinear.keplerian_elements(gm),
+            Err(PhysicsError::VelocityError { .. })
+        ));
+        let parabolic = CartesianState::new(r, 0.0, 0.0, 0.0, 2.0_f64.sqrt() * vc, 0.0, e, frame);
+        assert!(matches!(
+            parabolic.keplerian_elements(gm),
+            Err(PhysicsError::ParabolicEccentricity { .. })
+        ));
+    }
+
     #[test]
     fn test_serde() {
         let e = Epoch::now().unwrap();
@@ -611,4 +928,39 @@ mod cartesian_state_ut {
 
         assert_eq!(rtn, state);
     }
+
+    #[test]
+    fn test_serde_json() {
+        let epoch = Epoch::from_gregorian_utc(2024, 2, 29, 12, 34, 56, 789_012_345);
+        let frame = EARTH_J2000.with_mu_km3_s2(398_600.435_436_096);
+        let state = CartesianState::try_keplerian(
+            7_123.456_789,
+            0.012_345_678_9,
+            51.6,
+            123.4,
+            56.7,
+            89.0,
+            epoch,
+            frame,
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains("\"epoch\":\"2024-02-29T12:36:"), "{json}");
+        assert!(json.contains(" TDB\""), "{json}");
+        let rtn: CartesianState = serde_json::from_str(&json).unwrap();
+
+        // Exact equality, instead of the tolerances of the PartialEq implementation
+        assert_eq!(
+            (rtn.epoch - state.epoch).total_nanoseconds(),
+            0,
+            "{} vs {}",
+            rtn.epoch,
+            state.epoch
+        );
+        assert_eq!(rtn.radius_km, state.radius_km);
+        assert_eq!(rtn.velocity_km_s, state.velocity_km_s);
+        assert_eq!(rtn.frame, state.frame);
+        assert_eq!(rtn.frame.mu_km3_s2, Some(398_600.435_436_096));
+    }
 }
@@ -0,0 +1,284 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::f64::consts::TAU;
+
+use super::Vector3;
+use crate::errors::MathError;
+
+/// Maximum number of iterations to solve the equinoctial Kepler equation.
+const MAX_KEPLER_ITER: usize = 100;
+
+/// Equinoctial elements of an elliptical orbit (cf. Broucke and Cefola, 1972), in the convention of SPICE's `eqncpv`.
+///
+/// These are non-singular for circular and equatorial orbits, but cannot represent an inclination of 180 degrees.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EquinoctialElements {
+    pub sma_km: f64,
+    /// Eccentricity times the sine of the longitude of periapsis
+    pub h: f64,
+    /// Eccentricity times the cosine of the longitude of periapsis
+    pub k: f64,
+    /// Mean anomaly plus the longitude of periapsis
+    pub mean_longitude_rad: f64,
+    /// Tangent of half the inclination times the sine of the longitude of the ascending node
+    pub p: f64,
+    /// Tangent of half the inclination times the cosine of the longitude of the ascending node
+    pub q: f64,
+}
+
+impl EquinoctialElements {
+    /// Returns the unit vectors of the equinoctial frame, which lie in the orbit plane.
+    pub fn basis(&self) -> (Vector3, Vector3) {
+        let (p, q) = (self.p, self.q);
+        let scale = 1.0 / (1.0 + p * p + q * q);
+        (
+            scale * Vector3::new(1.0 - p * p + q * q, 2.0 * p * q, -2.0 * p),
+            scale * Vector3::new(2.0 * p * q, 1.0 + p * p - q * q, 2.0 * q),
+        )
+    }
+
+    /// Returns the eccentricity.
+    pub fn ecc(&self) -> f64 {
+        self.h.hypot(self.k)
+    }
+
+    /// Returns the position and velocity of these elements about a central body of gravitational parameter `gm_km3_s2`.
+    ///
+    /// # Errors
+    /// 1. The orbit is not elliptical, or the semi major axis or the gravitational parameter is not strictly positive;
+    /// 2. The equinoctial Kepler equation does not converge.
+    pub fn to_pos_vel(&self, gm_km3_s2: f64) -> Result<(Vector3, Vector3), MathError> {
+        let (a_km, h, k) = (self.sma_km, self.h, self.k);
+        if !(gm_km3_s2.is_finite() && gm_km3_s2 > 0.0) {
+            return Err(MathError::DomainError {
+                value: gm_km3_s2,
+                msg: "equinoctial elements require a strictly positive gravitational parameter",
+            });
+        } else if !(a_km.is_finite() && a_km > 0.0) {
+            return Err(MathError::DomainError {
+                value: a_km,
+                msg: "equinoctial elements require a strictly positive semi major axis",
+            });
+        }
+        let ecc = self.ecc();
+        if ecc.is_nan() || ecc >= 1.0 {
+            return Err(MathError::DomainError {
+                value: ecc,
+                msg: "equinoctial elements require an elliptical orbit",
+            });
+        }
+
+        // Solve the Kepler equation for the eccentric longitude, with Newton-Raphson iterations.
+        let mean_longitude_rad = self.mean_longitude_rad.rem_euclid(TAU);
+        let mut ecc_long_rad = mean_longitude_rad;
+        let mut converged = false;
+        for _ in 0..MAX_KEPLER_ITER {
+            let (sin_f, cos_f) = ecc_long_rad.sin_cos();
+            let delta = (ecc_long_rad + h * cos_f - k * sin_f - mean_longitude_rad)
+                / (1.0 - h * sin_f - k * cos_f);
+            ecc_long_rad -= delta;
+            if delta.abs() < 1e-15 * TAU {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err(MathError::MaxIterationsReached {
+                iter: MAX_KEPLER_ITER,
+                action: "solving the equinoctial Kepler equation",
+            });
+        }
+
+        let (sin_f, cos_f) = ecc_long_rad.sin_cos();
+        let b = 1.0 / (1.0 + (1.0 - h * h - k * k).sqrt());
+        let mean_motion_rad_s = (gm_km3_s2 / a_km.powi(3)).sqrt();
+        let r_km = a_km * (1.0 - k * cos_f - h * sin_f);
+
+        // Position and velocity in the equinoctial frame
+        let x1_km = a_km * ((1.0 - h * h * b) * cos_f + h * k * b * sin_f - k);
+        let y1_km = a_km * ((1.0 - k * k * b) * sin_f + h * k * b * cos_f - h);
+        let scale = mean_motion_rad_s * a_km * a_km / r_km;
+        let vx1_km_s = scale * (h * k * b * cos_f - (1.0 - h * h * b) * sin_f);
+        let vy1_km_s = scale * ((1.0 - k * k * b) * cos_f - h * k * b * sin_f);
+
+        let (f_hat, g_hat) = self.basis();
+        Ok((
+            x1_km * f_hat + y1_km * g_hat,
+            vx1_km_s * f_hat + vy1_km_s * g_hat,
+        ))
+    }
+
+    /// Computes the equinoctial elements of the provided position and velocity about a central body of gravitational
+    /// parameter `gm_km3_s2`.
+    ///
+    /// # Errors
+    /// 1. The gravitational parameter is not strictly positive;
+    /// 2. The orbit is not elliptical, or is retrograde equatorial.
+    pub fn from_pos_vel(
+        pos_km: &Vector3,
+        vel_km_s: &Vector3,
+        gm_km3_s2: f64,
+    ) -> Result<Self, MathError> {
+        if !(gm_km3_s2.is_finite() && gm_km3_s2 > 0.0) {
+            return Err(MathError::DomainError {
+                value: gm_km3_s2,
+                msg: "equinoctial elements require a strictly positive gravitational parameter",
+            });
+        }
+
+        let r_km = pos_km.norm();
+        let h_vec = pos_km.cross(vel_km_s);
+        if r_km < f64::EPSILON || h_vec.norm() < f64::EPSILON {
+            return Err(MathError::DivisionByZero {
+                action: "computing the equinoctial elements of a rectilinear orbit",
+            });
+        }
+        let h_hat = h_vec.normalize();
+        if 1.0 + h_hat.z < f64::EPSILON {
+            return Err(MathError::DomainError {
+                value: h_hat.z,
+                msg: "equinoctial elements cannot represent retrograde equatorial orbits",
+            });
+        }
+
+        let sma_km = 1.0 / (2.0 / r_km - vel_km_s.norm_squared() / gm_km3_s2);
+        if sma_km.is_nan() || sma_km <= 0.0 {
+            return Err(MathError::DomainError {
+                value: sma_km,
+                msg: "equinoctial elements require an elliptical orbit",
+            });
+        }
+
+        let mut me = Self {
+            sma_km,
+            p: h_hat.x / (1.0 + h_hat.z),
+            q: -h_hat.y / (1.0 + h_hat.z),
+            ..Default::default()
+        };
+        let (f_hat, g_hat) = me.basis();
+
+        let ecc_vec = vel_km_s.cross(&h_vec) / gm_km3_s2 - pos_km / r_km;
+        let (h, k) = (ecc_vec.dot(&g_hat), ecc_vec.dot(&f_hat));
+        me.h = h;
+        me.k = k;
+
+        let (x1_km, y1_km) = (pos_km.dot(&f_hat), pos_km.dot(&g_hat));
+        let sqrt_one_me2 = (1.0 - h * h - k * k).sqrt();
+        let b = 1.0 / (1.0 + sqrt_one_me2);
+        let sin_f = h + ((1.0 - h * h * b) * y1_km - h * k * b * x1_km) / (sma_km * sqrt_one_me2);
+        let cos_f = k + ((1.0 - k * k * b) * x1_km - h * k * b * y1_km) / (sma_km * sqrt_one_me2);
+        let ecc_long_rad = sin_f.atan2(cos_f);
+        me.mean_longitude_rad =
+            (ecc_long_rad + h * ecc_long_rad.cos() - k * ecc_long_rad.sin()).rem_euclid(TAU);
+
+        Ok(me)
+    }
+}
+
+#[cfg(test)]
+mod equinoctial_ut {
+    use super::{EquinoctialElements, Vector3};
+    use crate::{errors::MathError, math::propagation::propagate_two_body};
+
+    const GM_EARTH: f64 = 398_600.435_436;
+
+    #[test]
+    fn equinoctial_round_trip() {
+        for (sma_km, ecc, inc_deg, raan_deg, aop_deg, ma_deg) in [
+            (7000.0, 0.0, 0.0, 0.0, 0.0, 10.0),
+            (7000.0, 0.01, 51.6, 120.0, 30.0, 200.0),
+            (26_560.0, 0.74, 63.4, 300.0, 270.0, 359.0),
+            (42_164.0, 1e-4, 0.05, 75.0, 10.0, 45.0),
+            (9000.0, 0.3, 170.0, 20.0, 200.0, 90.0),
+        ] {
+            let (raan, aop) = (f64::to_radians(raan_deg), f64::to_radians(aop_deg));
+            let tan_half_inc = (0.5 * f64::to_radians(inc_deg)).tan();
+            let elements = EquinoctialElements {
+                sma_km,
+                h: ecc * (aop + raan).sin(),
+                k: ecc * (aop + raan).cos(),
+                mean_longitude_rad: (f64::to_radians(ma_deg) + aop + raan)
+                    .rem_euclid(core::f64::consts::TAU),
+                p: tan_half_inc * raan.sin(),
+                q: tan_half_inc * raan.cos(),
+            };
+
+            let (pos, vel) = elements.to_pos_vel(GM_EARTH).unwrap();
+            // Consistency with the classical orbital elements
+            let h_hat = pos.cross(&vel).normalize();
+            assert!((h_hat.z.acos().to_degrees() - inc_deg).abs() < 1e-9);
+            let energy = 0.5 * vel.norm_squared() - GM_EARTH / pos.norm();
+            assert!((-GM_EARTH / (2.0 * energy) - sma_km).abs() < 1e-6);
+
+            let back = EquinoctialElements::from_pos_vel(&pos, &vel, GM_EARTH).unwrap();
+            assert!((back.sma_km - sma_km).abs() < 1e-6);
+            assert!((back.h - elements.h).abs() < 1e-12);
+            assert!((back.k - elements.k).abs() < 1e-12);
+            assert!((back.p - elements.p).abs() < 1e-10);
+            assert!((back.q - elements.q).abs() < 1e-10);
+            let dl = (back.mean_longitude_rad - elements.mean_longitude_rad).sin();
+            assert!(dl.abs() < 1e-12, "{back:?} != {elements:?}");
+        }
+    }
+
+    #[test]
+    fn equinoctial_motion() {
+        // Advancing the mean longitude by the mean motion is a two-body propagation
+        let elements = EquinoctialElements {
+            sma_km: 8000.0,
+            h: 0.05,
+            k: -0.1,
+            mean_longitude_rad: 1.0,
+            p: 0.2,
+            q: -0.3,
+        };
+        let (pos, vel) = elements.to_pos_vel(GM_EARTH).unwrap();
+        let dt_s = 1234.5;
+        let later = EquinoctialElements {
+            mean_longitude_rad: 1.0 + (GM_EARTH / 8000.0_f64.powi(3)).sqrt() * dt_s,
+            ..elements
+        };
+        let (exp_pos, exp_vel) = propagate_two_body(&pos, &vel, dt_s, GM_EARTH).unwrap();
+        let (pos, vel) = later.to_pos_vel(GM_EARTH).unwrap();
+        assert!((pos - exp_pos).norm() < 1e-7);
+        assert!((vel - exp_vel).norm() < 1e-10);
+    }
+
+    #[test]
+    fn equinoctial_errors() {
+        let elements = EquinoctialElements {
+            sma_km: 8000.0,
+            h: 0.6,
+            k: 0.8,
+            ..Default::default()
+        };
+        assert_eq!(
+            elements.to_pos_vel(GM_EARTH),
+            Err(MathError::DomainError {
+                value: 1.0,
+                msg: "equinoctial elements require an elliptical orbit",
+            })
+        );
+
+        let pos = Vector3::new(7000.0, 0.0, 0.0);
+        assert_eq!(
+            EquinoctialElements::from_pos_vel(&pos, &Vector3::new(0.0, -7.5, 0.0), GM_EARTH),
+            Err(MathError::DomainError {
+                value: -1.0,
+                msg: "equinoctial elements cannot represent retrograde equatorial orbits",
+            })
+        );
+        assert!(
+            EquinoctialElements::from_pos_vel(&pos, &Vector3::new(0.0, 12.0, 0.0), GM_EARTH)
+                .is_err()
+        );
+    }
+}
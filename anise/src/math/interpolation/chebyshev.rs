@@ -11,53 +11,124 @@
 use crate::errors::MathError;
 
 use hifitime::Epoch;
+use snafu::ensure;
 
-use super::InterpolationError;
+use super::{
+    CorruptedDataSnafu, InterpolationError, NonFiniteAbscissaSnafu, OutsideChebyshevDomainSnafu,
+};
 
-/// Attempts to evaluate a Chebyshev polynomial given the coefficients, returning the value and its derivative
+/// Tolerance on the normalized time beyond which an epoch is considered outside of the domain of a Chebyshev spline.
+/// This only accommodates the rounding of the normalization of the epochs at the bounds of the spline.
+const CHEBYSHEV_DOMAIN_TOLERANCE: f64 = 1e-9;
+
+/// Evaluates the Chebyshev series of the provided coefficients at the provided epoch, returning the value and its time derivative.
 ///
-/// # Notes
-/// 1. At this point, the splines are expected to be in Chebyshev format and no verification is done.
+/// The spline covers the epochs within `radius_s` of `midpoint_s` (both in seconds past J2000 TDB), and the series is evaluated with
+/// the Clenshaw recurrence. The derivative is computed by differentiating this recurrence, and is scaled by `1 / radius_s` to be with
+/// respect to time instead of the normalized time.
+///
+/// # Errors
+/// + There are no coefficients.
+/// + The radius is zero, or the epoch is not finite.
+/// + The epoch is outside of the spline, i.e. the normalized time is outside of [-1; 1] beyond a tolerance of 1e-9.
 pub fn chebyshev_eval(
-    normalized_time: f64,
     spline_coeffs: &[f64],
     spline_radius_s: f64,
-    eval_epoch: Epoch,
-    degree: usize,
+    spline_midpoint_s: f64,
+    epoch_et_s: f64,
 ) -> Result<(f64, f64), InterpolationError> {
-    if spline_radius_s.abs() < f64::EPSILON {
-        return Err(InterpolationError::InterpMath {
-            source: MathError::DivisionByZero {
-                action: "spline radius in Chebyshev eval is zero",
-            },
-        });
-    }
+    let normalized_time = chebyshev_normalized_time(
+        spline_coeffs,
+        spline_radius_s,
+        spline_midpoint_s,
+        epoch_et_s,
+    )?;
+
     // Workspace arrays
     let mut w = [0.0_f64; 3];
     let mut dw = [0.0_f64; 3];
 
-    for j in (2..=degree + 1).rev() {
+    for coeff in spline_coeffs.iter().skip(1).rev() {
         w[2] = w[1];
         w[1] = w[0];
-        w[0] = (spline_coeffs
-            .get(j - 1)
-            .ok_or(InterpolationError::MissingInterpolationData { epoch: eval_epoch })?)
-            + (2.0 * normalized_time * w[1] - w[2]);
+        w[0] = coeff + (2.0 * normalized_time * w[1] - w[2]);
 
         dw[2] = dw[1];
         dw[1] = dw[0];
         dw[0] = w[1] * 2. + dw[1] * 2.0 * normalized_time - dw[2];
     }
 
-    let val = (spline_coeffs
-        .first()
-        .ok_or(InterpolationError::MissingInterpolationData { epoch: eval_epoch })?)
-        + (normalized_time * w[0] - w[1]);
+    let val = spline_coeffs[0] + (normalized_time * w[0] - w[1]);
 
     let deriv = (w[0] + normalized_time * dw[0] - dw[1]) / spline_radius_s;
     Ok((val, deriv))
 }
 
+/// Evaluates the Chebyshev series of the provided coefficients at the provided epoch like `chebyshev_eval`, but only returns the value,
+/// skipping the recurrence of the derivative. The value is bit-for-bit identical to that of `chebyshev_eval`.
+///
+/// # Errors
+/// Those of `chebyshev_eval`.
+pub fn chebyshev_eval_value(
+    spline_coeffs: &[f64],
+    spline_radius_s: f64,
+    spline_midpoint_s: f64,
+    epoch_et_s: f64,
+) -> Result<f64, InterpolationError> {
+    let normalized_time = chebyshev_normalized_time(
+        spline_coeffs,
+        spline_radius_s,
+        spline_midpoint_s,
+        epoch_et_s,
+    )?;
+
+    let mut w = [0.0_f64; 3];
+    for coeff in spline_coeffs.iter().skip(1).rev() {
+        w[2] = w[1];
+        w[1] = w[0];
+        w[0] = coeff + (2.0 * normalized_time * w[1] - w[2]);
+    }
+
+    Ok(spline_coeffs[0] + (normalized_time * w[0] - w[1]))
+}
+
+/// Checks the inputs of `chebyshev_eval` and returns the normalized time of the epoch in the spline.
+fn chebyshev_normalized_time(
+    spline_coeffs: &[f64],
+    spline_radius_s: f64,
+    spline_midpoint_s: f64,
+    epoch_et_s: f64,
+) -> Result<f64, InterpolationError> {
+    ensure!(
+        !spline_coeffs.is_empty(),
+        CorruptedDataSnafu {
+            what: "Chebyshev series without any coefficient"
+        }
+    );
+    ensure!(
+        epoch_et_s.is_finite(),
+        NonFiniteAbscissaSnafu { x_eval: epoch_et_s }
+    );
+    if spline_radius_s.abs() < f64::EPSILON {
+        return Err(InterpolationError::InterpMath {
+            source: MathError::DivisionByZero {
+                action: "spline radius in Chebyshev eval is zero",
+            },
+        });
+    }
+
+    let normalized_time = (epoch_et_s - spline_midpoint_s) / spline_radius_s;
+    ensure!(
+        normalized_time.abs() <= 1.0 + CHEBYSHEV_DOMAIN_TOLERANCE,
+        OutsideChebyshevDomainSnafu {
+            epoch_et_s,
+            normalized_time
+        }
+    );
+
+    Ok(normalized_time)
+}
+
 /// Attempts to evaluate a Chebyshev polynomial given the coefficients, returning only the value
 ///
 /// # Notes
@@ -83,7 +154,118 @@ pub fn chebyshev_eval_poly(
     let val = (spline_coeffs
         .first()
         .ok_or(InterpolationError::MissingInterpolationData { epoch: eval_epoch })?)
-        + (normalized_time * w[0]);
+        + (normalized_time * w[0] - w[1]);
 
     Ok(val)
 }
+
+/// Attempts to evaluate the integral from zero (the midpoint of the spline) to the normalized time of a Chebyshev polynomial
+/// given its coefficients, returning the integral with respect to the normalized time.
+///
+/// # Notes
+/// 1. Integrating from the midpoint means that the integration constant is the value of the antiderivative at the midpoint.
+/// 2. Multiply the result by the spline radius to get the integral with respect to time.
+pub fn chebyshev_integral_eval(
+    normalized_time: f64,
+    spline_coeffs: &[f64],
+    eval_epoch: Epoch,
+    degree: usize,
+) -> Result<f64, InterpolationError> {
+    let coeff = |k: usize| -> Result<f64, InterpolationError> {
+        if k > degree {
+            Ok(0.0)
+        } else {
+            spline_coeffs
+                .get(k)
+                .copied()
+                .ok_or(InterpolationError::MissingInterpolationData { epoch: eval_epoch })
+        }
+    };
+
+    // Chebyshev polynomials at the normalized time and at zero, from the three term recurrence (stable within [-1; 1]).
+    let (mut t_prev, mut t_cur) = (1.0, normalized_time);
+    let (mut z_prev, mut z_cur) = (1.0, 0.0);
+    let mut integral = 0.0;
+
+    for k in 1..=degree + 1 {
+        // Coefficient of T_k in the antiderivative of the series
+        let antideriv_coeff = if k == 1 {
+            coeff(0)? - coeff(2)? / 2.0
+        } else {
+            (coeff(k - 1)? - coeff(k + 1)?) / (2.0 * k as f64)
+        };
+        integral += antideriv_coeff * (t_cur - z_cur);
+
+        (t_prev, t_cur) = (t_cur, 2.0 * normalized_time * t_cur - t_prev);
+        (z_prev, z_cur) = (z_cur, -z_prev);
+    }
+
+    Ok(integral)
+}
+
+#[test]
+fn chebyshev_eval_known_series() {
+    // f(x) = 3 - 2 T1(x) + 0.5 T2(x) + 0.25 T3(x) = 2.5 - 2.75 x + x^2 + x^3 over a spline of 600 s centered on 1e8 s
+    let coeffs = [3.0, -2.0, 0.5, 0.25];
+    let (radius_s, midpoint_s) = (300.0, 1e8);
+    for k in 0..=60 {
+        let epoch_et_s = midpoint_s - radius_s + 10.0 * k as f64;
+        let x = (epoch_et_s - midpoint_s) / radius_s;
+        let (val, deriv) = chebyshev_eval(&coeffs, radius_s, midpoint_s, epoch_et_s).unwrap();
+        assert!((val - (2.5 - 2.75 * x + x.powi(2) + x.powi(3))).abs() < 1e-13);
+        assert!((deriv - (-2.75 + 2.0 * x + 3.0 * x.powi(2)) / radius_s).abs() < 1e-15);
+    }
+
+    // T_n(cos(theta)) = cos(n theta), so its derivative with respect to x is n sin(n theta) / sin(theta)
+    let mut coeffs = [0.0; 11];
+    coeffs[10] = 1.0;
+    for k in 1..30 {
+        let theta = core::f64::consts::PI * k as f64 / 30.0;
+        let (val, deriv) = chebyshev_eval(&coeffs, 1.0, 0.0, theta.cos()).unwrap();
+        assert!((val - (10.0 * theta).cos()).abs() < 1e-13);
+        assert!((deriv - 10.0 * (10.0 * theta).sin() / theta.sin()).abs() < 1e-11);
+    }
+
+    // A constant series has a zero derivative
+    assert_eq!(chebyshev_eval(&[4.2], 2.0, 0.0, 1.0).unwrap(), (4.2, 0.0));
+
+    // The value only evaluation matches the full evaluation exactly
+    for k in 0..=60 {
+        let epoch_et_s = midpoint_s - radius_s + 10.0 * k as f64;
+        assert_eq!(
+            chebyshev_eval_value(&coeffs, radius_s, midpoint_s, epoch_et_s).unwrap(),
+            chebyshev_eval(&coeffs, radius_s, midpoint_s, epoch_et_s)
+                .unwrap()
+                .0
+        );
+    }
+    assert!(chebyshev_eval_value(&coeffs, radius_s, midpoint_s, 0.0).is_err());
+}
+
+#[test]
+fn chebyshev_eval_invalid() {
+    let coeffs = [1.0, 2.0, 3.0];
+    assert_eq!(
+        chebyshev_eval(&[], 1.0, 0.0, 0.0),
+        Err(InterpolationError::CorruptedData {
+            what: "Chebyshev series without any coefficient"
+        })
+    );
+    assert!(matches!(
+        chebyshev_eval(&coeffs, 0.0, 0.0, 0.0),
+        Err(InterpolationError::InterpMath { .. })
+    ));
+    assert!(matches!(
+        chebyshev_eval(&coeffs, 1.0, 0.0, f64::NAN),
+        Err(InterpolationError::NonFiniteAbscissa { .. })
+    ));
+    assert_eq!(
+        chebyshev_eval(&coeffs, 100.0, 0.0, -101.0),
+        Err(InterpolationError::OutsideChebyshevDomain {
+            epoch_et_s: -101.0,
+            normalized_time: -1.01
+        })
+    );
+    // Rounding at the bounds of the spline is tolerated
+    assert!(chebyshev_eval(&coeffs, 100.0, 0.0, 100.0 + 1e-8).is_ok());
+}
@@ -60,21 +60,13 @@
 
 use crate::errors::MathError;
 use log::error;
+use snafu::ensure;
 
-use super::{InterpolationError, MAX_SAMPLES};
+use super::{InterpolationError, NonFiniteAbscissaSnafu, MAX_SAMPLES};
 
-/// From the abscissas (xs), the ordinates (ys), and the first derivatives (ydots), build the Hermite interpolation of the function and evaluate it at the requested abscissa (x).
-///
-/// # Runtime verifications
-/// 1. Ensure that all provided arrays are of the same size.
-/// 2. Ensure that there are no more than 32 items to interpolate.
-/// 3. Ensure no division by zero errors (zero is set to core::f64::EPSILON, which is about 2e-16).
-pub fn hermite_eval(
-    xs: &[f64],
-    ys: &[f64],
-    ydots: &[f64],
-    x_eval: f64,
-) -> Result<(f64, f64), InterpolationError> {
+/// Ensures that the samples of a Hermite interpolation can be interpolated, i.e. that all of the arrays are of the same size with
+/// at least one and at most MAX_SAMPLES items, that all of the values are finite, and that the abscissas are strictly increasing.
+fn check_samples(xs: &[f64], ys: &[f64], ydots: &[f64]) -> Result<(), InterpolationError> {
     if xs.len() != ys.len() || xs.len() != ydots.len() {
         return Err(InterpolationError::CorruptedData {
             what: "lengths of abscissas (xs), ordinates (ys), and first derivatives (ydots) differ",
@@ -90,18 +82,114 @@ pub fn hermite_eval(
         });
     }
 
+    for (index, ((x, y), ydot)) in xs.iter().zip(ys).zip(ydots).enumerate() {
+        if !x.is_finite() {
+            return Err(InterpolationError::InvalidSample {
+                index,
+                reason: "abscissa is not finite",
+            });
+        } else if !y.is_finite() {
+            return Err(InterpolationError::InvalidSample {
+                index,
+                reason: "ordinate is not finite",
+            });
+        } else if !ydot.is_finite() {
+            return Err(InterpolationError::InvalidSample {
+                index,
+                reason: "first derivative is not finite",
+            });
+        } else if index > 0 && *x <= xs[index - 1] {
+            // Equal abscissas would divide by zero when building the interpolation table.
+            return Err(InterpolationError::InvalidSample {
+                index,
+                reason: "abscissa is not greater than the previous one",
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// From the abscissas (xs), the ordinates (ys), and the first derivatives (ydots), build the Hermite interpolation of the function and evaluate it at the requested abscissa (x).
+///
+/// Only the differences between the abscissas, and between the abscissas and the requested abscissa, are used. These differences are exact
+/// for nearby abscissas, so large abscissas (e.g. modern epochs in seconds past J2000) need not be shifted to the window first.
+/// To interpolate several functions sampled at the same abscissas, e.g. the three components of a position, use `hermite_eval_multi`.
+///
+/// # Runtime verifications
+/// 1. Ensure that all provided arrays are of the same size.
+/// 2. Ensure that there are no more than 32 items to interpolate.
+/// 3. Ensure that all of the values, including the requested abscissa, are finite and that the abscissas are strictly increasing.
+/// 4. Ensure no division by zero errors (zero is set to core::f64::EPSILON, which is about 2e-16).
+pub fn hermite_eval(
+    xs: &[f64],
+    ys: &[f64],
+    ydots: &[f64],
+    x_eval: f64,
+) -> Result<(f64, f64), InterpolationError> {
+    let [f_df] = hermite_eval_multi(xs, [ys], [ydots], x_eval)?;
+    Ok(f_df)
+}
+
+/// From the abscissas (xs), and the ordinates (ys) and first derivatives (ydots) of `N` functions, build the Hermite interpolation of each function
+/// and evaluate it at the requested abscissa (x), returning the interpolated value and first derivative of each function in the same order.
+///
+/// The quantities that only depend on the abscissas are computed once for all of the functions, so this is faster than calling `hermite_eval`
+/// for each function, and the results are bit-for-bit identical.
+///
+/// # Runtime verifications
+/// Those of `hermite_eval`, for each function.
+pub fn hermite_eval_multi<const N: usize>(
+    xs: &[f64],
+    ys: [&[f64]; N],
+    ydots: [&[f64]; N],
+    x_eval: f64,
+) -> Result<[(f64, f64); N], InterpolationError> {
+    hermite_table(xs, ys, ydots, x_eval, true)
+}
+
+/// Like `hermite_eval_multi`, but only returns the interpolated value of each function.
+///
+/// The interpolated values do not depend on the derivative columns of the interpolation table, so these are skipped, and the values
+/// are bit-for-bit identical to those of `hermite_eval_multi`.
+pub fn hermite_value_multi<const N: usize>(
+    xs: &[f64],
+    ys: [&[f64]; N],
+    ydots: [&[f64]; N],
+    x_eval: f64,
+) -> Result<[f64; N], InterpolationError> {
+    Ok(hermite_table(xs, ys, ydots, x_eval, false)?.map(|(f, _)| f))
+}
+
+/// Builds the triangular interpolation tables of `hermite_eval_multi`, only computing the derivative columns if `with_deriv` is set
+/// (the returned derivatives are meaningless otherwise).
+#[inline(always)]
+fn hermite_table<const N: usize>(
+    xs: &[f64],
+    ys: [&[f64]; N],
+    ydots: [&[f64]; N],
+    x_eval: f64,
+    with_deriv: bool,
+) -> Result<[(f64, f64); N], InterpolationError> {
+    for (ys, ydots) in ys.iter().zip(&ydots) {
+        check_samples(xs, ys, ydots)?;
+    }
+    ensure!(x_eval.is_finite(), NonFiniteAbscissaSnafu { x_eval });
+
     // At this point, we know that the lengths of items is correct, so we can directly address them without worry for overflowing the array.
 
-    let work: &mut [f64] = &mut [0.0; 8 * MAX_SAMPLES];
+    let mut works = [[0.0; 4 * MAX_SAMPLES]; N];
     let n: usize = xs.len();
 
     /*  Copy the input array into WORK.  After this, the first column */
     /*  of WORK represents the first column of our triangular */
     /*  interpolation table. */
 
-    for i in 0..n {
-        work[2 * i] = ys[i];
-        work[2 * i + 1] = ydots[i];
+    for (work, (ys, ydots)) in works.iter_mut().zip(ys.iter().zip(&ydots)) {
+        for i in 0..n {
+            work[2 * i] = ys[i];
+            work[2 * i + 1] = ydots[i];
+        }
     }
 
     /*  Compute the second column of the interpolation table: this */
@@ -127,36 +215,42 @@ pub fn hermite_eval(
             });
         }
 
-        /*  The second column of WORK contains interpolated derivative */
-        /*  values. */
-
-        /*  The odd-indexed interpolated derivatives are simply the input */
-        /*  derivatives. */
-
         let prev = 2 * i - 1;
         let curr = 2 * i;
-        work[prev + 2 * n - 1] = work[prev];
+        for work in works.iter_mut() {
+            /*  The second column of WORK contains interpolated derivative */
+            /*  values. */
+
+            /*  The odd-indexed interpolated derivatives are simply the input */
+            /*  derivatives. */
 
-        /*  The even-indexed interpolated derivatives are the slopes of */
-        /*  the linear interpolating polynomials for adjacent input */
-        /*  abscissa/ordinate pairs. */
+            if with_deriv {
+                work[prev + 2 * n - 1] = work[prev];
 
-        work[prev + 2 * n] = (work[curr] - work[prev - 1]) / denom;
+                /*  The even-indexed interpolated derivatives are the slopes of */
+                /*  the linear interpolating polynomials for adjacent input */
+                /*  abscissa/ordinate pairs. */
+
+                work[prev + 2 * n] = (work[curr] - work[prev - 1]) / denom;
+            }
 
-        /*  The first column of WORK contains interpolated function values. */
-        /*  The odd-indexed entries are the linear Taylor polynomials, */
-        /*  for each input abscissa value, evaluated at X. */
+            /*  The first column of WORK contains interpolated function values. */
+            /*  The odd-indexed entries are the linear Taylor polynomials, */
+            /*  for each input abscissa value, evaluated at X. */
 
-        let temp = work[prev] * (x_eval - xs[i - 1]) + work[prev - 1];
-        work[prev] = (c1 * work[prev - 1] + c2 * work[curr]) / denom;
-        work[prev - 1] = temp;
+            let temp = work[prev] * c2 + work[prev - 1];
+            work[prev] = (c1 * work[prev - 1] + c2 * work[curr]) / denom;
+            work[prev - 1] = temp;
+        }
     }
 
     /*  The last column entries were not computed by the preceding loop; */
     /*  compute them now. */
 
-    work[4 * n - 2] = work[(2 * n) - 1];
-    work[2 * (n - 1)] += work[(2 * n) - 1] * (x_eval - xs[n - 1]);
+    for work in works.iter_mut() {
+        work[4 * n - 2] = work[(2 * n) - 1];
+        work[2 * (n - 1)] += work[(2 * n) - 1] * (x_eval - xs[n - 1]);
+    }
 
     /*  Compute columns 3 through 2*N of the table. */
 
@@ -185,33 +279,174 @@ pub fn hermite_eval(
                 });
             }
 
-            /*  Compute the interpolated derivative at X for the Ith */
-            /*  interpolant. This is the derivative with respect to X of */
-            /*  the expression for the interpolated function value, which */
-            /*  is the second expression below. This derivative computation */
-            /*  is done first because it relies on the interpolated */
-            /*  function values from the previous column of the */
-            /*  interpolation table. */
+            for work in works.iter_mut() {
+                /*  Compute the interpolated derivative at X for the Ith */
+                /*  interpolant. This is the derivative with respect to X of */
+                /*  the expression for the interpolated function value, which */
+                /*  is the second expression below. This derivative computation */
+                /*  is done first because it relies on the interpolated */
+                /*  function values from the previous column of the */
+                /*  interpolation table. */
 
-            /*  The derivative expression here corresponds to equation */
-            /*  2.35 on page 64 in reference [2]. */
+                /*  The derivative expression here corresponds to equation */
+                /*  2.35 on page 64 in reference [2]. */
 
-            work[i + 2 * n - 1] =
-                (c1 * work[i + 2 * n - 1] + c2 * work[i + 2 * n] + (work[i] - work[i - 1])) / denom;
+                if with_deriv {
+                    work[i + 2 * n - 1] =
+                        (c1 * work[i + 2 * n - 1] + c2 * work[i + 2 * n] + (work[i] - work[i - 1]))
+                            / denom;
+                }
 
-            /*  Compute the interpolated function value at X for the Ith */
-            /*  interpolant. */
+                /*  Compute the interpolated function value at X for the Ith */
+                /*  interpolant. */
 
-            work[i - 1] = (c1 * work[i - 1] + c2 * work[i]) / denom;
+                work[i - 1] = (c1 * work[i - 1] + c2 * work[i]) / denom;
+            }
         }
     }
 
     /*  Our interpolated function value is sitting in WORK(1,1) at this */
     /*  point. The interpolated derivative is located in WORK(1,2). */
 
-    let f = work[0];
-    let df = work[2 * n];
-    Ok((f, df))
+    Ok(works.map(|work| (work[0], work[2 * n])))
+}
+
+/// From the abscissas (xs), the ordinates (ys), and the first derivatives (ydots), build the Hermite interpolation of the function and evaluate
+/// it at the requested abscissa (x), returning the interpolated value, its first derivative, and its second derivative.
+///
+/// This uses the same interpolation polynomial as `hermite_eval`, but is computed with Neville's algorithm on the table where each abscissa is repeated twice,
+/// which allows carrying the second derivative alongside the first derivative.
+///
+/// # Runtime verifications
+/// 1. Ensure that all provided arrays are of the same size.
+/// 2. Ensure that there are no more than 32 items to interpolate.
+/// 3. Ensure that all of the values, including the requested abscissa, are finite and that the abscissas are strictly increasing.
+/// 4. Ensure no division by zero errors (zero is set to core::f64::EPSILON, which is about 2e-16).
+pub fn hermite_eval_deriv2(
+    xs: &[f64],
+    ys: &[f64],
+    ydots: &[f64],
+    x_eval: f64,
+) -> Result<(f64, f64, f64), InterpolationError> {
+    check_samples(xs, ys, ydots)?;
+    ensure!(x_eval.is_finite(), NonFiniteAbscissaSnafu { x_eval });
+
+    let n = xs.len();
+    // Each abscissa appears twice in the interpolation table, the k-th entry of the table corresponds to xs[k / 2].
+    let mut vals = [0.0; 2 * MAX_SAMPLES];
+    let mut derivs = [0.0; 2 * MAX_SAMPLES];
+    let mut derivs2 = [0.0; 2 * MAX_SAMPLES];
+
+    // First column of the table: linear interpolants over pairs of consecutive abscissas in the table.
+    for k in 0..2 * n - 1 {
+        let (i, ip1) = (k / 2, k.div_ceil(2));
+        if i == ip1 {
+            // Repeated abscissa, so this is the linear Taylor polynomial.
+            vals[k] = ys[i] + ydots[i] * (x_eval - xs[i]);
+            derivs[k] = ydots[i];
+        } else {
+            let denom = xs[ip1] - xs[i];
+            if denom.abs() < f64::EPSILON {
+                return Err(InterpolationError::InterpMath {
+                    source: MathError::DivisionByZero {
+                        action:
+                            "hermite data contains likely duplicate abcissa, remove duplicate states",
+                    },
+                });
+            }
+            vals[k] = ((xs[ip1] - x_eval) * ys[i] + (x_eval - xs[i]) * ys[ip1]) / denom;
+            derivs[k] = (ys[ip1] - ys[i]) / denom;
+        }
+    }
+
+    // Subsequent columns, each spanning one more abscissa of the table than the previous one.
+    for j in 2..2 * n {
+        for k in 0..2 * n - j {
+            let (xi, xij) = (xs[k / 2], xs[(k + j) / 2]);
+            let c1 = xij - x_eval;
+            let c2 = x_eval - xi;
+            let denom = xij - xi;
+            if denom.abs() < f64::EPSILON {
+                return Err(InterpolationError::InterpMath {
+                    source: MathError::DivisionByZero {
+                        action: "hermite data contains duplicate states",
+                    },
+                });
+            }
+
+            // Derivatives first, since they rely on the values of the previous column.
+            derivs2[k] =
+                (c1 * derivs2[k] + c2 * derivs2[k + 1] + 2.0 * (derivs[k + 1] - derivs[k])) / denom;
+            derivs[k] = (c1 * derivs[k] + c2 * derivs[k + 1] + (vals[k + 1] - vals[k])) / denom;
+            vals[k] = (c1 * vals[k] + c2 * vals[k + 1]) / denom;
+        }
+    }
+
+    Ok((vals[0], derivs[0], derivs2[0]))
+}
+
+/// From the abscissas (xs), the ordinates (ys), and the first derivatives (ydots), compute the coefficients of the Newton form
+/// of the Hermite interpolation of the function, which `hermite_newton_eval` evaluates at any abscissa.
+///
+/// This is the same interpolation polynomial as `hermite_eval`, but these coefficients do not depend on the requested abscissa,
+/// so computing them once and evaluating them at many abscissas is much faster than calling `hermite_eval` for each abscissa.
+/// Only the first `2 * xs.len()` coefficients are used, and the results only agree with `hermite_eval` to within rounding errors.
+///
+/// # Runtime verifications
+/// 1. Ensure that all provided arrays are of the same size.
+/// 2. Ensure that there are no more than 32 items to interpolate.
+/// 3. Ensure that all of the values are finite and that the abscissas are strictly increasing.
+/// 4. Ensure no division by zero errors (zero is set to core::f64::EPSILON, which is about 2e-16).
+pub fn hermite_newton_coefficients(
+    xs: &[f64],
+    ys: &[f64],
+    ydots: &[f64],
+) -> Result<[f64; 2 * MAX_SAMPLES], InterpolationError> {
+    check_samples(xs, ys, ydots)?;
+
+    // Each abscissa appears twice in the divided differences table, the k-th entry of the table corresponds to xs[k / 2].
+    let m = 2 * xs.len();
+    let mut coeffs = [0.0; 2 * MAX_SAMPLES];
+    for k in 0..m {
+        coeffs[k] = ys[k / 2];
+    }
+
+    // Compute the table in place, from the bottom up so that each entry still holds the previous column when it's needed.
+    // The first column uses the derivatives for repeated abscissas.
+    for j in 1..m {
+        for k in (j..m).rev() {
+            if j == 1 && k % 2 == 1 {
+                coeffs[k] = ydots[k / 2];
+                continue;
+            }
+            let denom = xs[k / 2] - xs[(k - j) / 2];
+            if denom.abs() < f64::EPSILON {
+                return Err(InterpolationError::InterpMath {
+                    source: MathError::DivisionByZero {
+                        action: "hermite data contains duplicate states",
+                    },
+                });
+            }
+            coeffs[k] = (coeffs[k] - coeffs[k - 1]) / denom;
+        }
+    }
+
+    Ok(coeffs)
+}
+
+/// Evaluates the Newton form of the Hermite interpolation built by `hermite_newton_coefficients` from the same abscissas (xs)
+/// at the requested abscissa (x), returning the interpolated value and its first derivative.
+pub fn hermite_newton_eval(xs: &[f64], coeffs: &[f64; 2 * MAX_SAMPLES], x_eval: f64) -> (f64, f64) {
+    // Horner's scheme on the nested form, carrying the derivative alongside the value.
+    let m = 2 * xs.len();
+    let mut f = coeffs[m - 1];
+    let mut df = 0.0;
+    for k in (0..m - 1).rev() {
+        let dx = x_eval - xs[k / 2];
+        df = df * dx + f;
+        f = f * dx + coeffs[k];
+    }
+    (f, df)
 }
 
 #[test]
@@ -235,4 +470,289 @@ fn hermite_spice_docs_example() {
 
     assert!((x - 141.0).abs() < f64::EPSILON, "X error");
     assert!((vx - 456.0).abs() < f64::EPSILON, "VX error");
+
+    // The value only interpolation matches exactly
+    for t in [-0.5, 1.0, 2.0, 4.2] {
+        let [x] = hermite_value_multi(&ts, [&yvals], [&ydotvals], t).unwrap();
+        assert_eq!(x, hermite_eval(&ts, &yvals, &ydotvals, t).unwrap().0);
+    }
+}
+
+#[test]
+fn hermite_deriv2_spice_docs_example() {
+    let ts = [-1.0, 0.0, 3.0, 5.0];
+    let yvals = [6.0, 5.0, 2210.0, 78180.0];
+    let ydotvals = [3.0, 0.0, 5115.0, 109395.0];
+
+    // The value and first derivative must match the SPICE algorithm.
+    for t in [-1.0, -0.5, 0.0, 1.1, 2.0, 3.0, 4.7, 5.0] {
+        let (x, vx) = hermite_eval(&ts, &yvals, &ydotvals, t).unwrap();
+        let (x2, vx2, _) = hermite_eval_deriv2(&ts, &yvals, &ydotvals, t).unwrap();
+        assert!((x - x2).abs() < 1e-9, "f(x) differs at {t}");
+        assert!((vx - vx2).abs() < 1e-9, "f'(x) differs at {t}");
+    }
+
+    // Check the second derivative against a central finite difference of the first derivative.
+    let h = 1e-5;
+    for t in [-0.5, 1.1, 2.0, 4.7] {
+        let (_, _, ddx) = hermite_eval_deriv2(&ts, &yvals, &ydotvals, t).unwrap();
+        let (_, vx_p) = hermite_eval(&ts, &yvals, &ydotvals, t + h).unwrap();
+        let (_, vx_m) = hermite_eval(&ts, &yvals, &ydotvals, t - h).unwrap();
+        let ddx_fd = (vx_p - vx_m) / (2.0 * h);
+        assert!(
+            (ddx - ddx_fd).abs() < 1e-5 * ddx.abs().max(1.0),
+            "f''(x) error at {t}: {ddx} vs {ddx_fd}"
+        );
+    }
+}
+
+#[test]
+fn hermite_deriv2_polynomials() {
+    // With N samples, the Hermite interpolation is exact for polynomials up to degree 2N - 1, including their derivatives.
+    let coeffs = [0.3, -1.2, 0.7, 2.0, -0.4, 0.05, 0.6, -0.25];
+    let poly = |t: f64, degree: usize| {
+        let (mut f, mut df, mut ddf) = (0.0, 0.0, 0.0);
+        for (k, c) in coeffs.iter().enumerate().take(degree + 1) {
+            let k_f64 = k as f64;
+            f += c * t.powi(k as i32);
+            if k > 0 {
+                df += c * k_f64 * t.powi(k as i32 - 1);
+            }
+            if k > 1 {
+                ddf += c * k_f64 * (k_f64 - 1.0) * t.powi(k as i32 - 2);
+            }
+        }
+        (f, df, ddf)
+    };
+
+    for (n, ts) in [
+        (1, vec![0.4]),
+        (2, vec![-0.5, 1.0]),
+        (3, vec![-1.0, 0.2, 1.5]),
+        (4, vec![-1.0, -0.1, 0.8, 2.0]),
+    ] {
+        let degree: usize = 2 * n - 1;
+        let ys = ts.iter().map(|t| poly(*t, degree).0).collect::<Vec<_>>();
+        let ydots = ts.iter().map(|t| poly(*t, degree).1).collect::<Vec<_>>();
+
+        for t in [-1.2, -0.7, 0.0, 0.33, 1.1, 2.2] {
+            let (f, df, ddf) = hermite_eval_deriv2(&ts, &ys, &ydots, t).unwrap();
+            let (exp_f, exp_df, exp_ddf) = poly(t, degree);
+            assert!((f - exp_f).abs() < 1e-12, "f error with {n} samples at {t}");
+            assert!(
+                (df - exp_df).abs() < 1e-11,
+                "f' error with {n} samples at {t}"
+            );
+            assert!(
+                (ddf - exp_ddf).abs() < 1e-10,
+                "f'' error with {n} samples at {t}: {ddf} vs {exp_ddf}"
+            );
+        }
+    }
+
+    // A single sample is only exact for lines, so its second derivative must be zero.
+    let (_, _, ddf) = hermite_eval_deriv2(&[1.0], &[2.0], &[3.0], 5.0).unwrap();
+    assert_eq!(ddf, 0.0);
+
+    // The maximum number of samples is supported, but no more.
+    let ts = (0..MAX_SAMPLES)
+        .map(|i| -1.0 + 2.0 * i as f64 / (MAX_SAMPLES - 1) as f64)
+        .collect::<Vec<_>>();
+    let ys = ts.iter().map(|t| poly(*t, 3).0).collect::<Vec<_>>();
+    let ydots = ts.iter().map(|t| poly(*t, 3).1).collect::<Vec<_>>();
+    let (f, df, ddf) = hermite_eval_deriv2(&ts, &ys, &ydots, 0.123).unwrap();
+    let (exp_f, exp_df, exp_ddf) = poly(0.123, 3);
+    assert!((f - exp_f).abs() < 1e-9);
+    assert!((df - exp_df).abs() < 1e-7);
+    assert!((ddf - exp_ddf).abs() < 1e-5);
+
+    let mut ts = ts;
+    ts.push(1.5);
+    let ys = vec![0.0; MAX_SAMPLES + 1];
+    assert_eq!(
+        hermite_eval_deriv2(&ts, &ys, &ys, 0.0),
+        Err(InterpolationError::CorruptedData {
+            what: "list of abscissas (xs) contains more items than MAX_SAMPLES (32)",
+        })
+    );
+}
+
+#[test]
+fn hermite_newton_spice_docs_example() {
+    let ts = [-1.0, 0.0, 3.0, 5.0];
+    let yvals = [6.0, 5.0, 2210.0, 78180.0];
+    let ydotvals = [3.0, 0.0, 5115.0, 109395.0];
+
+    let coeffs = hermite_newton_coefficients(&ts, &yvals, &ydotvals).unwrap();
+    for t in [-1.0, -0.5, 0.0, 1.1, 2.0, 3.0, 4.7, 5.0] {
+        let (x, vx) = hermite_eval(&ts, &yvals, &ydotvals, t).unwrap();
+        let (x2, vx2) = hermite_newton_eval(&ts, &coeffs, t);
+        assert!((x - x2).abs() < 1e-9, "f(x) differs at {t}");
+        assert!((vx - vx2).abs() < 1e-9, "f'(x) differs at {t}");
+    }
+
+    let (x, vx) = hermite_newton_eval(&ts, &coeffs, 2.0);
+    assert!((x - 141.0).abs() < 1e-12, "X error");
+    assert!((vx - 456.0).abs() < 1e-12, "VX error");
+
+    let (x, vx) = hermite_newton_eval(
+        &[0.4],
+        &hermite_newton_coefficients(&[0.4], &[2.0], &[3.0]).unwrap(),
+        1.4,
+    );
+    assert_eq!((x, vx), (5.0, 3.0));
+
+    assert!(hermite_newton_coefficients(&[1.0, 1.0], &[2.0, 2.0], &[0.0, 0.0]).is_err());
+    assert!(hermite_newton_coefficients(&[1.0, 2.0], &[2.0], &[0.0, 0.0]).is_err());
+    assert!(hermite_newton_coefficients(&[], &[], &[]).is_err());
+}
+
+#[test]
+fn hermite_invalid_samples() {
+    let ts = [-1.0, 0.0, 3.0, 5.0];
+    let yvals = [6.0, 5.0, 2210.0, 78180.0];
+    let ydotvals = [3.0, 0.0, 5115.0, 109395.0];
+
+    // Duplicate and decreasing abscissas
+    for (xs, index) in [([-1.0, 0.0, 0.0, 5.0], 2), ([-1.0, 0.0, 3.0, 2.0], 3)] {
+        let expected = Err(InterpolationError::InvalidSample {
+            index,
+            reason: "abscissa is not greater than the previous one",
+        });
+        assert_eq!(hermite_eval(&xs, &yvals, &ydotvals, 1.0), expected);
+        assert!(hermite_eval_deriv2(&xs, &yvals, &ydotvals, 1.0).is_err());
+        assert!(hermite_newton_coefficients(&xs, &yvals, &ydotvals).is_err());
+    }
+
+    // Non finite samples
+    let mut ys = yvals;
+    ys[1] = f64::NAN;
+    assert_eq!(
+        hermite_eval(&ts, &ys, &ydotvals, 1.0),
+        Err(InterpolationError::InvalidSample {
+            index: 1,
+            reason: "ordinate is not finite",
+        })
+    );
+    let mut ydots = ydotvals;
+    ydots[3] = f64::INFINITY;
+    assert_eq!(
+        hermite_eval(&ts, &yvals, &ydots, 1.0),
+        Err(InterpolationError::InvalidSample {
+            index: 3,
+            reason: "first derivative is not finite",
+        })
+    );
+    let mut xs = ts;
+    xs[0] = f64::NEG_INFINITY;
+    assert_eq!(
+        hermite_eval(&xs, &yvals, &ydotvals, 1.0),
+        Err(InterpolationError::InvalidSample {
+            index: 0,
+            reason: "abscissa is not finite",
+        })
+    );
+
+    // Non finite request
+    assert!(matches!(
+        hermite_eval(&ts, &yvals, &ydotvals, f64::NAN),
+        Err(InterpolationError::NonFiniteAbscissa { .. })
+    ));
+    assert!(matches!(
+        hermite_eval_deriv2(&ts, &yvals, &ydotvals, f64::NAN),
+        Err(InterpolationError::NonFiniteAbscissa { .. })
+    ));
+}
+
+#[test]
+fn hermite_large_abscissas() {
+    // Modern epochs are around 7e8 seconds past J2000, with states a minute apart: the interpolation only uses differences
+    // of abscissas, which are exact for such nearby values, so no precision is lost compared to abscissas near zero.
+    let t0 = 7.5e8 + 0.123;
+    let poly = |t: f64| {
+        let dt = (t - t0) / 60.0;
+        (
+            7000.0 + dt * (1.5 + dt * (-0.2 + dt * (0.01 + dt * 1e-3))),
+            (1.5 + dt * (-0.4 + dt * (0.03 + dt * 4e-3))) / 60.0,
+        )
+    };
+    let ts: Vec<f64> = (0..6).map(|n| t0 + 60.0 * n as f64).collect();
+    let ys: Vec<f64> = ts.iter().map(|t| poly(*t).0).collect();
+    let ydots: Vec<f64> = ts.iter().map(|t| poly(*t).1).collect();
+
+    for k in 0..=300 {
+        let t = t0 + 0.997 * k as f64;
+        let (exp_f, exp_df) = poly(t);
+        let (f, df) = hermite_eval(&ts, &ys, &ydots, t).unwrap();
+        let (f2, df2, _) = hermite_eval_deriv2(&ts, &ys, &ydots, t).unwrap();
+        for (f, df) in [(f, df), (f2, df2)] {
+            assert!((f - exp_f).abs() < 1e-11, "f error at {t}: {:e}", f - exp_f);
+            assert!(
+                (df - exp_df).abs() < 1e-11,
+                "f' error at {t}: {:e}",
+                df - exp_df
+            );
+        }
+    }
+}
+
+#[test]
+fn hermite_multi_bit_identical() {
+    // Pseudo-random samples from a linear congruential generator, at abscissas of modern epochs.
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut uniform = || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    for n in 1..=MAX_SAMPLES {
+        let mut xs = [0.0; MAX_SAMPLES];
+        xs[0] = 7.5e8 * uniform();
+        for i in 1..n {
+            xs[i] = xs[i - 1] + 1.0 + 100.0 * uniform();
+        }
+        let (xs, mut ys, mut ydots) = (&xs[..n], [[0.0; MAX_SAMPLES]; 3], [[0.0; MAX_SAMPLES]; 3]);
+        for c in 0..3 {
+            for i in 0..n {
+                ys[c][i] = 1e4 * (uniform() - 0.5);
+                ydots[c][i] = 10.0 * (uniform() - 0.5);
+            }
+        }
+        let x_eval = xs[0] + (xs[n - 1] - xs[0]) * uniform();
+
+        let fused = hermite_eval_multi(
+            xs,
+            [0, 1, 2].map(|c| &ys[c][..n]),
+            [0, 1, 2].map(|c| &ydots[c][..n]),
+            x_eval,
+        )
+        .unwrap();
+        for c in 0..3 {
+            let (f, df) = hermite_eval(xs, &ys[c][..n], &ydots[c][..n], x_eval).unwrap();
+            assert_eq!(fused[c].0.to_bits(), f.to_bits(), "{n} samples");
+            assert_eq!(fused[c].1.to_bits(), df.to_bits(), "{n} samples");
+        }
+    }
+
+    // The samples of every function are checked
+    let ts = [-1.0, 0.0, 3.0, 5.0];
+    let yvals = [6.0, 5.0, 2210.0, 78180.0];
+    let ydotvals = [3.0, 0.0, 5115.0, 109395.0];
+    let mut ydots = ydotvals;
+    ydots[2] = f64::NAN;
+    assert_eq!(
+        hermite_eval_multi(&ts, [&yvals, &yvals], [&ydotvals, &ydots], 1.0),
+        Err(InterpolationError::InvalidSample {
+            index: 2,
+            reason: "first derivative is not finite",
+        })
+    );
+    assert_eq!(
+        hermite_eval_multi(&ts, [&yvals, &yvals[..3]], [&ydotvals, &ydotvals], 1.0),
+        Err(InterpolationError::CorruptedData {
+            what: "lengths of abscissas (xs), ordinates (ys), and first derivatives (ydots) differ",
+        })
+    );
 }
@@ -9,9 +9,19 @@
  */
 
 use crate::errors::MathError;
-
-use super::{InterpolationError, MAX_SAMPLES};
-
+use snafu::ensure;
+
+use super::{InterpolationError, NonFiniteAbscissaSnafu, MAX_SAMPLES};
+
+/// From the abscissas (xs) and the ordinates (ys), build the Lagrange interpolation of the function and evaluate it and its derivative
+/// at the requested abscissa (x_eval), like SPICE's `lgrind`.
+///
+/// The abscissas need not be evenly spaced nor sorted, but they must be distinct.
+///
+/// # Runtime verifications
+/// 1. Ensure that both arrays are of the same size, with at least one and at most MAX_SAMPLES items.
+/// 2. Ensure that all of the samples and the requested abscissa are finite.
+/// 3. Ensure that no two abscissas are equal, which would divide by zero.
 pub fn lagrange_eval(
     xs: &[f64],
     ys: &[f64],
@@ -19,14 +29,33 @@ pub fn lagrange_eval(
 ) -> Result<(f64, f64), InterpolationError> {
     if xs.len() != ys.len() {
         return Err(InterpolationError::CorruptedData {
-            what: "lengths of abscissas (xs), ordinates (ys), and first derivatives (ydots) differ",
+            what: "lengths of abscissas (xs) and ordinates (ys) differ",
         });
     } else if xs.is_empty() {
         return Err(InterpolationError::CorruptedData {
             what: "list of abscissas (xs) is empty",
         });
+    } else if xs.len() > MAX_SAMPLES {
+        return Err(InterpolationError::CorruptedData {
+            what: "list of abscissas (xs) contains more items than MAX_SAMPLES (32)",
+        });
     }
 
+    for (index, (x, y)) in xs.iter().zip(ys).enumerate() {
+        if !x.is_finite() {
+            return Err(InterpolationError::InvalidSample {
+                index,
+                reason: "abscissa is not finite",
+            });
+        } else if !y.is_finite() {
+            return Err(InterpolationError::InvalidSample {
+                index,
+                reason: "ordinate is not finite",
+            });
+        }
+    }
+    ensure!(x_eval.is_finite(), NonFiniteAbscissaSnafu { x_eval });
+
     // At this point, we know that the lengths of items is correct, so we can directly address them without worry for overflowing the array.
 
     let work: &mut [f64] = &mut [0.0; MAX_SAMPLES];
@@ -100,3 +129,93 @@ fn lagrange_spice_docs_example() {
     assert!((x - expected_x).abs() < f64::EPSILON, "X error");
     assert!((dx - expected_dx).abs() < f64::EPSILON, "dX error");
 }
+
+#[test]
+fn lagrange_polynomials() {
+    // Pseudo-random coefficients and unevenly spaced abscissas, reproducible across runs.
+    let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut uniform = || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    for degree in 0..12 {
+        let coeffs: Vec<f64> = (0..=degree).map(|_| 2.0 * uniform() - 1.0).collect();
+        let poly = |x: f64| {
+            coeffs
+                .iter()
+                .rev()
+                .fold((0.0, 0.0), |(f, df), c| (f * x + c, df * x + f))
+        };
+
+        // Interpolating with exactly degree + 1 samples, in any order, reproduces the polynomial.
+        let mut xs: Vec<f64> = (0..=degree).map(|n| n as f64 + 0.8 * uniform()).collect();
+        xs.reverse();
+        let ys: Vec<f64> = xs.iter().map(|x| poly(*x).0).collect();
+
+        for _ in 0..20 {
+            let x = (degree as f64 + 1.0) * uniform();
+            let (f, df) = lagrange_eval(&xs, &ys, x).unwrap();
+            let (exp_f, exp_df) = poly(x);
+            let scale = poly(degree as f64 + 1.0).0.abs().max(1.0);
+            assert!(
+                (f - exp_f).abs() < 1e-12 * scale,
+                "degree {degree}: f({x}) error is {:e}",
+                f - exp_f
+            );
+            assert!(
+                (df - exp_df).abs() < 1e-11 * scale,
+                "degree {degree}: f'({x}) error is {:e}",
+                df - exp_df
+            );
+        }
+    }
+}
+
+#[test]
+fn lagrange_invalid_samples() {
+    let xs = [0.0, 1.0, 2.0];
+    let ys = [1.0, 2.0, 4.0];
+
+    assert_eq!(
+        lagrange_eval(&xs, &ys[..2], 0.5),
+        Err(InterpolationError::CorruptedData {
+            what: "lengths of abscissas (xs) and ordinates (ys) differ"
+        })
+    );
+    assert!(matches!(
+        lagrange_eval(&[], &[], 0.5),
+        Err(InterpolationError::CorruptedData { .. })
+    ));
+    let many = [0.0; MAX_SAMPLES + 1];
+    assert_eq!(
+        lagrange_eval(&many, &many, 0.5),
+        Err(InterpolationError::CorruptedData {
+            what: "list of abscissas (xs) contains more items than MAX_SAMPLES (32)"
+        })
+    );
+    assert_eq!(
+        lagrange_eval(&[0.0, f64::NAN, 2.0], &ys, 0.5),
+        Err(InterpolationError::InvalidSample {
+            index: 1,
+            reason: "abscissa is not finite"
+        })
+    );
+    assert_eq!(
+        lagrange_eval(&xs, &[1.0, 2.0, f64::INFINITY], 0.5),
+        Err(InterpolationError::InvalidSample {
+            index: 2,
+            reason: "ordinate is not finite"
+        })
+    );
+    assert!(matches!(
+        lagrange_eval(&xs, &ys, f64::NAN),
+        Err(InterpolationError::NonFiniteAbscissa { .. })
+    ));
+    assert!(matches!(
+        lagrange_eval(&[0.0, 1.0, 0.0], &ys, 0.5),
+        Err(InterpolationError::InterpMath { .. })
+    ));
+}
@@ -12,21 +12,31 @@ mod chebyshev;
 mod hermite;
 mod lagrange;
 
-pub use chebyshev::{chebyshev_eval, chebyshev_eval_poly};
-pub use hermite::hermite_eval;
+pub use chebyshev::{
+    chebyshev_eval, chebyshev_eval_poly, chebyshev_eval_value, chebyshev_integral_eval,
+};
+use core::fmt;
+pub use hermite::{
+    hermite_eval, hermite_eval_deriv2, hermite_eval_multi, hermite_newton_coefficients,
+    hermite_newton_eval, hermite_value_multi,
+};
 use hifitime::Epoch;
 pub use lagrange::lagrange_eval;
 use snafu::Snafu;
 
-use crate::errors::{DecodingError, MathError};
+#[cfg(feature = "std")]
+use crate::errors::DecodingError;
+use crate::errors::MathError;
 
 /// Defines the maximum degree for an interpolation.
+/// This covers the largest windows allowed by SPICE, i.e. 28 states for the Lagrange types (degree 27) and 14 states for the Hermite types (degree 27).
 /// Until https://github.com/rust-lang/rust/issues/60551 , we cannot do operations on const generic, so we need some hack around it.
 pub(crate) const MAX_SAMPLES: usize = 32;
 
 #[derive(Copy, Clone, Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 pub enum InterpolationError {
+    #[cfg(feature = "std")]
     #[snafu(display("decoding error during interpolation: {source}"))]
     InterpDecoding {
         #[snafu(backtrace)]
@@ -37,16 +47,29 @@ pub enum InterpolationError {
         #[snafu(backtrace)]
         source: MathError,
     },
-    #[snafu(display("spline valid from {start} to {end} but requested {req}"))]
+    #[snafu(display("{}", CoverageMessage { req: *req, start: *start, end: *end, id: *id, center_id: *center_id }))]
     NoInterpolationData {
         req: Epoch,
         start: Epoch,
         end: Epoch,
+        /// ID of the data, e.g. the target of an SPK segment or the frame of a BPC segment
+        id: i32,
+        /// ID relative to which the data is expressed, e.g. the center of an SPK segment or the inertial frame of a BPC segment
+        center_id: i32,
     },
     #[snafu(display("no interpolation data to {epoch}, but prior checks succeeded (check integrity of the data?)"))]
     MissingInterpolationData { epoch: Epoch },
     #[snafu(display("interpolation data corrupted: {what}"))]
     CorruptedData { what: &'static str },
+    #[snafu(display("interpolation sample {index} is invalid: {reason}"))]
+    InvalidSample { index: usize, reason: &'static str },
+    #[snafu(display("cannot interpolate at {x_eval}, which is not finite"))]
+    NonFiniteAbscissa { x_eval: f64 },
+    #[snafu(display("epoch {epoch_et_s} s past J2000 TDB is outside of the Chebyshev spline (normalized time of {normalized_time})"))]
+    OutsideChebyshevDomain {
+        epoch_et_s: f64,
+        normalized_time: f64,
+    },
     #[snafu(display("{op} is unsupported for {kind}"))]
     UnsupportedOperation {
         kind: &'static str,
@@ -57,3 +80,73 @@ pub enum InterpolationError {
     ))]
     UnimplementedType { issue: u32, dataset: &'static str },
 }
+
+/// Describes how far the requested epoch is from the coverage of the data of `id` relative to `center_id`.
+struct CoverageMessage {
+    req: Epoch,
+    start: Epoch,
+    end: Epoch,
+    id: i32,
+    center_id: i32,
+}
+
+impl fmt::Display for CoverageMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "epoch {} is ", self.req)?;
+        if self.req < self.start {
+            write!(f, "{} before the start of coverage", self.start - self.req)?;
+        } else if self.req > self.end {
+            write!(f, "{} past the end of coverage", self.req - self.end)?;
+        } else {
+            // E.g. the data is empty
+            write!(f, "not covered by the data")?;
+        }
+        write!(
+            f,
+            " for {} relative to {} (from {} to {})",
+            self.id, self.center_id, self.start, self.end
+        )
+    }
+}
+
+#[cfg(test)]
+mod interpolation_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use super::InterpolationError;
+
+    #[test]
+    fn no_interpolation_data_display() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2023, 1, 1);
+        let end = Epoch::from_gregorian_utc_at_midnight(2023, 4, 27);
+        let error = |req| InterpolationError::NoInterpolationData {
+            req,
+            start,
+            end,
+            id: 301,
+            center_id: 3,
+        };
+
+        assert_eq!(
+            error(end + 3.days() + 2.hours()).to_string(),
+            format!(
+                "epoch {} is 3 days 2 h past the end of coverage for 301 relative to 3 (from {start} to {end})",
+                end + 3.days() + 2.hours()
+            )
+        );
+        assert_eq!(
+            error(start - 90.seconds()).to_string(),
+            format!(
+                "epoch {} is 1 min 30 s before the start of coverage for 301 relative to 3 (from {start} to {end})",
+                start - 90.seconds()
+            )
+        );
+        assert_eq!(
+            error(start + 1.days()).to_string(),
+            format!(
+                "epoch {} is not covered by the data for 301 relative to 3 (from {start} to {end})",
+                start + 1.days()
+            )
+        );
+    }
+}
@@ -15,16 +15,29 @@ pub type Vector6 = nalgebra::Vector6<f64>;
 pub type Matrix3 = nalgebra::Matrix3<f64>;
 pub type Matrix6 = nalgebra::Matrix6<f64>;
 
+#[cfg(feature = "std")]
 pub mod angles;
+#[cfg(feature = "std")]
 pub mod cartesian;
 #[cfg(feature = "python")]
 mod cartesian_py;
+#[cfg(feature = "std")]
+pub mod equinoctial;
 pub mod interpolation;
+#[cfg(feature = "std")]
+pub mod propagation;
+#[cfg(feature = "std")]
 pub mod rotation;
+#[cfg(feature = "std")]
+pub mod sgp4;
+#[cfg(feature = "std")]
 pub mod units;
 
 use nalgebra::allocator::Allocator;
 use nalgebra::{DefaultAllocator, DimName, OVector};
+// Without the standard library, the floating point functions (e.g. `sqrt`) are those of libm through nalgebra.
+#[cfg(not(feature = "std"))]
+use nalgebra::ComplexField;
 
 /// Returns the root sum squared (RSS) between two vectors of any dimension N.
 pub fn root_sum_squared<N: DimName>(vec_a: &OVector<f64, N>, vec_b: &OVector<f64, N>) -> f64
@@ -0,0 +1,415 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Duration;
+
+use super::{cartesian::CartesianState, Vector3};
+use crate::errors::MathError;
+
+/// Maximum number of iterations to solve the universal Kepler equation.
+const MAX_KEPLER_ITER: usize = 100;
+/// Maximum number of times the search interval of the universal anomaly may be doubled.
+const MAX_BRACKET_ITER: usize = 2048;
+
+/// Returns the Stumpff functions C(z) and S(z).
+///
+/// Close to zero, i.e. for near parabolic orbits, the series expansions are used to avoid catastrophic cancellations.
+pub(crate) fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1.0 {
+        let sqrt_z = z.sqrt();
+        (
+            (1.0 - sqrt_z.cos()) / z,
+            (sqrt_z - sqrt_z.sin()) / (sqrt_z * z),
+        )
+    } else if z < -1.0 {
+        let sqrt_z = (-z).sqrt();
+        (
+            (sqrt_z.cosh() - 1.0) / -z,
+            (sqrt_z.sinh() - sqrt_z) / (sqrt_z * -z),
+        )
+    } else {
+        let (mut c, mut s) = (0.0, 0.0);
+        let (mut term_c, mut term_s) = (0.5, 1.0 / 6.0);
+        for k in 0..12 {
+            c += term_c;
+            s += term_s;
+            let k = k as f64;
+            term_c *= -z / ((2.0 * k + 3.0) * (2.0 * k + 4.0));
+            term_s *= -z / ((2.0 * k + 4.0) * (2.0 * k + 5.0));
+        }
+        (c, s)
+    }
+}
+
+/// Propagates the provided position and velocity by `dt_s` seconds along the two-body orbit about a central body of
+/// gravitational parameter `gm_km3_s2`, returning the new position and velocity.
+///
+/// This solves the universal Kepler equation (in lieu of SPICE's `prop2b`), so elliptical, parabolic, and hyperbolic orbits are all supported.
+/// The universal anomaly is found with Newton-Raphson iterations, which fall back to bisection when they leave the bracketing interval of the solution.
+///
+/// # Errors
+/// 1. The gravitational parameter is not strictly positive, or the time step or the state are not finite;
+/// 2. The position is at the center of the central body;
+/// 3. The universal Kepler equation does not converge.
+pub fn propagate_two_body(
+    pos_km: &Vector3,
+    vel_km_s: &Vector3,
+    dt_s: f64,
+    gm_km3_s2: f64,
+) -> Result<(Vector3, Vector3), MathError> {
+    if !(gm_km3_s2.is_finite() && gm_km3_s2 > 0.0) {
+        return Err(MathError::DomainError {
+            value: gm_km3_s2,
+            msg: "two-body propagation requires a strictly positive gravitational parameter",
+        });
+    } else if !dt_s.is_finite() {
+        return Err(MathError::DomainError {
+            value: dt_s,
+            msg: "two-body propagation requires a finite time step",
+        });
+    } else if !(pos_km.iter().chain(vel_km_s.iter()).all(|x| x.is_finite())) {
+        return Err(MathError::DomainError {
+            value: pos_km.norm() + vel_km_s.norm(),
+            msg: "two-body propagation requires a finite position and velocity",
+        });
+    }
+
+    let r0_km = pos_km.norm();
+    if r0_km < f64::EPSILON {
+        return Err(MathError::DivisionByZero {
+            action: "propagating a state located at the center of the central body",
+        });
+    } else if dt_s == 0.0 {
+        return Ok((*pos_km, *vel_km_s));
+    }
+
+    let sqrt_mu = gm_km3_s2.sqrt();
+    let rv0 = pos_km.dot(vel_km_s) / sqrt_mu;
+    // Reciprocal of the semi major axis: positive for elliptical orbits, zero for parabolic orbits, negative for hyperbolic orbits.
+    let alpha = 2.0 / r0_km - vel_km_s.norm_squared() / gm_km3_s2;
+    let target = sqrt_mu * dt_s;
+
+    // Universal Kepler equation (whose root is the universal anomaly) and its derivative, which is the orbital radius.
+    // Overflows only happen for very large anomalies, where the sign of the equation is the sign of the anomaly.
+    let kepler = |chi: f64| {
+        let chi2 = chi * chi;
+        let (c, s) = stumpff(alpha * chi2);
+        let f = rv0 * chi2 * c + (1.0 - alpha * r0_km) * chi2 * chi * s + r0_km * chi - target;
+        let r = rv0 * chi * (1.0 - alpha * chi2 * s) + (1.0 - alpha * r0_km) * chi2 * c + r0_km;
+        if f.is_nan() {
+            (chi.signum() * f64::INFINITY, r)
+        } else {
+            (f, r)
+        }
+    };
+
+    // Initial guess of the universal anomaly (Vallado, Algorithm 8), the hyperbolic one may be undefined.
+    let hyperbolic_guess = if alpha < 0.0 {
+        let sma_km = 1.0 / alpha;
+        let arg = (-2.0 * gm_km3_s2 * alpha * dt_s)
+            / (rv0 * sqrt_mu
+                + dt_s.signum() * (-gm_km3_s2 * sma_km).sqrt() * (1.0 - r0_km * alpha));
+        dt_s.signum() * (-sma_km).sqrt() * arg.ln()
+    } else {
+        f64::NAN
+    };
+    let guess = if alpha > 0.0 {
+        target * alpha
+    } else if hyperbolic_guess.is_normal() && hyperbolic_guess * dt_s > 0.0 {
+        hyperbolic_guess
+    } else {
+        target / r0_km
+    };
+    if !guess.is_finite() {
+        return Err(MathError::DomainError {
+            value: guess,
+            msg: "two-body propagation overflowed when guessing the universal anomaly",
+        });
+    }
+
+    // The equation is monotonic in the universal anomaly, and zero anomaly corresponds to zero time.
+    let (mut lo, mut hi) = (0.0_f64, 0.0_f64);
+    let mut bound = guess;
+    let mut bracketed = false;
+    for _ in 0..MAX_BRACKET_ITER {
+        let (f, _) = kepler(bound);
+        if (dt_s > 0.0) == (f > 0.0) {
+            if dt_s > 0.0 {
+                hi = bound;
+            } else {
+                lo = bound;
+            }
+            bracketed = true;
+            break;
+        }
+        bound *= 2.0;
+    }
+    if !bracketed {
+        return Err(MathError::MaxIterationsReached {
+            iter: MAX_BRACKET_ITER,
+            action: "bracketing the universal anomaly in two-body propagation",
+        });
+    }
+
+    let mut chi = guess.clamp(lo, hi);
+    let mut prev_step = hi - lo;
+    let mut converged = false;
+    for _ in 0..MAX_KEPLER_ITER {
+        let (f, r) = kepler(chi);
+        if f == 0.0 {
+            converged = true;
+            break;
+        } else if f < 0.0 {
+            lo = chi;
+        } else {
+            hi = chi;
+        }
+
+        // Bisect if the Newton step leaves the interval or does not converge fast enough (e.g. far on the exponential branch of hyperbolas).
+        let mut next = chi - f / r;
+        if !(next > lo && next < hi) || (next - chi).abs() > 0.5 * prev_step.abs() {
+            next = 0.5 * (lo + hi);
+        }
+        prev_step = next - chi;
+
+        let tol = 1e-12 * chi.abs().max(1.0);
+        if (next - chi).abs() <= tol || hi - lo <= tol {
+            chi = next;
+            converged = true;
+            break;
+        }
+        chi = next;
+    }
+    if !converged {
+        return Err(MathError::MaxIterationsReached {
+            iter: MAX_KEPLER_ITER,
+            action: "solving the universal Kepler equation in two-body propagation",
+        });
+    }
+
+    // Lagrange coefficients
+    let chi2 = chi * chi;
+    let (c, s) = stumpff(alpha * chi2);
+    let f = 1.0 - chi2 / r0_km * c;
+    let g = dt_s - chi2 * chi / sqrt_mu * s;
+    let pos_f_km = f * pos_km + g * vel_km_s;
+    let r_km = pos_f_km.norm();
+    let f_dot = sqrt_mu / (r_km * r0_km) * (alpha * chi2 * chi * s - chi);
+    let g_dot = 1.0 - chi2 / r_km * c;
+
+    Ok((pos_f_km, f_dot * pos_km + g_dot * vel_km_s))
+}
+
+/// Propagates the state by `dt` along its two-body orbit about a central body of gravitational parameter `gm_km3_s2`.
+///
+/// The returned state is in the same frame as the input state, and its epoch is shifted by `dt`.
+/// Refer to [propagate_two_body] for the formulation and the errors.
+pub fn kepler_universal(
+    state: &CartesianState,
+    dt: Duration,
+    gm_km3_s2: f64,
+) -> Result<CartesianState, MathError> {
+    let (radius_km, velocity_km_s) = propagate_two_body(
+        &state.radius_km,
+        &state.velocity_km_s,
+        dt.to_seconds(),
+        gm_km3_s2,
+    )?;
+
+    Ok(CartesianState {
+        radius_km,
+        velocity_km_s,
+        epoch: state.epoch + dt,
+        frame: state.frame,
+    })
+}
+
+#[cfg(test)]
+mod propagation_ut {
+    use super::{kepler_universal, propagate_two_body, stumpff, Vector3};
+    use crate::errors::MathError;
+    use core::f64::consts::TAU;
+
+    const GM_EARTH: f64 = 398_600.435_436;
+
+    /// Returns the specific energy and the angular momentum of the state.
+    fn invariants(pos: &Vector3, vel: &Vector3) -> (f64, Vector3) {
+        (
+            vel.norm_squared() / 2.0 - GM_EARTH / pos.norm(),
+            pos.cross(vel),
+        )
+    }
+
+    #[test]
+    fn stumpff_continuity() {
+        // The series and closed forms must agree where they switch.
+        for z in [1.0_f64, -1.0] {
+            let (c_series, s_series) = stumpff(z);
+            let (c_closed, s_closed) = stumpff(z * (1.0 + 1e-15));
+            assert!((c_series - c_closed).abs() < 1e-14, "C({z})");
+            assert!((s_series - s_closed).abs() < 1e-14, "S({z})");
+        }
+        assert_eq!(stumpff(0.0), (0.5, 1.0 / 6.0));
+    }
+
+    #[test]
+    fn two_body_circular() {
+        let r_km = 7000.0;
+        let v_km_s = (GM_EARTH / r_km).sqrt();
+        let mean_motion = v_km_s / r_km;
+        let pos = Vector3::new(r_km, 0.0, 0.0);
+        let vel = Vector3::new(0.0, v_km_s, 0.0);
+
+        for dt_s in [-4000.0, -1.0, 1e-3, 100.0, 1234.5, 86_400.0] {
+            let (pos_f, vel_f) = propagate_two_body(&pos, &vel, dt_s, GM_EARTH).unwrap();
+            let angle = mean_motion * dt_s;
+            let exp_pos = r_km * Vector3::new(angle.cos(), angle.sin(), 0.0);
+            let exp_vel = v_km_s * Vector3::new(-angle.sin(), angle.cos(), 0.0);
+            assert!((pos_f - exp_pos).norm() < 1e-7, "pos error at {dt_s}");
+            assert!((vel_f - exp_vel).norm() < 1e-10, "vel error at {dt_s}");
+        }
+
+        assert_eq!(
+            propagate_two_body(&pos, &vel, 0.0, GM_EARTH).unwrap(),
+            (pos, vel)
+        );
+    }
+
+    #[test]
+    fn two_body_elliptical_period() {
+        // Highly eccentric orbit, at periapsis with e = 0.7
+        let rp_km = 7000.0;
+        let ecc = 0.7;
+        let sma_km = rp_km / (1.0 - ecc);
+        let pos = Vector3::new(rp_km, 0.0, 0.0);
+        let vel = Vector3::new(0.0, 0.0, (GM_EARTH * (1.0 + ecc) / rp_km).sqrt());
+        let period_s = TAU * (sma_km.powi(3) / GM_EARTH).sqrt();
+
+        for revs in [1.0, 3.0, -2.0] {
+            let (pos_f, vel_f) = propagate_two_body(&pos, &vel, revs * period_s, GM_EARTH).unwrap();
+            assert!((pos_f - pos).norm() < 1e-6, "{revs} revolutions");
+            assert!((vel_f - vel).norm() < 1e-9, "{revs} revolutions");
+        }
+
+        // Half a period reaches apoapsis
+        let (pos_f, _) = propagate_two_body(&pos, &vel, period_s / 2.0, GM_EARTH).unwrap();
+        assert!((pos_f.norm() - sma_km * (1.0 + ecc)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn two_body_hyperbolic_and_parabolic() {
+        let r_km = 8000.0;
+        let v_esc_km_s = (2.0 * GM_EARTH / r_km).sqrt();
+        let pos = Vector3::new(r_km, 1000.0, -500.0);
+
+        // Hyperbolic flyby, exactly parabolic, and near parabolic states
+        for v_km_s in [2.0 * v_esc_km_s, v_esc_km_s, v_esc_km_s * (1.0 + 1e-12)] {
+            let vel = Vector3::new(-0.3, v_km_s * 0.9, v_km_s * 0.2);
+            let (energy, momentum) = invariants(&pos, &vel);
+
+            for dt_s in [-20_000.0, -60.0, 90.0, 7200.0, 10.0 * 86_400.0] {
+                let (pos_f, vel_f) = propagate_two_body(&pos, &vel, dt_s, GM_EARTH).unwrap();
+                let (energy_f, momentum_f) = invariants(&pos_f, &vel_f);
+                assert!(
+                    (energy_f - energy).abs() < 1e-9 * energy.abs().max(1.0),
+                    "energy not conserved for {v_km_s} km/s after {dt_s} s"
+                );
+                assert!(
+                    (momentum_f - momentum).norm() < 1e-9 * momentum.norm(),
+                    "angular momentum not conserved for {v_km_s} km/s after {dt_s} s"
+                );
+
+                // Propagating back returns to the initial state
+                let (pos_b, vel_b) = propagate_two_body(&pos_f, &vel_f, -dt_s, GM_EARTH).unwrap();
+                assert!(
+                    (pos_b - pos).norm() < 1e-6 * pos_f.norm().max(r_km) / r_km,
+                    "{v_km_s} {dt_s} {}",
+                    (pos_b - pos).norm()
+                );
+                assert!((vel_b - vel).norm() < 1e-9 * vel.norm());
+            }
+        }
+    }
+
+    #[test]
+    fn kepler_universal_states() {
+        use crate::{constants::frames::EARTH_J2000, math::cartesian::CartesianState};
+        use hifitime::{Epoch, TimeUnits};
+
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 2, 29);
+
+        // A full elliptical period returns to the initial state
+        let state = CartesianState::new(
+            -2436.45,
+            -2436.45,
+            6891.037,
+            5.088611,
+            -5.088611,
+            0.0,
+            epoch,
+            EARTH_J2000,
+        );
+        let sma_km = 1.0 / (2.0 / state.rmag_km() - state.vmag_km_s().powi(2) / GM_EARTH);
+        let period = (TAU * (sma_km.powi(3) / GM_EARTH).sqrt()).seconds();
+        let state_f = kepler_universal(&state, period, GM_EARTH).unwrap();
+        assert_eq!(state_f.epoch, epoch + period);
+        assert_eq!(state_f.frame, state.frame);
+        assert!((state_f.radius_km - state.radius_km).norm() < 1e-6);
+        assert!((state_f.velocity_km_s - state.velocity_km_s).norm() < 1e-9);
+
+        // Hyperbolic flyby from periapsis: the inbound and outbound legs are mirror images
+        let rp_km = 6878.0;
+        let ecc = 1.5;
+        let state = CartesianState::new(
+            rp_km,
+            0.0,
+            0.0,
+            0.0,
+            (GM_EARTH * (1.0 + ecc) / rp_km).sqrt(),
+            0.0,
+            epoch,
+            EARTH_J2000,
+        );
+        for dt in [10.minutes(), 2.hours(), 3.days()] {
+            let outbound = kepler_universal(&state, dt, GM_EARTH).unwrap();
+            let inbound = kepler_universal(&state, -dt, GM_EARTH).unwrap();
+            assert_eq!(inbound.epoch, epoch - dt);
+            assert!(outbound.rmag_km() > rp_km);
+            assert!((outbound.radius_km.x - inbound.radius_km.x).abs() < 1e-6 * outbound.rmag_km());
+            assert!((outbound.radius_km.y + inbound.radius_km.y).abs() < 1e-6 * outbound.rmag_km());
+            assert!((outbound.velocity_km_s.x + inbound.velocity_km_s.x).abs() < 1e-9);
+            assert!((outbound.velocity_km_s.y - inbound.velocity_km_s.y).abs() < 1e-9);
+
+            // The specific energy of the hyperbola is conserved
+            let (energy, _) = invariants(&outbound.radius_km, &outbound.velocity_km_s);
+            assert!((energy - GM_EARTH * (ecc - 1.0) / (2.0 * rp_km)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn two_body_errors() {
+        let pos = Vector3::new(7000.0, 0.0, 0.0);
+        let vel = Vector3::new(0.0, 7.5, 0.0);
+        assert_eq!(
+            propagate_two_body(&pos, &vel, 10.0, 0.0),
+            Err(MathError::DomainError {
+                value: 0.0,
+                msg: "two-body propagation requires a strictly positive gravitational parameter",
+            })
+        );
+        assert_eq!(
+            propagate_two_body(&Vector3::zeros(), &vel, 10.0, GM_EARTH),
+            Err(MathError::DivisionByZero {
+                action: "propagating a state located at the center of the central body",
+            })
+        );
+        assert!(propagate_two_body(&pos, &vel, f64::NAN, GM_EARTH).is_err());
+    }
+}
@@ -103,6 +103,35 @@ impl DCM {
         full_dcm
     }
 
+    /// Rotates the provided 6x6 covariance of a state from the `from` frame into the `to` frame, consistently with the rotation of the state.
+    ///
+    /// The covariance is rotated with the state DCM (cf. `state_dcm`), so the velocity covariance accounts for the transport theorem if the time
+    /// derivative of this DCM is set. For example, the transpose of `Orbit::dcm_from_ric_to_inertial` rotates an inertial covariance into RIC.
+    pub fn rotate_covariance(&self, covar: &Matrix6) -> Matrix6 {
+        let state_dcm = self.state_dcm();
+        state_dcm * covar * state_dcm.transpose()
+    }
+
+    /// Returns the angular velocity vector, in radians per second, of the `to` frame with respect to the `from` frame,
+    /// expressed in the `from` frame (like SPICE's `xf2rav`).
+    ///
+    /// # Errors
+    /// + The time derivative of this DCM is not set.
+    pub fn angular_velocity_rad_s(&self) -> Result<Vector3, PhysicsError> {
+        let rot_mat_dt = self.rot_mat_dt.ok_or(PhysicsError::DCMMissingDerivative {
+            action: "computing the angular velocity",
+        })?;
+
+        // The time derivative of the DCM is -[w]x * R, where [w]x is the skew symmetric matrix of the angular velocity.
+        let omega_tilde = -self.rot_mat.transpose() * rot_mat_dt;
+
+        Ok(Vector3::new(
+            omega_tilde[(2, 1)],
+            omega_tilde[(0, 2)],
+            omega_tilde[(1, 0)],
+        ))
+    }
+
     /// Builds an identity rotation
     pub fn identity(from: i32, to: i32) -> Self {
         let rot_mat = Matrix3::identity();
@@ -408,7 +437,7 @@ impl fmt::Display for DCM {
 mod ut_dcm {
     use crate::math::Matrix3;
 
-    use super::{Vector3, DCM};
+    use super::{Vector3, Vector6, DCM};
     use core::f64::consts::FRAC_PI_2;
 
     #[test]
@@ -471,4 +500,67 @@ mod ut_dcm {
                 < f64::EPSILON
         );
     }
+
+    #[test]
+    fn test_angular_velocity() {
+        use crate::{errors::PhysicsError, math::rotation::r3_dot};
+
+        // Frame spinning about the Z axis at the rotation rate of the Earth
+        let rate_rad_s = 7.292115e-5;
+        let angle_rad = 1.234;
+        let mut dcm = DCM::r3(angle_rad, 0, 1);
+        assert_eq!(
+            dcm.angular_velocity_rad_s(),
+            Err(PhysicsError::DCMMissingDerivative {
+                action: "computing the angular velocity",
+            })
+        );
+
+        dcm.rot_mat_dt = Some(rate_rad_s * r3_dot(angle_rad));
+        let omega = dcm.angular_velocity_rad_s().unwrap();
+        assert!((omega - Vector3::new(0.0, 0.0, rate_rad_s)).norm() < 1e-18);
+
+        // The reverse rotation spins the other way around, and is expressed in the other frame.
+        let tilted = DCM {
+            rot_mat: DCM::r1(0.4, 1, 2).rot_mat * dcm.rot_mat,
+            rot_mat_dt: Some(DCM::r1(0.4, 1, 2).rot_mat * dcm.rot_mat_dt.unwrap()),
+            from: 0,
+            to: 2,
+        };
+        let omega_back = tilted.transpose().angular_velocity_rad_s().unwrap();
+        assert!((omega_back + tilted.rot_mat * omega).norm() < 1e-18);
+    }
+
+    #[test]
+    fn test_rotate_covariance() {
+        use crate::{constants::frames::EARTH_J2000, math::Matrix6, prelude::Orbit};
+        use hifitime::Epoch;
+
+        let mut frame = EARTH_J2000;
+        frame.mu_km3_s2 = Some(398_600.435_436);
+        let state = Orbit::new(
+            5946.673548,
+            1656.154606,
+            2259.012130,
+            -3.098683,
+            4.579534,
+            6.246542,
+            Epoch::from_gregorian_tai_at_midnight(2020, 1, 1),
+            frame,
+        );
+        let ric_to_inertial = state.dcm_from_ric_to_inertial().unwrap();
+        assert!(ric_to_inertial.rot_mat_dt.is_some());
+
+        let covar = Matrix6::from_diagonal(&Vector6::new(1e-2, 2e-2, 3e-2, 1e-6, 2e-6, 3e-6));
+        let covar_ric = ric_to_inertial.transpose().rotate_covariance(&covar);
+        // The covariance is rotated, and remains symmetric
+        assert!((covar_ric - covar).norm() > 1e-3);
+        assert!((covar_ric - covar_ric.transpose()).norm() < 1e-15);
+        // The trace of the position covariance is preserved by the rotation
+        let pos_trace: f64 = (0..3).map(|i| covar_ric[(i, i)]).sum();
+        assert!((pos_trace - 6e-2).abs() < 1e-15);
+
+        let covar_back = ric_to_inertial.rotate_covariance(&covar_ric);
+        assert!((covar_back - covar).norm() < 1e-12 * covar.norm());
+    }
 }
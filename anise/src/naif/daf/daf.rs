@@ -10,13 +10,14 @@
 
 use super::file_record::FileRecordError;
 use super::{
-    DAFError, DecodingNameSnafu, DecodingSummarySnafu, FileRecordSnafu, IOSnafu, NAIFDataSet,
-    NAIFRecord, NAIFSummaryRecord,
+    DAFError, DAFSummaries, DecodingCommentsSnafu, DecodingNameSnafu, DecodingSummarySnafu,
+    FileRecordSnafu, IOSnafu, NAIFDataSet, NAIFRecord, NAIFSummaryRecord,
 };
 pub use super::{FileRecord, NameRecord, SummaryRecord};
 use crate::errors::DecodingError;
 use crate::file2heap;
 use crate::naif::daf::DecodingDataSnafu;
+use crate::naif::Endian;
 use crate::{errors::IntegrityError, DBL_SIZE};
 use bytes::{Bytes, BytesMut};
 use core::fmt::Debug;
@@ -24,12 +25,20 @@ use core::hash::Hash;
 use core::marker::PhantomData;
 use core::ops::Deref;
 use hifitime::Epoch;
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use snafu::ResultExt;
+use std::borrow::Cow;
 
 use zerocopy::AsBytes;
 use zerocopy::{FromBytes, Ref};
 
+/// Number of characters of each comment record which store comments
+const COMMENT_RCRD_LEN: usize = 1000;
+/// End of line marker in the comment area
+const COMMENT_EOL: u8 = 0;
+/// End of comments marker in the comment area
+const COMMENT_EOT: u8 = 4;
+
 macro_rules! io_imports {
     () => {
         use std::fs::File;
@@ -45,33 +54,56 @@ pub(crate) const RCRD_LEN: usize = 1024;
 #[derive(Clone, Default, Debug, PartialEq)]
 pub struct GenericDAF<R: NAIFSummaryRecord, W: MutKind> {
     pub bytes: W,
-    pub crc32_checksum: u32,
+    /// CRC32 of the bytes of the file when they were parsed, unless it was not computed (cf. `load_mmap`)
+    pub crc32_checksum: Option<u32>,
+    /// Whether the bytes were converted from the other endianness when parsed (cf. `parse`)
+    pub endian_converted: bool,
     pub _daf_type: PhantomData<R>,
 }
 
 pub type DAF<R> = GenericDAF<R, Bytes>;
 pub type MutDAF<R> = GenericDAF<R, BytesMut>;
+/// DAF which borrows the buffer of the caller when it can be read in place, cf. `DAF::from_bytes`
+pub type BorrowedDAF<'a, R> = GenericDAF<R, Cow<'a, [u8]>>;
 
 pub trait MutKind: Deref<Target = [u8]> {}
 impl MutKind for Bytes {}
 impl MutKind for BytesMut {}
+impl MutKind for Cow<'_, [u8]> {}
 
 impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
-    /// Compute the CRC32 of the underlying bytes
+    /// Compute the CRC32 of the underlying bytes, as they are written in the file: if they were converted from the other
+    /// endianness when parsed, a copy of them is converted back first.
     pub fn crc32(&self) -> u32 {
+        if self.endian_converted {
+            let mut file_bytes = self.bytes.to_vec();
+            // Bytes which cannot be converted back were modified, and will not match the checksum of the file anyway.
+            if swap_endianness(&mut file_bytes, false).is_ok() {
+                return crc32fast::hash(&file_bytes);
+            }
+        }
         crc32fast::hash(&self.bytes)
     }
 
     /// Scrubs the data by computing the CRC32 of the bytes and making sure that it still matches the previously known hash
+    ///
+    /// If the checksum was not computed when the data was loaded (cf. `load_mmap`), there is no known hash to compare with
+    /// and the data is not scrubbed: use `check_crc32` to verify it against a known checksum instead.
     pub fn scrub(&self) -> Result<(), IntegrityError> {
-        if self.crc32() == self.crc32_checksum {
+        match self.crc32_checksum {
+            Some(expected) => self.check_crc32(expected),
+            None => Ok(()),
+        }
+    }
+
+    /// Computes the CRC32 of the underlying bytes and checks that it matches the expected checksum, e.g. a known-good
+    /// value pinned for this kernel.
+    pub fn check_crc32(&self, expected: u32) -> Result<(), IntegrityError> {
+        let computed = self.crc32();
+        if computed == expected {
             Ok(())
         } else {
-            // Compiler will optimize the double computation away
-            Err(IntegrityError::ChecksumInvalid {
-                expected: self.crc32_checksum,
-                computed: self.crc32(),
-            })
+            Err(IntegrityError::ChecksumInvalid { expected, computed })
         }
     }
 
@@ -166,6 +198,24 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
         })
     }
 
+    /// Returns an iterator over all of the summaries of this DAF and their names, in file order, following the links
+    /// between the summary records. Unlike `data_summaries`, this includes the summaries past the first summary record.
+    pub fn summaries(&self) -> Result<DAFSummaries<'_, R>, DAFError> {
+        let file_record = self.file_record()?;
+        if file_record.is_empty() {
+            return Err(DAFError::FileRecord {
+                kind: R::NAME,
+                source: FileRecordError::EmptyRecord,
+            });
+        }
+
+        Ok(DAFSummaries::new(
+            &self.bytes,
+            file_record.fwrd_idx(),
+            file_record.summary_size(),
+        ))
+    }
+
     /// Returns the summary given the name of the summary record
     pub fn summary_from_name(&self, name: &str) -> Result<(&R, usize), DAFError> {
         let idx = self
@@ -253,13 +303,24 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
 
     /// Provided a name that is in the summary, return its full data, if name is available.
     pub fn nth_data<'a, S: NAIFDataSet<'a>>(&'a self, idx: usize) -> Result<S, DAFError> {
+        let (_, data) = self.nth_summary_and_slice(idx).map_err(|e| match e {
+            DAFError::InvalidIndex { idx, .. } => DAFError::InvalidIndex {
+                idx,
+                kind: S::DATASET_NAME,
+            },
+            e => e,
+        })?;
+
+        // Convert it
+        S::from_f64_slice(data).context(DecodingDataSnafu { kind: R::NAME, idx })
+    }
+
+    /// Returns the summary of the n-th segment along with its raw data, without decoding it into a data set.
+    pub fn nth_summary_and_slice(&self, idx: usize) -> Result<(&R, &[f64]), DAFError> {
         let this_summary = self
             .data_summaries()?
             .get(idx)
-            .ok_or(DAFError::InvalidIndex {
-                idx,
-                kind: S::DATASET_NAME,
-            })?;
+            .ok_or(DAFError::InvalidIndex { idx, kind: R::NAME })?;
         // Grab the data in native endianness (TODO: How to support both big and little endian?)
         trace!("{idx} -> {this_summary:?}");
         if self.file_record()?.is_empty() {
@@ -269,6 +330,14 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
             });
         }
 
+        // Unused summary slots are zeroed out, and DAF indexes start at 1.
+        if this_summary.start_index() == 0 {
+            return Err(DAFError::EmptySummary {
+                kind: R::NAME,
+                idx: this_summary.start_index(),
+            });
+        }
+
         let start = (this_summary.start_index() - 1) * DBL_SIZE;
         let end = this_summary.end_index() * DBL_SIZE;
         let data: &[f64] = Ref::new_slice(
@@ -293,51 +362,61 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
         .unwrap()
         .into_slice();
 
-        // Convert it
-        S::from_f64_slice(data).context(DecodingDataSnafu { kind: R::NAME, idx })
+        Ok((this_summary, data))
     }
 
+    /// Returns the comments of this DAF, if any. Use `comment_text` to get an empty string when there are no comments.
     pub fn comments(&self) -> Result<Option<String>, DAFError> {
-        // TODO: This can be cleaned up to avoid allocating a string. In my initial tests there were a bunch of additional spaces, so I canceled those changes.
+        let rslt = self.comment_text()?;
+        if rslt.trim().is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(rslt))
+        }
+    }
+
+    /// Returns the text of the comment area of this DAF, which is empty if the DAF has no comments.
+    ///
+    /// The comment area spans the records between the file record and the first summary record. Per the NAIF convention,
+    /// only the first 1000 characters of each of these records are used, the end of each line is marked by a null character,
+    /// and the end of the comments is marked by an end-of-transmission character (ASCII 4).
+    pub fn comment_text(&self) -> Result<String, DAFError> {
         let mut rslt = String::new();
-        // FWRD has the initial record of the summary. So we assume that all records between the second record and that one are comments
-        for rid in 1..self.file_record()?.fwrd_idx() {
-            match core::str::from_utf8(
-                match self
-                    .bytes
-                    .get(rid * RCRD_LEN..(rid + 1) * RCRD_LEN)
-                    .ok_or_else(|| DecodingError::InaccessibleBytes {
-                        start: rid * RCRD_LEN,
-                        end: (rid + 1) * RCRD_LEN,
-                        size: self.bytes.len(),
-                    }) {
-                    Ok(it) => it,
-                    Err(source) => {
-                        return Err(DAFError::DecodingComments {
-                            kind: R::NAME,
-                            source,
-                        })
-                    }
-                },
-            ) {
-                Ok(s) => rslt += s.replace('\u{0}', "\n").trim(),
-                Err(e) => {
-                    // At this point, we know that the bytes are accessible because the embedded `match`
-                    // did not fail, so we can perform a direct access.
-                    let valid_s = core::str::from_utf8(
-                        &self.bytes[rid * RCRD_LEN..(rid * RCRD_LEN + e.valid_up_to())],
-                    )
-                    .unwrap();
-                    rslt += valid_s.replace('\u{0}', "\n").trim()
+        // FWRD is the one-indexed record of the first summary, so all of the records between the file record and that one are comments.
+        for rid in 1..self.file_record()?.fwrd_idx().saturating_sub(1) {
+            let rcrd = self
+                .bytes
+                .get(rid * RCRD_LEN..rid * RCRD_LEN + COMMENT_RCRD_LEN)
+                .ok_or_else(|| DecodingError::InaccessibleBytes {
+                    start: rid * RCRD_LEN,
+                    end: rid * RCRD_LEN + COMMENT_RCRD_LEN,
+                    size: self.bytes.len(),
+                })
+                .context(DecodingCommentsSnafu { kind: R::NAME })?;
+
+            for byte in rcrd.iter().copied() {
+                match byte {
+                    COMMENT_EOT => return Ok(rslt),
+                    COMMENT_EOL => rslt.push('\n'),
+                    byte => rslt.push(byte as char),
                 }
             }
         }
 
-        if rslt.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(rslt))
+        if !rslt.is_empty() {
+            warn!(
+                "DAF/{}: comment area has no end of comments marker, returning all of its text",
+                R::NAME
+            );
+            // Without the marker, the null padding of the last record would be returned as empty lines.
+            let text_len = rslt.trim_end_matches('\n').len();
+            rslt.truncate(text_len);
+            if text_len > 0 {
+                rslt.push('\n');
+            }
         }
+
+        Ok(rslt)
     }
 
     /// Writes the contents of this DAF file to a new location.
@@ -378,11 +457,39 @@ impl<R: NAIFSummaryRecord, W: MutKind> Hash for GenericDAF<R, W> {
 
 impl<R: NAIFSummaryRecord> DAF<R> {
     /// Parse the provided bytes as a SPICE Double Array File
+    ///
+    /// Files written on a machine of the other endianness are converted to the endianness of this machine, such that
+    /// the data can then be read without copies. The CRC32 checksum is that of the bytes of the file, before their conversion,
+    /// such that it matches the checksum expected by `check_then_parse`.
     pub fn parse<B: Deref<Target = [u8]>>(bytes: B) -> Result<Self, DAFError> {
-        let crc32_checksum = crc32fast::hash(&bytes);
+        Ok(Self::from_bytes(&bytes)?.into_shared())
+    }
+
+    /// Parse the provided shared bytes as a SPICE Double Array File without copying them, e.g. for kernels received over the network
+    /// or embedded in the binary with `include_bytes!` (cf. `from_static`); the data sets then borrow from the same buffer.
+    ///
+    /// The data must be 8-byte aligned to be read as doubles in place: bytes which are not aligned, or which are written in the
+    /// other endianness, are copied once like `parse` does.
+    pub fn parse_shared(bytes: Bytes) -> Result<Self, DAFError> {
+        let native_endian = bytes
+            .get(..FileRecord::SIZE)
+            .and_then(FileRecord::read_from)
+            .and_then(|file_record| file_record.file_endianness().ok())
+            .is_some_and(|endian| endian == Endian::u64_native());
+
+        if !native_endian || !(bytes.as_ptr() as usize).is_multiple_of(DBL_SIZE) {
+            debug!(
+                "copying DAF/{} which is not 8-byte aligned or not in the native endianness",
+                R::NAME
+            );
+            return Self::parse(bytes);
+        }
+
+        let crc32_checksum = Some(crc32fast::hash(&bytes));
         let me = Self {
-            bytes: Bytes::copy_from_slice(&bytes),
+            bytes,
             crc32_checksum,
+            endian_converted: false,
             _daf_type: PhantomData,
         };
         // Check that these calls will succeed.
@@ -406,6 +513,49 @@ impl<R: NAIFSummaryRecord> DAF<R> {
         Self::parse(bytes)
     }
 
+    /// Parse the provided bytes as a SPICE Double Array File without touching the file system nor copying them, e.g. for
+    /// kernels embedded with `include_bytes!` or downloaded over HTTP: the returned DAF, and its data sets, borrow the buffer.
+    ///
+    /// The data must be 8-byte aligned to be read as doubles in place: bytes which are not aligned, or which are written in the
+    /// other endianness, are copied once into the returned DAF like `parse` does. The integrity checks are those of `parse`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<BorrowedDAF<'_, R>, DAFError> {
+        let crc32_checksum = Some(crc32fast::hash(bytes));
+        let file_endian = bytes
+            .get(..FileRecord::SIZE)
+            .and_then(FileRecord::read_from)
+            .and_then(|file_record| file_record.file_endianness().ok());
+
+        let endian_converted = file_endian.is_some_and(|endian| endian != Endian::u64_native());
+        let bytes = match file_endian {
+            Some(endian) if endian_converted => {
+                debug!(
+                    "converting {endian:?} endian DAF/{} to native endianness",
+                    R::NAME
+                );
+                let mut native = bytes.to_vec();
+                swap_endianness(&mut native, true)
+                    .context(DecodingSummarySnafu { kind: R::NAME })?;
+                Cow::Owned(native)
+            }
+            _ if !(bytes.as_ptr() as usize).is_multiple_of(DBL_SIZE) => {
+                debug!("copying DAF/{} which is not 8-byte aligned", R::NAME);
+                Cow::Owned(bytes.to_vec())
+            }
+            _ => Cow::Borrowed(bytes),
+        };
+
+        let me = BorrowedDAF {
+            bytes,
+            crc32_checksum,
+            endian_converted,
+            _daf_type: PhantomData,
+        };
+        // Check that these calls will succeed.
+        me.file_record()?;
+        me.name_record()?;
+        Ok(me)
+    }
+
     pub fn load(path: &str) -> Result<Self, DAFError> {
         let bytes = file2heap!(path).context(IOSnafu {
             action: format!("loading {path:?}"),
@@ -414,9 +564,82 @@ impl<R: NAIFSummaryRecord> DAF<R> {
         Self::parse(bytes)
     }
 
-    /// Parse the provided static byte array as a SPICE Double Array File
+    /// Memory maps the provided file and parses it as a SPICE Double Array File, without copying it on the heap.
+    ///
+    /// The operating system only pages in the records which are accessed, and the data sets borrow from the mapped region.
+    /// Computing the CRC32 would read the whole file, so the checksum is not computed (`crc32_checksum` is `None`) and
+    /// `scrub` does not check anything: use `check_crc32` to verify the file against a known checksum.
+    /// If the file cannot be mapped, or was written in the other endianness (which cannot be converted without copying
+    /// it), it is read on the heap with `load` instead.
+    ///
+    /// # Safety (of the mapping)
+    /// The mapped bytes are not owned by this process: if the file is modified while it is mapped, the data sets change
+    /// with it, and if it is truncated, accessing the removed pages crashes the process. Hence, the file must not be
+    /// modified, or truncated, while it is mapped. To catch files which were corrupted before being mapped, e.g. by an
+    /// ASCII mode transfer, the file record and its FTP validation string are checked after mapping.
+    #[cfg(feature = "mmap")]
+    pub fn load_mmap(path: &str) -> Result<Self, DAFError> {
+        use crate::errors::InputOutputError;
+        use crate::file_mmap;
+
+        let mmap = match file_mmap!(path) {
+            Ok(mmap) => mmap,
+            Err(InputOutputError::IOUnknownError) => {
+                warn!("could not memory map {path:?}, reading it instead");
+                return Self::load(path);
+            }
+            Err(source) => {
+                return Err(DAFError::IO {
+                    action: format!("memory mapping {path:?}"),
+                    source,
+                })
+            }
+        };
+
+        let file_record = mmap
+            .get(..FileRecord::SIZE)
+            .and_then(FileRecord::read_from)
+            .ok_or_else(|| DecodingError::InaccessibleBytes {
+                start: 0,
+                end: FileRecord::SIZE,
+                size: mmap.len(),
+            })
+            .context(DecodingDataSnafu {
+                idx: 0_usize,
+                kind: R::NAME,
+            })?;
+        if file_record.is_empty() {
+            return Err(DAFError::FileRecord {
+                kind: R::NAME,
+                source: FileRecordError::EmptyRecord,
+            });
+        }
+        file_record
+            .check_ftp()
+            .context(FileRecordSnafu { kind: R::NAME })?;
+        let file_endian = file_record
+            .file_endianness()
+            .context(FileRecordSnafu { kind: R::NAME })?;
+        if file_endian != Endian::u64_native() {
+            debug!("{path:?} is {file_endian:?} endian and cannot be memory mapped, reading it instead");
+            return Self::load(path);
+        }
+
+        let me = Self {
+            bytes: Bytes::from_owner(mmap),
+            crc32_checksum: None,
+            endian_converted: false,
+            _daf_type: PhantomData,
+        };
+        // Check that these calls will succeed.
+        me.file_record()?;
+        me.name_record()?;
+        Ok(me)
+    }
+
+    /// Parse the provided static byte array as a SPICE Double Array File, without copying it if it is 8-byte aligned (cf. `parse_shared`).
     pub fn from_static<B: Deref<Target = [u8]>>(bytes: &'static B) -> Result<Self, DAFError> {
-        Self::parse(Bytes::from_static(bytes))
+        Self::parse_shared(Bytes::from_static(bytes))
     }
 
     /// Copies the underlying bytes of this DAF into a MutDAF, enabling modification of the DAF.
@@ -424,11 +647,138 @@ impl<R: NAIFSummaryRecord> DAF<R> {
         MutDAF {
             bytes: BytesMut::from_iter(&self.bytes),
             crc32_checksum: self.crc32_checksum,
+            endian_converted: self.endian_converted,
             _daf_type: PhantomData,
         }
     }
 }
 
+impl<R: NAIFSummaryRecord> BorrowedDAF<'_, R> {
+    /// Converts this DAF into one which owns its bytes, e.g. to load it into an Almanac, copying them only if they are borrowed.
+    pub fn into_shared(self) -> DAF<R> {
+        let bytes = match self.bytes {
+            Cow::Borrowed(bytes) => Bytes::copy_from_slice(bytes),
+            Cow::Owned(bytes) => Bytes::from(bytes),
+        };
+        DAF {
+            bytes,
+            crc32_checksum: self.crc32_checksum,
+            endian_converted: self.endian_converted,
+            _daf_type: PhantomData,
+        }
+    }
+}
+
+/// Swaps the byte order of the numerical data of a DAF in place: from the other endianness into that of this machine if
+/// `to_native` is set, and back into the other endianness otherwise, such that the bytes of the file can be recovered.
+///
+/// The integers of the file record, the summary records, and the data of each array are byte-swapped, while the
+/// character records (name and comment records) are left untouched.
+fn swap_endianness(bytes: &mut [u8], to_native: bool) -> Result<(), DecodingError> {
+    let inaccessible = |start: usize, end: usize, size: usize| DecodingError::InaccessibleBytes {
+        start,
+        end,
+        size,
+    };
+    let size = bytes.len();
+    if size < RCRD_LEN {
+        return Err(inaccessible(0, RCRD_LEN, size));
+    }
+
+    // The counts and addresses which drive the conversion are read in the native endianness, i.e. after swapping them
+    // if converting to this machine, and before otherwise.
+    let read_u32 = |bytes: &[u8], offset: usize| {
+        u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+    };
+    let read_f64 = |rcrd: &[u8], idx: usize| {
+        f64::from_ne_bytes(
+            rcrd[idx * DBL_SIZE..(idx + 1) * DBL_SIZE]
+                .try_into()
+                .unwrap(),
+        )
+    };
+    let read_i32 = |ints: &[u8], idx: usize| {
+        i32::from_ne_bytes(ints[idx * 4..(idx + 1) * 4].try_into().unwrap())
+    };
+
+    // File record: ND, NI, FWARD, BWARD, FREE, and the endianness flag.
+    let counts = |bytes: &[u8]| (read_u32(bytes, 8), read_u32(bytes, 12), read_u32(bytes, 76));
+    let native_counts = (!to_native).then(|| counts(bytes));
+    for offset in [8, 12, 76, 80, 84] {
+        bytes[offset..offset + 4].reverse();
+    }
+    let (nd, ni, fwrd) = native_counts.unwrap_or_else(|| counts(bytes));
+    bytes[88..96].copy_from_slice(match (Endian::u64_native(), to_native) {
+        (Endian::Little, true) | (Endian::Big, false) => b"LTL-IEEE",
+        (Endian::Big, true) | (Endian::Little, false) => b"BIG-IEEE",
+    });
+
+    // Summary records, which form a doubly linked list starting at FWARD.
+    let summary_size = nd + ni.div_ceil(2);
+    let mut arrays = Vec::new();
+    let mut rcrd = fwrd;
+    // Guard against cycles in the linked list of corrupted files.
+    for _ in 0..size / RCRD_LEN {
+        if rcrd == 0 {
+            break;
+        }
+        let start = (rcrd - 1).saturating_mul(RCRD_LEN);
+        let summary_rcrd = bytes
+            .get_mut(start..start.saturating_add(RCRD_LEN))
+            .ok_or_else(|| inaccessible(start, start.saturating_add(RCRD_LEN), size))?;
+
+        // Control area: next record, previous record, number of summaries.
+        let control = |rcrd: &[u8]| (read_f64(rcrd, 0) as usize, read_f64(rcrd, 2) as usize);
+        let native_control = (!to_native).then(|| control(summary_rcrd));
+        for dbl in summary_rcrd[..3 * DBL_SIZE].chunks_exact_mut(DBL_SIZE) {
+            dbl.reverse();
+        }
+        let (next, num_summaries) = native_control.unwrap_or_else(|| control(summary_rcrd));
+        let num_summaries = num_summaries.min((RCRD_LEN / DBL_SIZE - 3) / summary_size.max(1));
+
+        for sno in 0..num_summaries {
+            let offset = (3 + sno * summary_size) * DBL_SIZE;
+            for dbl in summary_rcrd[offset..offset + nd * DBL_SIZE].chunks_exact_mut(DBL_SIZE) {
+                dbl.reverse();
+            }
+            let ints_offset = offset + nd * DBL_SIZE;
+            let ints = &mut summary_rcrd[ints_offset..ints_offset + ni * 4];
+            // The last two integers are the initial and final addresses of the array.
+            let addresses = |ints: &[u8]| (read_i32(ints, ni - 2), read_i32(ints, ni - 1));
+            let native_addresses = (!to_native && ni >= 2).then(|| addresses(ints));
+            for int in ints.chunks_exact_mut(4) {
+                int.reverse();
+            }
+            if ni >= 2 {
+                arrays.push(native_addresses.unwrap_or_else(|| addresses(ints)));
+            }
+        }
+
+        rcrd = next;
+    }
+
+    // Data of each array, making sure to only swap once the data shared by several arrays.
+    arrays.retain(|(start, end)| *start >= 1 && end >= start);
+    arrays.sort_unstable();
+    let mut swapped_until = 0;
+    for (start, end) in arrays {
+        let first = ((start as usize) - 1).max(swapped_until);
+        let last = end as usize;
+        if first >= last {
+            continue;
+        }
+        let data = bytes
+            .get_mut(first * DBL_SIZE..last * DBL_SIZE)
+            .ok_or_else(|| inaccessible(first * DBL_SIZE, last * DBL_SIZE, size))?;
+        for dbl in data.chunks_exact_mut(DBL_SIZE) {
+            dbl.reverse();
+        }
+        swapped_until = last;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod daf_ut {
     use hifitime::Epoch;
@@ -436,13 +786,51 @@ mod daf_ut {
     use crate::{
         errors::IntegrityError,
         file2heap,
-        naif::{
-            daf::{datatypes::HermiteSetType13, file_record::FileRecordError, DAFError},
-            BPC,
-        },
+        naif::daf::{datatypes::HermiteSetType13, DAFError},
         prelude::SPK,
     };
 
+    #[test]
+    fn comment_area() {
+        use crate::naif::spk::segment::spk_segment_ut::build_spk;
+
+        // Without comment records
+        let spk = SPK::parse(build_spk(false, &[], &[])).unwrap();
+        assert_eq!(spk.comment_text().unwrap(), "");
+        assert_eq!(spk.comments().unwrap(), None);
+
+        // Insert a comment record after the file record, and shift the summary and name records accordingly.
+        let mut bytes = build_spk(false, &[], &[]);
+        let mut comments = b"; de440s.bsp LOG FILE\0\0Created 2021-01-07.\0\x04".to_vec();
+        comments.resize(1024, b' ');
+        bytes.splice(1024..1024, comments);
+        for offset in [76, 80] {
+            bytes[offset..offset + 4].copy_from_slice(&3_u32.to_le_bytes());
+        }
+
+        let spk = SPK::parse(bytes.clone()).unwrap();
+        assert_eq!(
+            spk.comment_text().unwrap(),
+            "; de440s.bsp LOG FILE\n\nCreated 2021-01-07.\n"
+        );
+        assert!(spk.comments().unwrap().is_some());
+
+        // Without the end of comments marker, the text is returned without the padding of the record
+        let mut unterminated = bytes.clone();
+        unterminated[1024 + 43..2048].fill(0);
+        let spk = SPK::parse(unterminated).unwrap();
+        assert_eq!(
+            spk.comment_text().unwrap(),
+            "; de440s.bsp LOG FILE\n\nCreated 2021-01-07.\n"
+        );
+
+        // An empty comment area starts with the end of comments marker
+        bytes[1024] = 4;
+        let spk = SPK::parse(bytes).unwrap();
+        assert_eq!(spk.comment_text().unwrap(), "");
+        assert_eq!(spk.comments().unwrap(), None);
+    }
+
     #[test]
     fn crc32_errors() {
         let mut traj = SPK::load("../data/gmat-hermite.bsp").unwrap();
@@ -462,7 +850,7 @@ mod daf_ut {
         );
 
         // Change the checksum of the traj and check that scrub fails
-        traj.crc32_checksum += 1;
+        traj.crc32_checksum = Some(nominal_crc + 1);
         assert_eq!(
             traj.scrub(),
             Err(IntegrityError::ChecksumInvalid {
@@ -472,6 +860,199 @@ mod daf_ut {
         );
     }
 
+    #[test]
+    fn crc32_expected() {
+        use crate::naif::spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord};
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 1.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 9,
+            start_idx: 385,
+            end_idx: 386,
+        };
+        let bytes = build_spk(false, &[summary], &[1.0, 2.0]);
+        let spk = SPK::parse(bytes.as_slice()).unwrap();
+
+        // The checksum is computed at parse time and can be queried
+        let known_good = crc32fast::hash(&bytes);
+        assert_eq!(spk.crc32_checksum, Some(known_good));
+        assert_eq!(spk.scrub(), Ok(()));
+        assert_eq!(spk.crc32(), known_good);
+        assert_eq!(spk.check_crc32(known_good), Ok(()));
+        assert_eq!(
+            spk.check_crc32(known_good ^ 1),
+            Err(IntegrityError::ChecksumInvalid {
+                expected: known_good ^ 1,
+                computed: known_good
+            })
+        );
+
+        // Any corruption of the bytes is caught
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+        let spk = SPK::parse(corrupted.as_slice()).unwrap();
+        assert_eq!(
+            spk.check_crc32(known_good),
+            Err(IntegrityError::ChecksumInvalid {
+                expected: known_good,
+                computed: crc32fast::hash(&corrupted)
+            })
+        );
+    }
+
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    #[test]
+    fn load_mmap_large() {
+        use crate::math::Vector3;
+        use crate::naif::{
+            daf::{datatypes::LagrangeSetType9, NAIFDataSet},
+            spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+        };
+        use std::io::Write;
+
+        // Resident memory of this process, in bytes.
+        let resident = || {
+            let statm = std::fs::read_to_string("/proc/self/statm").unwrap();
+            statm
+                .split_whitespace()
+                .nth(1)
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+                * 4096
+        };
+
+        // A small Lagrange Type 9 segment, followed by a 512 MB segment stored as a sparse file.
+        let mut type9 = vec![0.0; 30];
+        type9.extend((0..5).map(|i| i as f64 * 100.0));
+        type9.extend([3.0, 5.0]);
+        let large_len = 64 * 1024 * 1024;
+        let summary = |target_id, start_idx, len| SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 400.0,
+            target_id,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 9,
+            start_idx,
+            end_idx: start_idx + len - 1,
+        };
+        let small = summary(-10, 385, type9.len() as i32);
+        let large = summary(-20, small.end_idx + 1, large_len);
+        let bytes = build_spk(false, &[small, large], &type9);
+
+        let path = std::env::temp_dir().join(format!("anise-mmap-{}.bsp", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(&bytes).unwrap();
+        file.set_len((large.end_idx as u64) * 8).unwrap();
+        drop(file);
+        let file_size = std::fs::metadata(&path).unwrap().len() as usize;
+        assert!(file_size > 512 * 1024 * 1024);
+
+        let before = resident();
+        let spk = SPK::load_mmap(path.to_str().unwrap()).unwrap();
+        assert_eq!(spk.bytes.len(), file_size);
+        assert_eq!(spk.crc32_checksum, None);
+        let (summary, idx) = spk
+            .summary_from_id_at_epoch(-10, Epoch::from_et_seconds(150.0))
+            .unwrap();
+        assert_eq!(*summary, small);
+        let data = spk.nth_data::<LagrangeSetType9>(idx).unwrap();
+        let (pos, vel) = data
+            .evaluate(Epoch::from_et_seconds(150.0), summary)
+            .unwrap();
+        assert_eq!(pos, Vector3::zeros());
+        assert_eq!(vel, Vector3::zeros());
+        // Only a handful of pages of the file were loaded (allowing for other tests running in parallel).
+        assert!(
+            resident().saturating_sub(before) < file_size / 8,
+            "memory mapping paged in too much of the file"
+        );
+
+        drop(spk);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn load_mmap_validation_and_fallback() {
+        use crate::naif::{
+            daf::{file_record::FTP_STR, FileRecordError},
+            spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+        };
+
+        let summary = SPKSummaryRecord {
+            end_epoch_et_s: 1.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 9,
+            start_idx: 385,
+            end_idx: 386,
+            ..Default::default()
+        };
+        let write = |name: &str, bytes: &[u8]| {
+            let path =
+                std::env::temp_dir().join(format!("anise-{name}-{}.bsp", std::process::id()));
+            std::fs::write(&path, bytes).unwrap();
+            path.to_str().unwrap().to_string()
+        };
+        let ftp = 699..699 + FTP_STR.len();
+
+        // Files with a valid or a null FTP string are mapped, without computing their checksum.
+        let mut bytes = build_spk(false, &[summary], &[1.0, 2.0]);
+        let null_ftp = write("ftp-null", &bytes);
+        bytes[ftp.clone()].copy_from_slice(FTP_STR);
+        let valid_ftp = write("ftp-valid", &bytes);
+        for path in [&null_ftp, &valid_ftp] {
+            let spk = SPK::load_mmap(path).unwrap();
+            assert_eq!(spk.crc32_checksum, None);
+            assert_eq!(spk.scrub(), Ok(()));
+            assert_eq!(spk.data_summaries().unwrap()[0], summary);
+        }
+        assert_eq!(
+            SPK::load_mmap(&valid_ftp)
+                .unwrap()
+                .file_record()
+                .unwrap()
+                .ftp_str,
+            *FTP_STR
+        );
+
+        // A text mode transfer replaces the line terminators of the FTP string.
+        bytes[ftp.start + 8] = b'\n';
+        let corrupted = write("ftp-corrupted", &bytes);
+        assert_eq!(
+            SPK::load_mmap(&corrupted).unwrap_err(),
+            DAFError::FileRecord {
+                kind: "SPKSummaryRecord",
+                source: FileRecordError::CorruptedFTP
+            }
+        );
+
+        // Files in the other endianness are read on the heap and converted.
+        let swapped = write("ftp-swapped", &build_spk(true, &[summary], &[1.0, 2.0]));
+        let spk = SPK::load_mmap(&swapped).unwrap();
+        assert_eq!(
+            spk.crc32_checksum,
+            Some(crc32fast::hash(&build_spk(true, &[summary], &[1.0, 2.0])))
+        );
+        assert_eq!(spk.data_summaries().unwrap()[0], summary);
+
+        // Empty files cannot be mapped, and fail like in `load`.
+        let empty = write("ftp-empty", &[]);
+        assert_eq!(SPK::load_mmap(&empty).is_err(), SPK::load(&empty).is_err());
+
+        for path in [null_ftp, valid_ftp, corrupted, swapped, empty] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
     #[test]
     fn summary_from_name() {
         let epoch = Epoch::now().unwrap();
@@ -507,21 +1088,307 @@ mod daf_ut {
 
     #[test]
     fn load_big_endian() {
-        // Ensure this fails
+        // Both files store the same ephemeris, in each endianness.
+        let little = SPK::load("../data/gmat-hermite.bsp").unwrap();
+        let big = SPK::load("../data/gmat-hermite-big-endian.bsp").unwrap();
+
+        assert_eq!(big.data_summaries(), little.data_summaries());
         assert_eq!(
-            SPK::load("../data/gmat-hermite-big-endian.bsp"),
-            Err(DAFError::FileRecord {
-                kind: "SPKSummaryRecord",
-                source: FileRecordError::WrongEndian
-            })
+            big.name_record()
+                .unwrap()
+                .nth_name(0, big.file_record().unwrap().summary_size()),
+            little
+                .name_record()
+                .unwrap()
+                .nth_name(0, little.file_record().unwrap().summary_size())
         );
+        if big.nth_data::<HermiteSetType13>(0).unwrap()
+            != little.nth_data::<HermiteSetType13>(0).unwrap()
+        {
+            panic!("big endian data differs from little endian data");
+        }
+    }
+
+    #[test]
+    fn parse_both_endians() {
+        use crate::{
+            almanac::Almanac,
+            naif::spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+            prelude::Frame,
+        };
+
+        let summaries = [SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 80.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 13,
+            start_idx: 385,
+            end_idx: 385 + 16 - 1,
+        }];
+        // Two Type 13 states, followed by their epochs, and the window size and number of records.
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([7000.0, 600.0, 0.0, 0.0, 7.5, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
 
-        // Now ensure the error is correctly printed
-        if let Err(e) = BPC::load("../data/gmat-hermite-big-endian.bsp") {
+        let little_bytes = build_spk(false, &summaries, &data);
+        let big_bytes = build_spk(true, &summaries, &data);
+        let little = SPK::parse(little_bytes.as_slice()).unwrap();
+        let big = SPK::parse(big_bytes.as_slice()).unwrap();
+
+        assert_eq!(big.file_record(), little.file_record());
+        assert_eq!(big.data_summaries(), little.data_summaries());
+        assert_eq!(big.data_summaries().unwrap()[0], summaries[0]);
+        assert_eq!(big.bytes, little.bytes);
+        // The checksums are those of the files, like in `check_then_parse`
+        assert!(big.endian_converted && !little.endian_converted);
+        assert_eq!(big.crc32_checksum, Some(crc32fast::hash(&big_bytes)));
+        assert_eq!(big.crc32(), crc32fast::hash(&big_bytes));
+        assert_eq!(little.crc32(), crc32fast::hash(&little_bytes));
+        assert_eq!(big.scrub(), Ok(()));
+        assert_eq!(big.to_mutable().scrub(), Ok(()));
+        assert!(SPK::check_then_parse(big_bytes.as_slice(), big.crc32()).is_ok());
+
+        let target = Frame::from_ephem_j2000(-10);
+        let little = Almanac::default().with_spk(little).unwrap();
+        let big = Almanac::default().with_spk(big).unwrap();
+        for t in [1.0, 12.5, 40.0, 79.0] {
+            let epoch = Epoch::from_et_seconds(t);
             assert_eq!(
-                format!("{e}"),
-                "DAF/BPCSummaryRecord: file record issue: endian of file does not match the endian order of the machine".to_string()
+                big.translate_to_parent(target, epoch).unwrap(),
+                little.translate_to_parent(target, epoch).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn summaries_across_records() {
+        use zerocopy::AsBytes;
+
+        use crate::naif::{
+            daf::NAIFSummaryRecord,
+            spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+        };
+
+        // A summary record holds at most 25 SPK summaries, so 30 segments need a second summary record.
+        let summaries = (0..30)
+            .map(|i| SPKSummaryRecord {
+                target_id: -100 - i,
+                center_id: 399,
+                frame_id: 1,
+                data_type_i: 13,
+                start_idx: 385,
+                end_idx: 400,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut bytes = build_spk(false, &summaries[..25], &[0.0; 16]);
+        // The first summary record (record 2) now links to the fourth one, followed by the fifth (name) record.
+        let second_rcrd = bytes.len();
+        bytes[1024..1032].copy_from_slice(&((second_rcrd / 1024 + 1) as f64).to_ne_bytes());
+        for v in [0.0, 2.0, 5.0] {
+            bytes.extend(f64::to_ne_bytes(v));
+        }
+        for summary in &summaries[25..] {
+            bytes.extend(summary.as_bytes());
+        }
+        bytes.resize(second_rcrd + 1024, 0);
+        bytes.resize(second_rcrd + 2048, b' ');
+        // Name the last segment of each summary record.
+        bytes[2048 + 24 * 40..2048 + 24 * 40 + 4].copy_from_slice(b"LAST");
+        bytes[second_rcrd + 1024 + 4 * 40..second_rcrd + 1024 + 4 * 40 + 4]
+            .copy_from_slice(b"LAST");
+
+        let spk = SPK::parse(bytes.clone()).unwrap();
+        assert_eq!(spk.data_summaries().unwrap()[..25], summaries[..25]);
+        let all = spk
+            .summaries()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(all.len(), 30);
+        for (i, (summary, name)) in all.iter().enumerate() {
+            assert_eq!(**summary, summaries[i]);
+            assert_eq!(*name, if i == 24 || i == 29 { "LAST" } else { "" });
+        }
+        assert_eq!(all[29].0.id(), -129);
+
+        // A second summary record linking back to the first one is a cycle.
+        bytes[second_rcrd..second_rcrd + 8].copy_from_slice(&2.0_f64.to_ne_bytes());
+        let spk = SPK::parse(bytes.clone()).unwrap();
+        let mut iter = spk.summaries().unwrap();
+        assert_eq!(iter.by_ref().take(30).filter(|s| s.is_ok()).count(), 30);
+        assert_eq!(
+            iter.next(),
+            Some(Err(DAFError::SummaryRecordCycle {
+                kind: "SPKSummaryRecord",
+                rcrd: 2
+            }))
+        );
+        assert!(iter.next().is_none());
+
+        // And a link past the end of the file is reported as such.
+        bytes[second_rcrd..second_rcrd + 8].copy_from_slice(&99.0_f64.to_ne_bytes());
+        let spk = SPK::parse(bytes).unwrap();
+        let last = spk.summaries().unwrap().last().unwrap();
+        assert!(matches!(last, Err(DAFError::DecodingSummary { .. })));
+    }
+
+    #[test]
+    fn parse_shared_without_copy() {
+        use bytes::Bytes;
+
+        use crate::{
+            naif::spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+            DBL_SIZE,
+        };
+
+        let summaries = [SPKSummaryRecord {
+            end_epoch_et_s: 80.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 13,
+            start_idx: 385,
+            end_idx: 385 + 16 - 1,
+            ..Default::default()
+        }];
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([7000.0, 600.0, 0.0, 0.0, 7.5, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        let spk_bytes = build_spk(false, &summaries, &data);
+
+        // Copy the file into a larger buffer, at an aligned and at a misaligned address.
+        let at_offset = |misalignment: usize| {
+            let mut buffer = vec![0_u8; spk_bytes.len() + 2 * DBL_SIZE];
+            let start = (DBL_SIZE - buffer.as_ptr() as usize % DBL_SIZE) % DBL_SIZE + misalignment;
+            buffer[start..start + spk_bytes.len()].copy_from_slice(&spk_bytes);
+            Bytes::from(buffer).slice(start..start + spk_bytes.len())
+        };
+
+        let aligned = at_offset(0);
+        let shared = SPK::parse_shared(aligned.clone()).unwrap();
+        assert_eq!(shared.bytes.as_ptr(), aligned.as_ptr());
+
+        let misaligned = at_offset(1);
+        let copied = SPK::parse_shared(misaligned.clone()).unwrap();
+        assert_ne!(copied.bytes.as_ptr(), misaligned.as_ptr());
+
+        // Both are identical to the parsed file, and so is a file in the other endianness, apart from its checksum.
+        let parsed = SPK::parse(spk_bytes).unwrap();
+        let swapped = SPK::parse_shared(Bytes::from(build_spk(true, &summaries, &data))).unwrap();
+        assert_eq!(shared.crc32_checksum, parsed.crc32_checksum);
+        assert_eq!(copied.crc32_checksum, parsed.crc32_checksum);
+        for spk in [&shared, &copied, &swapped] {
+            assert_eq!(spk.bytes, parsed.bytes);
+            assert_eq!(spk.data_summaries(), parsed.data_summaries());
+            assert!(
+                spk.nth_data::<HermiteSetType13>(0).unwrap()
+                    == parsed.nth_data::<HermiteSetType13>(0).unwrap()
+            );
+        }
+
+        // Invalid files are rejected like in `parse`
+        assert!(SPK::parse_shared(aligned.slice(..512)).is_err());
+    }
+
+    #[test]
+    fn from_bytes_without_copy() {
+        use std::borrow::Cow;
+
+        use crate::{
+            naif::spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+            DBL_SIZE,
+        };
+
+        let summaries = [SPKSummaryRecord {
+            end_epoch_et_s: 80.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 13,
+            start_idx: 385,
+            end_idx: 385 + 16 - 1,
+            ..Default::default()
+        }];
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([7000.0, 600.0, 0.0, 0.0, 7.5, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        let spk_bytes = build_spk(false, &summaries, &data);
+
+        // Copy the file into a larger buffer, such that it can be borrowed at an aligned and at a misaligned address.
+        let mut buffer = vec![0_u8; spk_bytes.len() + 2 * DBL_SIZE];
+        let start = (DBL_SIZE - buffer.as_ptr() as usize % DBL_SIZE) % DBL_SIZE;
+        buffer[start..start + spk_bytes.len()].copy_from_slice(&spk_bytes);
+        let aligned = &buffer[start..start + spk_bytes.len()];
+
+        let borrowed = SPK::from_bytes(aligned).unwrap();
+        assert!(
+            matches!(borrowed.bytes, Cow::Borrowed(bytes) if bytes.as_ptr() == aligned.as_ptr())
+        );
+
+        let mut misaligned_buffer = vec![0_u8; spk_bytes.len() + 2 * DBL_SIZE];
+        misaligned_buffer[start + 1..start + 1 + spk_bytes.len()].copy_from_slice(&spk_bytes);
+        let copied =
+            SPK::from_bytes(&misaligned_buffer[start + 1..start + 1 + spk_bytes.len()]).unwrap();
+        assert!(matches!(copied.bytes, Cow::Owned(_)));
+
+        let swapped_bytes = build_spk(true, &summaries, &data);
+        let swapped = SPK::from_bytes(&swapped_bytes).unwrap();
+        assert!(matches!(swapped.bytes, Cow::Owned(_)));
+        assert!(swapped.endian_converted);
+        assert_eq!(
+            swapped.crc32_checksum,
+            Some(crc32fast::hash(&swapped_bytes))
+        );
+        assert!(swapped.scrub().is_ok());
+
+        // All are identical to the parsed file, including once they own their bytes.
+        let parsed = SPK::parse(&spk_bytes[..]).unwrap();
+        assert_eq!(borrowed.crc32_checksum, parsed.crc32_checksum);
+        assert_eq!(copied.crc32_checksum, parsed.crc32_checksum);
+        for spk in [&borrowed, &copied, &swapped] {
+            assert_eq!(&spk.bytes[..], &parsed.bytes[..]);
+            assert_eq!(spk.data_summaries(), parsed.data_summaries());
+            assert!(
+                spk.nth_data::<HermiteSetType13>(0).unwrap()
+                    == parsed.nth_data::<HermiteSetType13>(0).unwrap()
             );
         }
+        assert_eq!(borrowed.clone().into_shared(), parsed);
+        assert_eq!(
+            swapped.into_shared().crc32(),
+            crc32fast::hash(&swapped_bytes)
+        );
+
+        // Invalid files are rejected like in `parse`
+        assert!(SPK::from_bytes(&aligned[..512]).is_err());
+    }
+
+    #[test]
+    fn unsupported_binary_format() {
+        use crate::naif::{
+            daf::FileRecordError,
+            spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+        };
+
+        // Files written on VAX machines do not use IEEE doubles.
+        let mut bytes = build_spk(false, &[SPKSummaryRecord::default()], &[0.0; 4]);
+        bytes[88..96].copy_from_slice(b"VAX-GFLT");
+        let err = SPK::parse(bytes).unwrap_err();
+        assert_eq!(
+            err,
+            DAFError::FileRecord {
+                kind: "SPKSummaryRecord",
+                source: FileRecordError::InvalidEndian {
+                    read: "VAX-GFLT".to_string()
+                }
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "DAF/SPKSummaryRecord: file record declares the unsupported binary format `VAX-GFLT` (only `BIG-IEEE` and `LTL-IEEE` are supported)"
+        );
     }
 }
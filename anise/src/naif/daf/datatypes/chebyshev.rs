@@ -15,10 +15,18 @@ use snafu::{ensure, ResultExt};
 use crate::{
     errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
     math::{
-        interpolation::{chebyshev_eval, InterpDecodingSnafu, InterpolationError},
+        interpolation::{
+            chebyshev_eval, chebyshev_eval_value, InterpDecodingSnafu, InterpolationError,
+        },
         Vector3,
     },
-    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+    naif::daf::{
+        datatypes::{
+            check_epoch_span, check_finite, check_summary_coverage, non_finite, nth_record_data,
+            NO_RECORDS,
+        },
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
 };
 
 #[derive(PartialEq)]
@@ -32,7 +40,31 @@ pub struct Type2ChebyshevSet<'a> {
 
 impl<'a> Type2ChebyshevSet<'a> {
     pub fn degree(&self) -> usize {
-        (self.rsize - 2) / 3 - 1
+        (self.rsize.saturating_sub(2) / 3).saturating_sub(1)
+    }
+
+    /// Checks that the record size matches three sets of coefficients of the same degree, and the number of records.
+    fn check_rsize(&self) -> Result<(), IntegrityError> {
+        if self.rsize < 5 || !(self.rsize - 2).is_multiple_of(3) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "record size (RSIZE)",
+                value: self.rsize as f64,
+                reason: "must be two plus three times the number of coefficients per component",
+            });
+        }
+
+        if self.rsize.checked_mul(self.num_records) != Some(self.record_data.len()) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of records",
+                value: self.num_records as f64,
+                reason:
+                    "record data length must be the record size (RSIZE) times the number of records",
+            });
+        }
+
+        Ok(())
     }
 
     fn spline_idx<S: NAIFSummaryRecord>(
@@ -48,14 +80,68 @@ impl<'a> Type2ChebyshevSet<'a> {
                 req: epoch,
                 start: summary.start_epoch(),
                 end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
             });
         }
 
         let window_duration_s = self.interval_length.to_seconds();
 
-        let ephem_start_delta_s = epoch.to_et_seconds() - summary.start_epoch_et_s();
+        // Like SPICE, the records are counted from the start of the first record, which may precede the start of the segment.
+        let ephem_start_delta_s = epoch.to_et_seconds() - self.init_epoch.to_et_seconds();
+
+        Ok(((ephem_start_delta_s / window_duration_s) as usize)
+            .saturating_add(1)
+            .min(self.num_records))
+    }
+
+    /// Returns the record covering the provided epoch and the radius of its spline in seconds.
+    fn record_at<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Type2ChebyshevRecord<'a>, f64), InterpolationError> {
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })
+            .context(InterpDecodingSnafu)?;
+
+        let spline_idx = self.spline_idx(epoch, summary)?;
+
+        let window_duration_s = self.interval_length.to_seconds();
+        let radius_s = window_duration_s / 2.0;
+
+        // Now, build the X, Y, Z data from the record data.
+        let record = self
+            .nth_record(spline_idx.saturating_sub(1))
+            .context(InterpDecodingSnafu)?;
+
+        Ok((record, radius_s))
+    }
+
+    /// Evaluates only the position at the provided epoch, which skips the computation of the derivative of each Chebyshev series.
+    ///
+    /// The position is identical to that returned by `evaluate`.
+    pub fn evaluate_position<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Vector3, InterpolationError> {
+        let (record, radius_s) = self.record_at(epoch, summary)?;
 
-        Ok(((ephem_start_delta_s / window_duration_s) as usize + 1).min(self.num_records))
+        let mut state = Vector3::zeros();
+        for (cno, coeffs) in [record.x_coeffs, record.y_coeffs, record.z_coeffs]
+            .iter()
+            .enumerate()
+        {
+            state[cno] = chebyshev_eval_value(
+                coeffs,
+                radius_s,
+                record.midpoint_et_s,
+                epoch.to_et_seconds(),
+            )?;
+        }
+
+        Ok(state)
     }
 }
 
@@ -91,22 +177,32 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
         let seconds_since_j2000 = slice[slice.len() - 4];
         if !seconds_since_j2000.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "seconds since J2000 ET",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "seconds since J2000 ET",
+                    seconds_since_j2000,
+                    slice.len() - 4,
+                ),
             });
         }
 
+        check_epoch_span(
+            Self::DATASET_NAME,
+            "seconds since J2000 ET",
+            seconds_since_j2000,
+        )
+        .map_err(|source| DecodingError::Integrity { source })?;
         let start_epoch = Epoch::from_et_seconds(seconds_since_j2000);
 
         let interval_length_s = slice[slice.len() - 3];
         if !interval_length_s.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "interval length in seconds",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "interval length in seconds",
+                    interval_length_s,
+                    slice.len() - 3,
+                ),
             });
         } else if interval_length_s <= 0.0 {
             return Err(DecodingError::Integrity {
@@ -133,14 +229,22 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
     }
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
-        Ok(Self::RecordKind::from_slice_f64(
-            self.record_data
-                .get(n * self.rsize..(n + 1) * self.rsize)
-                .ok_or(DecodingError::InaccessibleBytes {
-                    start: n * self.rsize,
-                    end: (n + 1) * self.rsize,
-                    size: self.record_data.len(),
-                })?,
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })?;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            self.rsize,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(NO_RECORDS);
+        }
+        Ok((
+            self.init_epoch,
+            self.init_epoch + (self.num_records as f64) * self.interval_length,
         ))
     }
 
@@ -149,17 +253,7 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
         epoch: Epoch,
         summary: &S,
     ) -> Result<(Vector3, Vector3), InterpolationError> {
-        let spline_idx = self.spline_idx(epoch, summary)?;
-
-        let window_duration_s = self.interval_length.to_seconds();
-        let radius_s = window_duration_s / 2.0;
-
-        // Now, build the X, Y, Z data from the record data.
-        let record = self
-            .nth_record(spline_idx - 1)
-            .context(InterpDecodingSnafu)?;
-
-        let normalized_time = (epoch.to_et_seconds() - record.midpoint_et_s) / radius_s;
+        let (record, radius_s) = self.record_at(epoch, summary)?;
 
         let mut state = Vector3::zeros();
         let mut rate = Vector3::zeros();
@@ -168,8 +262,12 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
             .iter()
             .enumerate()
         {
-            let (val, deriv) =
-                chebyshev_eval(normalized_time, coeffs, radius_s, epoch, self.degree())?;
+            let (val, deriv) = chebyshev_eval(
+                coeffs,
+                radius_s,
+                record.midpoint_et_s,
+                epoch.to_et_seconds(),
+            )?;
             state[cno] = val;
             rate[cno] = deriv;
         }
@@ -177,18 +275,18 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
         Ok((state, rate))
     }
 
-    fn check_integrity(&self) -> Result<(), IntegrityError> {
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
         // Verify that none of the data is invalid once when we load it.
-        for val in self.record_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the record data",
-                });
-            }
-        }
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
 
-        Ok(())
+        self.check_rsize()?;
+
+        check_summary_coverage(self, summary)
     }
 
     fn truncate<S: NAIFSummaryRecord>(
@@ -197,21 +295,39 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
         new_start: Option<Epoch>,
         new_end: Option<Epoch>,
     ) -> Result<Self, InterpolationError> {
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })
+            .context(InterpDecodingSnafu)?;
+
         let start_idx = if let Some(start) = new_start {
-            self.spline_idx(start, summary)? - 1
+            self.spline_idx(start, summary)?.saturating_sub(1)
         } else {
             0
         };
 
         let end_idx = if let Some(end) = new_end {
+            // The record following that of the new end is kept, unless the new end is in the last record.
             self.spline_idx(end, summary)?
+                .min(self.num_records.saturating_sub(1))
         } else {
-            self.num_records - 1
+            self.num_records.saturating_sub(1)
         };
 
-        self.record_data = &self.record_data[start_idx * self.rsize..(end_idx + 1) * self.rsize];
-        self.num_records = (self.record_data.len() / self.rsize) - 1;
-        self.init_epoch = self.nth_record(0).unwrap().midpoint_epoch() - 0.5 * self.interval_length;
+        self.record_data = self
+            .record_data
+            .get(start_idx * self.rsize..(end_idx + 1) * self.rsize)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start: start_idx * self.rsize,
+                end: (end_idx + 1) * self.rsize,
+                size: self.record_data.len(),
+            })
+            .context(InterpDecodingSnafu)?;
+        self.num_records = self.record_data.len() / self.rsize;
+        self.init_epoch = self
+            .nth_record(0)
+            .context(InterpDecodingSnafu)?
+            .midpoint_epoch()
+            - 0.5 * self.interval_length;
 
         Ok(self)
     }
@@ -273,13 +389,228 @@ impl<'a> NAIFDataRecord<'a> for Type2ChebyshevRecord<'a> {
 
 #[cfg(test)]
 mod chebyshev_ut {
+    use hifitime::{Epoch, TimeUnits};
+
     use crate::{
         errors::{DecodingError, IntegrityError},
-        naif::daf::NAIFDataSet,
+        math::interpolation::InterpolationError,
+        naif::{
+            daf::{NAIFDataSet, NAIFSummaryRecord},
+            spk::summary::SPKSummaryRecord,
+        },
     };
 
     use super::Type2ChebyshevSet;
 
+    /// Evaluates the Chebyshev series of the first kind up to degree three, and its derivative with respect to the normalized time.
+    fn cheb3(coeffs: &[f64; 4], x: f64) -> (f64, f64) {
+        (
+            coeffs[0]
+                + coeffs[1] * x
+                + coeffs[2] * (2.0 * x * x - 1.0)
+                + coeffs[3] * (4.0 * x.powi(3) - 3.0 * x),
+            coeffs[1] + coeffs[2] * 4.0 * x + coeffs[3] * (12.0 * x * x - 3.0),
+        )
+    }
+
+    #[test]
+    fn type2_evaluate() {
+        let interval_s = 100.0;
+        let radius_s = interval_s / 2.0;
+        let num_records = 3;
+        let coeffs = |record: usize, component: usize| -> [f64; 4] {
+            let k = (record * 3 + component) as f64;
+            [1e4 + k, -20.0 * k, 3.0 + k, 0.5 - 0.1 * k]
+        };
+
+        let mut data = Vec::new();
+        for n in 0..num_records {
+            data.extend([radius_s + interval_s * n as f64, radius_s]);
+            for component in 0..3 {
+                data.extend(coeffs(n, component));
+            }
+        }
+        // Metadata: initial epoch, interval length, record size, number of records
+        data.extend([0.0, interval_s, 14.0, num_records as f64]);
+
+        let dataset = Type2ChebyshevSet::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.degree(), 3);
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: interval_s * num_records as f64,
+            ..Default::default()
+        };
+
+        // Within the records, exactly at the boundaries between records, and at the end of the segment.
+        for (t, record) in [
+            (0.0, 0),
+            (12.3, 0),
+            (99.9, 0),
+            (100.0, 1),
+            (150.0, 1),
+            (200.0, 2),
+            (271.0, 2),
+            (300.0, 2),
+        ] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap();
+            let x = (t - (radius_s + interval_s * record as f64)) / radius_s;
+            for component in 0..3 {
+                let (exp_pos, exp_deriv) = cheb3(&coeffs(record, component), x);
+                assert!(
+                    (pos[component] - exp_pos).abs() < 1e-9,
+                    "pos error at {t} s"
+                );
+                assert!(
+                    (vel[component] - exp_deriv / radius_s).abs() < 1e-12,
+                    "vel error at {t} s"
+                );
+            }
+        }
+
+        // The position only evaluation matches the full evaluation exactly
+        for t in [0.0, 12.3, 150.0, 300.0] {
+            let epoch = Epoch::from_et_seconds(t);
+            assert_eq!(
+                dataset.evaluate_position(epoch, &summary).unwrap(),
+                dataset.evaluate(epoch, &summary).unwrap().0
+            );
+        }
+
+        // The records are counted from the initial epoch of the data, even if the segment starts later.
+        let late_summary = SPKSummaryRecord {
+            start_epoch_et_s: 130.0,
+            ..summary
+        };
+        let (pos, _) = dataset
+            .evaluate(Epoch::from_et_seconds(150.0), &late_summary)
+            .unwrap();
+        assert!((pos[0] - cheb3(&coeffs(1, 0), 0.0).0).abs() < 1e-9);
+
+        let epoch = Epoch::from_et_seconds(300.0) + 1.seconds();
+        assert_eq!(
+            dataset.evaluate(epoch, &summary),
+            Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.target_id,
+                center_id: summary.center_id,
+            })
+        );
+    }
+
+    #[test]
+    fn type2_truncate() {
+        let interval_s = 100.0;
+        let num_records = 4;
+        let mut data = Vec::new();
+        for n in 0..num_records {
+            data.extend([50.0 + interval_s * n as f64, 50.0]);
+            data.extend([n as f64; 3]);
+        }
+        // Metadata: initial epoch, interval length, record size, number of records
+        data.extend([0.0, interval_s, 5.0, num_records as f64]);
+
+        let dataset = Type2ChebyshevSet::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: interval_s * num_records as f64,
+            ..Default::default()
+        };
+
+        let truncated = dataset
+            .truncate(&summary, Some(Epoch::from_et_seconds(150.0)), None)
+            .unwrap();
+        let truncated_summary = SPKSummaryRecord {
+            start_epoch_et_s: 150.0,
+            ..summary
+        };
+        truncated.check_integrity(&truncated_summary).unwrap();
+        // The summary must be updated along with the data
+        assert_eq!(
+            truncated.check_integrity(&summary),
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset: "Chebyshev Type 2",
+                summary_start_et_s: 0.0,
+                summary_end_et_s: 400.0,
+                data_start_et_s: 100.0,
+                data_end_et_s: 400.0,
+            })
+        );
+        assert_eq!(truncated.num_records, 3);
+        assert_eq!(truncated.init_epoch, Epoch::from_et_seconds(100.0));
+        assert_eq!(truncated.nth_record(2).unwrap().x_coeffs, &[3.0]);
+        assert!(truncated.nth_record(3).is_err());
+
+        // Truncating the end to within the last record keeps all of the data up to that record.
+        let dataset = Type2ChebyshevSet::from_f64_slice(&data).unwrap();
+        let truncated = dataset
+            .truncate(&summary, None, Some(Epoch::from_et_seconds(350.0)))
+            .unwrap();
+        let truncated_summary = SPKSummaryRecord {
+            end_epoch_et_s: 350.0,
+            ..summary
+        };
+        truncated.check_integrity(&truncated_summary).unwrap();
+        assert_eq!(truncated.num_records, 4);
+    }
+
+    #[test]
+    fn invalid_rsize() {
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 100.0,
+            ..Default::default()
+        };
+
+        // One record of degree one, with an invalid record size or number of records.
+        for (rsize, num_records, variable, value) in [
+            (2, 4, "record size (RSIZE)", 2.0),
+            (7, 1, "record size (RSIZE)", 7.0),
+            (8, 2, "number of records", 2.0),
+        ] {
+            let data = [
+                50.0,
+                50.0,
+                1.0,
+                2.0,
+                3.0,
+                4.0,
+                5.0,
+                6.0,
+                0.0,
+                100.0,
+                rsize as f64,
+                num_records as f64,
+            ];
+            let dataset = Type2ChebyshevSet::from_f64_slice(&data).unwrap();
+
+            let err = IntegrityError::InvalidValue {
+                dataset: "Chebyshev Type 2",
+                variable,
+                value,
+                reason: if variable == "number of records" {
+                    "record data length must be the record size (RSIZE) times the number of records"
+                } else {
+                    "must be two plus three times the number of coefficients per component"
+                },
+            };
+            assert_eq!(dataset.check_integrity(&summary), Err(err));
+            assert_eq!(
+                dataset.evaluate(Epoch::from_et_seconds(50.0), &summary),
+                Err(InterpolationError::InterpDecoding {
+                    source: DecodingError::Integrity { source: err }
+                })
+            );
+        }
+    }
+
     #[test]
     fn too_small() {
         if Type2ChebyshevSet::from_f64_slice(&[0.1, 0.2, 0.3, 0.4])
@@ -294,16 +625,17 @@ mod chebyshev_ut {
     }
 
     #[test]
-    fn subnormal() {
+    fn non_finite() {
         match Type2ChebyshevSet::from_f64_slice(&[0.0, f64::INFINITY, 0.0, 0.0, 0.0]) {
             Ok(_) => panic!("test failed on invalid init_epoch"),
             Err(e) => {
                 assert_eq!(
                     e,
                     DecodingError::Integrity {
-                        source: IntegrityError::SubNormal {
+                        source: IntegrityError::InfiniteValue {
                             dataset: "Chebyshev Type 2",
                             variable: "seconds since J2000 ET",
+                            offset: 1,
                         },
                     }
                 );
@@ -316,9 +648,10 @@ mod chebyshev_ut {
                 assert_eq!(
                     e,
                     DecodingError::Integrity {
-                        source: IntegrityError::SubNormal {
+                        source: IntegrityError::InfiniteValue {
                             dataset: "Chebyshev Type 2",
                             variable: "interval length in seconds",
+                            offset: 2,
                         },
                     }
                 );
@@ -345,14 +678,15 @@ mod chebyshev_ut {
         // Load a slice whose metadata is OK but the record data is not
         let dataset =
             Type2ChebyshevSet::from_f64_slice(&[f64::INFINITY, 0.0, 2e-16, 0.0, 0.0]).unwrap();
-        match dataset.check_integrity() {
+        match dataset.check_integrity(&SPKSummaryRecord::default()) {
             Ok(_) => panic!("test failed on invalid interval_length"),
             Err(e) => {
                 assert_eq!(
                     e,
-                    IntegrityError::SubNormal {
+                    IntegrityError::InfiniteValue {
                         dataset: "Chebyshev Type 2",
                         variable: "one of the record data",
+                        offset: 0,
                     },
                 );
             }
@@ -0,0 +1,542 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::ResultExt;
+
+use crate::{
+    errors::{DecodingError, IntegrityError},
+    math::{
+        interpolation::{chebyshev_eval, InterpDecodingSnafu, InterpolationError},
+        Vector3,
+    },
+    naif::daf::{
+        datatypes::{
+            check_finite, check_summary_coverage, data_epoch, nth_record_data, NO_RECORDS,
+        },
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
+};
+
+use super::{epoch_registry::search_epoch, generic_segment::GenericSegment, Type3ChebyshevRecord};
+
+/// Chebyshev polynomials of position and velocity over intervals of unequal lengths, stored in a generic segment.
+///
+/// Each packet has the same layout as a Type 3 record: the midpoint and radius of its interval, then the coefficients.
+#[derive(PartialEq)]
+pub struct ChebyshevSetType14<'a> {
+    /// Degree of the Chebyshev polynomials
+    pub degree: usize,
+    /// Total number of packets stored in this data
+    pub num_records: usize,
+    /// Coefficient packets, ANISE expects these to be ordered chronologically with the epoch data.
+    pub record_data: &'a [f64],
+    /// Start epoch of the interval of each packet
+    pub epoch_data: &'a [f64],
+    /// Epoch registry to reduce the search space in epoch data.
+    pub epoch_registry: &'a [f64],
+    /// Offsets in the segment data of the record data, the epoch data, and the epoch registry
+    pub data_offsets: [usize; 3],
+}
+
+impl<'a> ChebyshevSetType14<'a> {
+    /// Returns the number of doubles in each packet.
+    pub fn packet_size(&self) -> usize {
+        6 * (self.degree + 1) + 2
+    }
+}
+
+impl<'a> fmt::Display for ChebyshevSetType14<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Chebyshev Type 14 with {} packets of degree {} ({} epoch directories)",
+            self.num_records,
+            self.degree,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for ChebyshevSetType14<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = Type3ChebyshevRecord<'a>;
+    const DATASET_NAME: &'static str = "Chebyshev Type 14";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        let segment = GenericSegment::from_f64_slice(Self::DATASET_NAME, slice)?;
+
+        // The only constant is the degree of the polynomials
+        let degree_f64 = segment.constants.first().copied().unwrap_or(f64::NAN);
+        if segment.constants.len() != 1
+            || !degree_f64.is_finite()
+            || degree_f64 < 0.0
+            || degree_f64.fract() != 0.0
+        {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "Chebyshev degree",
+                    value: degree_f64,
+                    reason: "must be the only constant, as a non-negative integer",
+                },
+            });
+        }
+
+        let me = Self {
+            degree: degree_f64 as usize,
+            num_records: segment.num_packets,
+            record_data: segment.packet_data,
+            epoch_data: segment.reference_values,
+            epoch_registry: segment.reference_directory,
+            data_offsets: segment.offsets,
+        };
+
+        if segment.packet_size != me.packet_size() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "packet size",
+                    value: segment.packet_size as f64,
+                    reason: "must be two plus six times the number of coefficients per component",
+                },
+            });
+        }
+
+        segment
+            .check_references(Self::DATASET_NAME)
+            .map_err(|source| DecodingError::Integrity { source })?;
+
+        Ok(me)
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let size = self.packet_size();
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            size,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(NO_RECORDS);
+        }
+        // Each packet is only evaluated within its own interval.
+        let first = self.nth_record(0).context(InterpDecodingSnafu)?;
+        let last = self
+            .nth_record(self.num_records - 1)
+            .context(InterpDecodingSnafu)?;
+        Ok((
+            data_epoch(
+                Self::DATASET_NAME,
+                "start of the first packet",
+                first.midpoint_et_s - first.radius.to_seconds(),
+            )?,
+            data_epoch(
+                Self::DATASET_NAME,
+                "end of the last packet",
+                last.midpoint_et_s + last.radius.to_seconds(),
+            )?,
+        ))
+    }
+
+    /// Evaluates the packet whose interval starts at the latest epoch which is not after the requested epoch.
+    ///
+    /// The position and the velocity are each evaluated from their own coefficients, as they are in SPICE. An epoch outside of the
+    /// interval of that packet, e.g. if the reference epochs do not match the packets, is an error instead of an extrapolation.
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if self.num_records == 0
+            || epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        let epoch_et_s = epoch.to_et_seconds();
+        let idx = match search_epoch(self.epoch_data, self.epoch_registry, epoch_et_s) {
+            Ok(idx) => idx,
+            Err(0) => 0,
+            Err(idx) => idx - 1,
+        };
+
+        let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+        let radius_s = record.radius.to_seconds();
+        if radius_s <= 0.0 {
+            return Err(InterpolationError::CorruptedData {
+                what: "Chebyshev Type 14 packet radius is not strictly positive",
+            });
+        }
+
+        let mut state = Vector3::zeros();
+        let mut rate = Vector3::zeros();
+
+        for (cno, coeffs) in [record.x_coeffs, record.y_coeffs, record.z_coeffs]
+            .iter()
+            .enumerate()
+        {
+            (state[cno], _) = chebyshev_eval(coeffs, radius_s, record.midpoint_et_s, epoch_et_s)?;
+        }
+
+        for (cno, coeffs) in [record.vx_coeffs, record.vy_coeffs, record.vz_coeffs]
+            .iter()
+            .enumerate()
+        {
+            (rate[cno], _) = chebyshev_eval(coeffs, radius_s, record.midpoint_et_s, epoch_et_s)?;
+        }
+
+        Ok((state, rate))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.data_offsets[1],
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.data_offsets[2],
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            self.data_offsets[0],
+        )?;
+
+        check_summary_coverage(self, summary)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod chebyshev14_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::{interpolation::InterpolationError, Vector3},
+        naif::{
+            daf::{datatypes::generic_segment::build_generic_segment, NAIFDataSet},
+            spk::summary::SPKSummaryRecord,
+        },
+    };
+
+    use super::ChebyshevSetType14;
+
+    /// Degree 2 packet over [start, end] of the exact polynomial x = y = -z = t^2 (in kilometers, t in seconds).
+    fn packet(start_et_s: f64, end_et_s: f64) -> Vec<f64> {
+        let (mid, radius) = ((start_et_s + end_et_s) / 2.0, (end_et_s - start_et_s) / 2.0);
+        // With t = mid + radius * tau: t^2 = mid^2 + radius^2 / 2 + 2 mid radius T1 + radius^2 / 2 T2
+        let pos = [
+            mid * mid + radius * radius / 2.0,
+            2.0 * mid * radius,
+            radius * radius / 2.0,
+        ];
+        // And 2t = 2 mid + 2 radius T1
+        let vel = [2.0 * mid, 2.0 * radius, 0.0];
+        let mut packet = vec![mid, radius];
+        for sign in [1.0, 1.0, -1.0] {
+            packet.extend(pos.iter().map(|c| sign * c));
+        }
+        for sign in [1.0, 1.0, -1.0] {
+            packet.extend(vel.iter().map(|c| sign * c));
+        }
+        packet
+    }
+
+    #[test]
+    fn type14_evaluate() {
+        let bounds = [0.0, 10.0, 110.0, 115.0];
+        let mut packets = Vec::new();
+        for window in bounds.windows(2) {
+            packets.extend(packet(window[0], window[1]));
+        }
+        let data = build_generic_segment(&[2.0], &packets, 20, &bounds[..3], &[]);
+
+        let dataset = ChebyshevSetType14::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.degree, 2);
+        assert_eq!(dataset.num_records, 3);
+        assert_eq!(
+            format!("{dataset}"),
+            "Chebyshev Type 14 with 3 packets of degree 2 (0 epoch directories)"
+        );
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 115.0,
+            ..Default::default()
+        };
+        dataset.check_integrity(&summary).unwrap();
+        // The last packet ends at 115 seconds
+        let late_summary = SPKSummaryRecord {
+            end_epoch_et_s: 116.0,
+            ..summary
+        };
+        assert_eq!(
+            dataset.check_integrity(&late_summary),
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset: "Chebyshev Type 14",
+                summary_start_et_s: 0.0,
+                summary_end_et_s: 116.0,
+                data_start_et_s: 0.0,
+                data_end_et_s: 115.0,
+            })
+        );
+
+        for epoch_et_s in [0.0, 3.3, 10.0, 60.0, 109.99, 110.0, 112.5, 115.0] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(epoch_et_s), &summary)
+                .unwrap();
+            let exp = epoch_et_s * epoch_et_s;
+            assert!((pos[0] - exp).abs() < 1e-9, "x error at {epoch_et_s}");
+            assert!((pos[1] - exp).abs() < 1e-9, "y error at {epoch_et_s}");
+            assert!((pos[2] + exp).abs() < 1e-9, "z error at {epoch_et_s}");
+            assert!((vel[0] - 2.0 * epoch_et_s).abs() < 1e-9);
+            assert!((vel[2] + 2.0 * epoch_et_s).abs() < 1e-9);
+        }
+
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(115.1), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn type14_registry() {
+        // 250 packets of one second each: the epoch registry has two entries.
+        let mut packets = Vec::new();
+        let starts: Vec<f64> = (0..250).map(|i| i as f64).collect();
+        for start in &starts {
+            packets.extend(packet(*start, start + 1.0));
+        }
+        let data = build_generic_segment(&[2.0], &packets, 20, &starts, &[100.0, 200.0]);
+        let dataset = ChebyshevSetType14::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 250.0,
+            ..Default::default()
+        };
+        for epoch_et_s in [0.5, 99.5, 100.0, 150.25, 200.0, 249.9] {
+            let (pos, _) = dataset
+                .evaluate(Epoch::from_et_seconds(epoch_et_s), &summary)
+                .unwrap();
+            assert!((pos[0] - epoch_et_s * epoch_et_s).abs() < 1e-8);
+        }
+    }
+
+    /// Packet over [start, end] fitted to a circular orbit, whose radius is 7000 km and whose period is about 105 minutes.
+    fn circular_packet(start_et_s: f64, end_et_s: f64, degree: usize) -> Vec<f64> {
+        let omega_rad_s = 1e-3;
+        let (mid, radius) = ((start_et_s + end_et_s) / 2.0, (end_et_s - start_et_s) / 2.0);
+        // Chebyshev coefficients from the values at the Chebyshev nodes, which is exact up to the degree of the fit.
+        let num_nodes = degree + 1;
+        let fit = |f: &dyn Fn(f64) -> f64| -> Vec<f64> {
+            (0..num_nodes)
+                .map(|k| {
+                    let sum: f64 = (0..num_nodes)
+                        .map(|j| {
+                            let theta = core::f64::consts::PI * (j as f64 + 0.5) / num_nodes as f64;
+                            f(mid + radius * theta.cos()) * (k as f64 * theta).cos()
+                        })
+                        .sum();
+                    let scale = if k == 0 { 1.0 } else { 2.0 };
+                    scale * sum / num_nodes as f64
+                })
+                .collect()
+        };
+        let mut packet = vec![mid, radius];
+        packet.extend(fit(&|t| 7000.0 * (omega_rad_s * t).cos()));
+        packet.extend(fit(&|t| 7000.0 * (omega_rad_s * t).sin()));
+        packet.extend(fit(&|t| 700.0 * (2.0 * omega_rad_s * t).sin()));
+        packet.extend(fit(&|t| -7.0 * (omega_rad_s * t).sin()));
+        packet.extend(fit(&|t| 7.0 * (omega_rad_s * t).cos()));
+        packet.extend(fit(&|t| 1.4 * (2.0 * omega_rad_s * t).cos()));
+        packet
+    }
+
+    #[test]
+    fn type14_continuity() {
+        let degree = 14;
+        let bounds = [0.0, 300.0, 1200.0, 1500.0, 2400.0, 2450.0, 3000.0];
+        let mut packets = Vec::new();
+        for window in bounds.windows(2) {
+            packets.extend(circular_packet(window[0], window[1], degree));
+        }
+        let data = build_generic_segment(
+            &[degree as f64],
+            &packets,
+            6 * (degree + 1) + 2,
+            &bounds[..6],
+            &[],
+        );
+        let dataset = ChebyshevSetType14::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 3000.0,
+            ..Default::default()
+        };
+
+        let truth = |epoch: Epoch| {
+            let t = 1e-3 * epoch.to_et_seconds();
+            (
+                Vector3::new(7000.0 * t.cos(), 7000.0 * t.sin(), 700.0 * (2.0 * t).sin()),
+                Vector3::new(-7.0 * t.sin(), 7.0 * t.cos(), 1.4 * (2.0 * t).cos()),
+            )
+        };
+
+        // On either side of each boundary between two packets, the states only differ by the motion over two nanoseconds.
+        for boundary in &bounds[1..6] {
+            let epoch = Epoch::from_et_seconds(*boundary);
+            let (before, after) = (epoch - 1_i64.nanoseconds(), epoch + 1_i64.nanoseconds());
+            let (before_pos, before_vel) = dataset.evaluate(before, &summary).unwrap();
+            let (after_pos, after_vel) = dataset.evaluate(after, &summary).unwrap();
+            let (true_before_pos, true_before_vel) = truth(before);
+            let (true_after_pos, true_after_vel) = truth(after);
+
+            let pos_jump_km =
+                ((after_pos - before_pos) - (true_after_pos - true_before_pos)).norm();
+            let vel_jump_km_s =
+                ((after_vel - before_vel) - (true_after_vel - true_before_vel)).norm();
+            assert!(
+                pos_jump_km < 1e-9,
+                "position jump of {pos_jump_km:e} km at {boundary}"
+            );
+            assert!(
+                vel_jump_km_s < 1e-12,
+                "velocity jump of {vel_jump_km_s:e} km/s at {boundary}"
+            );
+
+            let (pos, vel) = dataset.evaluate(epoch, &summary).unwrap();
+            assert!((pos - truth(epoch).0).norm() < 1e-9);
+            assert!((vel - truth(epoch).1).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn mismatched_reference_epochs() {
+        // The second reference epoch is after the start of the interval of the second packet,
+        // so the first packet would be extrapolated between them.
+        let mut packets = packet(0.0, 10.0);
+        packets.extend(packet(10.0, 20.0));
+        let data = build_generic_segment(&[2.0], &packets, 20, &[0.0, 12.0], &[]);
+        let dataset = ChebyshevSetType14::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 20.0,
+            ..Default::default()
+        };
+
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(9.0), &summary)
+            .is_ok());
+        assert_eq!(
+            dataset.evaluate(Epoch::from_et_seconds(11.0), &summary),
+            Err(InterpolationError::OutsideChebyshevDomain {
+                epoch_et_s: 11.0,
+                normalized_time: 1.2
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        let mut packets = packet(0.0, 10.0);
+        packets.extend(packet(10.0, 20.0));
+
+        // One reference epoch missing
+        let data = build_generic_segment(&[2.0], &packets, 20, &[0.0], &[]);
+        match ChebyshevSetType14::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on missing reference epochs"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "Chebyshev Type 14",
+                        variable: "number of reference values (NREF)",
+                        value: 1.0,
+                        reason: "must match the number of packets (NPKT)",
+                    }
+                }
+            ),
+        }
+
+        // A directory entry for only two reference epochs
+        let data = build_generic_segment(&[2.0], &packets, 20, &[0.0, 10.0], &[10.0]);
+        match ChebyshevSetType14::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on invalid directory"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "Chebyshev Type 14",
+                        variable: "reference directory size (NRDR)",
+                        value: 1.0,
+                        reason:
+                            "must be one entry per 100 reference values, excluding the last one",
+                    }
+                }
+            ),
+        }
+
+        // The packet size does not match the degree
+        let data = build_generic_segment(&[3.0], &packets, 20, &[0.0, 10.0], &[]);
+        match ChebyshevSetType14::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on invalid packet size"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "Chebyshev Type 14",
+                        variable: "packet size",
+                        value: 20.0,
+                        reason:
+                            "must be two plus six times the number of coefficients per component",
+                    }
+                }
+            ),
+        }
+
+        // More packets declared than stored
+        let mut data = build_generic_segment(&[2.0], &packets, 20, &[0.0, 10.0], &[]);
+        let npkt_idx = data.len() - 17 + 11;
+        data[npkt_idx] = 3.0;
+        match ChebyshevSetType14::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on too many packets"),
+            Err(e) => assert!(matches!(e, DecodingError::InaccessibleBytes { .. })),
+        }
+    }
+}
@@ -0,0 +1,499 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::{Duration, Epoch, TimeUnits, JD_J2000};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{
+            chebyshev_eval_poly, chebyshev_integral_eval, InterpDecodingSnafu, InterpolationError,
+        },
+        Vector3,
+    },
+    naif::daf::{
+        datatypes::{
+            check_epoch_span, check_finite, check_summary_coverage, non_finite, nth_record_data,
+            NO_RECORDS,
+        },
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
+};
+
+/// Chebyshev coefficients of the velocity only, the position being the integral of the velocity from the
+/// position at the midpoint of each record. This is the type used by the INPOP ephemerides.
+///
+/// Its evaluation has not been validated against SPICE on an INPOP kernel yet (cf. the ignored
+/// `validate_chebyshev_type20_inpop_kernel` integration test).
+#[derive(PartialEq)]
+pub struct ChebyshevSetType20<'a> {
+    /// Distance scale in kilometers (DSCALE), i.e. the distance unit of the data
+    pub distance_scale_km: f64,
+    /// Time scale in seconds (TSCALE), i.e. the time unit of the velocity data
+    pub time_scale_s: f64,
+    pub init_epoch: Epoch,
+    pub interval_length: Duration,
+    pub rsize: usize,
+    pub num_records: usize,
+    pub record_data: &'a [f64],
+}
+
+impl<'a> ChebyshevSetType20<'a> {
+    /// Returns the degree of the velocity polynomials.
+    pub fn degree(&self) -> usize {
+        (self.rsize / 3).saturating_sub(2)
+    }
+
+    /// Checks that the record size matches three sets of coefficients of the same degree each followed by a position, and the number of records.
+    fn check_rsize(&self) -> Result<(), IntegrityError> {
+        if self.rsize < 6 || !self.rsize.is_multiple_of(3) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "record size (RSIZE)",
+                value: self.rsize as f64,
+                reason: "must be three times the number of coefficients per component plus one",
+            });
+        }
+
+        if self.rsize.checked_mul(self.num_records) != Some(self.record_data.len()) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of records",
+                value: self.num_records as f64,
+                reason:
+                    "record data length must be the record size (RSIZE) times the number of records",
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the record covering the provided epoch.
+    fn record_idx<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<usize, InterpolationError> {
+        if self.num_records == 0
+            || epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            // No need to go any further.
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        // Unlike Type 2, the records are counted from the initial epoch, which may precede the start of the segment.
+        let init_delta_s = (epoch - self.init_epoch).to_seconds();
+
+        Ok(
+            ((init_delta_s / self.interval_length.to_seconds()).max(0.0) as usize)
+                .min(self.num_records - 1),
+        )
+    }
+}
+
+impl<'a> fmt::Display for ChebyshevSetType20<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "start: {:E}\tlength: {}\tDSCALE: {} km\tTSCALE: {} s\trsize: {}\tnum_records: {}\tlen data: {}",
+            self.init_epoch,
+            self.interval_length,
+            self.distance_scale_km,
+            self.time_scale_s,
+            self.rsize,
+            self.num_records,
+            self.record_data.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for ChebyshevSetType20<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = Type20ChebyshevRecord<'a>;
+    const DATASET_NAME: &'static str = "Chebyshev Type 20";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 7,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 7_usize,
+                got: slice.len()
+            }
+        );
+        // For this kind of record, the data is stored at the very end of the dataset:
+        // DSCALE, TSCALE, the integer and fractional parts of the initial Julian date (TDB), the interval length in days,
+        // the record size, and the number of records.
+        let meta = &slice[slice.len() - 7..];
+
+        for (n, (value, variable)) in meta[..5]
+            .iter()
+            .zip([
+                "distance scale (DSCALE)",
+                "time scale (TSCALE)",
+                "initial Julian date",
+                "initial fractional Julian date",
+                "interval length in days",
+            ])
+            .enumerate()
+        {
+            if !value.is_finite() {
+                return Err(DecodingError::Integrity {
+                    source: non_finite(Self::DATASET_NAME, variable, *value, slice.len() - 7 + n),
+                });
+            }
+        }
+
+        for (value, variable) in [
+            (meta[0], "distance scale (DSCALE)"),
+            (meta[1], "time scale (TSCALE)"),
+            (meta[4], "interval length in days"),
+        ] {
+            if value <= 0.0 {
+                return Err(DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: Self::DATASET_NAME,
+                        variable,
+                        value,
+                        reason: "must be strictly greater than zero",
+                    },
+                });
+            }
+        }
+
+        // The integer and fractional parts are kept separate until now to preserve precision.
+        let init_et_s = ((meta[2] - JD_J2000) + meta[3]) * 86_400.0;
+        check_epoch_span(Self::DATASET_NAME, "initial Julian date", init_et_s)
+            .map_err(|source| DecodingError::Integrity { source })?;
+
+        Ok(Self {
+            distance_scale_km: meta[0],
+            time_scale_s: meta[1],
+            init_epoch: Epoch::from_et_seconds(init_et_s),
+            interval_length: meta[4].days(),
+            rsize: meta[5] as usize,
+            num_records: meta[6] as usize,
+            record_data: &slice[0..slice.len() - 7],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })?;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            self.rsize,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(NO_RECORDS);
+        }
+        Ok((
+            self.init_epoch,
+            self.init_epoch + (self.num_records as f64) * self.interval_length,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })
+            .context(InterpDecodingSnafu)?;
+
+        let record_idx = self.record_idx(epoch, summary)?;
+
+        let radius_s = self.interval_length.to_seconds() / 2.0;
+        let midpoint = self.init_epoch + self.interval_length * (record_idx as f64 + 0.5);
+        let normalized_time = (epoch - midpoint).to_seconds() / radius_s;
+
+        let record = self.nth_record(record_idx).context(InterpDecodingSnafu)?;
+
+        let mut pos = Vector3::zeros();
+        let mut vel = Vector3::zeros();
+
+        for (cno, (coeffs, midpoint_pos)) in [
+            (record.x_coeffs, record.x_midpoint),
+            (record.y_coeffs, record.y_midpoint),
+            (record.z_coeffs, record.z_midpoint),
+        ]
+        .iter()
+        .enumerate()
+        {
+            // The velocity is in DSCALE per TSCALE, so the integral over time must be expressed in TSCALE.
+            let integral = chebyshev_integral_eval(normalized_time, coeffs, epoch, self.degree())?;
+            pos[cno] = midpoint_pos + integral * radius_s / self.time_scale_s;
+            vel[cno] = chebyshev_eval_poly(normalized_time, coeffs, epoch, self.degree())?;
+        }
+
+        Ok((
+            pos * self.distance_scale_km,
+            vel * self.distance_scale_km / self.time_scale_s,
+        ))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        self.check_rsize()?;
+
+        check_summary_coverage(self, summary)
+    }
+}
+
+/// A record of a Chebyshev Type 20 set, in the distance (DSCALE) and time (TSCALE) units of that set.
+pub struct Type20ChebyshevRecord<'a> {
+    pub x_coeffs: &'a [f64],
+    pub x_midpoint: f64,
+    pub y_coeffs: &'a [f64],
+    pub y_midpoint: f64,
+    pub z_coeffs: &'a [f64],
+    pub z_midpoint: f64,
+}
+
+impl<'a> fmt::Display for Type20ChebyshevRecord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "midpoint: [{}, {}, {}]\nvx: {:?}\nvy: {:?}\nvz: {:?}",
+            self.x_midpoint,
+            self.y_midpoint,
+            self.z_midpoint,
+            self.x_coeffs,
+            self.y_coeffs,
+            self.z_coeffs
+        )
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for Type20ChebyshevRecord<'a> {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        // Each component is its velocity coefficients followed by its position at the midpoint.
+        let block = slice.len() / 3;
+        Self {
+            x_coeffs: &slice[..block - 1],
+            x_midpoint: slice[block - 1],
+            y_coeffs: &slice[block..2 * block - 1],
+            y_midpoint: slice[2 * block - 1],
+            z_coeffs: &slice[2 * block..3 * block - 1],
+            z_midpoint: slice[3 * block - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod chebyshev20_ut {
+    use hifitime::{Epoch, TimeUnits, JD_J2000};
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::interpolation::InterpolationError,
+        naif::{
+            daf::{NAIFDataSet, NAIFSummaryRecord},
+            spk::summary::SPKSummaryRecord,
+        },
+    };
+
+    use super::ChebyshevSetType20;
+
+    const AU_KM: f64 = 149_597_870.7;
+    const DAY_S: f64 = 86_400.0;
+
+    /// Quartic position in km, and its derivative in km/s.
+    fn poly(t: f64, scale: f64) -> (f64, f64) {
+        (
+            scale * (1e8 + 30.0 * t + 1e-4 * t.powi(2) + 2e-10 * t.powi(3) + 1e-16 * t.powi(4)),
+            scale * (30.0 + 2e-4 * t + 6e-10 * t.powi(2) + 4e-16 * t.powi(3)),
+        )
+    }
+
+    /// Builds a Type 20 data slice of records of one day starting six hours after J2000, in AU and days like INPOP.
+    fn type20_data(num_records: usize) -> Vec<f64> {
+        let init_et_s = 0.25 * DAY_S;
+        let radius_s = DAY_S / 2.0;
+        let mut data = Vec::new();
+        for n in 0..num_records {
+            let mid = init_et_s + DAY_S * n as f64 + radius_s;
+            for scale in [1.0, -2.0, 0.5] {
+                // Velocity as a polynomial of the normalized time, converted to the Chebyshev basis
+                let (b, c, d, e) = (30.0, 1e-4, 2e-10, 1e-16);
+                let a0 = b + 2.0 * c * mid + 3.0 * d * mid.powi(2) + 4.0 * e * mid.powi(3);
+                let a1 = radius_s * (2.0 * c + 6.0 * d * mid + 12.0 * e * mid.powi(2));
+                let a2 = radius_s.powi(2) * (3.0 * d + 12.0 * e * mid);
+                let a3 = radius_s.powi(3) * 4.0 * e;
+                let to_au_day = scale * DAY_S / AU_KM;
+                data.extend([
+                    (a0 + a2 / 2.0) * to_au_day,
+                    (a1 + 3.0 * a3 / 4.0) * to_au_day,
+                    a2 / 2.0 * to_au_day,
+                    a3 / 4.0 * to_au_day,
+                    poly(mid, scale).0 / AU_KM,
+                ]);
+            }
+        }
+        data.extend([AU_KM, DAY_S, JD_J2000, 0.25, 1.0, 15.0, num_records as f64]);
+        data
+    }
+
+    #[test]
+    fn type20_evaluate() {
+        let num_records = 4;
+        let data = type20_data(num_records);
+        let dataset = ChebyshevSetType20::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.25 * DAY_S,
+            end_epoch_et_s: 0.25 * DAY_S + DAY_S * num_records as f64,
+            ..Default::default()
+        };
+        dataset.check_integrity(&summary).unwrap();
+        let early_summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            ..summary
+        };
+        assert_eq!(
+            dataset.check_integrity(&early_summary),
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset: "Chebyshev Type 20",
+                summary_start_et_s: 0.0,
+                summary_end_et_s: summary.end_epoch_et_s,
+                data_start_et_s: 0.25 * DAY_S,
+                data_end_et_s: summary.end_epoch_et_s,
+            })
+        );
+        assert_eq!(dataset.degree(), 3);
+        assert_eq!(dataset.init_epoch, Epoch::from_et_seconds(0.25 * DAY_S));
+
+        // Start, midpoints, either side of the boundaries between records, and the end of the segment.
+        for t_days in [0.25, 0.75, 1.0, 1.25 - 1e-6, 1.25, 1.25 + 1e-6, 3.1, 4.25] {
+            let epoch = Epoch::from_et_seconds(t_days * DAY_S);
+            let (pos, vel) = dataset.evaluate(epoch, &summary).unwrap();
+            let t = epoch.to_et_seconds();
+            for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                let (exp_pos, exp_vel) = poly(t, *scale);
+                assert!(
+                    (pos[i] - exp_pos).abs() < 1e-6,
+                    "pos error at {t_days} days: {:.3e} km",
+                    pos[i] - exp_pos
+                );
+                assert!(
+                    (vel[i] - exp_vel).abs() < 1e-12,
+                    "vel error at {t_days} days: {:.3e} km/s",
+                    vel[i] - exp_vel
+                );
+            }
+        }
+
+        let epoch = summary.end_epoch() + 1.seconds();
+        assert_eq!(
+            dataset.evaluate(epoch, &summary),
+            Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.target_id,
+                center_id: summary.center_id,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        let invalid = |idx_from_end: usize, value: f64| {
+            let mut data = type20_data(2);
+            let idx = data.len() - idx_from_end;
+            data[idx] = value;
+            data
+        };
+
+        let data = invalid(7, 0.0);
+        match ChebyshevSetType20::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on invalid DSCALE"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "Chebyshev Type 20",
+                        variable: "distance scale (DSCALE)",
+                        value: 0.0,
+                        reason: "must be strictly greater than zero",
+                    },
+                }
+            ),
+        }
+
+        let data = invalid(4, f64::NAN);
+        match ChebyshevSetType20::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on invalid fractional Julian date"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::NaNValue {
+                        dataset: "Chebyshev Type 20",
+                        variable: "initial fractional Julian date",
+                        offset: data.len() - 4,
+                    },
+                }
+            ),
+        }
+
+        for (idx_from_end, value, variable) in [
+            (2, 14.0, "record size (RSIZE)"),
+            (1, 3.0, "number of records"),
+        ] {
+            let data = invalid(idx_from_end, value);
+            let dataset = ChebyshevSetType20::from_f64_slice(&data).unwrap();
+            match dataset.check_integrity(&SPKSummaryRecord::default()) {
+                Ok(_) => panic!("test failed on invalid {variable}"),
+                Err(IntegrityError::InvalidValue {
+                    variable: err_variable,
+                    value: err_value,
+                    ..
+                }) => {
+                    assert_eq!(err_variable, variable);
+                    assert_eq!(err_value, value);
+                }
+                Err(e) => panic!("unexpected error {e}"),
+            }
+        }
+
+        let mut data = type20_data(2);
+        data[3] = f64::INFINITY;
+        assert_eq!(
+            ChebyshevSetType20::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InfiniteValue {
+                dataset: "Chebyshev Type 20",
+                variable: "one of the record data",
+                offset: 3,
+            })
+        );
+    }
+}
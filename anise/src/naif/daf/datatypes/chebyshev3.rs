@@ -18,7 +18,13 @@ use crate::{
         interpolation::{chebyshev_eval_poly, InterpDecodingSnafu, InterpolationError},
         Vector3,
     },
-    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+    naif::daf::{
+        datatypes::{
+            check_epoch_span, check_finite, check_summary_coverage, non_finite, nth_record_data,
+            NO_RECORDS,
+        },
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
 };
 
 #[derive(PartialEq)]
@@ -32,7 +38,31 @@ pub struct Type3ChebyshevSet<'a> {
 
 impl<'a> Type3ChebyshevSet<'a> {
     pub fn degree(&self) -> usize {
-        (self.rsize - 2) / 6 - 1
+        (self.rsize.saturating_sub(2) / 6).saturating_sub(1)
+    }
+
+    /// Checks that the record size matches six sets of coefficients of the same degree, and the number of records.
+    fn check_rsize(&self) -> Result<(), IntegrityError> {
+        if self.rsize < 8 || !(self.rsize - 2).is_multiple_of(6) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "record size (RSIZE)",
+                value: self.rsize as f64,
+                reason: "must be two plus six times the number of coefficients per component",
+            });
+        }
+
+        if self.rsize.checked_mul(self.num_records) != Some(self.record_data.len()) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of records",
+                value: self.num_records as f64,
+                reason:
+                    "record data length must be the record size (RSIZE) times the number of records",
+            });
+        }
+
+        Ok(())
     }
 
     fn spline_idx<S: NAIFSummaryRecord>(
@@ -48,6 +78,8 @@ impl<'a> Type3ChebyshevSet<'a> {
                 req: epoch,
                 start: summary.start_epoch(),
                 end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
             });
         }
 
@@ -55,7 +87,76 @@ impl<'a> Type3ChebyshevSet<'a> {
 
         let ephem_start_delta_s = epoch.to_et_seconds() - summary.start_epoch_et_s();
 
-        Ok(((ephem_start_delta_s / window_duration_s) as usize + 1).min(self.num_records))
+        Ok(((ephem_start_delta_s / window_duration_s) as usize)
+            .saturating_add(1)
+            .min(self.num_records))
+    }
+
+    /// Returns the record covering the provided epoch and the normalized time of that epoch in its spline.
+    fn record_at<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Type3ChebyshevRecord<'a>, f64), InterpolationError> {
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })
+            .context(InterpDecodingSnafu)?;
+
+        let spline_idx = self.spline_idx(epoch, summary)?;
+
+        let window_duration_s = self.interval_length.to_seconds();
+        let radius_s = window_duration_s / 2.0;
+
+        let record = self
+            .nth_record(spline_idx.saturating_sub(1))
+            .context(InterpDecodingSnafu)?;
+
+        let normalized_time = (epoch.to_et_seconds() - record.midpoint_et_s) / radius_s;
+
+        Ok((record, normalized_time))
+    }
+
+    /// Evaluates the Chebyshev series of each of the three provided components.
+    fn eval_components(
+        &self,
+        coeffs: [&[f64]; 3],
+        normalized_time: f64,
+        epoch: Epoch,
+    ) -> Result<Vector3, InterpolationError> {
+        let mut vector = Vector3::zeros();
+        for (cno, coeffs) in coeffs.iter().enumerate() {
+            vector[cno] = chebyshev_eval_poly(normalized_time, coeffs, epoch, self.degree())?;
+        }
+        Ok(vector)
+    }
+
+    /// Evaluates only the position at the provided epoch. The position and velocity are stored as separate Chebyshev series,
+    /// so this skips the evaluation of the velocity, and the position is identical to that returned by `evaluate`.
+    pub fn evaluate_position<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Vector3, InterpolationError> {
+        let (record, normalized_time) = self.record_at(epoch, summary)?;
+        self.eval_components(
+            [record.x_coeffs, record.y_coeffs, record.z_coeffs],
+            normalized_time,
+            epoch,
+        )
+    }
+
+    /// Evaluates only the velocity at the provided epoch, skipping the evaluation of the position like `evaluate_position`.
+    pub fn evaluate_velocity<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Vector3, InterpolationError> {
+        let (record, normalized_time) = self.record_at(epoch, summary)?;
+        self.eval_components(
+            [record.vx_coeffs, record.vy_coeffs, record.vz_coeffs],
+            normalized_time,
+            epoch,
+        )
     }
 }
 
@@ -91,22 +192,32 @@ impl<'a> NAIFDataSet<'a> for Type3ChebyshevSet<'a> {
         let seconds_since_j2000 = slice[slice.len() - 4];
         if !seconds_since_j2000.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "seconds since J2000 ET",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "seconds since J2000 ET",
+                    seconds_since_j2000,
+                    slice.len() - 4,
+                ),
             });
         }
 
+        check_epoch_span(
+            Self::DATASET_NAME,
+            "seconds since J2000 ET",
+            seconds_since_j2000,
+        )
+        .map_err(|source| DecodingError::Integrity { source })?;
         let start_epoch = Epoch::from_et_seconds(seconds_since_j2000);
 
         let interval_length_s = slice[slice.len() - 3];
         if !interval_length_s.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "interval length in seconds",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "interval length in seconds",
+                    interval_length_s,
+                    slice.len() - 3,
+                ),
             });
         } else if interval_length_s <= 0.0 {
             return Err(DecodingError::Integrity {
@@ -133,14 +244,22 @@ impl<'a> NAIFDataSet<'a> for Type3ChebyshevSet<'a> {
     }
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
-        Ok(Self::RecordKind::from_slice_f64(
-            self.record_data
-                .get(n * self.rsize..(n + 1) * self.rsize)
-                .ok_or(DecodingError::InaccessibleBytes {
-                    start: n * self.rsize,
-                    end: (n + 1) * self.rsize,
-                    size: self.record_data.len(),
-                })?,
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })?;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            self.rsize,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(NO_RECORDS);
+        }
+        Ok((
+            self.init_epoch,
+            self.init_epoch + (self.num_records as f64) * self.interval_length,
         ))
     }
 
@@ -149,51 +268,34 @@ impl<'a> NAIFDataSet<'a> for Type3ChebyshevSet<'a> {
         epoch: Epoch,
         summary: &S,
     ) -> Result<(Vector3, Vector3), InterpolationError> {
-        let spline_idx = self.spline_idx(epoch, summary)?;
-
-        let window_duration_s = self.interval_length.to_seconds();
-        let radius_s = window_duration_s / 2.0;
-
-        let record = self
-            .nth_record(spline_idx - 1)
-            .context(InterpDecodingSnafu)?;
-
-        let normalized_time = (epoch.to_et_seconds() - record.midpoint_et_s) / radius_s;
-
-        let mut state = Vector3::zeros();
-        let mut rate = Vector3::zeros();
-
-        for (cno, coeffs) in [record.x_coeffs, record.y_coeffs, record.z_coeffs]
-            .iter()
-            .enumerate()
-        {
-            let val = chebyshev_eval_poly(normalized_time, coeffs, epoch, self.degree())?;
-            state[cno] = val;
-        }
-
-        for (cno, coeffs) in [record.vx_coeffs, record.vy_coeffs, record.vz_coeffs]
-            .iter()
-            .enumerate()
-        {
-            let val = chebyshev_eval_poly(normalized_time, coeffs, epoch, self.degree())?;
-            rate[cno] = val;
-        }
+        let (record, normalized_time) = self.record_at(epoch, summary)?;
+
+        let state = self.eval_components(
+            [record.x_coeffs, record.y_coeffs, record.z_coeffs],
+            normalized_time,
+            epoch,
+        )?;
+        let rate = self.eval_components(
+            [record.vx_coeffs, record.vy_coeffs, record.vz_coeffs],
+            normalized_time,
+            epoch,
+        )?;
 
         Ok((state, rate))
     }
 
-    fn check_integrity(&self) -> Result<(), IntegrityError> {
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
         // Verify that none of the data is invalid once when we load it.
-        for val in self.record_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the record data",
-                });
-            }
-        }
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
 
-        Ok(())
+        self.check_rsize()?;
+
+        check_summary_coverage(self, summary)
     }
 
     fn truncate<S: NAIFSummaryRecord>(
@@ -202,21 +304,39 @@ impl<'a> NAIFDataSet<'a> for Type3ChebyshevSet<'a> {
         new_start: Option<Epoch>,
         new_end: Option<Epoch>,
     ) -> Result<Self, InterpolationError> {
+        self.check_rsize()
+            .map_err(|source| DecodingError::Integrity { source })
+            .context(InterpDecodingSnafu)?;
+
         let start_idx = if let Some(start) = new_start {
-            self.spline_idx(start, summary)? - 1
+            self.spline_idx(start, summary)?.saturating_sub(1)
         } else {
             0
         };
 
         let end_idx = if let Some(end) = new_end {
+            // The record following that of the new end is kept, unless the new end is in the last record.
             self.spline_idx(end, summary)?
+                .min(self.num_records.saturating_sub(1))
         } else {
-            self.num_records - 1
+            self.num_records.saturating_sub(1)
         };
 
-        self.record_data = &self.record_data[start_idx * self.rsize..(end_idx + 1) * self.rsize];
-        self.num_records = (self.record_data.len() / self.rsize) - 1;
-        self.init_epoch = self.nth_record(0).unwrap().midpoint_epoch() - 0.5 * self.interval_length;
+        self.record_data = self
+            .record_data
+            .get(start_idx * self.rsize..(end_idx + 1) * self.rsize)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start: start_idx * self.rsize,
+                end: (end_idx + 1) * self.rsize,
+                size: self.record_data.len(),
+            })
+            .context(InterpDecodingSnafu)?;
+        self.num_records = self.record_data.len() / self.rsize;
+        self.init_epoch = self
+            .nth_record(0)
+            .context(InterpDecodingSnafu)?
+            .midpoint_epoch()
+            - 0.5 * self.interval_length;
 
         Ok(self)
     }
@@ -292,13 +412,181 @@ impl<'a> NAIFDataRecord<'a> for Type3ChebyshevRecord<'a> {
 
 #[cfg(test)]
 mod chebyshev_ut {
+    use hifitime::Epoch;
+
     use crate::{
         errors::{DecodingError, IntegrityError},
-        naif::daf::NAIFDataSet,
+        math::interpolation::InterpolationError,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
     };
 
     use super::Type3ChebyshevSet;
 
+    /// Evaluates the Chebyshev series of the first kind up to degree two.
+    fn cheb2(coeffs: &[f64], x: f64) -> f64 {
+        coeffs[0] + coeffs[1] * x + coeffs[2] * (2.0 * x * x - 1.0)
+    }
+
+    /// Builds a Type 3 data slice of degree two, with the requested record size.
+    fn type3_data(num_records: usize, interval_s: f64, rsize: usize) -> Vec<f64> {
+        let mut data = Vec::new();
+        for n in 0..num_records {
+            data.extend([interval_s * (n as f64 + 0.5), interval_s / 2.0]);
+            data.extend(
+                (0..18).map(|k| (1 + n * 18 + k) as f64 * if k % 2 == 0 { 1.0 } else { -0.5 }),
+            );
+        }
+        // Metadata: initial epoch, interval length, record size, number of records
+        data.extend([0.0, interval_s, rsize as f64, num_records as f64]);
+        data
+    }
+
+    #[test]
+    fn type3_evaluate() {
+        let interval_s = 100.0;
+        let data = type3_data(3, interval_s, 20);
+        let dataset = Type3ChebyshevSet::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.degree(), 2);
+
+        let late_summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 301.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            dataset.check_integrity(&late_summary),
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset: "Chebyshev Type 3",
+                summary_start_et_s: 0.0,
+                summary_end_et_s: 301.0,
+                data_start_et_s: 0.0,
+                data_end_et_s: 300.0,
+            })
+        );
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 300.0,
+            ..Default::default()
+        };
+
+        // Within the records, exactly at the boundaries between records, and at the end of the segment.
+        for (t, record) in [
+            (0.0, 0),
+            (42.0, 0),
+            (100.0, 1),
+            (175.5, 1),
+            (200.0, 2),
+            (300.0, 2),
+        ] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap();
+            let x = (t - interval_s * (record as f64 + 0.5)) / (interval_s / 2.0);
+            let coeffs = &data[record * 20 + 2..(record + 1) * 20];
+            for i in 0..3 {
+                let exp_pos = cheb2(&coeffs[3 * i..3 * i + 3], x);
+                let exp_vel = cheb2(&coeffs[9 + 3 * i..9 + 3 * i + 3], x);
+                assert!((pos[i] - exp_pos).abs() < 1e-12, "pos error at {t} s");
+                assert!((vel[i] - exp_vel).abs() < 1e-12, "vel error at {t} s");
+            }
+
+            // The position and velocity only evaluations match the full evaluation exactly
+            let epoch = Epoch::from_et_seconds(t);
+            assert_eq!(dataset.evaluate_position(epoch, &summary).unwrap(), pos);
+            assert_eq!(dataset.evaluate_velocity(epoch, &summary).unwrap(), vel);
+        }
+    }
+
+    #[test]
+    fn type3_velocity_consistency() {
+        // Single record where the velocity coefficients are the derivative of the position coefficients:
+        // d/dt (a + b T1 + c T2) = (b T0 + 4c T1) / radius
+        let interval_s = 3600.0;
+        let radius_s = interval_s / 2.0;
+        let pos_coeffs = [[7e3, 1.2e3, -3.4], [-2e3, 5.6e2, 7.8], [1e2, -9.1e1, 2.3]];
+        let mut data = vec![radius_s, radius_s];
+        for coeffs in pos_coeffs {
+            data.extend(coeffs);
+        }
+        for [_, b, c] in pos_coeffs {
+            data.extend([b / radius_s, 4.0 * c / radius_s, 0.0]);
+        }
+        data.extend([0.0, interval_s, 20.0, 1.0]);
+
+        let dataset = Type3ChebyshevSet::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: interval_s,
+            ..Default::default()
+        };
+
+        let h_s = 1e-2;
+        for t in [1.0, 600.0, 1800.0, 2999.9, 3599.0] {
+            let (_, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap();
+            let (pos_before, _) = dataset
+                .evaluate(Epoch::from_et_seconds(t - h_s), &summary)
+                .unwrap();
+            let (pos_after, _) = dataset
+                .evaluate(Epoch::from_et_seconds(t + h_s), &summary)
+                .unwrap();
+            let numerical_vel = (pos_after - pos_before) / (2.0 * h_s);
+            assert!(
+                (vel - numerical_vel).norm() < 1e-6,
+                "velocity inconsistent with position at {t} s: {vel} vs {numerical_vel}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_rsize() {
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 300.0,
+            ..Default::default()
+        };
+
+        // Record sizes which are not six sets of coefficients, and a record size which doesn't match the number of records.
+        for (rsize, num_records, variable, value) in [
+            (6, 10, "record size (RSIZE)", 6.0),
+            (19, 3, "record size (RSIZE)", 19.0),
+            (26, 3, "number of records", 3.0),
+        ] {
+            let mut data = type3_data(3, 100.0, 20);
+            let len = data.len();
+            data[len - 2] = rsize as f64;
+            data[len - 1] = num_records as f64;
+            let dataset = Type3ChebyshevSet::from_f64_slice(&data).unwrap();
+
+            let err = IntegrityError::InvalidValue {
+                dataset: "Chebyshev Type 3",
+                variable,
+                value,
+                reason: if variable == "number of records" {
+                    "record data length must be the record size (RSIZE) times the number of records"
+                } else {
+                    "must be two plus six times the number of coefficients per component"
+                },
+            };
+            assert_eq!(dataset.check_integrity(&summary), Err(err));
+            assert_eq!(
+                dataset.evaluate(Epoch::from_et_seconds(150.0), &summary),
+                Err(InterpolationError::InterpDecoding {
+                    source: DecodingError::Integrity { source: err }
+                })
+            );
+        }
+    }
+
     #[test]
     fn too_small() {
         if Type3ChebyshevSet::from_f64_slice(&[0.1, 0.2, 0.3, 0.4])
@@ -313,16 +601,17 @@ mod chebyshev_ut {
     }
 
     #[test]
-    fn subnormal() {
+    fn non_finite() {
         match Type3ChebyshevSet::from_f64_slice(&[0.0, f64::INFINITY, 0.0, 0.0, 0.0]) {
             Ok(_) => panic!("test failed on invalid init_epoch"),
             Err(e) => {
                 assert_eq!(
                     e,
                     DecodingError::Integrity {
-                        source: IntegrityError::SubNormal {
+                        source: IntegrityError::InfiniteValue {
                             dataset: "Chebyshev Type 3",
                             variable: "seconds since J2000 ET",
+                            offset: 1,
                         },
                     }
                 );
@@ -335,9 +624,10 @@ mod chebyshev_ut {
                 assert_eq!(
                     e,
                     DecodingError::Integrity {
-                        source: IntegrityError::SubNormal {
+                        source: IntegrityError::InfiniteValue {
                             dataset: "Chebyshev Type 3",
                             variable: "interval length in seconds",
+                            offset: 2,
                         },
                     }
                 );
@@ -364,14 +654,15 @@ mod chebyshev_ut {
         // Load a slice whose metadata is OK but the record data is not
         let dataset =
             Type3ChebyshevSet::from_f64_slice(&[f64::INFINITY, 0.0, 2e-16, 0.0, 0.0]).unwrap();
-        match dataset.check_integrity() {
+        match dataset.check_integrity(&SPKSummaryRecord::default()) {
             Ok(_) => panic!("test failed on invalid interval_length"),
             Err(e) => {
                 assert_eq!(
                     e,
-                    IntegrityError::SubNormal {
+                    IntegrityError::InfiniteValue {
                         dataset: "Chebyshev Type 3",
                         variable: "one of the record data",
+                        offset: 0,
                     },
                 );
             }
@@ -0,0 +1,473 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpMathSnafu, InterpolationError},
+        propagation::propagate_two_body,
+        rotate_vector, Vector3,
+    },
+    naif::daf::{datatypes::check_finite, NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+};
+
+/// Number of doubles in a Type 15 segment.
+const TYPE15_RECORD_SIZE: usize = 16;
+
+/// Conic elements of a precessing conic, and the parameters of the central body driving its J2 precession.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct PrecessingConicRecord {
+    /// Epoch of periapsis, in seconds past J2000 TDB
+    pub periapsis_epoch_et_s: f64,
+    /// Pole of the trajectory, i.e. the direction of its angular momentum
+    pub trajectory_pole: Vector3,
+    /// Direction of periapsis
+    pub periapsis: Vector3,
+    pub semi_latus_rectum_km: f64,
+    pub eccentricity: f64,
+    /// J2 processing flag: 1 disables the precession of periapsis, 2 disables the regression of the node, and 3 disables both.
+    pub j2_flag: f64,
+    /// Pole of the central body
+    pub central_body_pole: Vector3,
+    pub gm_km3_s2: f64,
+    pub j2: f64,
+    pub central_body_radius_km: f64,
+}
+
+impl PrecessingConicRecord {
+    /// Returns whether the regression of the line of nodes is applied.
+    pub fn node_regression(&self) -> bool {
+        self.j2_flag != 2.0 && self.j2_flag != 3.0
+    }
+
+    /// Returns whether the precession of the line of apsides is applied.
+    pub fn apsidal_precession(&self) -> bool {
+        self.j2_flag != 1.0 && self.j2_flag != 3.0
+    }
+}
+
+impl fmt::Display for PrecessingConicRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for PrecessingConicRecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        Self {
+            periapsis_epoch_et_s: slice[0],
+            trajectory_pole: Vector3::new(slice[1], slice[2], slice[3]),
+            periapsis: Vector3::new(slice[4], slice[5], slice[6]),
+            semi_latus_rectum_km: slice[7],
+            eccentricity: slice[8],
+            j2_flag: slice[9],
+            central_body_pole: Vector3::new(slice[10], slice[11], slice[12]),
+            gm_km3_s2: slice[13],
+            j2: slice[14],
+            central_body_radius_km: slice[15],
+        }
+    }
+}
+
+/// A single conic whose line of apsides precesses and whose line of nodes regresses because of the J2 of the central body.
+#[derive(PartialEq)]
+pub struct PrecessingConicSetType15<'a> {
+    pub record_data: &'a [f64],
+}
+
+impl<'a> fmt::Display for PrecessingConicSetType15<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let record = PrecessingConicRecord::from_slice_f64(self.record_data);
+        write!(
+            f,
+            "Precessing conic Type 15 with p = {} km and e = {} (GM = {} km^3/s^2, J2 = {})",
+            record.semi_latus_rectum_km, record.eccentricity, record.gm_km3_s2, record.j2
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for PrecessingConicSetType15<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = PrecessingConicRecord;
+    const DATASET_NAME: &'static str = "Precessing conic Type 15";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= TYPE15_RECORD_SIZE,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: TYPE15_RECORD_SIZE,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            record_data: &slice[..TYPE15_RECORD_SIZE],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        if n > 0 {
+            return Err(DecodingError::InaccessibleBytes {
+                start: n.saturating_mul(TYPE15_RECORD_SIZE),
+                end: n.saturating_add(1).saturating_mul(TYPE15_RECORD_SIZE),
+                size: self.record_data.len(),
+            });
+        }
+        Ok(Self::RecordKind::from_slice_f64(self.record_data))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        // The conic is propagated to any epoch, so only the summary bounds the coverage.
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "coverage without a summary",
+        })
+    }
+
+    /// Propagates the conic from periapsis to the requested epoch (like SPICE's `spke15`), then rotates the state about
+    /// the trajectory pole by the precession of periapsis, and about the central body pole by the regression of the node.
+    ///
+    /// As in SPICE, the velocity is rotated like the position and does not include the (slow) rate of these rotations.
+    /// The J2 effects are only applied to elliptical orbits, since they are driven by the mean motion.
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        let record = self.nth_record(0).context(InterpDecodingSnafu)?;
+        let (p_km, ecc) = (record.semi_latus_rectum_km, record.eccentricity);
+        let traj_pole_norm = record.trajectory_pole.norm();
+        let periapsis_norm = record.periapsis.norm();
+        if !(p_km > 0.0 && ecc >= 0.0 && traj_pole_norm > 0.0 && periapsis_norm > 0.0) {
+            return Err(InterpolationError::CorruptedData {
+                what: "precessing conic has a non-positive semi-latus rectum, a negative eccentricity, or a zero vector",
+            });
+        }
+
+        // State at periapsis, in the plane normal to the trajectory pole
+        let traj_pole = record.trajectory_pole / traj_pole_norm;
+        let periapsis = record.periapsis / periapsis_norm;
+        let periapsis_km = p_km / (1.0 + ecc);
+        let speed_km_s = (record.gm_km3_s2 / p_km).sqrt() * (1.0 + ecc);
+        let dt_s = epoch.to_et_seconds() - record.periapsis_epoch_et_s;
+
+        let (mut pos_km, mut vel_km_s) = propagate_two_body(
+            &(periapsis_km * periapsis),
+            &(speed_km_s * traj_pole.cross(&periapsis).normalize()),
+            dt_s,
+            record.gm_km3_s2,
+        )
+        .context(InterpMathSnafu)?;
+
+        if ecc < 1.0 && record.j2 != 0.0 {
+            let one_minus_e2 = 1.0 - ecc * ecc;
+            let mean_motion_rad_s =
+                one_minus_e2 / p_km * (record.gm_km3_s2 * one_minus_e2 / p_km).sqrt();
+            let mean_anomaly_rad = mean_motion_rad_s * dt_s;
+            let z =
+                1.5 * record.j2 * (record.central_body_radius_km / p_km).powi(2) * mean_anomaly_rad;
+
+            let body_pole_norm = record.central_body_pole.norm();
+            if body_pole_norm.is_nan() || body_pole_norm <= 0.0 {
+                return Err(InterpolationError::CorruptedData {
+                    what: "precessing conic has a zero central body pole",
+                });
+            }
+            let body_pole = record.central_body_pole / body_pole_norm;
+            let cos_inc = body_pole.dot(&traj_pole);
+
+            if record.apsidal_precession() {
+                let dperi_rad = z * (2.5 * cos_inc * cos_inc - 0.5);
+                pos_km = rotate_vector(&pos_km, &traj_pole, dperi_rad);
+                vel_km_s = rotate_vector(&vel_km_s, &traj_pole, dperi_rad);
+            }
+
+            if record.node_regression() {
+                let dnode_rad = -z * cos_inc;
+                pos_km = rotate_vector(&pos_km, &body_pole, dnode_rad);
+                vel_km_s = rotate_vector(&vel_km_s, &body_pole, dnode_rad);
+            }
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, _summary: &S) -> Result<(), IntegrityError> {
+        // The conic is propagated to any epoch, so any summary is covered.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        let record = PrecessingConicRecord::from_slice_f64(self.record_data);
+        let invalid = |variable, value, reason| IntegrityError::InvalidValue {
+            dataset: Self::DATASET_NAME,
+            variable,
+            value,
+            reason,
+        };
+
+        if record.semi_latus_rectum_km <= 0.0 {
+            return Err(invalid(
+                "semi-latus rectum",
+                record.semi_latus_rectum_km,
+                "must be strictly positive",
+            ));
+        }
+        if record.eccentricity < 0.0 {
+            return Err(invalid(
+                "eccentricity",
+                record.eccentricity,
+                "must be non-negative",
+            ));
+        }
+        if record.gm_km3_s2 <= 0.0 {
+            return Err(invalid(
+                "gravitational parameter (GM)",
+                record.gm_km3_s2,
+                "must be strictly positive",
+            ));
+        }
+        if record.central_body_radius_km < 0.0 {
+            return Err(invalid(
+                "central body radius",
+                record.central_body_radius_km,
+                "must be non-negative",
+            ));
+        }
+        for (variable, vector) in [
+            ("trajectory pole norm", record.trajectory_pole),
+            ("periapsis vector norm", record.periapsis),
+            ("central body pole norm", record.central_body_pole),
+        ] {
+            if vector.norm() == 0.0 {
+                return Err(invalid(variable, 0.0, "must be strictly positive"));
+            }
+        }
+
+        let cos_angle = record
+            .trajectory_pole
+            .normalize()
+            .dot(&record.periapsis.normalize());
+        if cos_angle.abs() > 1e-5 {
+            return Err(invalid(
+                "cosine of the angle between the trajectory pole and periapsis",
+                cos_angle,
+                "must be zero, periapsis must lie in the orbit plane",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod conic_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::{propagation::propagate_two_body, Vector3},
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::PrecessingConicSetType15;
+
+    const GM_EARTH: f64 = 398_600.435_436;
+    const J2_EARTH: f64 = 1.082_626_68e-3;
+    const RADIUS_EARTH_KM: f64 = 6378.137;
+
+    /// A 7000 km x 8000 km orbit, inclined by 60 degrees about the X axis, with periapsis on the X axis.
+    fn type15_data(j2_flag: f64, j2: f64) -> Vec<f64> {
+        let inc = 60.0_f64.to_radians();
+        let (rp_km, ra_km) = (7000.0, 8000.0);
+        let ecc = (ra_km - rp_km) / (ra_km + rp_km);
+        let p_km = rp_km * (1.0 + ecc);
+        vec![
+            100.0,
+            0.0,
+            -inc.sin(),
+            inc.cos(),
+            1.0,
+            0.0,
+            0.0,
+            p_km,
+            ecc,
+            j2_flag,
+            0.0,
+            0.0,
+            1.0,
+            GM_EARTH,
+            j2,
+            RADIUS_EARTH_KM,
+        ]
+    }
+
+    fn summary() -> SPKSummaryRecord {
+        SPKSummaryRecord {
+            start_epoch_et_s: -86_400.0,
+            end_epoch_et_s: 86_400.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn type15_evaluate() {
+        let data = type15_data(0.0, J2_EARTH);
+        let dataset = PrecessingConicSetType15::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        let summary = summary();
+
+        // At periapsis, there is no precession yet
+        let (pos, vel) = dataset
+            .evaluate(Epoch::from_et_seconds(100.0), &summary)
+            .unwrap();
+        assert!((pos - Vector3::new(7000.0, 0.0, 0.0)).norm() < 1e-9);
+        assert!(vel.x.abs() < 1e-12);
+        assert!((vel.y / vel.z - 60.0_f64.to_radians().tan().recip()).abs() < 1e-12);
+
+        // Without J2, this is a plain two-body propagation
+        let data_no_j2 = type15_data(3.0, J2_EARTH);
+        let no_j2 = PrecessingConicSetType15::from_f64_slice(&data_no_j2).unwrap();
+        let (pos_p, vel_p) = no_j2
+            .evaluate(Epoch::from_et_seconds(100.0), &summary)
+            .unwrap();
+        let epoch = Epoch::from_et_seconds(50_000.0);
+        let (exp_pos, exp_vel) = propagate_two_body(&pos_p, &vel_p, 49_900.0, GM_EARTH).unwrap();
+        let (pos, vel) = no_j2.evaluate(epoch, &summary).unwrap();
+        assert!((pos - exp_pos).norm() < 1e-9);
+        assert!((vel - exp_vel).norm() < 1e-12);
+
+        // With J2, the angular momentum keeps its inclination and regresses westward (prograde orbit), and the
+        // orbit keeps its size and shape.
+        let (pos_j2, vel_j2) = dataset.evaluate(epoch, &summary).unwrap();
+        assert!((pos_j2.norm() - pos.norm()).abs() < 1e-9);
+        assert!((vel_j2.norm() - vel.norm()).abs() < 1e-12);
+        let h = pos_j2.cross(&vel_j2).normalize();
+        assert!((h.z - 60.0_f64.to_radians().cos()).abs() < 1e-12);
+        // Initially, the ascending node is on the +X axis, so h points toward -Y; regression moves h toward -X.
+        assert!(h.x < -1e-2);
+        assert!(h.y < 0.0);
+
+        // Only the regression of the node: the state rotated about Z
+        let data_node = type15_data(1.0, J2_EARTH);
+        let node = PrecessingConicSetType15::from_f64_slice(&data_node).unwrap();
+        let (pos_n, _) = node.evaluate(epoch, &summary).unwrap();
+        assert!((pos_n.z - pos.z).abs() < 1e-9);
+        assert!((pos_n - pos).norm() > 1.0);
+
+        // Only the precession of periapsis: the state rotated about the trajectory pole
+        let data_apse = type15_data(2.0, J2_EARTH);
+        let apse = PrecessingConicSetType15::from_f64_slice(&data_apse).unwrap();
+        let (pos_a, vel_a) = apse.evaluate(epoch, &summary).unwrap();
+        let traj_pole = Vector3::new(data[1], data[2], data[3]);
+        assert!(pos_a.dot(&traj_pole).abs() < 1e-9);
+        assert!(vel_a.dot(&traj_pole).abs() < 1e-12);
+        assert!((pos_a - pos).norm() > 1.0);
+
+        // The coverage is that of the summary
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(86_401.0), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_data() {
+        match PrecessingConicSetType15::from_f64_slice(&[0.0; 15]) {
+            Ok(_) => panic!("test failed on too few doubles"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::TooFewDoubles {
+                    dataset: "Precessing conic Type 15",
+                    got: 15,
+                    need: 16,
+                }
+            ),
+        }
+
+        let check = |idx: usize, value: f64| {
+            let mut data = type15_data(0.0, J2_EARTH);
+            data[idx] = value;
+            PrecessingConicSetType15::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default())
+        };
+
+        assert_eq!(
+            check(7, -1.0),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Precessing conic Type 15",
+                variable: "semi-latus rectum",
+                value: -1.0,
+                reason: "must be strictly positive",
+            })
+        );
+        assert_eq!(
+            check(8, -0.1),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Precessing conic Type 15",
+                variable: "eccentricity",
+                value: -0.1,
+                reason: "must be non-negative",
+            })
+        );
+        assert_eq!(
+            check(8, f64::NAN),
+            Err(IntegrityError::NaNValue {
+                dataset: "Precessing conic Type 15",
+                variable: "one of the record data",
+                offset: 8,
+            })
+        );
+        match check(5, 0.5) {
+            Err(IntegrityError::InvalidValue {
+                variable, value, ..
+            }) => {
+                assert_eq!(
+                    variable,
+                    "cosine of the angle between the trajectory pole and periapsis"
+                );
+                assert!(
+                    (value + 0.5 * 60.0_f64.to_radians().sin() / 1.25_f64.sqrt()).abs() < 1e-12
+                );
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        // Evaluation does not rely on the integrity check to reject an invalid conic
+        let mut data = type15_data(0.0, J2_EARTH);
+        data[7] = 0.0;
+        let dataset = PrecessingConicSetType15::from_f64_slice(&data).unwrap();
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(0.0), &summary())
+            .is_err());
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+/// Number of epochs between two entries in the epoch registry (or epoch directory) of unequal time step data (e.g. Type 9 and 13).
+pub(crate) const EPOCH_REGISTRY_STRIDE: usize = 100;
+
+/// Searches for the provided epoch in the epoch data, returning the same result as a `binary_search` on the whole epoch data.
+///
+/// The epoch registry stores every 100th epoch, so it is searched first to find the block of 100 epochs
+/// which contains the requested epoch, and only that block of the epoch data is then searched.
+/// If the registry is empty or does not have the expected number of entries, the whole epoch data is searched.
+///
+/// # Note
+/// Type 13 data stores `(N-1) / 100` registry entries whereas Type 1 data stores `N / 100` entries,
+/// so both are accepted: they only differ when the last registry entry is the last epoch.
+pub(crate) fn search_epoch(
+    epoch_data: &[f64],
+    epoch_registry: &[f64],
+    epoch_et_s: f64,
+) -> Result<usize, usize> {
+    let (start_idx, end_idx) = if epoch_registry.is_empty()
+        || epoch_registry.len() < epoch_data.len().saturating_sub(1) / EPOCH_REGISTRY_STRIDE
+        || epoch_registry.len() > epoch_data.len() / EPOCH_REGISTRY_STRIDE
+    {
+        (0, epoch_data.len())
+    } else {
+        // Index of the first registry entry greater than or equal to the requested epoch,
+        // i.e. the index of the 100-epoch block where that entry is the last epoch.
+        let block = epoch_registry.partition_point(|registry_et| *registry_et < epoch_et_s);
+
+        let start_idx = block * EPOCH_REGISTRY_STRIDE;
+        if block < epoch_registry.len() {
+            (start_idx, start_idx + EPOCH_REGISTRY_STRIDE)
+        } else {
+            // Past the last registry entry, so search whatever remains.
+            (start_idx, epoch_data.len())
+        }
+    };
+
+    // Data which was not integrity checked may include NaN epochs, which are ordered after all other epochs.
+    match epoch_data[start_idx..end_idx]
+        .binary_search_by(|epoch_et| epoch_et.total_cmp(&epoch_et_s))
+    {
+        Ok(idx) => Ok(start_idx + idx),
+        Err(idx) => Err(start_idx + idx),
+    }
+}
+
+/// Returns the index of the first entry of the epoch registry which does not match the epoch data, if any.
+///
+/// Each entry of the registry must be exactly equal to the corresponding 100th epoch. As in [search_epoch],
+/// registries with either `(N-1) / 100` or `N / 100` entries are accepted; an entry is missing or superfluous otherwise.
+pub(crate) fn registry_mismatch(epoch_data: &[f64], epoch_registry: &[f64]) -> Option<usize> {
+    let min_len = epoch_data.len().saturating_sub(1) / EPOCH_REGISTRY_STRIDE;
+    let max_len = epoch_data.len() / EPOCH_REGISTRY_STRIDE;
+    if epoch_registry.len() < min_len {
+        return Some(epoch_registry.len());
+    } else if epoch_registry.len() > max_len {
+        return Some(max_len);
+    }
+
+    epoch_registry
+        .iter()
+        .zip(
+            epoch_data
+                .iter()
+                .skip(EPOCH_REGISTRY_STRIDE - 1)
+                .step_by(EPOCH_REGISTRY_STRIDE),
+        )
+        .position(|(registry_et, epoch_et)| registry_et.to_bits() != epoch_et.to_bits())
+}
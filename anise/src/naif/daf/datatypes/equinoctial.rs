@@ -0,0 +1,406 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        equinoctial::EquinoctialElements,
+        interpolation::{InterpDecodingSnafu, InterpMathSnafu, InterpolationError},
+        Matrix3, Vector3,
+    },
+    naif::daf::{datatypes::check_finite, NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+};
+
+/// Number of doubles in a Type 17 segment.
+const TYPE17_RECORD_SIZE: usize = 12;
+
+/// Equinoctial elements at a reference epoch, their linear rates, and the pole of their reference plane.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct EquinoctialRecord {
+    /// Epoch of the elements, in seconds past J2000 TDB
+    pub epoch_et_s: f64,
+    pub elements: EquinoctialElements,
+    /// Rate of the longitude of periapsis (argument of periapsis plus longitude of the ascending node)
+    pub periapsis_longitude_rate_rad_s: f64,
+    pub mean_longitude_rate_rad_s: f64,
+    pub node_longitude_rate_rad_s: f64,
+    /// Right ascension of the pole of the reference plane of the elements
+    pub pole_ra_rad: f64,
+    /// Declination of the pole of the reference plane of the elements
+    pub pole_dec_rad: f64,
+}
+
+impl EquinoctialRecord {
+    /// Returns the rotation from the reference plane of the elements to the frame of the segment.
+    ///
+    /// The X axis of the reference plane is its ascending node on the XY plane of the frame of the segment.
+    pub fn reference_plane_rotation(&self) -> Matrix3 {
+        let (sin_ra, cos_ra) = self.pole_ra_rad.sin_cos();
+        let (sin_dec, cos_dec) = self.pole_dec_rad.sin_cos();
+        Matrix3::new(
+            -sin_ra,
+            -cos_ra * sin_dec,
+            cos_ra * cos_dec,
+            cos_ra,
+            -sin_ra * sin_dec,
+            sin_ra * cos_dec,
+            0.0,
+            cos_dec,
+            sin_dec,
+        )
+    }
+}
+
+impl fmt::Display for EquinoctialRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for EquinoctialRecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        Self {
+            epoch_et_s: slice[0],
+            elements: EquinoctialElements {
+                sma_km: slice[1],
+                h: slice[2],
+                k: slice[3],
+                mean_longitude_rad: slice[4],
+                p: slice[5],
+                q: slice[6],
+            },
+            periapsis_longitude_rate_rad_s: slice[7],
+            mean_longitude_rate_rad_s: slice[8],
+            node_longitude_rate_rad_s: slice[9],
+            pole_ra_rad: slice[10],
+            pole_dec_rad: slice[11],
+        }
+    }
+}
+
+/// A single set of equinoctial elements whose mean longitude, longitude of periapsis and longitude of the node vary linearly.
+///
+/// This has not yet been validated against a NAIF Type 17 reference segment: no such kernel is available in this repository.
+#[derive(PartialEq)]
+pub struct EquinoctialSetType17<'a> {
+    pub record_data: &'a [f64],
+}
+
+impl<'a> fmt::Display for EquinoctialSetType17<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let record = EquinoctialRecord::from_slice_f64(self.record_data);
+        write!(
+            f,
+            "Equinoctial Type 17 with a = {} km and e = {} at {}",
+            record.elements.sma_km,
+            record.elements.ecc(),
+            Epoch::from_et_seconds(record.epoch_et_s)
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for EquinoctialSetType17<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = EquinoctialRecord;
+    const DATASET_NAME: &'static str = "Equinoctial Type 17";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= TYPE17_RECORD_SIZE,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: TYPE17_RECORD_SIZE,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            record_data: &slice[..TYPE17_RECORD_SIZE],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        if n > 0 {
+            return Err(DecodingError::InaccessibleBytes {
+                start: n.saturating_mul(TYPE17_RECORD_SIZE),
+                end: n.saturating_add(1).saturating_mul(TYPE17_RECORD_SIZE),
+                size: self.record_data.len(),
+            });
+        }
+        Ok(Self::RecordKind::from_slice_f64(self.record_data))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        // The elements are propagated to any epoch, so only the summary bounds the coverage.
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "coverage without a summary",
+        })
+    }
+
+    /// Advances the mean longitude, the longitude of periapsis and the longitude of the node linearly from the epoch of
+    /// the elements (like SPICE's `spke17`), then converts the elements to a Cartesian state.
+    ///
+    /// The velocity is the time derivative of the position, including the rotation from the precession of the angles.
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        let record = self.nth_record(0).context(InterpDecodingSnafu)?;
+        let dt_s = epoch.to_et_seconds() - record.epoch_et_s;
+        let ref_elements = record.elements;
+
+        let (sin_dperi, cos_dperi) = (record.periapsis_longitude_rate_rad_s * dt_s).sin_cos();
+        let (sin_dnode, cos_dnode) = (record.node_longitude_rate_rad_s * dt_s).sin_cos();
+        let elements = EquinoctialElements {
+            sma_km: ref_elements.sma_km,
+            h: ref_elements.h * cos_dperi + ref_elements.k * sin_dperi,
+            k: ref_elements.k * cos_dperi - ref_elements.h * sin_dperi,
+            mean_longitude_rad: ref_elements.mean_longitude_rad
+                + record.mean_longitude_rate_rad_s * dt_s,
+            p: ref_elements.p * cos_dnode + ref_elements.q * sin_dnode,
+            q: ref_elements.q * cos_dnode - ref_elements.p * sin_dnode,
+        };
+
+        // The Keplerian motion is driven by the rate of the mean anomaly: computing the state with a unit mean motion
+        // and scaling the velocity by this rate avoids deriving a gravitational parameter.
+        let mean_anomaly_rate_rad_s =
+            record.mean_longitude_rate_rad_s - record.periapsis_longitude_rate_rad_s;
+        let (pos_km, unit_vel_km_s) = elements
+            .to_pos_vel(elements.sma_km.powi(3))
+            .context(InterpMathSnafu)?;
+
+        // The node rotates about the pole of the reference plane, and the argument of periapsis about the orbit normal.
+        let (f_hat, g_hat) = elements.basis();
+        let orbit_normal = f_hat.cross(&g_hat);
+        let vel_km_s = mean_anomaly_rate_rad_s * unit_vel_km_s
+            + record.node_longitude_rate_rad_s * Vector3::z().cross(&pos_km)
+            + (record.periapsis_longitude_rate_rad_s - record.node_longitude_rate_rad_s)
+                * orbit_normal.cross(&pos_km);
+
+        let rot = record.reference_plane_rotation();
+        Ok((rot * pos_km, rot * vel_km_s))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, _summary: &S) -> Result<(), IntegrityError> {
+        // The elements are propagated to any epoch, so any summary is covered.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        let record = EquinoctialRecord::from_slice_f64(self.record_data);
+        if record.elements.sma_km <= 0.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "semi major axis",
+                value: record.elements.sma_km,
+                reason: "must be strictly positive",
+            });
+        }
+        if record.elements.ecc() >= 1.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "eccentricity",
+                value: record.elements.ecc(),
+                reason: "must be less than one, only elliptical orbits are supported",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod equinoctial_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::{equinoctial::EquinoctialElements, propagation::propagate_two_body, Vector3},
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::EquinoctialSetType17;
+
+    const GM_EARTH: f64 = 398_600.435_436;
+
+    fn elements() -> EquinoctialElements {
+        EquinoctialElements {
+            sma_km: 8000.0,
+            h: 0.05,
+            k: -0.1,
+            mean_longitude_rad: 1.0,
+            p: 0.2,
+            q: -0.3,
+        }
+    }
+
+    /// Type 17 record at epoch 100 s, with the provided rates and pole of the reference plane.
+    fn type17_data(rates: [f64; 3], pole_ra_rad: f64, pole_dec_rad: f64) -> Vec<f64> {
+        let el = elements();
+        vec![
+            100.0,
+            el.sma_km,
+            el.h,
+            el.k,
+            el.mean_longitude_rad,
+            el.p,
+            el.q,
+            rates[0],
+            rates[1],
+            rates[2],
+            pole_ra_rad,
+            pole_dec_rad,
+        ]
+    }
+
+    fn summary() -> SPKSummaryRecord {
+        SPKSummaryRecord {
+            start_epoch_et_s: -3600.0,
+            end_epoch_et_s: 86_400.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn type17_evaluate() {
+        let mean_motion = (GM_EARTH / 8000.0_f64.powi(3)).sqrt();
+        let summary = summary();
+        let epoch = Epoch::from_et_seconds(1334.5);
+        let (pos0, vel0) = elements().to_pos_vel(GM_EARTH).unwrap();
+        let (exp_pos, exp_vel) = propagate_two_body(&pos0, &vel0, 1234.5, GM_EARTH).unwrap();
+
+        // Without precession, and with the pole of the reference plane along Z, this is a two-body propagation.
+        let data = type17_data(
+            [0.0, mean_motion, 0.0],
+            -90.0_f64.to_radians(),
+            90.0_f64.to_radians(),
+        );
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        dataset.check_integrity(&summary).unwrap();
+        let (pos, vel) = dataset.evaluate(epoch, &summary).unwrap();
+        assert!((pos - exp_pos).norm() < 1e-7);
+        assert!((vel - exp_vel).norm() < 1e-10);
+
+        // A reference plane whose pole is along X: the X axis of the plane is +Y, and its Y axis is +Z.
+        let data = type17_data([0.0, mean_motion, 0.0], 0.0, 0.0);
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        let (pos, vel) = dataset.evaluate(epoch, &summary).unwrap();
+        let rotate = |v: Vector3| Vector3::new(v.z, v.x, v.y);
+        assert!((pos - rotate(exp_pos)).norm() < 1e-7);
+        assert!((vel - rotate(exp_vel)).norm() < 1e-10);
+
+        // With precession, the velocity is the derivative of the position, and the shape is preserved.
+        let data = type17_data([1e-5, mean_motion + 1e-5, -2e-5], 0.3, 1.2);
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        let h_s = 1e-3;
+        let at = |t: f64| {
+            dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap()
+        };
+        for t in [100.0, 1334.5, 40_000.0] {
+            let (pos, vel) = at(t);
+            let (pos_m, _) = at(t - h_s);
+            let (pos_p, _) = at(t + h_s);
+            assert!(
+                (vel - (pos_p - pos_m) / (2.0 * h_s)).norm() < 1e-6,
+                "velocity inconsistent at {t}"
+            );
+
+            let ecc = elements().ecc();
+            assert!(pos.norm() > 8000.0 * (1.0 - ecc) && pos.norm() < 8000.0 * (1.0 + ecc));
+        }
+        let (pos, _) = at(100.0);
+        let rot = dataset.nth_record(0).unwrap().reference_plane_rotation();
+        assert!((pos - rot * pos0).norm() < 1e-9);
+
+        // The coverage is that of the summary
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(-3601.0), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_data() {
+        match EquinoctialSetType17::from_f64_slice(&[0.0; 11]) {
+            Ok(_) => panic!("test failed on too few doubles"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::TooFewDoubles {
+                    dataset: "Equinoctial Type 17",
+                    got: 11,
+                    need: 12,
+                }
+            ),
+        }
+
+        let mut data = type17_data([0.0; 3], 0.0, 0.0);
+        data[1] = -1.0;
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Equinoctial Type 17",
+                variable: "semi major axis",
+                value: -1.0,
+                reason: "must be strictly positive",
+            })
+        );
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(0.0), &summary())
+            .is_err());
+
+        let mut data = type17_data([0.0; 3], 0.0, 0.0);
+        data[2] = 0.6;
+        data[3] = 0.8;
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Equinoctial Type 17",
+                variable: "eccentricity",
+                value: 1.0,
+                reason: "must be less than one, only elliptical orbits are supported",
+            })
+        );
+
+        data[3] = f64::INFINITY;
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InfiniteValue {
+                dataset: "Equinoctial Type 17",
+                variable: "one of the record data",
+                offset: 3,
+            })
+        );
+    }
+}
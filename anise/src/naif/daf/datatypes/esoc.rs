@@ -0,0 +1,1162 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::Epoch;
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{
+            hermite_eval, hermite_eval_multi, lagrange_eval, InterpDecodingSnafu,
+            InterpolationError, MAX_SAMPLES,
+        },
+        Vector3,
+    },
+    naif::{
+        daf::{
+            datatypes::{check_finite, check_summary_coverage, epochs_coverage, nth_record_data},
+            NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+        },
+        spk::summary::SPKSummaryRecord,
+    },
+};
+
+use super::{
+    epoch_registry::{search_epoch, EPOCH_REGISTRY_STRIDE},
+    posvel::{PositionVelocityAccelerationRecord, PositionVelocityRecord},
+};
+
+/// Interpolation method of an ESOC Type 18 segment (or Type 19 mini-segment), which also defines the size of its packets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ESOCSubtype {
+    /// Subtype 0: Hermite interpolation of the position and of the velocity, each with their own derivative.
+    Hermite,
+    /// Subtype 1: Lagrange interpolation of each component of the position and velocity.
+    Lagrange,
+    /// Subtype 2, only in Type 19 mini-segments: Hermite interpolation of the position using the velocity as its derivative,
+    /// the velocity is the derivative of that interpolation.
+    HermiteCompact,
+}
+
+impl ESOCSubtype {
+    /// Returns the number of doubles in each packet of this subtype.
+    pub const fn packet_size(self) -> usize {
+        match self {
+            Self::Hermite => 12,
+            Self::Lagrange | Self::HermiteCompact => 6,
+        }
+    }
+}
+
+/// A packet of an ESOC Type 18 segment (or Type 19 mini-segment).
+///
+/// The packets of the compact Hermite subtype have the same layout as the Lagrange ones.
+#[derive(Copy, Clone, Debug)]
+pub enum ESOCRecord {
+    Hermite(PositionVelocityAccelerationRecord),
+    Lagrange(PositionVelocityRecord),
+}
+
+impl ESOCRecord {
+    pub fn to_pos_vel(&self) -> (Vector3, Vector3) {
+        match self {
+            Self::Hermite(record) => record.to_pos_vel(),
+            Self::Lagrange(record) => record.to_pos_vel(),
+        }
+    }
+}
+
+impl fmt::Display for ESOCRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hermite(record) => write!(f, "{record}"),
+            Self::Lagrange(record) => write!(f, "{record}"),
+        }
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for ESOCRecord {
+    /// Decodes a Hermite packet if the slice has (at least) twelve doubles, and a Lagrange packet otherwise.
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        if slice.len() >= ESOCSubtype::Hermite.packet_size() {
+            Self::Hermite(PositionVelocityAccelerationRecord::from_slice_f64(slice))
+        } else {
+            Self::Lagrange(PositionVelocityRecord::from_slice_f64(slice))
+        }
+    }
+}
+
+/// States at unequally spaced epochs, interpolated with a Hermite or Lagrange polynomial depending on the subtype.
+#[derive(PartialEq)]
+pub struct ESOCSetType18<'a> {
+    pub subtype: ESOCSubtype,
+    /// Number of states used for each interpolation
+    pub samples: usize,
+    /// Total number of packets stored in this data
+    pub num_records: usize,
+    /// Packet data, ANISE expects these to be ordered chronologically with the epoch data!
+    pub record_data: &'a [f64],
+    /// Epoch of each of the packets
+    pub epoch_data: &'a [f64],
+    /// Epoch registry to reduce the search space in epoch data.
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> ESOCSetType18<'a> {
+    /// Returns the degree of the interpolation polynomials, given the number of states actually available.
+    pub fn degree(&self) -> usize {
+        let samples = self.samples.min(self.num_records);
+        match self.subtype {
+            ESOCSubtype::Hermite | ESOCSubtype::HermiteCompact => (2 * samples).saturating_sub(1),
+            ESOCSubtype::Lagrange => samples.saturating_sub(1),
+        }
+    }
+
+    /// Decodes an ESOC segment, or, if `mini_segment` is set, a Type 19 mini-segment which also supports the compact Hermite subtype.
+    fn decode(slice: &'a [f64], mini_segment: bool) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 3,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 3_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset: the subtype,
+        // the window size (unlike Types 12 and 13, not minus one), and the number of packets.
+        let invalid = |variable, value, reason| DecodingError::Integrity {
+            source: IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable,
+                value,
+                reason,
+            },
+        };
+
+        let subtype_f64 = slice[slice.len() - 3];
+        let subtype = if subtype_f64 == 0.0 {
+            ESOCSubtype::Hermite
+        } else if subtype_f64 == 1.0 {
+            ESOCSubtype::Lagrange
+        } else if subtype_f64 == 2.0 && mini_segment {
+            ESOCSubtype::HermiteCompact
+        } else if mini_segment {
+            return Err(invalid(
+                "subtype",
+                subtype_f64,
+                "must be 0 (Hermite), 1 (Lagrange) or 2 (compact Hermite)",
+            ));
+        } else {
+            return Err(invalid(
+                "subtype",
+                subtype_f64,
+                "must be 0 (Hermite) or 1 (Lagrange)",
+            ));
+        };
+
+        let samples_f64 = slice[slice.len() - 2];
+        if !(samples_f64 >= 1.0 && samples_f64 <= MAX_SAMPLES as f64) || samples_f64.fract() != 0.0
+        {
+            return Err(invalid(
+                "window size",
+                samples_f64,
+                "must be an integer between 1 and 32 (MAX_SAMPLES)",
+            ));
+        }
+
+        let num_records_f64 = slice[slice.len() - 1];
+        if !(num_records_f64.is_finite() && num_records_f64 >= 0.0)
+            || num_records_f64.fract() != 0.0
+        {
+            return Err(invalid(
+                "number of records",
+                num_records_f64,
+                "must be a non-negative integer",
+            ));
+        }
+        let num_records = num_records_f64 as usize;
+
+        let record_data_end_idx = subtype.packet_size().saturating_mul(num_records);
+        let epoch_data_end_idx = record_data_end_idx.saturating_add(num_records);
+        let epoch_registry_end_idx = epoch_data_end_idx
+            .saturating_add(num_records.saturating_sub(1) / EPOCH_REGISTRY_STRIDE);
+        if epoch_registry_end_idx > slice.len() - 3 {
+            return Err(DecodingError::InaccessibleBytes {
+                start: 0,
+                end: epoch_registry_end_idx.saturating_add(3),
+                size: slice.len(),
+            });
+        } else if epoch_registry_end_idx < slice.len() - 3 {
+            return Err(invalid(
+                "number of records",
+                num_records_f64,
+                "does not account for all of the data of the segment",
+            ));
+        }
+
+        Ok(Self {
+            subtype,
+            samples: samples_f64 as usize,
+            num_records,
+            record_data: &slice[..record_data_end_idx],
+            epoch_data: &slice[record_data_end_idx..epoch_data_end_idx],
+            epoch_registry: &slice[epoch_data_end_idx..epoch_registry_end_idx],
+        })
+    }
+
+    /// Returns the indexes of the first state and one past the last state of the interpolation window,
+    /// given the index where the requested epoch would be inserted in the epoch data.
+    ///
+    /// The window is centered on the requested epoch, and shifted to stay within the data.
+    fn window_indices(&self, idx: usize) -> (usize, usize) {
+        let samples = self.samples.min(self.num_records);
+        let first_idx = idx
+            .saturating_sub(samples / 2)
+            .min(self.num_records - samples);
+        (first_idx, first_idx + samples)
+    }
+}
+
+impl<'a> fmt::Display for ESOCSetType18<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ESOC Type 18 ({:?}) with {} states and {} samples per interpolation ({} epoch directories)",
+            self.subtype,
+            self.num_records,
+            self.samples,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for ESOCSetType18<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = ESOCRecord;
+    const DATASET_NAME: &'static str = "ESOC Type 18";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        Self::decode(slice, false)
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let size = self.subtype.packet_size();
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            size,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        epochs_coverage(Self::DATASET_NAME, self.epoch_data)
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let epoch_et_s = epoch.to_et_seconds();
+        let (first_et_s, last_et_s) = match (self.epoch_data.first(), self.epoch_data.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => {
+                return Err(InterpolationError::NoInterpolationData {
+                    req: epoch,
+                    start: summary.start_epoch(),
+                    end: summary.end_epoch(),
+                    id: summary.id(),
+                    center_id: summary.center_id(),
+                });
+            }
+        };
+        if epoch_et_s + 1e-9 < first_et_s || epoch_et_s - 1e-9 > last_et_s {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(first_et_s),
+                end: Epoch::from_et_seconds(last_et_s),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        let idx = match search_epoch(self.epoch_data, self.epoch_registry, epoch_et_s) {
+            Ok(idx) => {
+                // This state exists, no interpolation needed.
+                return Ok(self
+                    .nth_record(idx)
+                    .context(InterpDecodingSnafu)?
+                    .to_pos_vel());
+            }
+            Err(idx) => idx,
+        };
+
+        let (first_idx, last_idx) = self.window_indices(idx);
+        let samples = last_idx - first_idx;
+
+        // Statically allocated arrays of the maximum number of samples, for each of the components and their derivatives.
+        let mut epochs = [0.0; MAX_SAMPLES];
+        let mut values = [[0.0; MAX_SAMPLES]; 6];
+        let mut derivs = [[0.0; MAX_SAMPLES]; 6];
+        for (cno, idx) in (first_idx..last_idx).enumerate() {
+            match self.nth_record(idx).context(InterpDecodingSnafu)? {
+                ESOCRecord::Hermite(record) => {
+                    for (i, (value, deriv)) in [
+                        (record.x_km, record.dx_km_s),
+                        (record.y_km, record.dy_km_s),
+                        (record.z_km, record.dz_km_s),
+                        (record.vx_km_s, record.ax_km_s2),
+                        (record.vy_km_s, record.ay_km_s2),
+                        (record.vz_km_s, record.az_km_s2),
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    {
+                        values[i][cno] = value;
+                        derivs[i][cno] = deriv;
+                    }
+                }
+                ESOCRecord::Lagrange(record) => {
+                    for (i, value) in [
+                        record.x_km,
+                        record.y_km,
+                        record.z_km,
+                        record.vx_km_s,
+                        record.vy_km_s,
+                        record.vz_km_s,
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    {
+                        values[i][cno] = value;
+                    }
+                }
+            }
+            epochs[cno] = self.epoch_data[idx];
+        }
+
+        let mut state = [0.0; 6];
+        if self.subtype == ESOCSubtype::HermiteCompact {
+            // The velocity is the derivative of the position interpolation.
+            let [x, y, z] = hermite_eval_multi(
+                &epochs[..samples],
+                [0, 1, 2].map(|i| &values[i][..samples]),
+                [3, 4, 5].map(|i| &values[i][..samples]),
+                epoch_et_s,
+            )?;
+            [state[0], state[1], state[2]] = [x.0, y.0, z.0];
+            [state[3], state[4], state[5]] = [x.1, y.1, z.1];
+        } else {
+            // The position and the velocity are interpolated independently, as they are in SPICE.
+            for (i, component) in state.iter_mut().enumerate() {
+                (*component, _) = match self.subtype {
+                    ESOCSubtype::Lagrange => {
+                        lagrange_eval(&epochs[..samples], &values[i][..samples], epoch_et_s)?
+                    }
+                    _ => hermite_eval(
+                        &epochs[..samples],
+                        &values[i][..samples],
+                        &derivs[i][..samples],
+                        epoch_et_s,
+                    )?,
+                };
+            }
+        }
+
+        Ok((
+            Vector3::new(state[0], state[1], state[2]),
+            Vector3::new(state[3], state[4], state[5]),
+        ))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.record_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.record_data.len() + self.epoch_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        check_summary_coverage(self, summary)?;
+
+        Ok(())
+    }
+}
+
+/// ESOC piecewise interpolation: a sequence of Type 18 mini-segments, each covering one interval of the segment.
+#[derive(PartialEq)]
+pub struct ESOCSetType19<'a> {
+    /// If set, an epoch on the boundary between two intervals is evaluated with the later mini-segment, otherwise with the earlier one.
+    pub select_later: bool,
+    /// Number of intervals, i.e. of mini-segments
+    pub num_intervals: usize,
+    /// Data of all of the mini-segments
+    pub minisegment_data: &'a [f64],
+    /// Start epoch of each interval, followed by the end epoch of the last interval
+    pub interval_boundaries: &'a [f64],
+    /// Interval boundary registry to reduce the search space in the interval boundaries.
+    pub interval_registry: &'a [f64],
+    /// Index (starting at one) of the first double of each mini-segment, followed by the index past the last mini-segment
+    pub minisegment_pointers: &'a [f64],
+}
+
+impl<'a> ESOCSetType19<'a> {
+    /// Decodes the mini-segment of the n-th interval.
+    pub fn nth_interval(&self, n: usize) -> Result<ESOCSetType18<'a>, DecodingError> {
+        if n >= self.num_intervals {
+            return Err(DecodingError::InaccessibleBytes {
+                start: n,
+                end: n + 1,
+                size: self.num_intervals,
+            });
+        }
+        // Pointers were checked to be increasing and within the data at load time.
+        let start = self.minisegment_pointers[n] as usize - 1;
+        let end = self.minisegment_pointers[n + 1] as usize - 1;
+        ESOCSetType18::decode(&self.minisegment_data[start..end], true)
+    }
+
+    /// Returns the index of the interval whose mini-segment is used to evaluate the provided epoch, honoring the boundary flag.
+    ///
+    /// An epoch within one nanosecond of a boundary (i.e. the precision of an Epoch) is considered to be on that boundary.
+    fn interval_index(&self, epoch_et_s: f64) -> usize {
+        let bounds = self.interval_boundaries;
+        let boundary_idx = match search_epoch(bounds, self.interval_registry, epoch_et_s) {
+            Ok(idx) => Ok(idx),
+            Err(idx) if idx < bounds.len() && bounds[idx] - epoch_et_s < 1e-9 => Ok(idx),
+            Err(idx) if idx > 0 && epoch_et_s - bounds[idx - 1] < 1e-9 => Ok(idx - 1),
+            Err(idx) => Err(idx),
+        };
+        let idx = match boundary_idx {
+            Ok(idx) if self.select_later => idx,
+            Ok(idx) | Err(idx) => idx.saturating_sub(1),
+        };
+        idx.min(self.num_intervals - 1)
+    }
+
+    /// Returns the degree of the interpolation polynomials of the mini-segment used at the provided epoch.
+    pub fn degree_at(&self, epoch: Epoch) -> Result<usize, InterpolationError> {
+        Ok(self
+            .nth_interval(self.interval_index(epoch.to_et_seconds()))
+            .context(InterpDecodingSnafu)?
+            .degree())
+    }
+}
+
+impl<'a> fmt::Display for ESOCSetType19<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ESOC Type 19 with {} intervals ({} boundary directories), boundary epochs use the {} interval",
+            self.num_intervals,
+            self.interval_registry.len(),
+            if self.select_later { "later" } else { "earlier" }
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for ESOCSetType19<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = ESOCRecord;
+    const DATASET_NAME: &'static str = "ESOC Type 19";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 2_usize,
+                got: slice.len()
+            }
+        );
+
+        // The boundary flag and the number of intervals are the last two doubles, preceded by the
+        // pointers to each mini-segment, and the interval boundaries and their directory follow the mini-segments.
+        let invalid = |variable, value, reason| DecodingError::Integrity {
+            source: IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable,
+                value,
+                reason,
+            },
+        };
+
+        let flag_f64 = slice[slice.len() - 2];
+        let select_later = if flag_f64 == 0.0 {
+            false
+        } else if flag_f64 == 1.0 {
+            true
+        } else {
+            return Err(invalid(
+                "boundary flag",
+                flag_f64,
+                "must be 0 (earlier interval) or 1 (later interval)",
+            ));
+        };
+
+        let num_intervals_f64 = slice[slice.len() - 1];
+        if !(num_intervals_f64.is_finite() && num_intervals_f64 >= 1.0)
+            || num_intervals_f64.fract() != 0.0
+        {
+            return Err(invalid(
+                "number of intervals",
+                num_intervals_f64,
+                "must be a positive integer",
+            ));
+        }
+        let num_intervals = num_intervals_f64 as usize;
+
+        // Pointers and boundaries, along with the two metadata doubles
+        let min_len = num_intervals.saturating_add(2).saturating_mul(2);
+        if slice.len() < min_len {
+            return Err(DecodingError::InaccessibleBytes {
+                start: 0,
+                end: min_len,
+                size: slice.len(),
+            });
+        }
+
+        let pointers_start_idx = slice.len() - 2 - (num_intervals + 1);
+        let minisegment_pointers = &slice[pointers_start_idx..slice.len() - 2];
+
+        let mut prev_ptr = 1.0;
+        for (n, ptr) in minisegment_pointers.iter().enumerate() {
+            if ptr.fract() != 0.0
+                || (n == 0 && *ptr != 1.0)
+                || *ptr < prev_ptr
+                || *ptr - 1.0 + (num_intervals + 1) as f64 > pointers_start_idx as f64
+            {
+                return Err(invalid(
+                    "mini-segment pointer",
+                    *ptr,
+                    "must be increasing from 1 and point within the mini-segment data",
+                ));
+            }
+            prev_ptr = *ptr;
+        }
+
+        let minisegment_data_end_idx = prev_ptr as usize - 1;
+        let boundaries_end_idx = minisegment_data_end_idx + num_intervals + 1;
+        let registry_len = pointers_start_idx - boundaries_end_idx;
+        // Like the epoch directory, the boundary directory stores every 100th boundary.
+        if registry_len < num_intervals / EPOCH_REGISTRY_STRIDE
+            || registry_len > (num_intervals + 1) / EPOCH_REGISTRY_STRIDE
+        {
+            return Err(invalid(
+                "number of intervals",
+                num_intervals_f64,
+                "does not account for all of the data of the segment",
+            ));
+        }
+
+        Ok(Self {
+            select_later,
+            num_intervals,
+            minisegment_data: &slice[..minisegment_data_end_idx],
+            interval_boundaries: &slice[minisegment_data_end_idx..boundaries_end_idx],
+            interval_registry: &slice[boundaries_end_idx..pointers_start_idx],
+            minisegment_pointers,
+        })
+    }
+
+    /// Returns the n-th packet, counting the packets of each mini-segment in order.
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let mut remaining = n;
+        for interval in 0..self.num_intervals {
+            let minisegment = self.nth_interval(interval)?;
+            if remaining < minisegment.num_records {
+                return minisegment.nth_record(remaining);
+            }
+            remaining -= minisegment.num_records;
+        }
+        Err(DecodingError::InaccessibleBytes {
+            start: n,
+            end: n + 1,
+            size: n - remaining,
+        })
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        epochs_coverage(Self::DATASET_NAME, self.interval_boundaries)
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let epoch_et_s = epoch.to_et_seconds();
+        let first_et_s = self.interval_boundaries[0];
+        let last_et_s = self.interval_boundaries[self.num_intervals];
+        if epoch_et_s + 1e-9 < first_et_s || epoch_et_s - 1e-9 > last_et_s {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(first_et_s),
+                end: Epoch::from_et_seconds(last_et_s),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        self.nth_interval(self.interval_index(epoch_et_s))
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch, summary)
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the interval boundaries",
+            self.interval_boundaries,
+            self.minisegment_data.len(),
+        )?;
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the interval boundaries",
+            self.interval_registry,
+            self.minisegment_data.len() + self.interval_boundaries.len(),
+        )?;
+
+        for pair in self.interval_boundaries.windows(2) {
+            if pair[1] <= pair[0] {
+                return Err(IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "interval boundary",
+                    value: pair[1],
+                    reason: "must be strictly after the previous boundary",
+                });
+            }
+        }
+
+        check_summary_coverage(self, summary)?;
+
+        for interval in 0..self.num_intervals {
+            // Each mini-segment must cover its own interval, regardless of the summary of the segment.
+            let interval_summary = SPKSummaryRecord {
+                start_epoch_et_s: self.interval_boundaries[interval],
+                end_epoch_et_s: self.interval_boundaries[interval + 1],
+                ..Default::default()
+            };
+            match self.nth_interval(interval) {
+                Ok(minisegment) => minisegment.check_integrity(&interval_summary)?,
+                Err(DecodingError::Integrity { source }) => return Err(source),
+                Err(_) => {
+                    return Err(IntegrityError::InvalidValue {
+                        dataset: Self::DATASET_NAME,
+                        variable: "mini-segment",
+                        value: interval as f64,
+                        reason: "cannot be decoded",
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod esoc_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::interpolation::InterpolationError,
+        naif::{
+            daf::{NAIFDataSet, NAIFSummaryRecord},
+            spk::summary::SPKSummaryRecord,
+        },
+    };
+
+    use super::{ESOCSetType18, ESOCSetType19, ESOCSubtype};
+
+    /// Cubic polynomial of the position, and its first three derivatives.
+    fn poly(t: f64, scale: f64) -> [f64; 4] {
+        [
+            scale * (1e3 + 2.0 * t + 3e-3 * t.powi(2) + 1e-6 * t.powi(3)),
+            scale * (2.0 + 6e-3 * t + 3e-6 * t.powi(2)),
+            scale * (6e-3 + 6e-6 * t),
+            scale * 6e-6,
+        ]
+    }
+
+    /// Builds a Type 18 data slice, where the velocity is deliberately not the derivative of the position
+    /// (it is the derivative scaled by ten) to check that both are interpolated independently.
+    fn type18_data(subtype: ESOCSubtype, num_records: usize, window_size: usize) -> Vec<f64> {
+        let epochs: Vec<f64> = (0..num_records)
+            .map(|n| 10.0 * n as f64 + 3.0 * (n as f64).sin())
+            .collect();
+        esoc_data(subtype, &epochs, window_size, 0.0)
+    }
+
+    /// Builds the data of an ESOC segment or mini-segment at the provided epochs, with an offset on the position.
+    ///
+    /// The velocity is the derivative of the position for the compact Hermite subtype, and ten times that otherwise.
+    fn esoc_data(
+        subtype: ESOCSubtype,
+        epochs: &[f64],
+        window_size: usize,
+        offset_km: f64,
+    ) -> Vec<f64> {
+        let vel_scale = if subtype == ESOCSubtype::HermiteCompact {
+            1.0
+        } else {
+            10.0
+        };
+        let mut data = Vec::new();
+        for t in epochs {
+            let [x, y, z] = [poly(*t, 1.0), poly(*t, -2.0), poly(*t, 0.5)];
+            data.extend([x[0] + offset_km, y[0] + offset_km, z[0] + offset_km]);
+            if subtype == ESOCSubtype::Hermite {
+                data.extend([x[1], y[1], z[1]]);
+            }
+            data.extend([vel_scale * x[1], vel_scale * y[1], vel_scale * z[1]]);
+            if subtype == ESOCSubtype::Hermite {
+                data.extend([10.0 * x[2], 10.0 * y[2], 10.0 * z[2]]);
+            }
+        }
+        data.extend(epochs);
+        // The epoch registry stores every 100th epoch
+        data.extend((1..=(epochs.len() - 1) / 100).map(|k| epochs[100 * k - 1]));
+        let subtype_code = match subtype {
+            ESOCSubtype::Hermite => 0.0,
+            ESOCSubtype::Lagrange => 1.0,
+            ESOCSubtype::HermiteCompact => 2.0,
+        };
+        data.extend([subtype_code, window_size as f64, epochs.len() as f64]);
+        data
+    }
+
+    /// Builds a Type 19 data slice from its mini-segments and interval boundaries.
+    fn type19_data(minisegments: &[Vec<f64>], boundaries: &[f64], select_later: bool) -> Vec<f64> {
+        let mut data = Vec::new();
+        let mut pointers = vec![1.0];
+        for minisegment in minisegments {
+            data.extend(minisegment);
+            pointers.push((data.len() + 1) as f64);
+        }
+        data.extend(boundaries);
+        // The boundary directory stores every 100th boundary
+        data.extend((1..=(boundaries.len() - 1) / 100).map(|k| boundaries[100 * k - 1]));
+        data.extend(pointers);
+        data.extend([
+            if select_later { 1.0 } else { 0.0 },
+            minisegments.len() as f64,
+        ]);
+        data
+    }
+
+    #[test]
+    fn type18_evaluate() {
+        let summary = SPKSummaryRecord::default();
+        for (subtype, window_size) in [(ESOCSubtype::Hermite, 4), (ESOCSubtype::Lagrange, 6)] {
+            let data = type18_data(subtype, 250, window_size);
+            let dataset = ESOCSetType18::from_f64_slice(&data).unwrap();
+            dataset.check_integrity(&summary).unwrap();
+            let early_summary = SPKSummaryRecord {
+                start_epoch_et_s: -1.0,
+                ..Default::default()
+            };
+            assert!(matches!(
+                dataset.check_integrity(&early_summary),
+                Err(IntegrityError::SummaryCoverageMismatch { .. })
+            ));
+            assert_eq!(dataset.subtype, subtype);
+            assert_eq!(dataset.num_records, 250);
+            assert_eq!(dataset.epoch_registry.len(), 2);
+            let last_et_s = *dataset.epoch_data.last().unwrap();
+
+            // Nodes, near the edges, across the epoch registry, and in between
+            for t in [
+                0.0,
+                1.5,
+                10.0 + 3.0 * 1.0_f64.sin(),
+                500.0,
+                999.0,
+                1234.5,
+                last_et_s - 0.1,
+                last_et_s,
+            ] {
+                let epoch = Epoch::from_et_seconds(t);
+                let (pos, vel) = dataset.evaluate(epoch, &summary).unwrap();
+                // Account for the rounding of the epoch to the nanosecond
+                let t = epoch.to_et_seconds();
+                for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                    let exp = poly(t, *scale);
+                    assert!(
+                        (pos[i] - exp[0]).abs() < 1e-8,
+                        "{subtype:?} pos error at {t}"
+                    );
+                    assert!(
+                        (vel[i] - 10.0 * exp[1]).abs() < 1e-8,
+                        "{subtype:?} vel error at {t}"
+                    );
+                }
+            }
+
+            let epoch = Epoch::from_et_seconds(last_et_s + 1.0);
+            assert_eq!(
+                dataset.evaluate(epoch, &summary),
+                Err(InterpolationError::NoInterpolationData {
+                    req: epoch,
+                    start: Epoch::from_et_seconds(0.0),
+                    end: Epoch::from_et_seconds(last_et_s),
+                    id: summary.target_id,
+                    center_id: summary.center_id,
+                })
+            );
+        }
+
+        // A window larger than the number of states uses all of them.
+        let data = type18_data(ESOCSubtype::Lagrange, 3, 8);
+        let dataset = ESOCSetType18::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.degree(), 2);
+        let (pos, _) = dataset
+            .evaluate(Epoch::from_et_seconds(7.0), &summary)
+            .unwrap();
+        assert!((pos[0] - poly(7.0, 1.0)[0]).abs() < 1e-3);
+
+        // Without any state, the coverage of the summary is reported.
+        let empty = ESOCSetType18 {
+            subtype: ESOCSubtype::Hermite,
+            samples: 4,
+            num_records: 0,
+            record_data: &[],
+            epoch_data: &[],
+            epoch_registry: &[],
+        };
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 10.0,
+            end_epoch_et_s: 20.0,
+            ..summary
+        };
+        let epoch = Epoch::from_et_seconds(15.0);
+        assert_eq!(
+            empty.evaluate(epoch, &summary),
+            Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.target_id,
+                center_id: summary.center_id,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        let invalid = |idx_from_end: usize, value: f64| {
+            let mut data = type18_data(ESOCSubtype::Hermite, 10, 4);
+            let idx = data.len() - idx_from_end;
+            data[idx] = value;
+            match ESOCSetType18::from_f64_slice(&data) {
+                Ok(_) => panic!("test failed on invalid metadata"),
+                Err(e) => e,
+            }
+        };
+
+        assert_eq!(
+            invalid(3, 2.0),
+            DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "ESOC Type 18",
+                    variable: "subtype",
+                    value: 2.0,
+                    reason: "must be 0 (Hermite) or 1 (Lagrange)",
+                }
+            }
+        );
+        assert_eq!(
+            invalid(2, 33.0),
+            DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "ESOC Type 18",
+                    variable: "window size",
+                    value: 33.0,
+                    reason: "must be an integer between 1 and 32 (MAX_SAMPLES)",
+                }
+            }
+        );
+        assert_eq!(
+            invalid(1, 11.0),
+            DecodingError::InaccessibleBytes {
+                start: 0,
+                end: 11 * 13 + 3,
+                size: 10 * 13 + 3,
+            }
+        );
+        // The Lagrange subtype has smaller packets: the data of the Hermite subtype does not match.
+        assert_eq!(
+            invalid(3, 1.0),
+            DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "ESOC Type 18",
+                    variable: "number of records",
+                    value: 10.0,
+                    reason: "does not account for all of the data of the segment",
+                }
+            }
+        );
+
+        let mut data = type18_data(ESOCSubtype::Lagrange, 10, 4);
+        data[5] = f64::NAN;
+        assert_eq!(
+            ESOCSetType18::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::NaNValue {
+                dataset: "ESOC Type 18",
+                variable: "one of the record data",
+                offset: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn type19_evaluate() {
+        let summary = SPKSummaryRecord::default();
+        let subtypes = [
+            ESOCSubtype::Hermite,
+            ESOCSubtype::Lagrange,
+            ESOCSubtype::HermiteCompact,
+        ];
+        // Enough intervals to exercise the boundary directory
+        let boundaries: Vec<f64> = (0..=120).map(|n| 100.0 * n as f64).collect();
+
+        for select_later in [false, true] {
+            // Each mini-segment is padded with states outside of its interval, as is customary,
+            // and is offset by its index to know which one is used.
+            let minisegments: Vec<Vec<f64>> = boundaries
+                .windows(2)
+                .enumerate()
+                .map(|(n, pair)| {
+                    let epochs: Vec<f64> = (-2..=12)
+                        .map(|k| pair[0] + (pair[1] - pair[0]) * k as f64 / 10.0)
+                        .collect();
+                    esoc_data(subtypes[n % 3], &epochs, 6, 1e-3 * n as f64)
+                })
+                .collect();
+            let data = type19_data(&minisegments, &boundaries, select_later);
+            let dataset = ESOCSetType19::from_f64_slice(&data).unwrap();
+            dataset.check_integrity(&summary).unwrap();
+            let late_summary = SPKSummaryRecord {
+                end_epoch_et_s: 12_001.0,
+                ..Default::default()
+            };
+            assert_eq!(
+                dataset.check_integrity(&late_summary),
+                Err(IntegrityError::SummaryCoverageMismatch {
+                    dataset: "ESOC Type 19",
+                    summary_start_et_s: 0.0,
+                    summary_end_et_s: 12_001.0,
+                    data_start_et_s: 0.0,
+                    data_end_et_s: 12_000.0,
+                })
+            );
+            assert_eq!(dataset.num_intervals, 120);
+            assert_eq!(dataset.interval_registry.len(), 1);
+            assert_eq!(dataset.select_later, select_later);
+            assert_eq!(dataset.nth_interval(2).unwrap().subtype, subtypes[2]);
+            assert_eq!(
+                dataset.nth_record(16).unwrap().to_pos_vel().0[0],
+                1e-3 + poly(-20.0 + 100.0 + 10.0, 1.0)[0]
+            );
+
+            for (n, t) in [
+                (0, 0.0),
+                (0, 55.5),
+                (37, 3_777.7),
+                (99, 9_950.0),
+                (119, 12_000.0),
+            ] {
+                let epoch = Epoch::from_et_seconds(t);
+                let (pos, vel) = dataset.evaluate(epoch, &summary).unwrap();
+                let t = epoch.to_et_seconds();
+                let vel_scale = if subtypes[n % 3] == ESOCSubtype::HermiteCompact {
+                    1.0
+                } else {
+                    10.0
+                };
+                for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                    let exp = poly(t, *scale);
+                    assert!(
+                        (pos[i] - exp[0] - 1e-3 * n as f64).abs() < 1e-8,
+                        "pos error at {t}"
+                    );
+                    assert!(
+                        (vel[i] - vel_scale * exp[1]).abs() < 1e-8,
+                        "vel error at {t}"
+                    );
+                }
+            }
+
+            // Epochs on a boundary use the mini-segment selected by the flag, and the trajectory is otherwise continuous.
+            for (n, &t) in boundaries.iter().enumerate().take(120).skip(1) {
+                let (pos, _) = dataset
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap();
+                let expected_n = if select_later { n } else { n - 1 };
+                assert!(
+                    (pos[0] - poly(t, 1.0)[0] - 1e-3 * expected_n as f64).abs() < 1e-8,
+                    "wrong mini-segment at boundary {t}"
+                );
+                assert_eq!(dataset.degree_at(Epoch::from_et_seconds(t)).unwrap(), {
+                    match subtypes[expected_n % 3] {
+                        ESOCSubtype::Lagrange => 5,
+                        _ => 11,
+                    }
+                });
+
+                for (dt, interval) in [(-1e-3, n - 1), (1e-3, n)] {
+                    let epoch = Epoch::from_et_seconds(t + dt);
+                    let (pos, _) = dataset.evaluate(epoch, &summary).unwrap();
+                    let exp = poly(epoch.to_et_seconds(), 1.0)[0] + 1e-3 * interval as f64;
+                    assert!((pos[0] - exp).abs() < 1e-8, "discontinuity at {t}");
+                }
+            }
+
+            let epoch = Epoch::from_et_seconds(12_000.1);
+            assert_eq!(
+                dataset.evaluate(epoch, &summary),
+                Err(InterpolationError::NoInterpolationData {
+                    req: epoch,
+                    start: Epoch::from_et_seconds(0.0),
+                    end: Epoch::from_et_seconds(12_000.0),
+                    id: summary.target_id,
+                    center_id: summary.center_id,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn type19_invalid_data() {
+        let minisegments = vec![
+            esoc_data(ESOCSubtype::Lagrange, &[0.0, 1.0, 2.0], 2, 0.0),
+            esoc_data(ESOCSubtype::HermiteCompact, &[2.0, 3.0, 4.0], 2, 0.0),
+        ];
+        let boundaries = [0.0, 2.0, 4.0];
+        let invalid = |idx_from_end: usize, value: f64| {
+            let mut data = type19_data(&minisegments, &boundaries, false);
+            let idx = data.len() - idx_from_end;
+            data[idx] = value;
+            match ESOCSetType19::from_f64_slice(&data) {
+                Ok(_) => panic!("test failed on invalid metadata"),
+                Err(e) => e,
+            }
+        };
+
+        assert_eq!(
+            invalid(2, 0.5),
+            DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "ESOC Type 19",
+                    variable: "boundary flag",
+                    value: 0.5,
+                    reason: "must be 0 (earlier interval) or 1 (later interval)",
+                }
+            }
+        );
+        assert_eq!(
+            invalid(1, 0.0),
+            DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "ESOC Type 19",
+                    variable: "number of intervals",
+                    value: 0.0,
+                    reason: "must be a positive integer",
+                }
+            }
+        );
+        assert_eq!(
+            invalid(1, 3.0),
+            DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "ESOC Type 19",
+                    variable: "mini-segment pointer",
+                    value: 4.0,
+                    reason: "must be increasing from 1 and point within the mini-segment data",
+                }
+            }
+        );
+        assert_eq!(
+            invalid(1, 1000.0),
+            DecodingError::InaccessibleBytes {
+                start: 0,
+                end: 2004,
+                size: 56,
+            }
+        );
+
+        // Subtype 2 is only valid in a Type 19 mini-segment.
+        assert!(ESOCSetType18::from_f64_slice(&minisegments[1]).is_err());
+
+        let mut data = type19_data(&minisegments, &boundaries, true);
+        data[minisegments[0].len() + 5] = f64::NAN;
+        assert_eq!(
+            ESOCSetType19::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::NaNValue {
+                dataset: "ESOC Type 18",
+                variable: "one of the record data",
+                offset: 5,
+            })
+        );
+
+        let mut data = type19_data(&minisegments, &boundaries, true);
+        let first_boundary = minisegments[0].len() + minisegments[1].len();
+        data[first_boundary + 1] = -1.0;
+        assert_eq!(
+            ESOCSetType19::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InvalidValue {
+                dataset: "ESOC Type 19",
+                variable: "interval boundary",
+                value: -1.0,
+                reason: "must be strictly after the previous boundary",
+            })
+        );
+
+        // Each mini-segment must cover its interval
+        let data = type19_data(&minisegments, &[0.0, 2.5, 4.0], true);
+        assert_eq!(
+            ESOCSetType19::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset: "ESOC Type 18",
+                summary_start_et_s: 0.0,
+                summary_end_et_s: 2.5,
+                data_start_et_s: 0.0,
+                data_end_et_s: 2.0,
+            })
+        );
+    }
+}
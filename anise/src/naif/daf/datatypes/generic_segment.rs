@@ -0,0 +1,260 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use crate::errors::{DecodingError, IntegrityError, TooFewDoublesSnafu};
+use snafu::ensure;
+
+use super::epoch_registry::EPOCH_REGISTRY_STRIDE;
+
+// Indexes of the metadata items of a generic segment (cf. SPICE's `sgparam.inc`), where each `*BAS` is the offset
+// of the corresponding component from the start of the segment.
+const CONBAS: usize = 0;
+const NCON: usize = 1;
+const RDRBAS: usize = 2;
+const NRDR: usize = 3;
+const REFBAS: usize = 5;
+const NREF: usize = 6;
+const PKTBAS: usize = 10;
+const NPKT: usize = 11;
+const PKTSZ: usize = 14;
+const PKTOFF: usize = 15;
+
+/// Number of metadata items at the end of a generic segment, the last of which is this number itself.
+pub(crate) const GENERIC_SEGMENT_NMETA: usize = 17;
+
+/// Components of a generic segment (used by SPK Types 10, 14 and 18) with fixed size packets.
+///
+/// The metadata is stored at the very end of the segment, and gives the location and size of each component.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct GenericSegment<'a> {
+    /// Constant values, shared by all of the packets
+    pub constants: &'a [f64],
+    /// Reference values (typically epochs), one per packet
+    pub reference_values: &'a [f64],
+    /// Directory of every 100th reference value
+    pub reference_directory: &'a [f64],
+    /// Contiguous packet data
+    pub packet_data: &'a [f64],
+    /// Number of doubles in each packet
+    pub packet_size: usize,
+    pub num_packets: usize,
+    /// Offsets in the segment data of the packet data, the reference values, and the reference directory
+    pub offsets: [usize; 3],
+}
+
+impl<'a> GenericSegment<'a> {
+    pub fn from_f64_slice(dataset: &'static str, slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= GENERIC_SEGMENT_NMETA,
+            TooFewDoublesSnafu {
+                dataset,
+                need: GENERIC_SEGMENT_NMETA,
+                got: slice.len()
+            }
+        );
+
+        let nmeta = slice[slice.len() - 1];
+        if nmeta != GENERIC_SEGMENT_NMETA as f64 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset,
+                    variable: "number of generic segment metadata (NMETA)",
+                    value: nmeta,
+                    reason: "must be 17, the only supported generic segment layout",
+                },
+            });
+        }
+
+        let data_len = slice.len() - GENERIC_SEGMENT_NMETA;
+        let meta = &slice[data_len..];
+        let item = |idx: usize, variable: &'static str| -> Result<usize, DecodingError> {
+            let value = meta[idx];
+            if value.is_finite() && value >= 0.0 && value.fract() == 0.0 {
+                Ok(value as usize)
+            } else {
+                Err(DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset,
+                        variable,
+                        value,
+                        reason: "must be a non-negative integer",
+                    },
+                })
+            }
+        };
+        let component = |base: usize, len: usize| -> Result<&'a [f64], DecodingError> {
+            let end = base.saturating_add(len);
+            if end > data_len {
+                Err(DecodingError::InaccessibleBytes {
+                    start: base,
+                    end,
+                    size: data_len,
+                })
+            } else {
+                Ok(&slice[base..end])
+            }
+        };
+
+        let packet_size = item(PKTSZ, "generic segment packet size (PKTSZ)")?;
+        if packet_size == 0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset,
+                    variable: "generic segment packet size (PKTSZ)",
+                    value: 0.0,
+                    reason: "must be strictly positive, variable size packets are not supported",
+                },
+            });
+        }
+        let num_packets = item(NPKT, "generic segment number of packets (NPKT)")?;
+
+        let constants = component(
+            item(CONBAS, "generic segment constants base (CONBAS)")?,
+            item(NCON, "generic segment number of constants (NCON)")?,
+        )?;
+        let reference_base = item(REFBAS, "generic segment reference values base (REFBAS)")?;
+        let reference_values = component(
+            reference_base,
+            item(NREF, "generic segment number of reference values (NREF)")?,
+        )?;
+        let directory_base = item(RDRBAS, "generic segment reference directory base (RDRBAS)")?;
+        let reference_directory = component(
+            directory_base,
+            item(NRDR, "generic segment reference directory size (NRDR)")?,
+        )?;
+        let packet_base = item(PKTBAS, "generic segment packets base (PKTBAS)")?
+            .saturating_add(item(PKTOFF, "generic segment packet offset (PKTOFF)")?);
+        let packet_data = component(packet_base, num_packets.saturating_mul(packet_size))?;
+
+        Ok(Self {
+            constants,
+            reference_values,
+            reference_directory,
+            packet_data,
+            packet_size,
+            num_packets,
+            offsets: [packet_base, reference_base, directory_base],
+        })
+    }
+
+    /// Checks that there is one reference value per packet, and one directory entry per 100 reference values
+    /// (excluding the last one).
+    pub fn check_references(&self, dataset: &'static str) -> Result<(), IntegrityError> {
+        if self.reference_values.len() != self.num_packets {
+            return Err(IntegrityError::InvalidValue {
+                dataset,
+                variable: "number of reference values (NREF)",
+                value: self.reference_values.len() as f64,
+                reason: "must match the number of packets (NPKT)",
+            });
+        }
+
+        if self.reference_directory.len()
+            != self.reference_values.len().saturating_sub(1) / EPOCH_REGISTRY_STRIDE
+        {
+            return Err(IntegrityError::InvalidValue {
+                dataset,
+                variable: "reference directory size (NRDR)",
+                value: self.reference_directory.len() as f64,
+                reason: "must be one entry per 100 reference values, excluding the last one",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a generic segment with the constants first, then the packets, the reference values and their directory.
+#[cfg(test)]
+pub(crate) fn build_generic_segment(
+    constants: &[f64],
+    packets: &[f64],
+    packet_size: usize,
+    reference_values: &[f64],
+    reference_directory: &[f64],
+) -> Vec<f64> {
+    let mut data = constants.to_vec();
+    let pktbas = data.len();
+    data.extend(packets);
+    let refbas = data.len();
+    data.extend(reference_values);
+    let rdrbas = data.len();
+    data.extend(reference_directory);
+    let rsvbas = data.len();
+
+    let mut meta = [0.0; GENERIC_SEGMENT_NMETA];
+    meta[CONBAS] = 0.0;
+    meta[NCON] = constants.len() as f64;
+    meta[RDRBAS] = rdrbas as f64;
+    meta[NRDR] = reference_directory.len() as f64;
+    // Explicit reference values
+    meta[4] = 1.0;
+    meta[REFBAS] = refbas as f64;
+    meta[NREF] = reference_values.len() as f64;
+    // No packet directory
+    meta[7] = rsvbas as f64;
+    meta[9] = 0.0;
+    meta[PKTBAS] = pktbas as f64;
+    meta[NPKT] = (packets.len() / packet_size) as f64;
+    meta[12] = rsvbas as f64;
+    meta[PKTSZ] = packet_size as f64;
+    meta[PKTOFF] = 0.0;
+    meta[16] = GENERIC_SEGMENT_NMETA as f64;
+    data.extend(meta);
+    data
+}
+
+#[cfg(test)]
+mod generic_segment_ut {
+    use super::{build_generic_segment, GenericSegment};
+    use crate::errors::{DecodingError, IntegrityError};
+
+    #[test]
+    fn generic_segment_layout() {
+        let packets: Vec<f64> = (0..9).map(|i| i as f64).collect();
+        let data = build_generic_segment(&[-1.0, -2.0], &packets, 3, &[10.0, 20.0, 30.0], &[]);
+        let segment = GenericSegment::from_f64_slice("test", &data).unwrap();
+        assert_eq!(segment.constants, &[-1.0, -2.0]);
+        assert_eq!(segment.reference_values, &[10.0, 20.0, 30.0]);
+        assert!(segment.reference_directory.is_empty());
+        assert_eq!(segment.num_packets, 3);
+        assert_eq!(segment.packet_size, 3);
+        assert_eq!(segment.packet_data, packets.as_slice());
+
+        // The number of metadata must be exactly 17
+        let mut bad = data.clone();
+        let last = bad.len() - 1;
+        bad[last] = 15.0;
+        assert_eq!(
+            GenericSegment::from_f64_slice("test", &bad),
+            Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "test",
+                    variable: "number of generic segment metadata (NMETA)",
+                    value: 15.0,
+                    reason: "must be 17, the only supported generic segment layout",
+                }
+            })
+        );
+
+        // Components cannot overlap the metadata
+        let mut bad = data;
+        let npkt_idx = bad.len() - 17 + 11;
+        bad[npkt_idx] = 5.0;
+        assert_eq!(
+            GenericSegment::from_f64_slice("test", &bad),
+            Err(DecodingError::InaccessibleBytes {
+                start: 2,
+                end: 17,
+                size: 14
+            })
+        );
+    }
+}
@@ -9,31 +9,501 @@
  */
 
 use core::fmt;
+use core::ops::Range;
+use core::ptr;
+use core::slice::ChunksExact;
 use hifitime::{Duration, Epoch, TimeUnits};
+use log::{debug, warn};
 use snafu::{ensure, ResultExt};
 
 use crate::errors::{DecodingError, IntegrityError, TooFewDoublesSnafu};
 use crate::math::interpolation::{
-    hermite_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES,
+    hermite_eval_deriv2, hermite_eval_multi, hermite_newton_coefficients, hermite_newton_eval,
+    hermite_value_multi, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES,
 };
 use crate::naif::daf::NAIFSummaryRecord;
+use crate::naif::spk::summary::SPKSummaryRecord;
 use crate::{
+    frames::{Frame, FrameUid},
     math::{cartesian::CartesianState, Vector3},
-    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord},
+    naif::daf::{
+        datatypes::{
+            check_epoch_span, check_finite, check_summary_coverage, epochs_coverage, non_finite,
+            nth_record_data, NO_RECORDS,
+        },
+        NAIFDataRecord, NAIFDataSet, NAIFRecord,
+    },
     DBL_SIZE,
 };
 
-use super::posvel::PositionVelocityRecord;
+use super::{
+    epoch_registry::{registry_mismatch, search_epoch},
+    posvel::PositionVelocityRecord,
+};
 
 #[derive(PartialEq)]
 pub struct HermiteSetType12<'a> {
+    /// Epoch of the first state in the record data
     pub first_state_epoch: Epoch,
+    /// Time step between two consecutive states
     pub step_size: Duration,
+    /// Number of samples to use to build the interpolation
     pub window_size: usize,
+    /// Total number of records stored in this data
     pub num_records: usize,
+    /// State data used for the interpolation
     pub record_data: &'a [f64],
 }
 
+impl<'a> HermiteSetType12<'a> {
+    pub fn degree(&self) -> usize {
+        2 * self.window_size - 1
+    }
+
+    /// Returns the epoch of the last state in the record data
+    pub fn last_state_epoch(&self) -> Epoch {
+        self.first_state_epoch + (self.num_records.saturating_sub(1) as f64) * self.step_size
+    }
+
+    /// Returns the epoch of the n-th state in the record data, in seconds past J2000 TDB.
+    fn nth_epoch_et_s(&self, n: usize) -> f64 {
+        self.first_state_epoch.to_et_seconds() + (n as f64) * self.step_size.to_seconds()
+    }
+
+    /// Evaluates this data at the provided epoch using `samples` states instead of the window size stored in the file.
+    ///
+    /// The number of samples is clamped between 2 and MAX_SAMPLES, and to the number of records available.
+    /// Note that changing the window size changes the result compared to SPICE, which always uses the window size of the file.
+    pub fn evaluate_with_samples<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        samples: usize,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        self.interpolate(epoch, summary, clamp_samples(samples, self.num_records))
+    }
+
+    /// Evaluates this data at the provided epoch like `evaluate`, but epochs at most `tolerance` outside of the coverage
+    /// of this data are evaluated at the first or last state instead of returning an error.
+    ///
+    /// This is useful for epochs converted from another time scale, which may end up a few nanoseconds outside of the coverage.
+    /// A zero tolerance is as strict as `evaluate`.
+    pub fn evaluate_within<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        tolerance: Duration,
+    ) -> Result<CartesianState, InterpolationError> {
+        let epoch = clamp_epoch(
+            epoch,
+            self.first_state_epoch,
+            self.last_state_epoch(),
+            tolerance,
+        );
+        self.evaluate(epoch, summary)
+    }
+
+    /// Evaluates only the position at the provided epoch, which skips the computation of the derivatives of the interpolation.
+    ///
+    /// The position is identical to that returned by `evaluate`.
+    pub fn evaluate_position<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Vector3, InterpolationError> {
+        Ok(self
+            .interpolate_parts(epoch, summary, self.window_size, false, false)?
+            .0)
+    }
+
+    /// Builds the Hermite interpolation over `window_size` states around the provided epoch.
+    fn interpolate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        window_size: usize,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        self.interpolate_parts(epoch, summary, window_size, false, true)
+    }
+
+    /// Builds the Hermite interpolation like `interpolate`, but the returned velocity is zero unless `with_velocity` is set.
+    ///
+    /// If `drop_edges` is set, the first and last states of the window are not used (cf. `inner_window`).
+    fn interpolate_parts<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        window_size: usize,
+        drop_edges: bool,
+        with_velocity: bool,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        // Check that we even have interpolation data for that time
+        let epoch_et_s = epoch.to_et_seconds();
+        let first_et_s = self.first_state_epoch.to_et_seconds();
+        let last_et_s = self.nth_epoch_et_s(self.num_records.saturating_sub(1));
+        if epoch_et_s + 1e-9 < first_et_s || epoch_et_s - 1e-9 > last_et_s {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: self.first_state_epoch,
+                end: self.last_state_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        // The states are equally spaced, so we can directly compute the index of the nearest state.
+        let step_size_s = self.step_size.to_seconds();
+        let offset = (epoch_et_s - first_et_s) / step_size_s;
+        let nearest_idx = offset.round() as usize;
+        if (offset - offset.round()).abs() * step_size_s < 1e-9 {
+            // Oh wow, this state actually exists, no interpolation needed!
+            return Ok(self
+                .nth_record(nearest_idx.min(self.num_records.saturating_sub(1)))
+                .context(InterpDecodingSnafu)?
+                .to_pos_vel());
+        }
+
+        // Build the window like SPICE does: centered on the nearest state for odd window sizes,
+        // and with the epoch between the two middle states for even window sizes.
+        let first_idx = if window_size % 2 == 1 {
+            nearest_idx.saturating_sub((window_size - 1) / 2)
+        } else {
+            (offset.floor() as usize)
+                .saturating_add(1)
+                .saturating_sub(window_size / 2)
+        };
+        // Shift the window back if it would extend past the end of the data.
+        let first_idx = first_idx.min(self.num_records.saturating_sub(window_size));
+        let last_idx = self.num_records.min(first_idx.saturating_add(window_size));
+        let (first_idx, last_idx) = if drop_edges {
+            let inner = inner_window(last_idx - first_idx);
+            (first_idx + inner.start, first_idx + inner.end)
+        } else {
+            (first_idx, last_idx)
+        };
+        let samples = last_idx - first_idx;
+
+        // Statically allocated arrays of the maximum number of samples
+        let mut epochs = [0.0; MAX_SAMPLES];
+        let mut xs = [0.0; MAX_SAMPLES];
+        let mut ys = [0.0; MAX_SAMPLES];
+        let mut zs = [0.0; MAX_SAMPLES];
+        let mut vxs = [0.0; MAX_SAMPLES];
+        let mut vys = [0.0; MAX_SAMPLES];
+        let mut vzs = [0.0; MAX_SAMPLES];
+        for (cno, idx) in (first_idx..last_idx).enumerate() {
+            let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+            xs[cno] = record.x_km;
+            ys[cno] = record.y_km;
+            zs[cno] = record.z_km;
+            vxs[cno] = record.vx_km_s;
+            vys[cno] = record.vy_km_s;
+            vzs[cno] = record.vz_km_s;
+            epochs[cno] = self.nth_epoch_et_s(idx);
+        }
+
+        // Build the interpolation polynomials making sure to limit the slices to exactly the number of items we actually used
+        // The other ones are zeros, which would cause the interpolation function to fail.
+        if !with_velocity {
+            let [x_km, y_km, z_km] = hermite_value_multi(
+                &epochs[..samples],
+                [&xs[..samples], &ys[..samples], &zs[..samples]],
+                [&vxs[..samples], &vys[..samples], &vzs[..samples]],
+                epoch_et_s,
+            )?;
+            return Ok((Vector3::new(x_km, y_km, z_km), Vector3::zeros()));
+        }
+
+        let [(x_km, vx_km_s), (y_km, vy_km_s), (z_km, vz_km_s)] = hermite_eval_multi(
+            &epochs[..samples],
+            [&xs[..samples], &ys[..samples], &zs[..samples]],
+            [&vxs[..samples], &vys[..samples], &vzs[..samples]],
+            epoch_et_s,
+        )?;
+
+        // And build the result
+        let pos_km = Vector3::new(x_km, y_km, z_km);
+        let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    /// Evaluates this data at the provided epoch, and returns the state in the center frame of the summary,
+    /// whose gravitational parameter is set to `mu_km3_s2`, if provided.
+    pub fn to_cartesian_state(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<CartesianState, InterpolationError> {
+        let mut state = self.evaluate(epoch, summary)?;
+        state.frame.mu_km3_s2 = mu_km3_s2;
+        Ok(state)
+    }
+
+    /// Evaluates this data at the provided epoch like `to_cartesian_state`, and also returns an estimate of the interpolation error in km.
+    ///
+    /// This estimate is the distance between the interpolated position and the position interpolated over the same window without
+    /// its first and last states. The smaller window interpolates with a lower degree, so its error is typically much larger than that of the
+    /// full window: this distance is a conservative bound of the truncation error, and a cheap proxy to check that the states of this
+    /// data are dense enough for the required precision. It is zero at the epochs of the states, which are returned as is.
+    pub fn evaluate_with_error(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<(CartesianState, f64), InterpolationError> {
+        let (state, accuracy) = self.evaluate_with_accuracy(epoch, summary, mu_km3_s2)?;
+        Ok((state, accuracy.pos_km))
+    }
+
+    /// Evaluates this data at the provided epoch like `to_cartesian_state`, and also returns an estimate of the position
+    /// and velocity interpolation errors, computed like that of `evaluate_with_error`.
+    ///
+    /// This interpolates twice, so use `to_cartesian_state` when the estimate is not needed.
+    pub fn evaluate_with_accuracy(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<(CartesianState, InterpolationAccuracy), InterpolationError> {
+        let state = self.to_cartesian_state(epoch, summary, mu_km3_s2)?;
+        let (coarse_pos_km, coarse_vel_km_s) =
+            self.interpolate_parts(epoch, summary, self.window_size, true, true)?;
+        Ok((
+            state,
+            InterpolationAccuracy {
+                pos_km: (state.radius_km - coarse_pos_km).norm(),
+                vel_km_s: (state.velocity_km_s - coarse_vel_km_s).norm(),
+            },
+        ))
+    }
+
+    /// Evaluates this data at all of the provided epochs like `to_cartesian_state`, returning the states in the same order as the epochs.
+    ///
+    /// The states of this data are equally spaced, so each window is directly computed and no search is needed.
+    pub fn evaluate_batch(
+        &self,
+        epochs: &[Epoch],
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<Vec<CartesianState>, InterpolationError> {
+        epochs
+            .iter()
+            .map(|epoch| self.to_cartesian_state(*epoch, summary, mu_km3_s2))
+            .collect()
+    }
+
+    /// Returns the record nearest to the provided epoch, without interpolation, e.g. to check that interpolated states
+    /// remain close to the surrounding records. Epochs outside of the data return the first or the last record, and
+    /// epochs exactly between two records return the earlier one.
+    pub fn nearest_record(&self, epoch: Epoch) -> Result<NearestRecord, DecodingError> {
+        let offset = (epoch.to_et_seconds() - self.first_state_epoch.to_et_seconds())
+            / self.step_size.to_seconds();
+        let prev_idx = offset.floor();
+        let index = if offset - prev_idx <= 0.5 {
+            prev_idx
+        } else {
+            prev_idx + 1.0
+        };
+        // Saturating casts clamp the index to the first record (and NaN offsets, from a zero step size, to zero).
+        let index = (index as usize).min(self.num_records.saturating_sub(1));
+        let record_epoch = Epoch::from_et_seconds(self.nth_epoch_et_s(index));
+        Ok(NearestRecord {
+            index,
+            record: self.nth_record(index)?,
+            epoch: record_epoch,
+            offset: record_epoch - epoch,
+        })
+    }
+
+    /// Returns an iterator over the records of this data and their epochs, computed from the first epoch and the step size.
+    pub fn iter(&self) -> HermiteRecords<'a> {
+        HermiteRecords::new(
+            RecordEpochs::EqualStep {
+                first: self.first_state_epoch,
+                step: self.step_size,
+            },
+            self.record_data,
+            self.num_records,
+        )
+    }
+}
+
+/// Estimate of the interpolation error of a state, cf. [HermiteSetType13::evaluate_with_accuracy].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct InterpolationAccuracy {
+    /// Estimated error of the position, in km
+    pub pos_km: f64,
+    /// Estimated error of the velocity, in km/s
+    pub vel_km_s: f64,
+}
+
+/// Consistency of the stored velocities of a Type 13 segment with its positions, cf. [HermiteSetType13::check_physical_consistency].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct VelocityConsistency {
+    /// Number of pairs of consecutive records which were compared
+    pub num_pairs: usize,
+    /// Median of the relative disagreements of all of the compared pairs
+    pub median_rel_error: f64,
+    /// Index of the first record of the pair with the largest relative disagreement
+    pub worst_record: usize,
+    /// Relative disagreement of that pair
+    pub worst_rel_error: f64,
+    /// Whether the median relative disagreement exceeds the threshold of the check
+    pub flagged: bool,
+}
+
+/// State stored in a Hermite data set nearest to a requested epoch, cf. [HermiteSetType12::nearest_record] and [HermiteSetType13::nearest_record].
+#[derive(Copy, Clone, Debug)]
+pub struct NearestRecord {
+    /// Index of the record in the data set
+    pub index: usize,
+    /// Stored position and velocity, without any interpolation
+    pub record: PositionVelocityRecord,
+    /// Exact epoch of the record
+    pub epoch: Epoch,
+    /// Epoch of the record minus the requested epoch
+    pub offset: Duration,
+}
+
+impl NearestRecord {
+    /// Returns true if the record is strictly before the requested epoch.
+    pub fn is_before(&self) -> bool {
+        self.offset.is_negative()
+    }
+
+    /// Returns true if the record is strictly after the requested epoch.
+    pub fn is_after(&self) -> bool {
+        self.offset > Duration::ZERO
+    }
+}
+
+/// Epochs of the records returned by [HermiteRecords].
+#[derive(Copy, Clone, Debug)]
+enum RecordEpochs<'a> {
+    /// Equally spaced epochs of a Type 12 data set
+    EqualStep { first: Epoch, step: Duration },
+    /// Epochs stored in a Type 13 data set, in seconds past J2000 ET
+    Stored(&'a [f64]),
+}
+
+/// Iterator over the epochs and records of a Hermite data set, created with [HermiteSetType12::iter] or [HermiteSetType13::iter].
+///
+/// Only the records fully stored in the data are returned, and the iteration stops at the first stored epoch beyond the span
+/// of an [Epoch] (e.g. NaN), so malformed data ends the iteration early instead of panicking: cf. `check_integrity` to report it.
+#[derive(Clone, Debug)]
+pub struct HermiteRecords<'a> {
+    epochs: RecordEpochs<'a>,
+    states: ChunksExact<'a, f64>,
+    idx: usize,
+    len: usize,
+}
+
+impl<'a> HermiteRecords<'a> {
+    fn new(epochs: RecordEpochs<'a>, state_data: &'a [f64], num_records: usize) -> Self {
+        let rcrd_len = PositionVelocityRecord::SIZE / DBL_SIZE;
+        let mut len = num_records.min(state_data.len() / rcrd_len);
+        if let RecordEpochs::Stored(epoch_data) = epochs {
+            len = len.min(epoch_data.len());
+        }
+        Self {
+            epochs,
+            states: state_data.chunks_exact(rcrd_len),
+            idx: 0,
+            len,
+        }
+    }
+}
+
+impl<'a> Iterator for HermiteRecords<'a> {
+    type Item = (Epoch, PositionVelocityRecord);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.len {
+            return None;
+        }
+        let epoch = match self.epochs {
+            RecordEpochs::EqualStep { first, step } => first + (self.idx as f64) * step,
+            RecordEpochs::Stored(epoch_data) => {
+                let epoch_et_s = epoch_data[self.idx];
+                if check_epoch_span(HermiteSetType13::DATASET_NAME, "epoch data", epoch_et_s)
+                    .is_err()
+                {
+                    self.len = self.idx;
+                    return None;
+                }
+                Epoch::from_et_seconds(epoch_et_s)
+            }
+        };
+        let record = PositionVelocityRecord::from_slice_f64(self.states.next()?);
+        self.idx += 1;
+        Some((epoch, record))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for HermiteRecords<'a> {}
+
+/// Returns the coverage bound nearest to the provided epoch if it's outside of the coverage by at most `tolerance`,
+/// and the epoch itself otherwise.
+fn clamp_epoch(epoch: Epoch, start: Epoch, end: Epoch, tolerance: Duration) -> Epoch {
+    let bound = if epoch < start {
+        start
+    } else if epoch > end {
+        end
+    } else {
+        return epoch;
+    };
+    if (epoch - bound).abs() <= tolerance {
+        debug!("clamping {epoch} to the coverage bound {bound} (tolerance of {tolerance})");
+        bound
+    } else {
+        epoch
+    }
+}
+
+/// Clamps the requested number of interpolation samples to [2, MAX_SAMPLES] and to the number of records.
+fn clamp_samples(samples: usize, num_records: usize) -> usize {
+    samples.clamp(2, MAX_SAMPLES).min(num_records)
+}
+
+/// Returns the positions in an interpolation window of `samples` states of the states used by the coarse estimate of
+/// `evaluate_with_accuracy`: all but the first and the last, such that the estimate interpolates around the same epochs
+/// as the full window, or only the first one if the window has at most two states.
+fn inner_window(samples: usize) -> Range<usize> {
+    if samples > 2 {
+        1..samples - 1
+    } else {
+        0..samples.min(1)
+    }
+}
+
+/// Builds the Cartesian state of the provided position and velocity in the center frame of the summary.
+pub(crate) fn center_state<S: NAIFSummaryRecord>(
+    radius_km: Vector3,
+    velocity_km_s: Vector3,
+    epoch: Epoch,
+    summary: &S,
+    mu_km3_s2: Option<f64>,
+) -> CartesianState {
+    let mut frame = Frame::from(FrameUid {
+        ephemeris_id: summary.center_id(),
+        orientation_id: summary.orientation_id(),
+    });
+    frame.mu_km3_s2 = mu_km3_s2;
+    CartesianState {
+        radius_km,
+        velocity_km_s,
+        epoch,
+        frame,
+    }
+}
+
 impl<'a> fmt::Display for HermiteSetType12<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -66,27 +536,90 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
         let seconds_since_j2000 = slice[slice.len() - 4];
         if !seconds_since_j2000.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "seconds since J2000 ET",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "seconds since J2000 ET",
+                    seconds_since_j2000,
+                    slice.len() - 4,
+                ),
             });
         }
 
+        check_epoch_span(
+            Self::DATASET_NAME,
+            "seconds since J2000 ET",
+            seconds_since_j2000,
+        )
+        .map_err(|source| DecodingError::Integrity { source })?;
         let first_state_epoch = Epoch::from_et_seconds(seconds_since_j2000);
         let step_size_s = slice[slice.len() - 3];
         if !step_size_s.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "step size in seconds",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "step size in seconds",
+                    step_size_s,
+                    slice.len() - 3,
+                ),
             });
         }
 
         let step_size = step_size_s.seconds();
-        let window_size = slice[slice.len() - 2] as usize;
-        let num_records = slice[slice.len() - 1] as usize;
+        // NOTE: The Type 12 and 13 specify that the windows size minus one is stored!
+        let window_size_f64 = slice[slice.len() - 2] + 1.0;
+        if !window_size_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "window size",
+                    window_size_f64,
+                    slice.len() - 2,
+                ),
+            });
+        }
+        if window_size_f64 < 2.0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "window size",
+                    value: window_size_f64,
+                    reason: "must be at least two",
+                },
+            });
+        }
+        if window_size_f64 > MAX_SAMPLES as f64 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "window size",
+                    value: window_size_f64,
+                    reason: "must not exceed 32 (MAX_SAMPLES)",
+                },
+            });
+        }
+        let window_size = window_size_f64 as usize;
+        let num_records_f64 = slice[slice.len() - 1];
+        if !num_records_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "number of records",
+                    num_records_f64,
+                    slice.len() - 1,
+                ),
+            });
+        }
+        let num_records = num_records_f64 as usize;
+        if num_records == 0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be at least one",
+                },
+            });
+        }
 
         Ok(Self {
             first_state_epoch,
@@ -98,39 +631,79 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
     }
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
-        let rcrd_len = self.record_data.len() / self.num_records;
-        Ok(Self::RecordKind::from_slice_f64(
-            self.record_data
-                .get(n * rcrd_len..(n + 1) * rcrd_len)
-                .ok_or(DecodingError::InaccessibleBytes {
-                    start: n * rcrd_len,
-                    end: (n + 1) * rcrd_len,
-                    size: self.record_data.len(),
-                })?,
-        ))
+        let rcrd_len = PositionVelocityRecord::SIZE / DBL_SIZE;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            rcrd_len,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(NO_RECORDS);
+        }
+        Ok((self.first_state_epoch, self.last_state_epoch()))
     }
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
-        _epoch: Epoch,
-        _: &S,
-    ) -> Result<CartesianState, InterpolationError> {
-        Err(InterpolationError::UnimplementedType {
-            dataset: Self::DATASET_NAME,
-            issue: 14,
-        })
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let (pos_km, vel_km_s) = self.interpolate(epoch, summary, self.window_size)?;
+        Ok(center_state(pos_km, vel_km_s, epoch, summary, None))
     }
 
-    fn check_integrity(&self) -> Result<(), IntegrityError> {
-        for val in self.record_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the record data",
-                });
-            }
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        // NOTE: The ::SIZE returns the C representation memory size of this, but we only want the number of doubles.
+        let rcrd_len = PositionVelocityRecord::SIZE / DBL_SIZE;
+        if !self.record_data.len().is_multiple_of(rcrd_len) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "length of the record data",
+                value: self.record_data.len() as f64,
+                reason: "must be a multiple of the record length",
+            });
+        } else if self.record_data.len() / rcrd_len != self.num_records {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of records",
+                value: self.num_records as f64,
+                reason: "must match the number of records in the record data",
+            });
+        } else if self.window_size < 2 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "window size",
+                value: self.window_size as f64,
+                reason: "must be at least two",
+            });
+        } else if self.window_size > self.num_records {
+            return Err(IntegrityError::WindowExceedsRecords {
+                dataset: Self::DATASET_NAME,
+                window_size: self.window_size,
+                num_records: self.num_records,
+            });
         }
 
+        // The implied epochs are only increasing if the step size is positive.
+        if self.num_records > 1 && self.step_size <= Duration::ZERO {
+            return Err(IntegrityError::NonMonotonicEpochs {
+                dataset: Self::DATASET_NAME,
+                index: 1,
+            });
+        }
+
+        check_summary_coverage(self, summary)?;
+
         Ok(())
     }
 }
@@ -153,67 +726,687 @@ impl<'a> HermiteSetType13<'a> {
     pub fn degree(&self) -> usize {
         2 * self.samples - 1
     }
-}
 
-impl<'a> fmt::Display for HermiteSetType13<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Hermite Type 13 from {:E} to {:E} with degree {} ({} items, {} epoch directories)",
-            Epoch::from_et_seconds(*self.epoch_data.first().unwrap()),
-            Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
-            self.degree(),
-            self.epoch_data.len(),
-            self.epoch_registry.len()
-        )
+    /// Searches for the provided epoch in the epoch data, using the epoch registry to limit the search space.
+    fn search_epoch(&self, epoch_et_s: f64) -> Result<usize, usize> {
+        search_epoch(self.epoch_data, self.epoch_registry, epoch_et_s)
     }
-}
 
-impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
-    type StateKind = (Vector3, Vector3);
-    type RecordKind = PositionVelocityRecord;
-    const DATASET_NAME: &'static str = "Hermite Type 13";
+    /// Returns an error if the requested epoch is not within the epoch data.
+    fn check_coverage<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(), InterpolationError> {
+        // Note that a single state can only be returned as is, so it must be requested at its epoch.
+        if epoch.to_et_seconds() + 1e-9 < self.epoch_data[0]
+            || epoch.to_et_seconds() - 1e-9 > *self.epoch_data.last().unwrap()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(self.epoch_data[0]),
+                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+        Ok(())
+    }
 
-    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
-        ensure!(
-            slice.len() >= 3,
-            TooFewDoublesSnafu {
-                dataset: Self::DATASET_NAME,
-                need: 3_usize,
-                got: slice.len()
+    /// Returns the indexes of the first state and one past the last state of the interpolation window,
+    /// given the index where the requested epoch would be inserted in the epoch data.
+    ///
+    /// The window always has exactly `samples` consecutive states, or all of the states if there are fewer records.
+    /// As in SPICE, even windows have as many states before as after the requested epoch,
+    /// and odd windows are centered on the nearest state; windows are shifted to fit in the data near the segment edges.
+    fn window_indices(&self, idx: usize, epoch_et_s: f64, samples: usize) -> (usize, usize) {
+        let samples = samples.min(self.num_records);
+
+        let center_idx = if samples % 2 == 1
+            && idx > 0
+            && idx < self.num_records
+            && epoch_et_s - self.epoch_data[idx - 1] <= self.epoch_data[idx] - epoch_et_s
+        {
+            // The previous state is the nearest one
+            idx - 1
+        } else {
+            idx
+        };
+
+        let first_idx = center_idx
+            .saturating_sub(samples / 2)
+            .min(self.num_records - samples);
+
+        (first_idx, first_idx + samples)
+    }
+
+    /// Returns the indexes of the records of the provided window which have distinct epochs, and the number of such records.
+    ///
+    /// Records sharing the same epoch (e.g. at a maneuver) would make the Hermite interpolation divide by zero, so only one
+    /// of them is kept: the later record if the requested epoch is at or after their epoch, like SPICE, and the earlier one otherwise.
+    fn distinct_epochs(
+        &self,
+        first_idx: usize,
+        last_idx: usize,
+        epoch_et_s: f64,
+    ) -> ([usize; MAX_SAMPLES], usize) {
+        let mut indexes = [0; MAX_SAMPLES];
+        let mut count = 0;
+        for idx in first_idx..last_idx {
+            if count > 0 && self.epoch_data[indexes[count - 1]] == self.epoch_data[idx] {
+                if epoch_et_s >= self.epoch_data[idx] {
+                    indexes[count - 1] = idx;
+                }
+            } else {
+                indexes[count] = idx;
+                count += 1;
             }
+        }
+        (indexes, count)
+    }
+
+    /// Evaluates this data at the provided epoch using `samples` states instead of the window size stored in the file.
+    ///
+    /// The number of samples is clamped between 2 and MAX_SAMPLES, and to the number of records available.
+    /// Note that changing the window size changes the result compared to SPICE, which always uses the window size of the file.
+    pub fn evaluate_with_samples<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        samples: usize,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        self.interpolate(epoch, summary, clamp_samples(samples, self.num_records))
+    }
+
+    /// Evaluates this data at the provided epoch like `evaluate`, but epochs at most `tolerance` outside of the coverage
+    /// of this data are evaluated at the first or last state instead of returning an error.
+    ///
+    /// This is useful for epochs converted from another time scale, which may end up a few nanoseconds outside of the coverage.
+    /// A zero tolerance is as strict as `evaluate`.
+    pub fn evaluate_within<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        tolerance: Duration,
+    ) -> Result<CartesianState, InterpolationError> {
+        let epoch = clamp_epoch(
+            epoch,
+            Epoch::from_et_seconds(self.epoch_data[0]),
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
+            tolerance,
         );
+        self.evaluate(epoch, summary)
+    }
 
-        // For this kind of record, the metadata is stored at the very end of the dataset
-        let num_records_f64 = slice[slice.len() - 1];
-        if !num_records_f64.is_finite() {
-            return Err(DecodingError::Integrity {
-                source: IntegrityError::InvalidValue {
-                    dataset: Self::DATASET_NAME,
-                    variable: "number of records",
-                    value: num_records_f64,
-                    reason: "must be a finite value",
-                },
-            });
+    /// Evaluates only the position at the provided epoch, which skips the computation of the derivatives of the interpolation.
+    ///
+    /// The position is identical to that returned by `evaluate`.
+    pub fn evaluate_position<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Vector3, InterpolationError> {
+        let mut window = HermiteWindowCache::default();
+        if let Some((pos_km, _)) = self.window(epoch, summary, self.samples, &mut window)? {
+            return Ok(pos_km);
         }
-        let num_records = num_records_f64 as usize;
 
-        // NOTE: The Type 12 and 13 specify that the windows size minus one is stored!
-        let num_samples_f64 = slice[slice.len() - 2];
-        if !num_samples_f64.is_finite() {
-            return Err(DecodingError::Integrity {
-                source: IntegrityError::InvalidValue {
-                    dataset: Self::DATASET_NAME,
-                    variable: "number of interpolation samples",
-                    value: num_samples_f64,
-                    reason: "must be a finite value",
-                },
+        let [x_km, y_km, z_km] = hermite_value_multi(
+            window.epochs(),
+            [
+                window.positions(0),
+                window.positions(1),
+                window.positions(2),
+            ],
+            [
+                window.velocities(0),
+                window.velocities(1),
+                window.velocities(2),
+            ],
+            epoch.to_et_seconds(),
+        )?;
+
+        Ok(Vector3::new(x_km, y_km, z_km))
+    }
+
+    /// Builds the Hermite interpolation over `samples` states around the provided epoch.
+    fn interpolate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        samples: usize,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        let mut window = HermiteWindowCache::default();
+        if let Some(state) = self.window(epoch, summary, samples, &mut window)? {
+            return Ok(state);
+        }
+
+        let [(x_km, vx_km_s), (y_km, vy_km_s), (z_km, vz_km_s)] = hermite_eval_multi(
+            window.epochs(),
+            [
+                window.positions(0),
+                window.positions(1),
+                window.positions(2),
+            ],
+            [
+                window.velocities(0),
+                window.velocities(1),
+                window.velocities(2),
+            ],
+            epoch.to_et_seconds(),
+        )?;
+
+        Ok((
+            Vector3::new(x_km, y_km, z_km),
+            Vector3::new(vx_km_s, vy_km_s, vz_km_s),
+        ))
+    }
+
+    /// Evaluates this data at the provided epoch like `evaluate`, reusing the interpolation window of the provided cache
+    /// if the epoch falls in the same window as the previous query, which avoids searching and decoding that window again.
+    ///
+    /// The cache also stores the interpolation polynomials of its window, so each query within that window only evaluates them:
+    /// this makes dense sequential queries much faster, e.g. in propagation loops, and the results agree with `evaluate`
+    /// to within rounding errors.
+    pub fn evaluate_cached<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        cache: &mut HermiteWindowCache<'a>,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        if let Some(state) = self.window(epoch, summary, self.samples, cache)? {
+            return Ok(state);
+        }
+        cache.build_polynomials()?;
+
+        let epoch_et_s = epoch.to_et_seconds();
+        let mut pos_km = Vector3::zeros();
+        let mut vel_km_s = Vector3::zeros();
+        for i in 0..3 {
+            (pos_km[i], vel_km_s[i]) =
+                hermite_newton_eval(cache.epochs(), &cache.coeffs[i], epoch_et_s);
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    /// Ensures that the provided cache holds the interpolation window of this epoch, unless a record exists at this epoch,
+    /// in which case its state is returned since no interpolation is needed.
+    fn window<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+        samples: usize,
+        cache: &mut HermiteWindowCache<'a>,
+    ) -> Result<Option<(Vector3, Vector3)>, InterpolationError> {
+        // Check that we even have interpolation data for that time
+        self.check_coverage(epoch, summary)?;
+        if self.num_records == 1 {
+            return Ok(Some(
+                self.nth_record(0)
+                    .context(InterpDecodingSnafu)?
+                    .to_pos_vel(),
+            ));
+        }
+
+        let epoch_et_s = epoch.to_et_seconds();
+        if cache.contains(self, samples, epoch_et_s) {
+            return Ok(None);
+        }
+
+        // Now, perform a binary search on the epochs themselves, using the epoch registry to limit the search space.
+        match self.search_epoch(epoch_et_s) {
+            Ok(idx) => {
+                // Oh wow, this state actually exists, no interpolation needed!
+                // Like SPICE, the later record is returned if this epoch is duplicated (e.g. at a maneuver).
+                let idx = idx
+                    + self.epoch_data[idx + 1..]
+                        .iter()
+                        .take_while(|epoch_et| **epoch_et == self.epoch_data[idx])
+                        .count();
+                Ok(Some(
+                    self.nth_record(idx)
+                        .context(InterpDecodingSnafu)?
+                        .to_pos_vel(),
+                ))
+            }
+            // We didn't find it, so let's build an interpolation here.
+            Err(idx) => {
+                cache.fill(self, idx, samples, epoch_et_s)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Evaluates the position, velocity, and acceleration at the provided epoch.
+    ///
+    /// The acceleration is the second derivative of the Hermite interpolation of the position and velocity,
+    /// in the units of the data divided by seconds squared, typically km/s^2.
+    /// Unlike `evaluate`, the interpolation is performed even if the epoch is exactly that of a state as the data does not store the acceleration.
+    pub fn evaluate_with_accel<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Vector3, Vector3, Vector3), InterpolationError> {
+        self.check_coverage(epoch, summary)?;
+
+        let epoch_et_s = epoch.to_et_seconds();
+        let idx = match self.search_epoch(epoch_et_s) {
+            Ok(idx) => idx,
+            Err(idx) => idx,
+        };
+        let mut window = HermiteWindowCache::default();
+        window.fill(self, idx, self.samples, epoch_et_s)?;
+
+        let mut pos_km = Vector3::zeros();
+        let mut vel_km_s = Vector3::zeros();
+        let mut acc_km_s2 = Vector3::zeros();
+        for i in 0..3 {
+            (pos_km[i], vel_km_s[i], acc_km_s2[i]) = hermite_eval_deriv2(
+                window.epochs(),
+                window.positions(i),
+                window.velocities(i),
+                epoch_et_s,
+            )?;
+        }
+
+        Ok((pos_km, vel_km_s, acc_km_s2))
+    }
+
+    /// Evaluates this data at the provided epoch, and returns the state in the center frame of the summary,
+    /// whose gravitational parameter is set to `mu_km3_s2`, if provided.
+    pub fn to_cartesian_state(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<CartesianState, InterpolationError> {
+        let mut state = self.evaluate(epoch, summary)?;
+        state.frame.mu_km3_s2 = mu_km3_s2;
+        Ok(state)
+    }
+
+    /// Evaluates this data at the provided epoch like `to_cartesian_state`, and also returns an estimate of the interpolation error in km.
+    ///
+    /// This estimate is the distance between the interpolated position and the position interpolated over the same window without
+    /// its first and last states. The smaller window interpolates with a lower degree, so its error is typically much larger than that of the
+    /// full window: this distance is a conservative bound of the truncation error, and a cheap proxy to check that the states of this
+    /// data are dense enough for the required precision. It is zero at the epochs of the states, which are returned as is.
+    pub fn evaluate_with_error(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<(CartesianState, f64), InterpolationError> {
+        let (state, accuracy) = self.evaluate_with_accuracy(epoch, summary, mu_km3_s2)?;
+        Ok((state, accuracy.pos_km))
+    }
+
+    /// Evaluates this data at the provided epoch like `to_cartesian_state`, and also returns an estimate of the position
+    /// and velocity interpolation errors, computed like that of `evaluate_with_error`.
+    ///
+    /// This interpolates twice, so use `to_cartesian_state` when the estimate is not needed.
+    pub fn evaluate_with_accuracy(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<(CartesianState, InterpolationAccuracy), InterpolationError> {
+        let state = self.to_cartesian_state(epoch, summary, mu_km3_s2)?;
+        let mut window = HermiteWindowCache::default();
+        let (coarse_pos_km, coarse_vel_km_s) =
+            match self.window(epoch, summary, self.samples, &mut window)? {
+                Some(state) => state,
+                None => {
+                    let inner = inner_window(window.count);
+                    let [(x_km, vx_km_s), (y_km, vy_km_s), (z_km, vz_km_s)] = hermite_eval_multi(
+                        &window.epochs()[inner.clone()],
+                        [
+                            &window.positions(0)[inner.clone()],
+                            &window.positions(1)[inner.clone()],
+                            &window.positions(2)[inner.clone()],
+                        ],
+                        [
+                            &window.velocities(0)[inner.clone()],
+                            &window.velocities(1)[inner.clone()],
+                            &window.velocities(2)[inner],
+                        ],
+                        epoch.to_et_seconds(),
+                    )?;
+                    (
+                        Vector3::new(x_km, y_km, z_km),
+                        Vector3::new(vx_km_s, vy_km_s, vz_km_s),
+                    )
+                }
+            };
+        Ok((
+            state,
+            InterpolationAccuracy {
+                pos_km: (state.radius_km - coarse_pos_km).norm(),
+                vel_km_s: (state.velocity_km_s - coarse_vel_km_s).norm(),
+            },
+        ))
+    }
+
+    /// Evaluates this data at all of the provided epochs like `to_cartesian_state`, returning the states in the same order as the epochs.
+    ///
+    /// The epochs are evaluated in chronological order, so that consecutive epochs reuse the same interpolation window
+    /// (cf. [HermiteWindowCache]) instead of searching and decoding it again: this is much faster than calling `evaluate`
+    /// for each epoch when many epochs fall between the same records.
+    pub fn evaluate_batch(
+        &self,
+        epochs: &[Epoch],
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<Vec<CartesianState>, InterpolationError> {
+        let mut order: Vec<usize> = (0..epochs.len()).collect();
+        order.sort_by_key(|&n| epochs[n]);
+
+        let mut cache = HermiteWindowCache::default();
+        let mut states = vec![CartesianState::zero(summary.center_frame()); epochs.len()];
+        for n in order {
+            let (pos_km, vel_km_s) = self.evaluate_cached(epochs[n], summary, &mut cache)?;
+            states[n] = center_state(pos_km, vel_km_s, epochs[n], summary, mu_km3_s2);
+        }
+        Ok(states)
+    }
+
+    /// Compares the velocities stored in each pair of consecutive records with the finite difference of their positions,
+    /// to catch velocities in the wrong units (e.g. m/s instead of km/s) or with the wrong sign, which `check_integrity` cannot see.
+    ///
+    /// The relative disagreement of a pair is the norm of the difference between the finite difference and the mean of its two
+    /// velocities, divided by the largest norm of these two. It is of the order of the square of the angle swept between the records
+    /// for smooth trajectories, near one for velocities in the wrong units, and near two for velocities of the wrong sign.
+    /// Pairs of records at the same epoch (i.e. discontinuities) and of zero velocity are skipped.
+    ///
+    /// This is a report rather than an error because maneuvers legitimately cause local disagreements: the segment is only flagged
+    /// if the median relative disagreement exceeds the provided threshold.
+    pub fn check_physical_consistency(
+        &self,
+        max_median_rel_error: f64,
+    ) -> Result<VelocityConsistency, DecodingError> {
+        let mut report = VelocityConsistency::default();
+        let mut rel_errors = Vec::with_capacity(self.num_records.saturating_sub(1));
+        let mut prev = self.nth_record(0)?;
+        for n in 1..self.num_records {
+            let record = self.nth_record(n)?;
+            let dt_s = self.epoch_data[n] - self.epoch_data[n - 1];
+            let (prev_pos_km, prev_vel_km_s) = prev.to_pos_vel();
+            let (pos_km, vel_km_s) = record.to_pos_vel();
+            prev = record;
+
+            let finite_diff_km_s = (pos_km - prev_pos_km) / dt_s;
+            let mean_vel_km_s = 0.5 * (prev_vel_km_s + vel_km_s);
+            let scale_km_s = finite_diff_km_s.norm().max(mean_vel_km_s.norm());
+            if !(dt_s > 0.0 && scale_km_s > 0.0) {
+                continue;
+            }
+
+            let rel_error = (finite_diff_km_s - mean_vel_km_s).norm() / scale_km_s;
+            if rel_errors.is_empty() || rel_error > report.worst_rel_error {
+                report.worst_record = n - 1;
+                report.worst_rel_error = rel_error;
+            }
+            rel_errors.push(rel_error);
+        }
+
+        report.num_pairs = rel_errors.len();
+        if !rel_errors.is_empty() {
+            let mid = rel_errors.len() / 2;
+            rel_errors.select_nth_unstable_by(mid, f64::total_cmp);
+            report.median_rel_error = rel_errors[mid];
+            report.flagged = report.median_rel_error > max_median_rel_error;
+        }
+
+        Ok(report)
+    }
+
+    /// Returns the record nearest to the provided epoch, without interpolation, e.g. to check that interpolated states
+    /// remain close to the surrounding records. Epochs outside of the data return the first or the last record, and
+    /// epochs exactly between two records return the earlier one.
+    ///
+    /// If several records share the epoch of the nearest record (e.g. at a maneuver), any one of them may be returned.
+    pub fn nearest_record(&self, epoch: Epoch) -> Result<NearestRecord, DecodingError> {
+        let epoch_et_s = epoch.to_et_seconds();
+        let index = match self.search_epoch(epoch_et_s) {
+            Ok(idx) => idx,
+            Err(0) => 0,
+            Err(idx) if idx >= self.num_records => self.num_records - 1,
+            Err(idx) => {
+                if epoch_et_s - self.epoch_data[idx - 1] <= self.epoch_data[idx] - epoch_et_s {
+                    idx - 1
+                } else {
+                    idx
+                }
+            }
+        };
+        let record_epoch = Epoch::from_et_seconds(self.epoch_data[index]);
+        Ok(NearestRecord {
+            index,
+            record: self.nth_record(index)?,
+            epoch: record_epoch,
+            offset: record_epoch - epoch,
+        })
+    }
+
+    /// Returns an iterator over the records of this data and their stored epochs.
+    pub fn iter(&self) -> HermiteRecords<'a> {
+        HermiteRecords::new(
+            RecordEpochs::Stored(self.epoch_data),
+            self.state_data,
+            self.num_records,
+        )
+    }
+}
+
+/// Interpolation window of a Type 13 segment, decoded by [HermiteSetType13::evaluate_cached] and reused
+/// as long as the requested epochs fall between the same two records.
+///
+/// The cache is borrowed mutably by each query, so it cannot be shared between threads: use one cache per thread.
+/// It borrows the epoch data of the segment that filled it, so using it with another segment only invalidates it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HermiteWindowCache<'a> {
+    /// Epoch data of the segment which filled this cache
+    segment: &'a [f64],
+    /// Number of samples requested when filling this cache
+    samples: usize,
+    /// Index in the epoch data where the epochs using this window would be inserted
+    idx: usize,
+    /// Whether the previous record is the nearest one to the epochs using this window, which matters for odd windows
+    prev_nearest: bool,
+    /// Number of distinct records in this window
+    count: usize,
+    epochs: [f64; MAX_SAMPLES],
+    pos: [[f64; MAX_SAMPLES]; 3],
+    vel: [[f64; MAX_SAMPLES]; 3],
+    /// Whether the coefficients of the interpolation polynomials of this window are computed
+    has_coeffs: bool,
+    /// Coefficients of the Newton form of the interpolation polynomial of each axis
+    coeffs: [[f64; 2 * MAX_SAMPLES]; 3],
+}
+
+impl Default for HermiteWindowCache<'_> {
+    fn default() -> Self {
+        Self {
+            segment: &[],
+            samples: 0,
+            idx: 0,
+            prev_nearest: false,
+            count: 0,
+            epochs: [0.0; MAX_SAMPLES],
+            pos: [[0.0; MAX_SAMPLES]; 3],
+            vel: [[0.0; MAX_SAMPLES]; 3],
+            has_coeffs: false,
+            coeffs: [[0.0; 2 * MAX_SAMPLES]; 3],
+        }
+    }
+}
+
+impl<'a> HermiteWindowCache<'a> {
+    /// Returns whether the interpolation window of this epoch in the provided segment is the one of this cache.
+    fn contains(&self, segment: &HermiteSetType13<'a>, samples: usize, epoch_et_s: f64) -> bool {
+        let epochs = segment.epoch_data;
+        self.count > 0
+            && ptr::eq(self.segment, epochs)
+            && self.samples == samples
+            && (self.idx == 0 || epochs[self.idx - 1] < epoch_et_s)
+            && (self.idx == epochs.len() || epoch_et_s < epochs[self.idx])
+            && (samples.is_multiple_of(2)
+                || Self::prev_nearest(epochs, self.idx, epoch_et_s) == self.prev_nearest)
+    }
+
+    fn prev_nearest(epochs: &[f64], idx: usize, epoch_et_s: f64) -> bool {
+        idx > 0 && idx < epochs.len() && epoch_et_s - epochs[idx - 1] <= epochs[idx] - epoch_et_s
+    }
+
+    /// Decodes the interpolation window of this epoch, given the index where it would be inserted in the epoch data.
+    fn fill(
+        &mut self,
+        segment: &HermiteSetType13<'a>,
+        idx: usize,
+        samples: usize,
+        epoch_et_s: f64,
+    ) -> Result<(), InterpolationError> {
+        // Invalidate this cache until the whole window is decoded.
+        self.count = 0;
+        self.has_coeffs = false;
+
+        let (first_idx, last_idx) = segment.window_indices(idx, epoch_et_s, samples);
+        let (indexes, count) = segment.distinct_epochs(first_idx, last_idx, epoch_et_s);
+        for (cno, idx) in indexes[..count].iter().copied().enumerate() {
+            let record = segment.nth_record(idx).context(InterpDecodingSnafu)?;
+            let (pos_km, vel_km_s) = record.to_pos_vel();
+            for i in 0..3 {
+                self.pos[i][cno] = pos_km[i];
+                self.vel[i][cno] = vel_km_s[i];
+            }
+            self.epochs[cno] = segment.epoch_data[idx];
+        }
+
+        self.segment = segment.epoch_data;
+        self.samples = samples;
+        self.idx = idx;
+        self.prev_nearest = Self::prev_nearest(segment.epoch_data, idx, epoch_et_s);
+        self.count = count;
+        Ok(())
+    }
+
+    /// Computes the interpolation polynomials of this window, unless they already are.
+    fn build_polynomials(&mut self) -> Result<(), InterpolationError> {
+        if !self.has_coeffs {
+            for i in 0..3 {
+                self.coeffs[i] = hermite_newton_coefficients(
+                    &self.epochs[..self.count],
+                    &self.pos[i][..self.count],
+                    &self.vel[i][..self.count],
+                )?;
+            }
+            self.has_coeffs = true;
+        }
+        Ok(())
+    }
+
+    // The accessors limit the slices to exactly the number of items used: the other ones are zeros,
+    // which would cause the interpolation function to fail.
+    fn epochs(&self) -> &[f64] {
+        &self.epochs[..self.count]
+    }
+
+    fn positions(&self, axis: usize) -> &[f64] {
+        &self.pos[axis][..self.count]
+    }
+
+    fn velocities(&self, axis: usize) -> &[f64] {
+        &self.vel[axis][..self.count]
+    }
+}
+
+impl<'a> fmt::Display for HermiteSetType13<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.epoch_data.first(), self.epoch_data.last()) {
+            (Some(first_et_s), Some(last_et_s)) => {
+                let start = Epoch::from_et_seconds(*first_et_s);
+                let end = Epoch::from_et_seconds(*last_et_s);
+                write!(
+                    f,
+                    "Hermite Type 13 from {start:E} to {end:E} ({}) with degree {} ({} items, {} epoch directories)",
+                    end - start,
+                    self.degree(),
+                    self.epoch_data.len(),
+                    self.epoch_registry.len()
+                )
+            }
+            _ => write!(f, "Hermite Type 13 (empty)"),
+        }
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
+    type StateKind = CartesianState;
+    type RecordKind = PositionVelocityRecord;
+    const DATASET_NAME: &'static str = "Hermite Type 13";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 3,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 3_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset
+        let num_records_f64 = slice[slice.len() - 1];
+        if !num_records_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be a finite value",
+                },
+            });
+        }
+        let num_records = num_records_f64 as usize;
+
+        // NOTE: The Type 12 and 13 specify that the windows size minus one is stored!
+        let num_samples_f64 = slice[slice.len() - 2];
+        if !num_samples_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of interpolation samples",
+                    value: num_samples_f64,
+                    reason: "must be a finite value",
+                },
             });
         }
 
-        let samples = num_samples_f64 as usize + 1;
+        let samples = (num_samples_f64 as usize).saturating_add(1);
+        if samples > MAX_SAMPLES {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of interpolation samples",
+                    value: samples as f64,
+                    reason: "must not exceed 32 (MAX_SAMPLES)",
+                },
+            });
+        }
+        if num_records == 0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be at least one",
+                },
+            });
+        }
         // NOTE: The ::SIZE returns the C representation memory size of this, but we only want the number of doubles.
-        let state_data_end_idx = PositionVelocityRecord::SIZE / DBL_SIZE * num_records;
+        let state_data_end_idx =
+            (PositionVelocityRecord::SIZE / DBL_SIZE).saturating_mul(num_records);
         let state_data =
             slice
                 .get(0..state_data_end_idx)
@@ -222,7 +1415,7 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
                     end: state_data_end_idx,
                     size: slice.len(),
                 })?;
-        let epoch_data_end_idx = state_data_end_idx + num_records;
+        let epoch_data_end_idx = state_data_end_idx.saturating_add(num_records);
         let epoch_data = slice.get(state_data_end_idx..epoch_data_end_idx).ok_or(
             DecodingError::InaccessibleBytes {
                 start: state_data_end_idx,
@@ -249,158 +1442,732 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
     }
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
-        let rcrd_len = self.state_data.len() / self.num_records;
-        Ok(Self::RecordKind::from_slice_f64(
-            self.state_data
-                .get(n * rcrd_len..(n + 1) * rcrd_len)
-                .ok_or(DecodingError::InaccessibleBytes {
-                    start: n * rcrd_len,
-                    end: (n + 1) * rcrd_len,
-                    size: self.state_data.len(),
-                })?,
-        ))
+        let rcrd_len = PositionVelocityRecord::SIZE / DBL_SIZE;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.state_data,
+            n,
+            rcrd_len,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        epochs_coverage(Self::DATASET_NAME, self.epoch_data)
     }
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
         epoch: Epoch,
-        _: &S,
+        summary: &S,
     ) -> Result<Self::StateKind, InterpolationError> {
-        // Start by doing a binary search on the epoch registry to limit the search space in the total number of epochs.
-        // TODO: use the epoch registry to reduce the search space
-        // Check that we even have interpolation data for that time
-        if epoch.to_et_seconds() + 1e-9 < self.epoch_data[0]
-            || epoch.to_et_seconds() - 1e-9 > *self.epoch_data.last().unwrap()
-        {
-            return Err(InterpolationError::NoInterpolationData {
-                req: epoch,
-                start: Epoch::from_et_seconds(self.epoch_data[0]),
-                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
+        let (pos_km, vel_km_s) = self.interpolate(epoch, summary, self.samples)?;
+        Ok(center_state(pos_km, vel_km_s, epoch, summary, None))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        // The window is clamped to the number of records when evaluating, but SPICE requires that a full window can be formed.
+        if self.samples > self.num_records {
+            return Err(IntegrityError::WindowExceedsRecords {
+                dataset: Self::DATASET_NAME,
+                window_size: self.samples,
+                num_records: self.num_records,
             });
         }
-        // Now, perform a binary search on the epochs themselves.
-        match self.epoch_data.binary_search_by(|epoch_et| {
-            epoch_et
-                .partial_cmp(&epoch.to_et_seconds())
-                .expect("epochs in Hermite data is now NaN or infinite but was not before")
-        }) {
-            Ok(idx) => {
-                // Oh wow, this state actually exists, no interpolation needed!
-                Ok(self
-                    .nth_record(idx)
-                    .context(InterpDecodingSnafu)?
-                    .to_pos_vel())
+
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.state_data.len(),
+        )?;
+
+        // The binary search on the epochs assumes that they are sorted.
+        // Duplicated epochs are allowed by the SPK specification, e.g. for a state discontinuity at a maneuver.
+        let mut duplicates = 0;
+        for (i, pair) in self.epoch_data.windows(2).enumerate() {
+            if pair[1] < pair[0] {
+                return Err(IntegrityError::NonMonotonicEpochs {
+                    dataset: Self::DATASET_NAME,
+                    index: i + 1,
+                });
+            } else if pair[1] == pair[0] {
+                duplicates += 1;
             }
-            Err(idx) => {
-                // We didn't find it, so let's build an interpolation here.
-                let num_left = self.samples / 2;
+        }
+        if duplicates > 0 {
+            warn!(
+                "{} contains {duplicates} duplicated epochs: the later record is used at those epochs",
+                Self::DATASET_NAME
+            );
+        }
 
-                // Ensure that we aren't fetching out of the window
-                let mut first_idx = idx.saturating_sub(num_left);
-                let last_idx = self.num_records.min(first_idx + self.samples);
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.state_data.len() + self.epoch_data.len(),
+        )?;
 
-                // Check that we have enough samples
-                if last_idx == self.num_records {
-                    first_idx = last_idx - 2 * num_left;
-                }
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the state data",
+            self.state_data,
+            0,
+        )?;
 
-                // Statically allocated arrays of the maximum number of samples
-                let mut epochs = [0.0; MAX_SAMPLES];
-                let mut xs = [0.0; MAX_SAMPLES];
-                let mut ys = [0.0; MAX_SAMPLES];
-                let mut zs = [0.0; MAX_SAMPLES];
-                let mut vxs = [0.0; MAX_SAMPLES];
-                let mut vys = [0.0; MAX_SAMPLES];
-                let mut vzs = [0.0; MAX_SAMPLES];
-                for (cno, idx) in (first_idx..last_idx).enumerate() {
-                    let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
-                    xs[cno] = record.x_km;
-                    ys[cno] = record.y_km;
-                    zs[cno] = record.z_km;
-                    vxs[cno] = record.vx_km_s;
-                    vys[cno] = record.vy_km_s;
-                    vzs[cno] = record.vz_km_s;
-                    epochs[cno] = self.epoch_data[idx];
-                }
+        // The registry bounds the epoch search, so a corrupted registry would return the wrong window.
+        if let Some(index) = registry_mismatch(self.epoch_data, self.epoch_registry) {
+            return Err(IntegrityError::RegistryMismatch {
+                dataset: Self::DATASET_NAME,
+                index,
+            });
+        }
 
-                // TODO: Build a container that uses the underlying data and provides an index into it.
+        check_summary_coverage(self, summary)?;
 
-                // Build the interpolation polynomials making sure to limit the slices to exactly the number of items we actually used
-                // The other ones are zeros, which would cause the interpolation function to fail.
-                let (x_km, vx_km_s) = hermite_eval(
-                    &epochs[..self.samples],
-                    &xs[..self.samples],
-                    &vxs[..self.samples],
-                    epoch.to_et_seconds(),
-                )?;
+        Ok(())
+    }
+}
 
-                let (y_km, vy_km_s) = hermite_eval(
-                    &epochs[..self.samples],
-                    &ys[..self.samples],
-                    &vys[..self.samples],
-                    epoch.to_et_seconds(),
-                )?;
+#[cfg(test)]
+mod hermite_ut {
+    use hifitime::{Epoch, TimeUnits};
 
-                let (z_km, vz_km_s) = hermite_eval(
-                    &epochs[..self.samples],
-                    &zs[..self.samples],
-                    &vzs[..self.samples],
-                    epoch.to_et_seconds(),
-                )?;
+    use crate::{
+        constants::frames::EARTH_J2000,
+        errors::{DecodingError, IntegrityError},
+        math::{cartesian::CartesianState, interpolation::InterpolationError, Vector3},
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
 
-                // And build the result
-                let pos_km = Vector3::new(x_km, y_km, z_km);
-                let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
+    use super::{HermiteSetType12, HermiteSetType13, InterpolationAccuracy};
 
-                Ok((pos_km, vel_km_s))
-            }
+    /// Cubic polynomial used to build synthetic data, which the Hermite interpolation must reproduce exactly.
+    fn poly(t: f64, scale: f64) -> (f64, f64) {
+        (
+            scale * (1e3 + 2.0 * t + 3e-3 * t.powi(2) + 1e-6 * t.powi(3)),
+            scale * (2.0 + 6e-3 * t + 3e-6 * t.powi(2)),
+        )
+    }
+
+    /// Builds a Type 12 data slice with the provided number of records and window size.
+    fn type12_data(num_records: usize, window_size: usize, step_s: f64) -> Vec<f64> {
+        let mut data = Vec::with_capacity(num_records * 6 + 4);
+        for n in 0..num_records {
+            let t = n as f64 * step_s;
+            let (x, vx) = poly(t, 1.0);
+            let (y, vy) = poly(t, -2.0);
+            let (z, vz) = poly(t, 0.5);
+            data.extend([x, y, z, vx, vy, vz]);
         }
+        // Metadata: start epoch, step size, window size minus one, number of records
+        data.extend([0.0, step_s, (window_size - 1) as f64, num_records as f64]);
+        data
     }
 
-    fn check_integrity(&self) -> Result<(), IntegrityError> {
-        // Verify that none of the data is invalid once when we load it.
-        for val in self.epoch_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the epoch data",
-                });
+    #[test]
+    fn type12_evaluate() {
+        let data = type12_data(20, 4, 60.0);
+        let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.window_size, 4);
+        assert_eq!(dataset.num_records, 20);
+        assert_eq!(
+            dataset.last_state_epoch(),
+            Epoch::from_et_seconds(19.0 * 60.0)
+        );
+
+        let summary = SPKSummaryRecord::default();
+
+        // Check the nodes themselves, and epochs near the start, in the middle, and near the end of the segment.
+        for t in [0.0, 15.0, 30.0, 61.0, 500.0, 600.0, 1111.1, 1130.0, 1140.0] {
+            let (pos, vel): (Vector3, Vector3) = dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap()
+                .into();
+            for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                let (exp_pos, exp_vel) = poly(t, *scale);
+                assert!((pos[i] - exp_pos).abs() < 1e-9, "pos error at {t}");
+                assert!((vel[i] - exp_vel).abs() < 1e-9, "vel error at {t}");
             }
         }
 
-        for val in self.epoch_registry {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the epoch registry data",
-                });
-            }
+        // Outside of the data bounds
+        for epoch in [
+            Epoch::from_et_seconds(0.0) - 1.seconds(),
+            Epoch::from_et_seconds(1140.0) + 1.seconds(),
+        ] {
+            assert_eq!(
+                dataset.evaluate(epoch, &summary),
+                Err(InterpolationError::NoInterpolationData {
+                    req: epoch,
+                    start: Epoch::from_et_seconds(0.0),
+                    end: Epoch::from_et_seconds(1140.0),
+                    id: summary.target_id,
+                    center_id: summary.center_id,
+                })
+            );
         }
+    }
 
-        for val in self.state_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the state data",
-                });
+    #[test]
+    fn type12_edge_windows() {
+        let summary = SPKSummaryRecord::default();
+
+        // Odd window sizes, window as large as the data, and a window larger than the data.
+        for (num_records, window_size) in [(7, 5), (7, 3), (5, 5), (3, 4)] {
+            let data = type12_data(num_records, window_size, 10.0);
+            let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+            let last_et_s = (num_records - 1) as f64 * 10.0;
+
+            for t in [
+                0.0,
+                0.5,
+                4.9,
+                5.0,
+                5.1,
+                last_et_s - 5.0,
+                last_et_s - 0.5,
+                last_et_s,
+            ] {
+                let (pos, vel): (Vector3, Vector3) = dataset
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap()
+                    .into();
+                for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                    let (exp_pos, exp_vel) = poly(t, *scale);
+                    assert!(
+                        (pos[i] - exp_pos).abs() < 1e-9,
+                        "pos error at {t} for {num_records} records and window {window_size}"
+                    );
+                    assert!(
+                        (vel[i] - exp_vel).abs() < 1e-9,
+                        "vel error at {t} for {num_records} records and window {window_size}"
+                    );
+                }
             }
         }
 
-        Ok(())
+        // A single state can only be queried at its own epoch.
+        let data = type12_data(1, 2, 10.0);
+        let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.last_state_epoch(), Epoch::from_et_seconds(0.0));
+        let pos = dataset
+            .evaluate(Epoch::from_et_seconds(0.0), &summary)
+            .unwrap()
+            .radius_km;
+        assert_eq!(pos[0], poly(0.0, 1.0).0);
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(1.0), &summary)
+            .is_err());
     }
-}
 
-#[cfg(test)]
-mod hermite_ut {
-    use crate::{
-        errors::{DecodingError, IntegrityError},
-        naif::daf::NAIFDataSet,
-    };
+    #[test]
+    fn type12_integrity() {
+        let data = type12_data(10, 4, 10.0);
+        HermiteSetType12::from_f64_slice(&data)
+            .unwrap()
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
 
-    use super::HermiteSetType13;
+        let invalid = |variable: &'static str, value: f64, reason: &'static str| {
+            Err(IntegrityError::InvalidValue {
+                dataset: "Hermite Type 12",
+                variable,
+                value,
+                reason,
+            })
+        };
 
-    #[test]
-    fn too_small() {
+        // Truncated record data
+        let mut truncated = data[..6 * 3 + 2].to_vec();
+        truncated.extend(&data[data.len() - 4..]);
+        let dataset = HermiteSetType12::from_f64_slice(&truncated).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            invalid(
+                "length of the record data",
+                20.0,
+                "must be a multiple of the record length"
+            )
+        );
+
+        // Claims ten records but carries three
+        let mut truncated = data[..6 * 3].to_vec();
+        truncated.extend(&data[data.len() - 4..]);
+        let dataset = HermiteSetType12::from_f64_slice(&truncated).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            invalid(
+                "number of records",
+                10.0,
+                "must match the number of records in the record data"
+            )
+        );
+
+        let large_window = type12_data(3, 4, 10.0);
+        let dataset = HermiteSetType12::from_f64_slice(&large_window).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::WindowExceedsRecords {
+                dataset: "Hermite Type 12",
+                window_size: 4,
+                num_records: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn type12_decoding_metadata() {
+        let window_size = |stored: f64| {
+            let mut data = type12_data(10, 4, 10.0);
+            let len = data.len();
+            data[len - 2] = stored;
+            HermiteSetType12::from_f64_slice(&data).map(|dataset| dataset.window_size)
+        };
+        assert_eq!(window_size(3.0), Ok(4));
+        assert_eq!(window_size(1.0), Ok(2));
+
+        // The window size minus one is stored, so a stored value of zero is a single sample.
+        for (stored, value) in [(0.0, 1.0), (-1.0, 0.0), (-2.0, -1.0), (-1e9, -1e9 + 1.0)] {
+            assert_eq!(
+                window_size(stored),
+                Err(DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "Hermite Type 12",
+                        variable: "window size",
+                        value,
+                        reason: "must be at least two",
+                    }
+                })
+            );
+        }
+
+        assert_eq!(
+            window_size(f64::NAN),
+            Err(DecodingError::Integrity {
+                source: IntegrityError::NaNValue {
+                    dataset: "Hermite Type 12",
+                    variable: "window size",
+                    offset: 62,
+                }
+            })
+        );
+        assert_eq!(
+            window_size(f64::NEG_INFINITY),
+            Err(DecodingError::Integrity {
+                source: IntegrityError::InfiniteValue {
+                    dataset: "Hermite Type 12",
+                    variable: "window size",
+                    offset: 62,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn type12_decoding_num_records() {
+        let num_records = |stored: f64| {
+            let mut data = type12_data(10, 4, 10.0);
+            let len = data.len();
+            data[len - 1] = stored;
+            HermiteSetType12::from_f64_slice(&data).map(|dataset| dataset.num_records)
+        };
+        assert_eq!(num_records(10.0), Ok(10));
+
+        for stored in [0.0, -3.0] {
+            assert_eq!(
+                num_records(stored),
+                Err(DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "Hermite Type 12",
+                        variable: "number of records",
+                        value: stored,
+                        reason: "must be at least one",
+                    }
+                })
+            );
+        }
+        assert_eq!(
+            num_records(f64::NAN),
+            Err(DecodingError::Integrity {
+                source: IntegrityError::NaNValue {
+                    dataset: "Hermite Type 12",
+                    variable: "number of records",
+                    offset: 63,
+                }
+            })
+        );
+    }
+
+    /// Builds a Type 13 data slice with unequally spaced epochs, including the epoch registry.
+    fn type13_data(num_records: usize, samples: usize) -> Vec<f64> {
+        let epochs: Vec<f64> = (0..num_records)
+            .map(|n| 10.0 * n as f64 + 3.0 * (n as f64).sin())
+            .collect();
+        let mut data = Vec::with_capacity(num_records * 7 + num_records / 100 + 2);
+        for t in &epochs {
+            let (x, vx) = poly(*t, 1.0);
+            let (y, vy) = poly(*t, -2.0);
+            let (z, vz) = poly(*t, 0.5);
+            data.extend([x, y, z, vx, vy, vz]);
+        }
+        data.extend(&epochs);
+        // The epoch registry stores every 100th epoch
+        data.extend((1..=(num_records - 1) / 100).map(|k| epochs[100 * k - 1]));
+        // Metadata: window size minus one, number of records
+        data.extend([(samples - 1) as f64, num_records as f64]);
+        data
+    }
+
+    #[test]
+    fn type13_epoch_registry() {
+        let data = type13_data(5_432, 8);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.num_records, 5_432);
+        assert_eq!(dataset.epoch_registry.len(), 54);
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+
+        let full_search = |epoch_et_s: f64| {
+            dataset
+                .epoch_data
+                .binary_search_by(|epoch_et| epoch_et.partial_cmp(&epoch_et_s).unwrap())
+        };
+
+        // Every epoch, every registry entry, and everything in between must match the full search.
+        let mut queries = Vec::new();
+        for window in dataset.epoch_data.windows(2) {
+            queries.extend([window[0], 0.5 * (window[0] + window[1])]);
+        }
+        queries.extend(dataset.epoch_registry);
+        queries.extend([
+            dataset.epoch_data[0] - 1.0,
+            *dataset.epoch_data.last().unwrap(),
+            *dataset.epoch_data.last().unwrap() + 1.0,
+        ]);
+        for epoch_et_s in queries {
+            assert_eq!(
+                dataset.search_epoch(epoch_et_s),
+                full_search(epoch_et_s),
+                "search mismatch at {epoch_et_s}"
+            );
+        }
+
+        // Without a usable registry, the search falls back to the whole epoch data.
+        let truncated_registry = HermiteSetType13 {
+            epoch_registry: &dataset.epoch_registry[..10],
+            ..dataset
+        };
+        let no_registry = HermiteSetType13 {
+            epoch_registry: &[],
+            ..dataset
+        };
+        for epoch_et_s in [
+            dataset.epoch_data[0],
+            dataset.epoch_registry[20],
+            dataset.epoch_registry[20] + 0.1,
+            *dataset.epoch_data.last().unwrap() - 0.1,
+        ] {
+            assert_eq!(
+                truncated_registry.search_epoch(epoch_et_s),
+                full_search(epoch_et_s)
+            );
+            assert_eq!(
+                no_registry.search_epoch(epoch_et_s),
+                full_search(epoch_et_s)
+            );
+        }
+
+        let summary = SPKSummaryRecord::default();
+        for t in [
+            dataset.epoch_data[0],
+            dataset.epoch_registry[10],
+            dataset.epoch_registry[10] + 1.5,
+            12_345.6,
+            *dataset.epoch_data.last().unwrap() - 0.1,
+        ] {
+            let (pos, vel): (Vector3, Vector3) = dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap()
+                .into();
+            for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                let (exp_pos, exp_vel) = poly(t, *scale);
+                assert!(
+                    (pos[i] - exp_pos).abs() < 1e-12 * exp_pos.abs(),
+                    "pos error at {t}"
+                );
+                assert!(
+                    (vel[i] - exp_vel).abs() < 1e-12 * exp_vel.abs(),
+                    "vel error at {t}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn type13_acceleration() {
+        let data = type13_data(500, 8);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord::default();
+
+        let h_s = 0.1;
+        for t in [
+            dataset.epoch_data[0] + 1.0,
+            dataset.epoch_data[10],
+            dataset.epoch_data[250] + 2.5,
+            *dataset.epoch_data.last().unwrap() - 1.0,
+        ] {
+            let epoch = Epoch::from_et_seconds(t);
+            let (pos, vel, acc) = dataset.evaluate_with_accel(epoch, &summary).unwrap();
+
+            // Position and velocity must match the regular evaluation.
+            let (exp_pos, exp_vel): (Vector3, Vector3) =
+                dataset.evaluate(epoch, &summary).unwrap().into();
+            assert!(
+                (pos - exp_pos).norm() < 1e-12 * exp_pos.norm(),
+                "pos error at {t}"
+            );
+            assert!(
+                (vel - exp_vel).norm() < 1e-12 * exp_vel.norm(),
+                "vel error at {t}"
+            );
+
+            // Acceleration must match the finite difference of the velocity.
+            let vel_before = dataset
+                .evaluate(Epoch::from_et_seconds(t - h_s), &summary)
+                .unwrap()
+                .velocity_km_s;
+            let vel_after = dataset
+                .evaluate(Epoch::from_et_seconds(t + h_s), &summary)
+                .unwrap()
+                .velocity_km_s;
+            let fd_acc = (vel_after - vel_before) / (2.0 * h_s);
+            assert!(
+                (acc - fd_acc).norm() < 1e-6 * acc.norm(),
+                "acc error at {t}: {acc} vs {fd_acc}"
+            );
+
+            // And the analytical second derivative of the polynomial
+            for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                let exp_acc = scale * (6e-3 + 6e-6 * t);
+                assert!(
+                    (acc[i] - exp_acc).abs() < 1e-9,
+                    "analytical acc error at {t}"
+                );
+            }
+        }
+
+        assert!(dataset
+            .evaluate_with_accel(Epoch::from_et_seconds(-10.0), &summary)
+            .is_err());
+    }
+
+    const GM_EARTH_KM3_S2: f64 = 398_600.435_436;
+
+    /// Builds a Type 13 data slice of a slightly eccentric low Earth orbit sampled every minute for about one and a
+    /// half orbits.
+    fn type13_two_body_data(samples: usize) -> Vec<f64> {
+        use crate::math::{cartesian::CartesianState, propagation::propagate_two_body, Vector3};
+
+        let pos0_km = Vector3::new(7000.0, 0.0, 0.0);
+        let vel0_km_s = Vector3::new(0.0, 7.6, 1.0);
+        HermiteSetType13::builder()
+            .with_window_size(samples)
+            .with_states((0..150).map(|n| {
+                let t = 60.0 * n as f64;
+                let (radius_km, velocity_km_s) =
+                    propagate_two_body(&pos0_km, &vel0_km_s, t, GM_EARTH_KM3_S2).unwrap();
+                CartesianState {
+                    radius_km,
+                    velocity_km_s,
+                    epoch: Epoch::from_et_seconds(t),
+                    frame: EARTH_J2000,
+                }
+            }))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn degree_25_windows() {
+        use crate::math::{propagation::propagate_two_body, Vector3};
+
+        let pos0_km = Vector3::new(7000.0, 0.0, 0.0);
+        let vel0_km_s = Vector3::new(0.0, 7.6, 1.0);
+        let summary = SPKSummaryRecord::default();
+
+        // Thirteen states is the largest odd window allowed by SPICE, i.e. a Hermite polynomial of degree 25.
+        let data = type13_two_body_data(13);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        assert_eq!(type13.degree(), 25);
+
+        let mut data = data[..150 * 6].to_vec();
+        data.extend([0.0, 60.0, 12.0, 150.0]);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        assert_eq!(type12.degree(), 25);
+
+        for t in [30.0, 95.5, 1234.5, 4321.0, 8850.0] {
+            let (exp_pos_km, exp_vel_km_s) =
+                propagate_two_body(&pos0_km, &vel0_km_s, t, GM_EARTH_KM3_S2).unwrap();
+            for (pos_km, vel_km_s) in [
+                type12
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap()
+                    .into(),
+                type13
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap()
+                    .into(),
+            ] {
+                assert!(
+                    (pos_km - exp_pos_km).norm() < 1e-7,
+                    "pos error at {t}: {:e}",
+                    (pos_km - exp_pos_km).norm()
+                );
+                assert!(
+                    (vel_km_s - exp_vel_km_s).norm() < 1e-10,
+                    "vel error at {t}: {:e}",
+                    (vel_km_s - exp_vel_km_s).norm()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn type13_two_body_acceleration() {
+        let gm_km3_s2 = GM_EARTH_KM3_S2;
+        let data = type13_two_body_data(8);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord::default();
+
+        for t in [30.0, 1234.5, 4321.0, 8880.0] {
+            let (pos_km, _, acc_km_s2) = dataset
+                .evaluate_with_accel(Epoch::from_et_seconds(t), &summary)
+                .unwrap();
+
+            let exp_acc_km_s2 = -gm_km3_s2 * pos_km / pos_km.norm().powi(3);
+            assert!(
+                (acc_km_s2 - exp_acc_km_s2).norm() < 1e-8 * exp_acc_km_s2.norm(),
+                "acc error at {t}: {:e}",
+                (acc_km_s2 - exp_acc_km_s2).norm() / exp_acc_km_s2.norm()
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_with_samples() {
+        use crate::math::interpolation::MAX_SAMPLES;
+
+        let summary = SPKSummaryRecord::default();
+
+        let data = type13_two_body_data(8);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let epoch = Epoch::from_et_seconds(1234.5);
+
+        // The window stored in the file is used by default
+        assert_eq!(
+            type13.evaluate_with_samples(epoch, &summary, 8),
+            type13.evaluate(epoch, &summary).map(Into::into)
+        );
+        // The number of samples is clamped
+        assert_eq!(
+            type13.evaluate_with_samples(epoch, &summary, 0),
+            type13.evaluate_with_samples(epoch, &summary, 2)
+        );
+        assert_eq!(
+            type13.evaluate_with_samples(epoch, &summary, 1_000),
+            type13.evaluate_with_samples(epoch, &summary, MAX_SAMPLES)
+        );
+        // And a different window gives a different result
+        let pos_km = type13.evaluate(epoch, &summary).unwrap().radius_km;
+        let (pos2_km, _) = type13.evaluate_with_samples(epoch, &summary, 2).unwrap();
+        assert!((pos_km - pos2_km).norm() > 1e-6);
+        // Up to the end of the data
+        let end = Epoch::from_et_seconds(type13.epoch_data[148] + 30.0);
+        assert!(type13.evaluate_with_samples(end, &summary, 5).is_ok());
+
+        // Type 12 windows are clamped to the number of records available
+        let data = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        let epoch = Epoch::from_et_seconds(123.4);
+        assert_eq!(
+            type12.evaluate_with_samples(epoch, &summary, 4),
+            type12.evaluate(epoch, &summary).map(Into::into)
+        );
+        assert_eq!(
+            type12.evaluate_with_samples(epoch, &summary, 64),
+            type12.evaluate_with_samples(epoch, &summary, 20)
+        );
+        // The cubic polynomial is reproduced by any window
+        let (pos_km, vel_km_s) = type12.evaluate_with_samples(epoch, &summary, 2).unwrap();
+        let (exp_pos, exp_vel) = type12.evaluate(epoch, &summary).unwrap().into();
+        assert!((pos_km - exp_pos).norm() < 1e-9 * exp_pos.norm());
+        assert!((vel_km_s - exp_vel).norm() < 1e-9 * exp_vel.norm());
+    }
+
+    #[test]
+    fn cartesian_state() {
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 1140.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            ..Default::default()
+        };
+        let epoch = Epoch::from_et_seconds(123.4);
+
+        let data12 = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data12).unwrap();
+        let data13 = type13_data(120, 8);
+        let type13 = HermiteSetType13::from_f64_slice(&data13).unwrap();
+
+        let states = [
+            (
+                type12.evaluate(epoch, &summary).unwrap().into(),
+                type12
+                    .to_cartesian_state(epoch, &summary, Some(398_600.435_436))
+                    .unwrap(),
+            ),
+            (
+                type13.evaluate(epoch, &summary).unwrap().into(),
+                type13
+                    .to_cartesian_state(epoch, &summary, Some(398_600.435_436))
+                    .unwrap(),
+            ),
+        ];
+        for ((pos, vel), state) in states {
+            assert_eq!(state.radius_km, pos);
+            assert_eq!(state.velocity_km_s, vel);
+            assert_eq!(state.epoch, epoch);
+            assert_eq!(
+                (state.frame.ephemeris_id, state.frame.orientation_id),
+                (EARTH_J2000.ephemeris_id, EARTH_J2000.orientation_id)
+            );
+            assert_eq!(state.frame.mu_km3_s2, Some(398_600.435_436));
+        }
+
+        let state = type13.to_cartesian_state(epoch, &summary, None).unwrap();
+        assert_eq!(state.frame.mu_km3_s2, None);
+        // Type 13 evaluations are already in the center frame of the summary
+        let state = type13.evaluate(epoch, &summary).unwrap();
+        assert_eq!(state.epoch, epoch);
+        assert_eq!(state.frame, summary.center_frame());
+        let moon_centered = SPKSummaryRecord {
+            center_id: 301,
+            frame_id: 17,
+            ..summary
+        };
+        let frame = type13.evaluate(epoch, &moon_centered).unwrap().frame;
+        assert_eq!((frame.ephemeris_id, frame.orientation_id), (301, 17));
+        assert!(type12
+            .to_cartesian_state(Epoch::from_et_seconds(-1.0), &summary, None)
+            .is_err());
+    }
+
+    #[test]
+    fn too_small() {
         if HermiteSetType13::from_f64_slice(&[0.1, 0.2])
             != Err(DecodingError::TooFewDoubles {
                 dataset: "Hermite Type 13",
@@ -412,6 +2179,1007 @@ mod hermite_ut {
         }
     }
 
+    #[test]
+    fn degenerate_records() {
+        use crate::math::Vector3;
+
+        let summary = SPKSummaryRecord::default();
+
+        // Zero records is rejected when decoding, instead of dividing by zero later
+        let zeros = [0.0_f64; 2 * 7 + 2];
+        assert_eq!(
+            HermiteSetType13::from_f64_slice(&zeros).err(),
+            Some(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "Hermite Type 13",
+                    variable: "number of records",
+                    value: 0.0,
+                    reason: "must be at least one",
+                },
+            })
+        );
+        assert!(HermiteSetType13::from_f64_slice(&[0.0, 7.0, 0.0]).is_err());
+
+        // A single record is only returned at its own epoch
+        let single_summary = SPKSummaryRecord {
+            start_epoch_et_s: 10.0,
+            end_epoch_et_s: 10.0,
+            ..Default::default()
+        };
+        let mut data = [7000.0, 0.0, 0.0, 0.0, 7.5, 0.0, 10.0, 0.0, 1.0];
+        HermiteSetType13::from_f64_slice(&data)
+            .unwrap()
+            .check_integrity(&single_summary)
+            .unwrap();
+        // ... even if it cannot form the window of eight states declared in the file
+        data[7] = 7.0;
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&single_summary),
+            Err(IntegrityError::WindowExceedsRecords {
+                dataset: "Hermite Type 13",
+                window_size: 8,
+                num_records: 1,
+            })
+        );
+
+        let expected = (Vector3::new(7000.0, 0.0, 0.0), Vector3::new(0.0, 7.5, 0.0));
+        for samples in [1, 2, 8] {
+            assert_eq!(
+                dataset.evaluate_with_samples(Epoch::from_et_seconds(10.0), &summary, samples),
+                Ok(expected)
+            );
+        }
+        assert_eq!(
+            dataset
+                .evaluate(Epoch::from_et_seconds(10.0), &summary)
+                .map(Into::into),
+            Ok(expected)
+        );
+
+        for t in [9.0, 10.5] {
+            let epoch = Epoch::from_et_seconds(t);
+            assert_eq!(
+                dataset.evaluate(epoch, &summary),
+                Err(InterpolationError::NoInterpolationData {
+                    req: epoch,
+                    start: Epoch::from_et_seconds(10.0),
+                    end: Epoch::from_et_seconds(10.0),
+                    id: summary.target_id,
+                    center_id: summary.center_id,
+                })
+            );
+            assert!(dataset.evaluate_with_accel(epoch, &summary).is_err());
+        }
+    }
+
+    #[test]
+    fn type13_window_indices() {
+        let num_records = 20;
+        for samples in 2..=9 {
+            let data = type13_data(num_records, samples);
+            let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+            let epochs = dataset.epoch_data;
+
+            for window in epochs.windows(2) {
+                for fraction in [0.1, 0.4, 0.6, 0.9] {
+                    let epoch_et_s = window[0] + fraction * (window[1] - window[0]);
+                    let idx = dataset.search_epoch(epoch_et_s).unwrap_err();
+                    let (first_idx, last_idx) = dataset.window_indices(idx, epoch_et_s, samples);
+
+                    assert_eq!(
+                        last_idx - first_idx,
+                        samples,
+                        "{samples} samples @ {epoch_et_s}"
+                    );
+                    assert!(last_idx <= num_records);
+                    if first_idx == 0 || last_idx == num_records {
+                        // Shifted to fit in the data
+                        continue;
+                    }
+
+                    let num_before = epochs[first_idx..last_idx]
+                        .iter()
+                        .filter(|epoch| **epoch < epoch_et_s)
+                        .count();
+                    if samples % 2 == 0 {
+                        assert_eq!(num_before, samples / 2, "{samples} samples @ {epoch_et_s}");
+                    } else {
+                        let nearest_idx = if fraction < 0.5 { idx - 1 } else { idx };
+                        assert_eq!(
+                            first_idx + samples / 2,
+                            nearest_idx,
+                            "{samples} samples @ {epoch_et_s}"
+                        );
+                    }
+                }
+            }
+
+            // Fewer records than samples uses all of the records
+            let (first_idx, last_idx) = dataset.window_indices(1, epochs[0] + 1.0, 50);
+            assert_eq!((first_idx, last_idx), (0, num_records));
+        }
+    }
+
+    #[test]
+    fn type13_display() {
+        let empty = HermiteSetType13 {
+            samples: 8,
+            num_records: 0,
+            state_data: &[],
+            epoch_data: &[],
+            epoch_registry: &[],
+        };
+        assert_eq!(format!("{empty}"), "Hermite Type 13 (empty)");
+
+        let data = [7000.0, 0.0, 0.0, 0.0, 7.5, 0.0, 10.0, 7.0, 1.0];
+        let single = HermiteSetType13::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            format!("{single}"),
+            format!(
+                "Hermite Type 13 from {0:E} to {0:E} (0 ns) with degree 15 (1 items, 0 epoch directories)",
+                Epoch::from_et_seconds(10.0)
+            )
+        );
+
+        let data = type13_data(250, 4);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let coverage = Epoch::from_et_seconds(data[6 * 250 + 249]) - Epoch::from_et_seconds(0.0);
+        assert!(format!("{dataset}").contains(&format!("({coverage})")));
+    }
+
+    #[test]
+    fn truncated_data() {
+        let summary = SPKSummaryRecord::default();
+        let num_records = 250;
+        let data = type13_data(num_records, 8);
+        let metadata = &data[data.len() - 2..];
+
+        // Truncating the segment anywhere, while keeping its metadata, must never panic.
+        for kept in 1..data.len() - 2 {
+            let mut truncated = data[..kept].to_vec();
+            truncated.extend(metadata);
+            if let Ok(dataset) = HermiteSetType13::from_f64_slice(&truncated) {
+                if dataset.check_integrity(&summary).is_ok() {
+                    for t in [0.0, 1234.5, 2480.0] {
+                        let _ = dataset.evaluate(Epoch::from_et_seconds(t), &summary);
+                    }
+                }
+            } else {
+                assert!(kept < 7 * num_records, "{kept} doubles kept");
+            }
+        }
+
+        // Metadata claiming more records than the segment stores
+        let mut lying = data.clone();
+        let len = lying.len();
+        lying[len - 1] = 1e6;
+        assert_eq!(
+            HermiteSetType13::from_f64_slice(&lying).err(),
+            Some(DecodingError::InaccessibleBytes {
+                start: 0,
+                end: 6_000_000,
+                size: len,
+            })
+        );
+
+        let mut lying = data.clone();
+        lying[len - 1] = (num_records + 2) as f64;
+        assert_eq!(
+            HermiteSetType13::from_f64_slice(&lying).err(),
+            Some(DecodingError::InaccessibleBytes {
+                start: 6 * (num_records + 2),
+                end: 7 * (num_records + 2),
+                size: len,
+            })
+        );
+    }
+
+    #[test]
+    fn non_monotonic_epochs() {
+        // Swap two epochs of the table
+        let num_records = 20;
+        let mut data = type13_data(num_records, 4);
+        HermiteSetType13::from_f64_slice(&data)
+            .unwrap()
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        data.swap(6 * num_records + 7, 6 * num_records + 8);
+        assert_eq!(
+            HermiteSetType13::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::NonMonotonicEpochs {
+                dataset: "Hermite Type 13",
+                index: 8,
+            })
+        );
+
+        // Duplicated epochs are valid, but not decreasing ones after them
+        let mut data = type13_data(num_records, 4);
+        data[6 * num_records + 15] = data[6 * num_records + 14];
+        HermiteSetType13::from_f64_slice(&data)
+            .unwrap()
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        data[6 * num_records + 16] = data[6 * num_records + 14] - 1.0;
+        assert_eq!(
+            HermiteSetType13::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::NonMonotonicEpochs {
+                dataset: "Hermite Type 13",
+                index: 16,
+            })
+        );
+
+        // Type 12 epochs are implied by the step size
+        for step_s in [0.0, -10.0] {
+            let data = type12_data(num_records, 4, step_s);
+            assert_eq!(
+                HermiteSetType12::from_f64_slice(&data)
+                    .unwrap()
+                    .check_integrity(&SPKSummaryRecord::default()),
+                Err(IntegrityError::NonMonotonicEpochs {
+                    dataset: "Hermite Type 12",
+                    index: 1,
+                })
+            );
+        }
+        HermiteSetType12::from_f64_slice(&type12_data(num_records, 4, 10.0))
+            .unwrap()
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn summary_coverage() {
+        let coverage = |start_epoch_et_s: f64, end_epoch_et_s: f64| SPKSummaryRecord {
+            start_epoch_et_s,
+            end_epoch_et_s,
+            ..Default::default()
+        };
+        let mismatch = |dataset: &'static str, summary: SPKSummaryRecord, data_end_et_s: f64| {
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset,
+                summary_start_et_s: summary.start_epoch_et_s,
+                summary_end_et_s: summary.end_epoch_et_s,
+                data_start_et_s: 0.0,
+                data_end_et_s,
+            })
+        };
+
+        // The states of Type 12 are every ten seconds from zero
+        let data = type12_data(10, 4, 10.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        for summary in [
+            coverage(0.0, 90.0),
+            coverage(15.0, 42.0),
+            coverage(0.0, 0.0),
+        ] {
+            type12.check_integrity(&summary).unwrap();
+        }
+        for summary in [coverage(-10.0, 90.0), coverage(0.0, 90.5)] {
+            assert_eq!(
+                type12.check_integrity(&summary),
+                mismatch("Hermite Type 12", summary, 90.0)
+            );
+        }
+        assert!(matches!(
+            type12.check_integrity(&coverage(f64::NAN, 90.0)),
+            Err(IntegrityError::SummaryCoverageMismatch { .. })
+        ));
+
+        // The epochs of Type 13 are in its epoch table, e.g. after a manual edit of the summary
+        let data = type13_data(20, 4);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let last_et_s = *type13.epoch_data.last().unwrap();
+        type13.check_integrity(&coverage(0.0, last_et_s)).unwrap();
+        // Rounding of the summary epochs is tolerated
+        type13
+            .check_integrity(&coverage(-1e-7, last_et_s + 1e-7))
+            .unwrap();
+        // The epochs of the coverage are rounded to the nanosecond
+        let data_end_et_s = type13.coverage().unwrap().1.to_et_seconds();
+        assert!((data_end_et_s - last_et_s).abs() < 1e-9);
+        for summary in [coverage(-1.0, last_et_s), coverage(0.0, last_et_s + 1.0)] {
+            assert_eq!(
+                type13.check_integrity(&summary),
+                mismatch("Hermite Type 13", summary, data_end_et_s)
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_cached() {
+        use super::HermiteWindowCache;
+
+        let summary = SPKSummaryRecord::default();
+        for samples in [4, 5] {
+            let data = type13_data(1_234, samples);
+            let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+            let mut cache = HermiteWindowCache::default();
+
+            // Dense sweep, including the exact epochs of records and the segment edges
+            let mut prev_idx = None;
+            let mut fills = 0;
+            for n in 0..=4_000 {
+                let epoch_et_s = dataset.epoch_data[0]
+                    + n as f64 / 4_000.0 * (dataset.epoch_data[1_233] - dataset.epoch_data[0]);
+                let epoch = Epoch::from_et_seconds(epoch_et_s);
+                let (pos_km, vel_km_s) = dataset
+                    .evaluate_cached(epoch, &summary, &mut cache)
+                    .unwrap();
+                let (exp_pos_km, exp_vel_km_s): (Vector3, Vector3) =
+                    dataset.evaluate(epoch, &summary).unwrap().into();
+                // The cached polynomials are evaluated differently, so they only agree to within rounding errors
+                assert!(
+                    (pos_km - exp_pos_km).norm() < 1e-12 * exp_pos_km.norm()
+                        && (vel_km_s - exp_vel_km_s).norm() < 1e-12 * exp_vel_km_s.norm(),
+                    "{samples} samples @ {epoch_et_s}"
+                );
+                if prev_idx != Some((cache.idx, cache.prev_nearest)) {
+                    fills += 1;
+                    prev_idx = Some((cache.idx, cache.prev_nearest));
+                }
+            }
+            // The window only changes when the epochs move to the next record, or past midpoints for odd windows
+            assert!(fills <= 2 * 1_234, "{fills}");
+
+            // Using the cache with another segment invalidates it
+            let other_data = type13_data(1_234, samples);
+            let other = HermiteSetType13::from_f64_slice(&other_data).unwrap();
+            let epoch_et_s = 0.5 * (dataset.epoch_data[10] + dataset.epoch_data[11]);
+            dataset
+                .evaluate_cached(Epoch::from_et_seconds(epoch_et_s), &summary, &mut cache)
+                .unwrap();
+            assert!(cache.contains(&dataset, samples, epoch_et_s));
+            assert!(!cache.contains(&other, samples, epoch_et_s));
+            assert!(!cache.contains(&dataset, samples + 1, epoch_et_s));
+            assert!(!cache.contains(&dataset, samples, dataset.epoch_data[11]));
+            assert!(!cache.contains(&dataset, samples, dataset.epoch_data[10]));
+
+            // Errors do not alter the cache
+            let before = cache.clone();
+            assert!(dataset
+                .evaluate_cached(Epoch::from_et_seconds(-1e3), &summary, &mut cache)
+                .is_err());
+            assert_eq!(cache, before);
+        }
+    }
+
+    #[test]
+    fn evaluate_with_error() {
+        use crate::math::{propagation::propagate_two_body, Vector3};
+
+        let pos0_km = Vector3::new(7000.0, 0.0, 0.0);
+        let vel0_km_s = Vector3::new(0.0, 7.6, 1.0);
+        let summary = SPKSummaryRecord::default();
+
+        // Sparse states, so that the interpolation error is well above the rounding errors
+        for step_s in [300.0, 600.0] {
+            let mut data = Vec::new();
+            for n in 0..30 {
+                let (pos_km, vel_km_s) =
+                    propagate_two_body(&pos0_km, &vel0_km_s, n as f64 * step_s, GM_EARTH_KM3_S2)
+                        .unwrap();
+                data.extend(pos_km.iter().chain(vel_km_s.iter()));
+            }
+            data.extend([0.0, step_s, 5.0, 30.0]);
+            let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+
+            for t in [0.5, 0.3, 7.5, 14.2, 28.9].map(|k| k * step_s) {
+                let (state, bound_km) = dataset
+                    .evaluate_with_error(Epoch::from_et_seconds(t), &summary, None)
+                    .unwrap();
+                let (exp_pos_km, _) =
+                    propagate_two_body(&pos0_km, &vel0_km_s, t, GM_EARTH_KM3_S2).unwrap();
+                let err_km = (state.radius_km - exp_pos_km).norm();
+                assert!(
+                    err_km <= bound_km,
+                    "{step_s} s steps @ {t}: {err_km:e} > {bound_km:e}"
+                );
+            }
+
+            // States are returned as is
+            let (_, bound_km) = dataset
+                .evaluate_with_error(Epoch::from_et_seconds(3.0 * step_s), &summary, None)
+                .unwrap();
+            assert_eq!(bound_km, 0.0);
+        }
+
+        let data = type13_two_body_data(4);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        for t in [30.0, 1234.5, 4321.0, 8888.0] {
+            let (state, bound_km) = dataset
+                .evaluate_with_error(Epoch::from_et_seconds(t), &summary, None)
+                .unwrap();
+            assert_eq!(
+                (state.radius_km, state.velocity_km_s),
+                dataset
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap()
+                    .into()
+            );
+            let (exp_pos_km, _) =
+                propagate_two_body(&pos0_km, &vel0_km_s, t, GM_EARTH_KM3_S2).unwrap();
+            let err_km = (state.radius_km - exp_pos_km).norm();
+            assert!(err_km <= bound_km, "{t}: {err_km:e} > {bound_km:e}");
+        }
+    }
+
+    #[test]
+    fn evaluate_with_accuracy() {
+        use crate::math::{
+            interpolation::hermite_eval_multi, propagation::propagate_two_body, Vector3,
+        };
+
+        let pos0_km = Vector3::new(7000.0, 0.0, 0.0);
+        let vel0_km_s = Vector3::new(0.0, 7.6, 1.0);
+        let summary = SPKSummaryRecord::default();
+
+        let mut mid_accuracies = Vec::new();
+        for step_s in [120.0, 300.0, 600.0] {
+            // The same states stored as Type 12 and Type 13 data, with windows of six states
+            let mut type12_data = Vec::new();
+            for n in 0..30 {
+                let (pos_km, vel_km_s) =
+                    propagate_two_body(&pos0_km, &vel0_km_s, n as f64 * step_s, GM_EARTH_KM3_S2)
+                        .unwrap();
+                type12_data.extend(pos_km.iter().chain(vel_km_s.iter()));
+            }
+            let mut type13_data = type12_data.clone();
+            type13_data.extend((0..30).map(|n| n as f64 * step_s));
+            type13_data.extend([5.0, 30.0]);
+            type12_data.extend([0.0, step_s, 5.0, 30.0]);
+            let type12 = HermiteSetType12::from_f64_slice(&type12_data).unwrap();
+            let type13 = HermiteSetType13::from_f64_slice(&type13_data).unwrap();
+
+            let accuracy_at = |k: f64| {
+                let epoch = Epoch::from_et_seconds(k * step_s);
+                let (exp_pos_km, exp_vel_km_s) =
+                    propagate_two_body(&pos0_km, &vel0_km_s, k * step_s, GM_EARTH_KM3_S2).unwrap();
+
+                let (state, accuracy) = type12
+                    .evaluate_with_accuracy(epoch, &summary, None)
+                    .unwrap();
+                assert_eq!(
+                    state,
+                    type12.to_cartesian_state(epoch, &summary, None).unwrap()
+                );
+                assert!((state.radius_km - exp_pos_km).norm() <= accuracy.pos_km);
+                assert!((state.velocity_km_s - exp_vel_km_s).norm() <= accuracy.vel_km_s);
+
+                // Both types select the same windows
+                let (type13_state, type13_accuracy) = type13
+                    .evaluate_with_accuracy(epoch, &summary, None)
+                    .unwrap();
+                assert_eq!(
+                    type13_state,
+                    type13.to_cartesian_state(epoch, &summary, None).unwrap()
+                );
+                assert!((type13_state.radius_km - state.radius_km).norm() < 1e-9);
+                assert!((type13_accuracy.pos_km - accuracy.pos_km).abs() < 1e-9);
+                assert!((type13_accuracy.vel_km_s - accuracy.vel_km_s).abs() < 1e-12);
+                assert_eq!(
+                    type13_accuracy.pos_km,
+                    type13.evaluate_with_error(epoch, &summary, None).unwrap().1
+                );
+                accuracy
+            };
+
+            // The windows are off-centered near the edges of the segment, so the estimate grows there
+            // compared to the nearest interval whose window is centered.
+            for (edge_k, inner_k) in [(0.5, 2.5), (28.5, 26.5)] {
+                let edge = accuracy_at(edge_k);
+                let inner = accuracy_at(inner_k);
+                assert!(edge.pos_km > inner.pos_km, "{step_s} s steps @ {edge_k}");
+                assert!(
+                    edge.vel_km_s > inner.vel_km_s,
+                    "{step_s} s steps @ {edge_k}"
+                );
+            }
+            mid_accuracies.push(accuracy_at(14.5));
+
+            // The estimate uses the states of the same window except its first and last ones, even if it is off-centered:
+            // the window of the first interval holds the first six states, so the estimate is built from the next four.
+            let epoch_et_s = 0.5 * step_s;
+            let inner: Vec<Vec<f64>> = (1..5)
+                .map(|n| type12_data[6 * n..6 * n + 6].to_vec())
+                .collect();
+            let epochs: Vec<f64> = (1..5).map(|n| n as f64 * step_s).collect();
+            let axis = |i: usize| inner.iter().map(|state| state[i]).collect::<Vec<f64>>();
+            let [(x_km, vx_km_s), (y_km, vy_km_s), (z_km, vz_km_s)] = hermite_eval_multi(
+                &epochs,
+                [&axis(0), &axis(1), &axis(2)],
+                [&axis(3), &axis(4), &axis(5)],
+                epoch_et_s,
+            )
+            .unwrap();
+            let epoch = Epoch::from_et_seconds(epoch_et_s);
+            for (state, accuracy) in [
+                type12
+                    .evaluate_with_accuracy(epoch, &summary, None)
+                    .unwrap(),
+                type13
+                    .evaluate_with_accuracy(epoch, &summary, None)
+                    .unwrap(),
+            ] {
+                let pos_km = (state.radius_km - Vector3::new(x_km, y_km, z_km)).norm();
+                let vel_km_s =
+                    (state.velocity_km_s - Vector3::new(vx_km_s, vy_km_s, vz_km_s)).norm();
+                assert!((accuracy.pos_km - pos_km).abs() < 1e-9, "{step_s} s steps");
+                assert!(
+                    (accuracy.vel_km_s - vel_km_s).abs() < 1e-12,
+                    "{step_s} s steps"
+                );
+            }
+
+            // States are returned as is
+            assert_eq!(accuracy_at(3.0), InterpolationAccuracy::default());
+        }
+
+        // And it grows with the spacing of the states
+        for pair in mid_accuracies.windows(2) {
+            assert!(pair[1].pos_km > pair[0].pos_km);
+            assert!(pair[1].vel_km_s > pair[0].vel_km_s);
+        }
+    }
+
+    #[test]
+    fn physical_consistency() {
+        let data = type13_two_body_data(8);
+        let report = HermiteSetType13::from_f64_slice(&data)
+            .unwrap()
+            .check_physical_consistency(1e-2)
+            .unwrap();
+        assert_eq!(report.num_pairs, 149);
+        assert!(!report.flagged);
+        // About the square of the angle swept in one minute of a low Earth orbit
+        assert!(report.worst_rel_error < 1e-3, "{report:?}");
+
+        // A maneuver is a local disagreement which does not flag the segment, but is reported as the worst pair.
+        let mut maneuver = data.clone();
+        for vel in &mut maneuver[6 * 70 + 3..6 * 70 + 6] {
+            *vel *= 1.01;
+        }
+        let report = HermiteSetType13::from_f64_slice(&maneuver)
+            .unwrap()
+            .check_physical_consistency(1e-2)
+            .unwrap();
+        assert!(!report.flagged);
+        assert!(report.worst_record == 69 || report.worst_record == 70);
+        assert!(report.worst_rel_error > 4e-3, "{report:?}");
+
+        // Velocities in m/s and with the wrong sign
+        for (scale, expected) in [(1e3, 1.0), (-1.0, 2.0)] {
+            let mut wrong = data.clone();
+            for state in wrong[..150 * 6].chunks_exact_mut(6) {
+                for vel in &mut state[3..] {
+                    *vel *= scale;
+                }
+            }
+            let report = HermiteSetType13::from_f64_slice(&wrong)
+                .unwrap()
+                .check_physical_consistency(1e-2)
+                .unwrap();
+            assert!(report.flagged);
+            assert!(
+                (report.median_rel_error - expected).abs() < 1e-2,
+                "{report:?}"
+            );
+        }
+
+        // Duplicated epochs are discontinuities, which are not compared.
+        let mut builder = HermiteSetType13::builder().with_window_size(2);
+        for (t, x) in [(0.0, 0.0), (10.0, 10.0), (10.0, 50.0), (20.0, 60.0)] {
+            builder = builder.with_state(CartesianState::new(
+                x,
+                0.0,
+                0.0,
+                1.0,
+                0.0,
+                0.0,
+                Epoch::from_et_seconds(t),
+                EARTH_J2000,
+            ));
+        }
+        let data = builder.build().unwrap();
+        let report = HermiteSetType13::from_f64_slice(&data)
+            .unwrap()
+            .check_physical_consistency(1e-2)
+            .unwrap();
+        assert_eq!(report.num_pairs, 2);
+        assert_eq!(report.worst_rel_error, 0.0);
+    }
+
+    #[test]
+    fn nearest_record() {
+        let data = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        for (t, index, offset_s) in [
+            (95.0, 2, 25.0),
+            (90.0, 1, -30.0),
+            (120.0, 2, 0.0),
+            (-1000.0, 0, 1000.0),
+            (1e6, 19, 1140.0 - 1e6),
+        ] {
+            let nearest = type12.nearest_record(Epoch::from_et_seconds(t)).unwrap();
+            assert_eq!(nearest.index, index, "at {t} s");
+            assert_eq!(nearest.epoch, Epoch::from_et_seconds(60.0 * index as f64));
+            assert_eq!(nearest.offset, offset_s.seconds(), "at {t} s");
+            assert_eq!(nearest.is_before(), offset_s < 0.0);
+            assert_eq!(nearest.is_after(), offset_s > 0.0);
+            assert_eq!(nearest.record.x_km, poly(60.0 * index as f64, 1.0).0);
+        }
+
+        // Type 13 epochs are unequally spaced: 0.0, 12.52, 22.73, 30.42, ...
+        let data = type13_data(20, 4);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        for (t, index) in [
+            (12.0, 1),
+            (17.0, 1),
+            (18.0, 2),
+            (type13.epoch_data[2], 2),
+            (-5.0, 0),
+            (1e4, 19),
+        ] {
+            let nearest = type13.nearest_record(Epoch::from_et_seconds(t)).unwrap();
+            let exp_et_s = type13.epoch_data[index];
+            assert_eq!(nearest.index, index, "at {t} s");
+            assert_eq!(nearest.epoch, Epoch::from_et_seconds(exp_et_s));
+            assert!((nearest.offset.to_seconds() - (exp_et_s - t)).abs() < 1e-9);
+            assert_eq!(nearest.is_before(), exp_et_s < t);
+            let (pos_km, vel_km_s) = nearest.record.to_pos_vel();
+            assert_eq!(pos_km.y, poly(exp_et_s, -2.0).0);
+            assert_eq!(vel_km_s.z, poly(exp_et_s, 0.5).1);
+        }
+    }
+
+    #[test]
+    fn record_iterators() {
+        let data = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        let records = type12.iter();
+        assert_eq!(records.len(), 20);
+        for (n, (epoch, record)) in records.enumerate() {
+            assert_eq!(epoch, Epoch::from_et_seconds(60.0 * n as f64));
+            assert_eq!(
+                record.to_pos_vel(),
+                type12.nth_record(n).unwrap().to_pos_vel()
+            );
+        }
+
+        let data = type13_data(250, 8);
+        let mut type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let mut records = type13.iter();
+        assert_eq!(records.len(), 250);
+        records.next();
+        assert_eq!(records.len(), 249);
+        for (n, (epoch, record)) in type13.iter().enumerate() {
+            assert_eq!(epoch, Epoch::from_et_seconds(type13.epoch_data[n]));
+            assert_eq!(
+                record.to_pos_vel(),
+                type13.nth_record(n).unwrap().to_pos_vel()
+            );
+        }
+
+        // Truncated state data only returns the records fully stored
+        type13.state_data = &type13.state_data[..6 * 100 + 3];
+        assert_eq!(type13.iter().len(), 100);
+        assert_eq!(type13.iter().count(), 100);
+
+        // Iteration stops at the first invalid epoch
+        let mut data = type13_data(10, 4);
+        data[6 * 10 + 4] = f64::NAN;
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        let mut records = type13.iter();
+        assert_eq!(records.by_ref().count(), 4);
+        assert_eq!(records.len(), 0);
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn position_only() {
+        let summary = SPKSummaryRecord::default();
+
+        let data = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        for t in [0.0, 60.0, 95.2, 600.5, 1140.0] {
+            let epoch = Epoch::from_et_seconds(t);
+            assert_eq!(
+                type12.evaluate_position(epoch, &summary).unwrap(),
+                type12.evaluate(epoch, &summary).unwrap().radius_km
+            );
+        }
+
+        let data = type13_data(250, 8);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+        for n in [0, 7, 125, 248] {
+            for epoch in [
+                Epoch::from_et_seconds(type13.epoch_data[n]),
+                Epoch::from_et_seconds((type13.epoch_data[n] + type13.epoch_data[n + 1]) / 2.0),
+            ] {
+                assert_eq!(
+                    type13.evaluate_position(epoch, &summary).unwrap(),
+                    type13.evaluate(epoch, &summary).unwrap().radius_km
+                );
+            }
+        }
+        let epoch = Epoch::from_et_seconds(type13.epoch_data[249] + 1.0);
+        assert_eq!(
+            type13.evaluate_position(epoch, &summary),
+            type13
+                .evaluate(epoch, &summary)
+                .map(|state| state.radius_km)
+        );
+    }
+
+    #[test]
+    fn evaluate_batch() {
+        let summary = SPKSummaryRecord::default();
+
+        let data = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        let data = type13_data(1_234, 5);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+
+        // Unordered epochs, with duplicates and the exact epochs of records
+        let epochs: Vec<Epoch> = [
+            900.0, 1.5, 0.0, 33.3, 1_140.0, 33.3, 600.0, 12.0, 60.0, 1_000.1,
+        ]
+        .iter()
+        .map(|et_s| Epoch::from_et_seconds(*et_s))
+        .collect();
+
+        let states = type12.evaluate_batch(&epochs, &summary, Some(1.0)).unwrap();
+        assert_eq!(states.len(), epochs.len());
+        for (epoch, state) in epochs.iter().zip(&states) {
+            assert_eq!(
+                *state,
+                type12
+                    .to_cartesian_state(*epoch, &summary, Some(1.0))
+                    .unwrap()
+            );
+        }
+
+        let states = type13.evaluate_batch(&epochs, &summary, None).unwrap();
+        assert_eq!(states.len(), epochs.len());
+        for (epoch, state) in epochs.iter().zip(&states) {
+            assert_eq!(state.epoch, *epoch);
+            let (pos_km, vel_km_s): (Vector3, Vector3) =
+                type13.evaluate(*epoch, &summary).unwrap().into();
+            assert!((state.radius_km - pos_km).norm() < 1e-12 * pos_km.norm());
+            assert!((state.velocity_km_s - vel_km_s).norm() < 1e-12 * vel_km_s.norm());
+        }
+
+        assert!(type13
+            .evaluate_batch(&[], &summary, None)
+            .unwrap()
+            .is_empty());
+
+        // Any epoch outside of the coverage fails the whole batch
+        let mut epochs = epochs;
+        epochs.push(Epoch::from_et_seconds(-1.0));
+        assert!(matches!(
+            type13.evaluate_batch(&epochs, &summary, None),
+            Err(InterpolationError::NoInterpolationData { .. })
+        ));
+        assert!(type12.evaluate_batch(&epochs, &summary, None).is_err());
+    }
+
+    #[test]
+    fn evaluate_within() {
+        let summary = SPKSummaryRecord::default();
+
+        let data = type12_data(20, 4, 60.0);
+        let type12 = HermiteSetType12::from_f64_slice(&data).unwrap();
+        let data = type13_data(20, 4);
+        let type13 = HermiteSetType13::from_f64_slice(&data).unwrap();
+
+        for (is_type12, start, end) in [
+            (true, type12.first_state_epoch, type12.last_state_epoch()),
+            (
+                false,
+                Epoch::from_et_seconds(type13.epoch_data[0]),
+                Epoch::from_et_seconds(type13.epoch_data[19]),
+            ),
+        ] {
+            let evaluate_within = |epoch, tolerance| {
+                if is_type12 {
+                    type12.evaluate_within(epoch, &summary, tolerance)
+                } else {
+                    type13.evaluate_within(epoch, &summary, tolerance)
+                }
+            };
+
+            for (epoch, bound) in [
+                (start - 1.microseconds(), start),
+                (end + 1.microseconds(), end),
+            ] {
+                // The default is as strict as evaluate
+                assert!(matches!(
+                    evaluate_within(epoch, 0.nanoseconds()),
+                    Err(InterpolationError::NoInterpolationData { .. })
+                ));
+                assert!(evaluate_within(epoch, 500.nanoseconds()).is_err());
+                assert_eq!(
+                    evaluate_within(epoch, 2.microseconds()),
+                    evaluate_within(bound, 0.nanoseconds())
+                );
+            }
+
+            // Epochs within the coverage are not altered
+            let mid = start + 0.4 * (end - start);
+            assert_eq!(
+                evaluate_within(mid, 1.microseconds()),
+                evaluate_within(mid, 0.nanoseconds())
+            );
+            assert!(evaluate_within(mid, 0.nanoseconds()).is_ok());
+        }
+    }
+
+    #[test]
+    fn duplicate_epochs() {
+        let summary = SPKSummaryRecord::default();
+        let samples = 6;
+        // Duplicate the 10th state, like at a maneuver, and make the later one differ
+        let epochs: Vec<f64> = (0..20).map(|n| 10.0 * n as f64).collect();
+        let mut records: Vec<[f64; 6]> = epochs
+            .iter()
+            .map(|t| {
+                let (x, vx) = poly(*t, 1.0);
+                let (y, vy) = poly(*t, -2.0);
+                let (z, vz) = poly(*t, 0.5);
+                [x, y, z, vx, vy, vz]
+            })
+            .collect();
+        let mut epochs = epochs;
+        epochs.insert(10, epochs[10]);
+        records.insert(10, records[10]);
+        records[11][3] += 1e-3;
+
+        let mut data: Vec<f64> = records.iter().flatten().copied().collect();
+        data.extend(&epochs);
+        data.extend([(samples - 1) as f64, epochs.len() as f64]);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        dataset.check_integrity(&summary).unwrap();
+
+        // The later record is returned at the duplicated epoch
+        let vel_km_s = dataset
+            .evaluate(Epoch::from_et_seconds(100.0), &summary)
+            .unwrap()
+            .velocity_km_s;
+        assert_eq!(vel_km_s.x, records[11][3]);
+
+        // Only one of the duplicates is kept in the window, depending on the side of the requested epoch
+        assert_eq!(dataset.window_indices(10, 95.0, samples), (7, 13));
+        let (indexes, count) = dataset.distinct_epochs(7, 13, 95.0);
+        assert_eq!(&indexes[..count], &[7, 8, 9, 10, 12]);
+        let (indexes, count) = dataset.distinct_epochs(7, 13, 105.0);
+        assert_eq!(&indexes[..count], &[7, 8, 9, 11, 12]);
+
+        // Which keeps the abscissas distinct so that the interpolation remains finite
+        for epoch_et_s in [95.0, 99.9, 100.1, 105.0] {
+            let epoch = Epoch::from_et_seconds(epoch_et_s);
+            let (pos_km, vel_km_s): (Vector3, Vector3) =
+                dataset.evaluate(epoch, &summary).unwrap().into();
+            assert!(pos_km.iter().chain(vel_km_s.iter()).all(|v| v.is_finite()));
+            let (_, _, acc_km_s2) = dataset.evaluate_with_accel(epoch, &summary).unwrap();
+            assert!(acc_km_s2.iter().all(|v| v.is_finite()));
+
+            // And the only difference is the velocity of the later record, so Y and Z are exactly interpolated
+            let (y, vy) = poly(epoch_et_s, -2.0);
+            assert!((pos_km.y - y).abs() < 1e-9 && (vel_km_s.y - vy).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn registry_mismatch() {
+        let num_records = 1_234;
+        let registry_idx = 7 * num_records;
+        let data = type13_data(num_records, 8);
+        HermiteSetType13::from_f64_slice(&data)
+            .unwrap()
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+
+        let mismatch = |index: usize| {
+            Err(IntegrityError::RegistryMismatch {
+                dataset: "Hermite Type 13",
+                index,
+            })
+        };
+
+        // Entry off by one bit
+        let mut corrupted = data.clone();
+        corrupted[registry_idx + 4] = f64::from_bits(corrupted[registry_idx + 4].to_bits() + 1);
+        let dataset = HermiteSetType13::from_f64_slice(&corrupted).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            mismatch(4)
+        );
+
+        // Missing entry
+        let mut truncated = data[..registry_idx + 11].to_vec();
+        truncated.extend(&data[data.len() - 2..]);
+        let dataset = HermiteSetType13::from_f64_slice(&truncated).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            mismatch(11)
+        );
+
+        // Superfluous entry
+        let mut extended = data[..data.len() - 2].to_vec();
+        extended.extend([1e9, 7.0, num_records as f64]);
+        let dataset = HermiteSetType13::from_f64_slice(&extended).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            mismatch(12)
+        );
+    }
+
+    #[test]
+    fn too_many_samples() {
+        let summary = SPKSummaryRecord::default();
+        let epoch = Epoch::from_et_seconds(123.4);
+
+        // SPICE allows windows of up to 14 states for the Hermite types, which are well within MAX_SAMPLES
+        let data = type13_data(64, 32);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        assert!(dataset.evaluate(epoch, &summary).is_ok());
+
+        let data = type13_data(64, 1_000);
+        assert_eq!(
+            HermiteSetType13::from_f64_slice(&data).err(),
+            Some(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "Hermite Type 13",
+                    variable: "number of interpolation samples",
+                    value: 1_000.0,
+                    reason: "must not exceed 32 (MAX_SAMPLES)",
+                },
+            })
+        );
+
+        // A window larger than the number of records cannot be formed, but is clamped when evaluating
+        let data = type13_data(4, 8);
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&summary),
+            Err(IntegrityError::WindowExceedsRecords {
+                dataset: "Hermite Type 13",
+                window_size: 8,
+                num_records: 4,
+            })
+        );
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(12.0), &summary)
+            .is_ok());
+
+        let data = type12_data(64, 32, 10.0);
+        let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+        assert!(dataset.evaluate(epoch, &summary).is_ok());
+
+        let data = type12_data(64, 33, 10.0);
+        assert_eq!(
+            HermiteSetType12::from_f64_slice(&data).err(),
+            Some(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "Hermite Type 12",
+                    variable: "window size",
+                    value: 33.0,
+                    reason: "must not exceed 32 (MAX_SAMPLES)",
+                },
+            })
+        );
+
+        let mut data = type12_data(64, 8, 10.0);
+        let len = data.len();
+        data[len - 2] = 1e300;
+        assert!(HermiteSetType12::from_f64_slice(&data).is_err());
+    }
+
     #[test]
     fn invalid_data() {
         // Two metadata, one state, one epoch
@@ -457,16 +3225,19 @@ mod hermite_ut {
 
         let mut invalid_epoch = zeros;
         invalid_epoch[zeros.len() - 3] = f64::INFINITY;
+        // Segments must have at least one record
+        invalid_epoch[zeros.len() - 1] = 1.0;
 
         let dataset = HermiteSetType13::from_f64_slice(&invalid_epoch).unwrap();
-        match dataset.check_integrity() {
+        match dataset.check_integrity(&SPKSummaryRecord::default()) {
             Ok(_) => panic!("test failed on invalid interval_length"),
             Err(e) => {
                 assert_eq!(
                     e,
-                    IntegrityError::SubNormal {
+                    IntegrityError::InfiniteValue {
                         dataset: "Hermite Type 13",
                         variable: "one of the epoch registry data",
+                        offset: zeros.len() - 3,
                     },
                 );
             }
@@ -478,14 +3249,15 @@ mod hermite_ut {
         invalid_record[zeros.len() - 1] = 1.0;
 
         let dataset = HermiteSetType13::from_f64_slice(&invalid_record).unwrap();
-        match dataset.check_integrity() {
+        match dataset.check_integrity(&SPKSummaryRecord::default()) {
             Ok(_) => panic!("test failed on invalid interval_length"),
             Err(e) => {
                 assert_eq!(
                     e,
-                    IntegrityError::SubNormal {
+                    IntegrityError::InfiniteValue {
                         dataset: "Hermite Type 13",
                         variable: "one of the state data",
+                        offset: 0,
                     },
                 );
             }
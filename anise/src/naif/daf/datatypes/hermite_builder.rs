@@ -0,0 +1,447 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use crate::errors::IntegrityError;
+use crate::math::cartesian::CartesianState;
+use crate::math::interpolation::MAX_SAMPLES;
+use crate::naif::daf::NAIFDataSet;
+
+use super::{non_finite, HermiteSetType12, HermiteSetType13};
+
+/// Window size of the builders unless another one is set, i.e. that of the spacecraft ephemerides produced by most orbit determination tools.
+const DEFAULT_WINDOW_SIZE: usize = 8;
+
+/// Maximum difference, in seconds, between the epoch of a state of a Type 12 set and the epoch implied by the step size.
+const STEP_TOLERANCE_S: f64 = 1e-6;
+
+/// Number of epochs between two consecutive entries of the epoch registry of a Type 13 set, as specified by SPICE.
+const REGISTRY_STEP: usize = 100;
+
+/// Builds the data of a [HermiteSetType12] from equally spaced states, e.g. to write tests or SPK segments.
+///
+/// The data is laid out as the DAF array of a Type 12 segment, so it can be decoded with `HermiteSetType12::from_f64_slice`.
+/// Only the position, velocity, and epoch of each state are stored: the frame and center are those of the summary of the segment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HermiteType12Builder {
+    window_size: usize,
+    states: Vec<CartesianState>,
+}
+
+impl Default for HermiteType12Builder {
+    fn default() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            states: Vec::new(),
+        }
+    }
+}
+
+impl HermiteType12Builder {
+    /// Sets the number of states used by each interpolation, 8 by default.
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+
+    /// Appends the provided state, which must be one step after the previous one.
+    pub fn with_state(mut self, state: CartesianState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Appends all of the provided states, in order.
+    pub fn with_states<I: IntoIterator<Item = CartesianState>>(mut self, states: I) -> Self {
+        self.states.extend(states);
+        self
+    }
+
+    /// Builds the data of the Type 12 set.
+    ///
+    /// # Errors
+    /// + The window size is less than two, greater than MAX_SAMPLES, or greater than the number of states.
+    /// + One of the states is not finite.
+    /// + The epochs are not strictly increasing, or not equally spaced to within a microsecond.
+    pub fn build(self) -> Result<Vec<f64>, IntegrityError> {
+        let dataset = HermiteSetType12::DATASET_NAME;
+        check_states(dataset, &self.states, self.window_size)?;
+
+        // The window size is at least two, so there are at least two states.
+        let first_et_s = self.states[0].epoch.to_et_seconds();
+        let step_s = self.states[1].epoch.to_et_seconds() - first_et_s;
+        for (n, state) in self.states.iter().enumerate().skip(1) {
+            let epoch_et_s = state.epoch.to_et_seconds();
+            if epoch_et_s <= self.states[n - 1].epoch.to_et_seconds() {
+                return Err(IntegrityError::NonMonotonicEpochs { dataset, index: n });
+            } else if (epoch_et_s - (first_et_s + n as f64 * step_s)).abs() > STEP_TOLERANCE_S {
+                return Err(IntegrityError::InvalidValue {
+                    dataset,
+                    variable: "epoch of a state in seconds past J2000 TDB",
+                    value: epoch_et_s,
+                    reason: "must be one step after the previous state",
+                });
+            }
+        }
+
+        let mut data = state_data(&self.states);
+        data.extend([
+            first_et_s,
+            step_s,
+            (self.window_size - 1) as f64,
+            self.states.len() as f64,
+        ]);
+        Ok(data)
+    }
+}
+
+/// Builds the data of a [HermiteSetType13] from states at any epochs, e.g. to write tests or SPK segments.
+///
+/// The data is laid out as the DAF array of a Type 13 segment, including its epoch registry, so it can be decoded with
+/// `HermiteSetType13::from_f64_slice`. Only the position, velocity, and epoch of each state are stored: the frame and center are
+/// those of the summary of the segment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HermiteType13Builder {
+    window_size: usize,
+    states: Vec<CartesianState>,
+}
+
+impl Default for HermiteType13Builder {
+    fn default() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            states: Vec::new(),
+        }
+    }
+}
+
+impl HermiteType13Builder {
+    /// Sets the number of states used by each interpolation, 8 by default.
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+
+    /// Appends the provided state, whose epoch must not be before that of the previous one.
+    pub fn with_state(mut self, state: CartesianState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Appends all of the provided states, in order.
+    pub fn with_states<I: IntoIterator<Item = CartesianState>>(mut self, states: I) -> Self {
+        self.states.extend(states);
+        self
+    }
+
+    /// Builds the data of the Type 13 set.
+    ///
+    /// Like in SPICE, two consecutive states may share the same epoch, e.g. for a discontinuity at a maneuver.
+    ///
+    /// # Errors
+    /// + The window size is less than two, greater than MAX_SAMPLES, or greater than the number of states.
+    /// + One of the states is not finite.
+    /// + One of the epochs is before that of the previous state.
+    pub fn build(self) -> Result<Vec<f64>, IntegrityError> {
+        let dataset = HermiteSetType13::DATASET_NAME;
+        check_states(dataset, &self.states, self.window_size)?;
+
+        let epochs: Vec<f64> = self
+            .states
+            .iter()
+            .map(|state| state.epoch.to_et_seconds())
+            .collect();
+        if let Some(n) = epochs.windows(2).position(|pair| pair[1] < pair[0]) {
+            return Err(IntegrityError::NonMonotonicEpochs {
+                dataset,
+                index: n + 1,
+            });
+        }
+
+        let mut data = state_data(&self.states);
+        data.extend(&epochs);
+        // The epoch registry stores every 100th epoch, excluding the last one.
+        data.extend(
+            (1..=(epochs.len() - 1) / REGISTRY_STEP).map(|k| epochs[REGISTRY_STEP * k - 1]),
+        );
+        data.extend([(self.window_size - 1) as f64, epochs.len() as f64]);
+        Ok(data)
+    }
+}
+
+impl<'a> HermiteSetType12<'a> {
+    /// Returns a builder of the data of a Type 12 set, cf. [HermiteType12Builder].
+    pub fn builder() -> HermiteType12Builder {
+        HermiteType12Builder::default()
+    }
+}
+
+impl<'a> HermiteSetType13<'a> {
+    /// Returns a builder of the data of a Type 13 set, cf. [HermiteType13Builder].
+    pub fn builder() -> HermiteType13Builder {
+        HermiteType13Builder::default()
+    }
+}
+
+/// Ensures that the states can be interpolated with the provided window size, and that they are all finite.
+fn check_states(
+    dataset: &'static str,
+    states: &[CartesianState],
+    window_size: usize,
+) -> Result<(), IntegrityError> {
+    let invalid_window = |reason| IntegrityError::InvalidValue {
+        dataset,
+        variable: "window size",
+        value: window_size as f64,
+        reason,
+    };
+    if window_size < 2 {
+        return Err(invalid_window("must be at least two"));
+    } else if window_size > MAX_SAMPLES {
+        return Err(invalid_window("must not exceed 32 (MAX_SAMPLES)"));
+    } else if window_size > states.len() {
+        return Err(invalid_window("must not exceed the number of records"));
+    }
+
+    // The offset of a non-finite value is that of its state.
+    for (n, state) in states.iter().enumerate() {
+        if let Some(value) = [state.epoch.to_et_seconds()]
+            .iter()
+            .chain(state.radius_km.iter())
+            .chain(state.velocity_km_s.iter())
+            .find(|val| !val.is_finite())
+        {
+            return Err(non_finite(dataset, "one of the states", *value, n));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the positions and velocities of the states, one record after the other.
+fn state_data(states: &[CartesianState]) -> Vec<f64> {
+    let mut data = Vec::with_capacity(states.len() * 7 + states.len() / REGISTRY_STEP + 4);
+    for state in states {
+        data.extend(state.radius_km.iter().chain(state.velocity_km_s.iter()));
+    }
+    data
+}
+
+#[cfg(test)]
+mod hermite_builder_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        constants::frames::EARTH_J2000,
+        errors::IntegrityError,
+        math::{cartesian::CartesianState, Vector3},
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::{HermiteSetType12, HermiteSetType13};
+
+    /// Returns a state on a circle, whose velocity is the derivative of its position.
+    fn state_at(epoch_et_s: f64) -> CartesianState {
+        // Epochs are stored to the nanosecond, so the state is computed at the stored epoch.
+        let epoch = Epoch::from_et_seconds(epoch_et_s);
+        let epoch_et_s = epoch.to_et_seconds();
+        let omega_rad_s = 1e-3;
+        let (sin_t, cos_t) = (omega_rad_s * epoch_et_s).sin_cos();
+        CartesianState {
+            radius_km: Vector3::new(7000.0 * cos_t, 7000.0 * sin_t, 100.0),
+            velocity_km_s: Vector3::new(-7.0 * sin_t, 7.0 * cos_t, 0.0),
+            epoch,
+            frame: EARTH_J2000,
+        }
+    }
+
+    #[test]
+    fn type12_round_trip() {
+        let states: Vec<CartesianState> =
+            (0..25).map(|n| state_at(10.0 + 30.0 * n as f64)).collect();
+        let data = HermiteSetType12::builder()
+            .with_window_size(6)
+            .with_states(states.clone())
+            .build()
+            .unwrap();
+
+        let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 10.0,
+            end_epoch_et_s: 730.0,
+            ..Default::default()
+        };
+        dataset.check_integrity(&summary).unwrap();
+        assert_eq!(dataset.first_state_epoch, states[0].epoch);
+        assert_eq!(dataset.step_size, 30.seconds());
+        assert_eq!(dataset.window_size, 6);
+        assert_eq!(dataset.num_records, 25);
+
+        for (n, state) in states.iter().enumerate() {
+            let record = dataset.nth_record(n).unwrap();
+            assert_eq!(record.to_pos_vel(), (state.radius_km, state.velocity_km_s));
+            let interp = dataset.evaluate(state.epoch, &summary).unwrap();
+            assert_eq!(interp.radius_km, state.radius_km);
+            assert_eq!(interp.velocity_km_s, state.velocity_km_s);
+        }
+        assert!(dataset.nth_record(25).is_err());
+
+        // Between the states, the interpolation matches the circle
+        let (pos_km, vel_km_s): (Vector3, Vector3) = dataset
+            .evaluate(Epoch::from_et_seconds(100.0), &summary)
+            .unwrap()
+            .into();
+        let expected = state_at(100.0);
+        assert!((pos_km - expected.radius_km).norm() < 1e-9);
+        assert!((vel_km_s - expected.velocity_km_s).norm() < 1e-12);
+
+        let data = HermiteSetType12::builder()
+            .with_window_size(2)
+            .with_state(states[0])
+            .with_state(states[1])
+            .build()
+            .unwrap();
+        assert_eq!(
+            HermiteSetType12::from_f64_slice(&data).unwrap().num_records,
+            2
+        );
+    }
+
+    #[test]
+    fn type13_round_trip() {
+        // Enough states for the epoch registry, with unequal steps and a duplicated epoch at a maneuver
+        let mut states: Vec<CartesianState> = (0..250)
+            .map(|n| state_at(10.0 * n as f64 + 3.0 * (n as f64).sin()))
+            .collect();
+        states.insert(120, states[119]);
+        states[120].velocity_km_s.z = 0.01;
+        let epochs: Vec<f64> = states
+            .iter()
+            .map(|state| state.epoch.to_et_seconds())
+            .collect();
+
+        let data = HermiteSetType13::builder()
+            .with_states(states.clone())
+            .build()
+            .unwrap();
+        let dataset = HermiteSetType13::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.samples, 8);
+        assert_eq!(dataset.num_records, 251);
+        assert_eq!(dataset.epoch_data, epochs.as_slice());
+        assert_eq!(dataset.epoch_registry, &[epochs[99], epochs[199]]);
+
+        for (n, state) in states.iter().enumerate() {
+            let record = dataset.nth_record(n).unwrap();
+            assert_eq!(record.to_pos_vel(), (state.radius_km, state.velocity_km_s));
+        }
+
+        let summary = SPKSummaryRecord::default();
+        let state = dataset
+            .evaluate(Epoch::from_et_seconds(505.0), &summary)
+            .unwrap();
+        let expected = state_at(505.0);
+        assert!((state.radius_km - expected.radius_km).norm() < 1e-9);
+        assert!((state.velocity_km_s - expected.velocity_km_s).norm() < 1e-12);
+    }
+
+    #[test]
+    fn invalid_states() {
+        let states: Vec<CartesianState> = (0..5).map(|n| state_at(60.0 * n as f64)).collect();
+        let invalid_window = |dataset, value, reason| {
+            Err(IntegrityError::InvalidValue {
+                dataset,
+                variable: "window size",
+                value,
+                reason,
+            })
+        };
+
+        for (window_size, reason) in [
+            (1, "must be at least two"),
+            (33, "must not exceed 32 (MAX_SAMPLES)"),
+            (6, "must not exceed the number of records"),
+        ] {
+            assert_eq!(
+                HermiteSetType12::builder()
+                    .with_window_size(window_size)
+                    .with_states(states.clone())
+                    .build(),
+                invalid_window("Hermite Type 12", window_size as f64, reason)
+            );
+            assert_eq!(
+                HermiteSetType13::builder()
+                    .with_window_size(window_size)
+                    .with_states(states.clone())
+                    .build(),
+                invalid_window("Hermite Type 13", window_size as f64, reason)
+            );
+        }
+        // The default window is larger than these states
+        assert!(HermiteSetType13::builder()
+            .with_states(states.clone())
+            .build()
+            .is_err());
+
+        let mut unordered = states.clone();
+        unordered.swap(2, 3);
+        assert_eq!(
+            HermiteSetType13::builder()
+                .with_window_size(4)
+                .with_states(unordered)
+                .build(),
+            Err(IntegrityError::NonMonotonicEpochs {
+                dataset: "Hermite Type 13",
+                index: 3
+            })
+        );
+        let mut zero_step = states.clone();
+        zero_step[1].epoch = zero_step[0].epoch;
+        assert_eq!(
+            HermiteSetType12::builder()
+                .with_window_size(4)
+                .with_states(zero_step)
+                .build(),
+            Err(IntegrityError::NonMonotonicEpochs {
+                dataset: "Hermite Type 12",
+                index: 1
+            })
+        );
+
+        let mut unequal = states.clone();
+        unequal[3] = state_at(181.0);
+        assert!(matches!(
+            HermiteSetType12::builder()
+                .with_window_size(4)
+                .with_states(unequal.clone())
+                .build(),
+            Err(IntegrityError::InvalidValue { value, .. }) if value == 181.0
+        ));
+        HermiteSetType13::builder()
+            .with_window_size(4)
+            .with_states(unequal)
+            .build()
+            .unwrap();
+
+        let mut non_finite = states;
+        non_finite[1].velocity_km_s.y = f64::NAN;
+        assert_eq!(
+            HermiteSetType13::builder()
+                .with_window_size(4)
+                .with_states(non_finite)
+                .build(),
+            Err(IntegrityError::NaNValue {
+                dataset: "Hermite Type 13",
+                variable: "one of the states",
+                offset: 1,
+            })
+        );
+    }
+}
@@ -15,25 +15,89 @@ use snafu::{ensure, ResultExt};
 use crate::{
     errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
     math::{
-        cartesian::CartesianState,
         interpolation::{lagrange_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES},
         Vector3,
     },
-    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord, NAIFSummaryRecord},
+    naif::daf::{
+        datatypes::{
+            check_finite, check_summary_coverage, epochs_coverage, non_finite, nth_record_data,
+            NO_RECORDS,
+        },
+        NAIFDataRecord, NAIFDataSet, NAIFRecord, NAIFSummaryRecord,
+    },
     DBL_SIZE,
 };
 
-use super::posvel::PositionVelocityRecord;
+use super::{epoch_registry::search_epoch, posvel::PositionVelocityRecord};
+
+/// Decodes the degree of the interpolating polynomials, which must fit in the statically allocated interpolation windows.
+fn decode_degree(dataset: &'static str, degree_f64: f64) -> Result<usize, DecodingError> {
+    if degree_f64.fract() == 0.0 && (0.0..MAX_SAMPLES as f64).contains(&degree_f64) {
+        Ok(degree_f64 as usize)
+    } else {
+        Err(DecodingError::Integrity {
+            source: IntegrityError::InvalidValue {
+                dataset,
+                variable: "polynomial degree",
+                value: degree_f64,
+                reason: "must be an integer between 0 and 31 (MAX_SAMPLES - 1)",
+            },
+        })
+    }
+}
 
 #[derive(PartialEq)]
 pub struct LagrangeSetType8<'a> {
+    /// Epoch of the first state in the record data
     pub first_state_epoch: Epoch,
+    /// Time step between two consecutive states
     pub step_size: Duration,
+    /// Degree of the interpolating polynomials, i.e. one less than the number of samples in an interpolation window
     pub degree: usize,
+    /// Total number of records stored in this data
     pub num_records: usize,
+    /// State data used for the interpolation
     pub record_data: &'a [f64],
 }
 
+impl<'a> LagrangeSetType8<'a> {
+    /// Returns the number of states used to build the interpolation
+    pub fn window_size(&self) -> usize {
+        self.degree + 1
+    }
+
+    /// Returns the epoch of the last state in the record data
+    pub fn last_state_epoch(&self) -> Epoch {
+        self.first_state_epoch + (self.num_records.saturating_sub(1) as f64) * self.step_size
+    }
+
+    /// Returns the epoch of the n-th state in the record data, in seconds past J2000 TDB.
+    fn nth_epoch_et_s(&self, n: usize) -> f64 {
+        self.first_state_epoch.to_et_seconds() + (n as f64) * self.step_size.to_seconds()
+    }
+
+    /// Returns the indexes of the first state and one past the last state of the interpolation window,
+    /// given the offset of the requested epoch from the first state, in number of steps.
+    ///
+    /// The window is built like SPICE does: centered on the nearest state for odd window sizes,
+    /// and with the epoch between the two middle states for even window sizes.
+    /// Near the start and the end of the data, the window is shifted to remain within the data.
+    fn window_indices(&self, offset: f64) -> (usize, usize) {
+        let window_size = self.window_size();
+        let first_idx = if window_size % 2 == 1 {
+            (offset.round() as usize).saturating_sub(self.degree / 2)
+        } else {
+            (offset.floor() as usize)
+                .saturating_add(1)
+                .saturating_sub(window_size / 2)
+        };
+        // Shift the window back if it would extend past the end of the data.
+        let first_idx = first_idx.min(self.num_records.saturating_sub(window_size));
+        let last_idx = self.num_records.min(first_idx.saturating_add(window_size));
+        (first_idx, last_idx)
+    }
+}
+
 impl<'a> fmt::Display for LagrangeSetType8<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -49,7 +113,7 @@ impl<'a> fmt::Display for LagrangeSetType8<'a> {
 }
 
 impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
-    type StateKind = CartesianState;
+    type StateKind = (Vector3, Vector3);
     type RecordKind = PositionVelocityRecord;
     const DATASET_NAME: &'static str = "Lagrange Type 8";
 
@@ -67,10 +131,12 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
         let seconds_since_j2000 = slice[slice.len() - 4];
         if !seconds_since_j2000.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "seconds since J2000 ET",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "seconds since J2000 ET",
+                    seconds_since_j2000,
+                    slice.len() - 4,
+                ),
             });
         }
 
@@ -78,15 +144,17 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
         let step_size_s = slice[slice.len() - 3];
         if !step_size_s.is_finite() {
             return Err(DecodingError::Integrity {
-                source: IntegrityError::SubNormal {
-                    dataset: "Hermite Type 12",
-                    variable: "step size in seconds",
-                },
+                source: non_finite(
+                    Self::DATASET_NAME,
+                    "step size in seconds",
+                    step_size_s,
+                    slice.len() - 3,
+                ),
             });
         }
 
         let step_size = step_size_s.seconds();
-        let degree = slice[slice.len() - 2] as usize;
+        let degree = decode_degree(Self::DATASET_NAME, slice[slice.len() - 2])?;
         let num_records = slice[slice.len() - 1] as usize;
 
         Ok(Self {
@@ -99,39 +167,99 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
     }
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
-        let rcrd_len = self.record_data.len() / self.num_records;
-        Ok(Self::RecordKind::from_slice_f64(
-            self.record_data
-                .get(n * rcrd_len..(n + 1) * rcrd_len)
-                .ok_or(DecodingError::InaccessibleBytes {
-                    start: n * rcrd_len,
-                    end: (n + 1) * rcrd_len,
-                    size: self.record_data.len(),
-                })?,
-        ))
+        let rcrd_len = PositionVelocityRecord::SIZE / DBL_SIZE;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            rcrd_len,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        if self.num_records == 0 {
+            return Err(NO_RECORDS);
+        }
+        Ok((self.first_state_epoch, self.last_state_epoch()))
     }
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
-        _epoch: Epoch,
-        _: &S,
-    ) -> Result<CartesianState, InterpolationError> {
-        Err(InterpolationError::UnimplementedType {
-            dataset: Self::DATASET_NAME,
-            issue: 12,
-        })
-    }
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        // Check that we even have interpolation data for that time
+        let epoch_et_s = epoch.to_et_seconds();
+        let first_et_s = self.first_state_epoch.to_et_seconds();
+        let last_et_s = self.nth_epoch_et_s(self.num_records.saturating_sub(1));
+        if epoch_et_s + 1e-9 < first_et_s || epoch_et_s - 1e-9 > last_et_s {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: self.first_state_epoch,
+                end: self.last_state_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
 
-    fn check_integrity(&self) -> Result<(), IntegrityError> {
-        for val in self.record_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the record data",
-                });
-            }
+        // The states are equally spaced, so we can directly compute the index of the nearest state.
+        let step_size_s = self.step_size.to_seconds();
+        let offset = (epoch_et_s - first_et_s) / step_size_s;
+        let nearest_idx = offset.round() as usize;
+        if (offset - offset.round()).abs() * step_size_s < 1e-9 {
+            // Oh wow, this state actually exists, no interpolation needed!
+            return Ok(self
+                .nth_record(nearest_idx.min(self.num_records.saturating_sub(1)))
+                .context(InterpDecodingSnafu)?
+                .to_pos_vel());
+        }
+
+        let (first_idx, last_idx) = self.window_indices(offset);
+        let samples = last_idx - first_idx;
+
+        // Statically allocated arrays of the maximum number of samples
+        let mut epochs = [0.0; MAX_SAMPLES];
+        let mut xs = [0.0; MAX_SAMPLES];
+        let mut ys = [0.0; MAX_SAMPLES];
+        let mut zs = [0.0; MAX_SAMPLES];
+        let mut vxs = [0.0; MAX_SAMPLES];
+        let mut vys = [0.0; MAX_SAMPLES];
+        let mut vzs = [0.0; MAX_SAMPLES];
+        for (cno, idx) in (first_idx..last_idx).enumerate() {
+            let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+            xs[cno] = record.x_km;
+            ys[cno] = record.y_km;
+            zs[cno] = record.z_km;
+            vxs[cno] = record.vx_km_s;
+            vys[cno] = record.vy_km_s;
+            vzs[cno] = record.vz_km_s;
+            epochs[cno] = self.nth_epoch_et_s(idx);
         }
 
+        // The positions and velocities are interpolated independently, as they are in SPICE.
+        let (x_km, _) = lagrange_eval(&epochs[..samples], &xs[..samples], epoch_et_s)?;
+        let (y_km, _) = lagrange_eval(&epochs[..samples], &ys[..samples], epoch_et_s)?;
+        let (z_km, _) = lagrange_eval(&epochs[..samples], &zs[..samples], epoch_et_s)?;
+        let (vx_km_s, _) = lagrange_eval(&epochs[..samples], &vxs[..samples], epoch_et_s)?;
+        let (vy_km_s, _) = lagrange_eval(&epochs[..samples], &vys[..samples], epoch_et_s)?;
+        let (vz_km_s, _) = lagrange_eval(&epochs[..samples], &vzs[..samples], epoch_et_s)?;
+
+        // And build the result
+        let pos_km = Vector3::new(x_km, y_km, z_km);
+        let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        check_summary_coverage(self, summary)?;
+
         Ok(())
     }
 }
@@ -145,6 +273,13 @@ pub struct LagrangeSetType9<'a> {
     pub epoch_registry: &'a [f64],
 }
 
+impl<'a> LagrangeSetType9<'a> {
+    /// Searches for the provided epoch in the epoch data, using the epoch registry to limit the search space.
+    fn search_epoch(&self, epoch_et_s: f64) -> Result<usize, usize> {
+        search_epoch(self.epoch_data, self.epoch_registry, epoch_et_s)
+    }
+}
+
 impl<'a> fmt::Display for LagrangeSetType9<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -175,15 +310,46 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
         );
 
         // For this kind of record, the metadata is stored at the very end of the dataset
-        let num_records = slice[slice.len() - 1] as usize;
-        let degree = slice[slice.len() - 2] as usize;
+        let num_records_f64 = slice[slice.len() - 1];
+        if !(num_records_f64.is_finite() && num_records_f64 >= 1.0) {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be at least one",
+                },
+            });
+        }
+        let num_records = num_records_f64 as usize;
+        let degree = decode_degree(Self::DATASET_NAME, slice[slice.len() - 2])?;
         // NOTE: The ::SIZE returns the C representation memory size of this, but we only want the number of doubles.
-        let state_data_end_idx = PositionVelocityRecord::SIZE / DBL_SIZE * num_records;
-        let state_data = slice.get(0..state_data_end_idx).unwrap();
-        let epoch_data_end_idx = state_data_end_idx + num_records;
-        let epoch_data = slice.get(state_data_end_idx..epoch_data_end_idx).unwrap();
+        let state_data_end_idx =
+            (PositionVelocityRecord::SIZE / DBL_SIZE).saturating_mul(num_records);
+        let state_data =
+            slice
+                .get(0..state_data_end_idx)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: state_data_end_idx,
+                    size: slice.len(),
+                })?;
+        let epoch_data_end_idx = state_data_end_idx.saturating_add(num_records);
+        let epoch_data = slice.get(state_data_end_idx..epoch_data_end_idx).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: state_data_end_idx,
+                end: epoch_data_end_idx,
+                size: slice.len(),
+            },
+        )?;
         // And the epoch directory is whatever remains minus the metadata
-        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 2).unwrap();
+        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 2).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: epoch_data_end_idx,
+                end: slice.len() - 2,
+                size: slice.len(),
+            },
+        )?;
 
         Ok(Self {
             degree,
@@ -195,25 +361,23 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
     }
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
-        let rcrd_len = self.state_data.len() / self.num_records;
-        Ok(Self::RecordKind::from_slice_f64(
-            self.state_data
-                .get(n * rcrd_len..(n + 1) * rcrd_len)
-                .ok_or(DecodingError::InaccessibleBytes {
-                    start: n * rcrd_len,
-                    end: (n + 1) * rcrd_len,
-                    size: self.state_data.len(),
-                })?,
-        ))
+        let rcrd_len = PositionVelocityRecord::SIZE / DBL_SIZE;
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.state_data,
+            n,
+            rcrd_len,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        epochs_coverage(Self::DATASET_NAME, self.epoch_data)
     }
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
         epoch: Epoch,
-        _: &S,
+        summary: &S,
     ) -> Result<Self::StateKind, InterpolationError> {
-        // Start by doing a binary search on the epoch registry to limit the search space in the total number of epochs.
-        // TODO: use the epoch registry to reduce the search space
         // Check that we even have interpolation data for that time
         if epoch.to_et_seconds() + 1e-9 < self.epoch_data[0]
             || epoch.to_et_seconds() - 1e-9 > *self.epoch_data.last().unwrap()
@@ -222,14 +386,12 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
                 req: epoch,
                 start: Epoch::from_et_seconds(self.epoch_data[0]),
                 end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap()),
+                id: summary.id(),
+                center_id: summary.center_id(),
             });
         }
-        // Now, perform a binary search on the epochs themselves.
-        match self.epoch_data.binary_search_by(|epoch_et| {
-            epoch_et
-                .partial_cmp(&epoch.to_et_seconds())
-                .expect("epochs in Hermite data is now NaN or infinite but was not before")
-        }) {
+        // Now, perform a binary search on the epochs themselves, using the epoch registry to limit the search space.
+        match self.search_epoch(epoch.to_et_seconds()) {
             Ok(idx) => {
                 // Oh wow, this state actually exists, no interpolation needed!
                 Ok(self
@@ -242,14 +404,12 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
                 let group_size = self.degree + 1;
                 let num_left = group_size / 2;
 
-                // Ensure that we aren't fetching out of the window
-                let mut first_idx = idx.saturating_sub(num_left);
-                let last_idx = self.num_records.min(first_idx + group_size);
-
-                // Check that we have enough samples
-                if last_idx == self.num_records {
-                    first_idx = last_idx - 2 * num_left;
-                }
+                // Ensure that we aren't fetching out of the window, shifting it back if it extends past the end of the data.
+                let first_idx = idx
+                    .saturating_sub(num_left)
+                    .min(self.num_records.saturating_sub(group_size));
+                let last_idx = self.num_records.min(first_idx.saturating_add(group_size));
+                let samples = last_idx - first_idx;
 
                 // Statically allocated arrays of the maximum number of samples
                 let mut epochs = [0.0; MAX_SAMPLES];
@@ -275,41 +435,23 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
 
                 // Build the interpolation polynomials making sure to limit the slices to exactly the number of items we actually used
                 // The other ones are zeros, which would cause the interpolation function to fail.
-                let (x_km, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &xs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (y_km, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &ys[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (z_km, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &zs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (vx_km_s, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &vxs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (vy_km_s, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &vys[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
-
-                let (vz_km_s, _) = lagrange_eval(
-                    &epochs[..group_size],
-                    &vzs[..group_size],
-                    epoch.to_et_seconds(),
-                )?;
+                let (x_km, _) =
+                    lagrange_eval(&epochs[..samples], &xs[..samples], epoch.to_et_seconds())?;
+
+                let (y_km, _) =
+                    lagrange_eval(&epochs[..samples], &ys[..samples], epoch.to_et_seconds())?;
+
+                let (z_km, _) =
+                    lagrange_eval(&epochs[..samples], &zs[..samples], epoch.to_et_seconds())?;
+
+                let (vx_km_s, _) =
+                    lagrange_eval(&epochs[..samples], &vxs[..samples], epoch.to_et_seconds())?;
+
+                let (vy_km_s, _) =
+                    lagrange_eval(&epochs[..samples], &vys[..samples], epoch.to_et_seconds())?;
+
+                let (vz_km_s, _) =
+                    lagrange_eval(&epochs[..samples], &vzs[..samples], epoch.to_et_seconds())?;
 
                 // And build the result
                 let pos_km = Vector3::new(x_km, y_km, z_km);
@@ -320,35 +462,298 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
         }
     }
 
-    fn check_integrity(&self) -> Result<(), IntegrityError> {
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
         // Verify that none of the data is invalid once when we load it.
-        for val in self.epoch_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the epoch data",
-                });
-            }
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.state_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.state_data.len() + self.epoch_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the state data",
+            self.state_data,
+            0,
+        )?;
+
+        check_summary_coverage(self, summary)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod lagrange_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        errors::IntegrityError,
+        math::interpolation::InterpolationError,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::{LagrangeSetType8, LagrangeSetType9};
+
+    /// Quartic polynomial used to build synthetic data, which a Lagrange interpolation of degree four or more must reproduce.
+    fn poly(t: f64, scale: f64) -> f64 {
+        scale * (1e3 + 2.0 * t + 3e-3 * t.powi(2) + 1e-6 * t.powi(3) + 1e-9 * t.powi(4))
+    }
+
+    /// Builds a Type 9 data slice with unequally spaced epochs, where the velocity is an independent polynomial.
+    fn type9_data(num_records: usize, degree: usize) -> Vec<f64> {
+        let epochs: Vec<f64> = (0..num_records)
+            .map(|n| 10.0 * n as f64 + 3.0 * (n as f64).sin())
+            .collect();
+        let mut data = Vec::with_capacity(num_records * 7 + num_records / 100 + 2);
+        for t in &epochs {
+            data.extend([
+                poly(*t, 1.0),
+                poly(*t, -2.0),
+                poly(*t, 0.5),
+                poly(*t, 1e-3),
+                poly(*t, -2e-3),
+                poly(*t, 5e-4),
+            ]);
         }
+        data.extend(&epochs);
+        data.extend((1..=(num_records - 1) / 100).map(|k| epochs[100 * k - 1]));
+        // Metadata: polynomial degree, number of records
+        data.extend([degree as f64, num_records as f64]);
+        data
+    }
 
-        for val in self.epoch_registry {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the epoch registry data",
-                });
+    /// Builds a Type 8 data slice with equally spaced epochs, where the velocity is an independent polynomial.
+    fn type8_data(num_records: usize, degree: usize, step_s: f64) -> Vec<f64> {
+        let mut data = Vec::with_capacity(num_records * 6 + 4);
+        for n in 0..num_records {
+            let t = n as f64 * step_s;
+            data.extend([
+                poly(t, 1.0),
+                poly(t, -2.0),
+                poly(t, 0.5),
+                poly(t, 1e-3),
+                poly(t, -2e-3),
+                poly(t, 5e-4),
+            ]);
+        }
+        // Metadata: start epoch, step size, polynomial degree, number of records
+        data.extend([0.0, step_s, degree as f64, num_records as f64]);
+        data
+    }
+
+    #[test]
+    fn type8_evaluate() {
+        let summary = SPKSummaryRecord::default();
+
+        for degree in [4, 5, 7] {
+            let data = type8_data(30, degree, 60.0);
+            let dataset = LagrangeSetType8::from_f64_slice(&data).unwrap();
+            assert_eq!(dataset.window_size(), degree + 1);
+            assert_eq!(
+                dataset.last_state_epoch(),
+                Epoch::from_et_seconds(29.0 * 60.0)
+            );
+            dataset.check_integrity(&summary).unwrap();
+            let early_summary = SPKSummaryRecord {
+                start_epoch_et_s: -60.0,
+                ..Default::default()
+            };
+            assert!(matches!(
+                dataset.check_integrity(&early_summary),
+                Err(IntegrityError::SummaryCoverageMismatch { .. })
+            ));
+
+            // The nodes themselves must return the stored states exactly.
+            for n in 0..dataset.num_records {
+                let record = dataset.nth_record(n).unwrap();
+                let state = dataset
+                    .evaluate(Epoch::from_et_seconds(n as f64 * 60.0), &summary)
+                    .unwrap();
+                assert_eq!(state, record.to_pos_vel(), "node #{n}");
+            }
+
+            for t in [0.1, 30.0, 61.0, 845.3, 1700.0, 1739.9] {
+                let (pos, vel) = dataset
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap();
+                for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                    let exp_pos = poly(t, *scale);
+                    let exp_vel = poly(t, scale * 1e-3);
+                    assert!(
+                        (pos[i] - exp_pos).abs() < 1e-12 * exp_pos.abs(),
+                        "pos error at {t} with degree {degree}"
+                    );
+                    assert!(
+                        (vel[i] - exp_vel).abs() < 1e-12 * exp_vel.abs(),
+                        "vel error at {t} with degree {degree}"
+                    );
+                }
+            }
+
+            // Outside of the data bounds
+            for epoch in [
+                Epoch::from_et_seconds(0.0) - 1.seconds(),
+                Epoch::from_et_seconds(29.0 * 60.0) + 1.seconds(),
+            ] {
+                assert_eq!(
+                    dataset.evaluate(epoch, &summary),
+                    Err(InterpolationError::NoInterpolationData {
+                        req: epoch,
+                        start: Epoch::from_et_seconds(0.0),
+                        end: Epoch::from_et_seconds(29.0 * 60.0),
+                        id: summary.target_id,
+                        center_id: summary.center_id,
+                    })
+                );
             }
         }
+    }
 
-        for val in self.state_data {
-            if !val.is_finite() {
-                return Err(IntegrityError::SubNormal {
-                    dataset: Self::DATASET_NAME,
-                    variable: "one of the state data",
-                });
+    #[test]
+    fn type8_windows() {
+        // Even window size (degree 3): the epoch is between the two middle states.
+        let data = type8_data(10, 3, 60.0);
+        let dataset = LagrangeSetType8::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.window_indices(4.5), (3, 7));
+        assert_eq!(dataset.window_indices(4.1), (3, 7));
+        assert_eq!(dataset.window_indices(4.9), (3, 7));
+        // Shifted at the start of the data
+        assert_eq!(dataset.window_indices(0.2), (0, 4));
+        assert_eq!(dataset.window_indices(1.2), (0, 4));
+        // Shifted at the end of the data
+        assert_eq!(dataset.window_indices(8.2), (6, 10));
+        assert_eq!(dataset.window_indices(8.9), (6, 10));
+
+        // Odd window size (degree 4): the window is centered on the nearest state.
+        let data = type8_data(10, 4, 60.0);
+        let dataset = LagrangeSetType8::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.window_indices(4.4), (2, 7));
+        assert_eq!(dataset.window_indices(4.6), (3, 8));
+        assert_eq!(dataset.window_indices(1.4), (0, 5));
+        assert_eq!(dataset.window_indices(8.6), (5, 10));
+
+        // Window larger than the data: all of the states are used.
+        let data = type8_data(3, 4, 60.0);
+        let dataset = LagrangeSetType8::from_f64_slice(&data).unwrap();
+        assert_eq!(dataset.window_indices(1.3), (0, 3));
+    }
+
+    #[test]
+    fn degree_25_windows() {
+        let summary = SPKSummaryRecord::default();
+
+        // Twenty-six samples per window, which the quartic must survive despite the high degree.
+        let type8_data = type8_data(60, 25, 60.0);
+        let type9_data = type9_data(60, 25);
+        let type8 = LagrangeSetType8::from_f64_slice(&type8_data).unwrap();
+        let type9 = LagrangeSetType9::from_f64_slice(&type9_data).unwrap();
+        assert_eq!(type8.degree, 25);
+        assert_eq!(type9.degree, 25);
+
+        for t in [1.0, 30.0, 123.4, 301.7, 550.0] {
+            for (pos, vel) in [
+                type8.evaluate(Epoch::from_et_seconds(t), &summary).unwrap(),
+                type9.evaluate(Epoch::from_et_seconds(t), &summary).unwrap(),
+            ] {
+                for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                    let exp_pos = poly(t, *scale);
+                    let exp_vel = poly(t, scale * 1e-3);
+                    assert!(
+                        (pos[i] - exp_pos).abs() < 1e-9 * exp_pos.abs(),
+                        "pos error at {t}: {:e}",
+                        (pos[i] - exp_pos) / exp_pos
+                    );
+                    assert!(
+                        (vel[i] - exp_vel).abs() < 1e-9 * exp_vel.abs(),
+                        "vel error at {t}"
+                    );
+                }
             }
         }
+    }
 
-        Ok(())
+    #[test]
+    fn type9_evaluate() {
+        let summary = SPKSummaryRecord::default();
+
+        // Odd and even window sizes
+        for degree in [4, 5, 7] {
+            let data = type9_data(250, degree);
+            let dataset = LagrangeSetType9::from_f64_slice(&data).unwrap();
+            assert_eq!(dataset.num_records, 250);
+            assert_eq!(dataset.epoch_registry.len(), 2);
+            dataset.check_integrity(&summary).unwrap();
+            let late_summary = SPKSummaryRecord {
+                end_epoch_et_s: dataset.epoch_data[249] + 1.0,
+                ..Default::default()
+            };
+            assert!(matches!(
+                dataset.check_integrity(&late_summary),
+                Err(IntegrityError::SummaryCoverageMismatch { .. })
+            ));
+
+            // The registry bounded search must match the full search.
+            for window in dataset.epoch_data.windows(2) {
+                for epoch_et_s in [window[0], 0.5 * (window[0] + window[1])] {
+                    assert_eq!(
+                        dataset.search_epoch(epoch_et_s),
+                        dataset.epoch_data.binary_search_by(|epoch_et| epoch_et
+                            .partial_cmp(&epoch_et_s)
+                            .unwrap())
+                    );
+                }
+            }
+
+            let first_et_s = dataset.epoch_data[0];
+            let last_et_s = *dataset.epoch_data.last().unwrap();
+            for t in [
+                first_et_s,
+                first_et_s + 0.1,
+                dataset.epoch_data[1] + 1.0,
+                dataset.epoch_data[99],
+                1234.5,
+                dataset.epoch_data[248] + 0.5,
+                last_et_s - 0.1,
+                last_et_s,
+            ] {
+                let (pos, vel) = dataset
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap();
+                for (i, scale) in [1.0, -2.0, 0.5].iter().enumerate() {
+                    let exp_pos = poly(t, *scale);
+                    let exp_vel = poly(t, scale * 1e-3);
+                    assert!(
+                        (pos[i] - exp_pos).abs() < 1e-12 * exp_pos.abs(),
+                        "pos error at {t} with degree {degree}"
+                    );
+                    assert!(
+                        (vel[i] - exp_vel).abs() < 1e-12 * exp_vel.abs(),
+                        "vel error at {t} with degree {degree}"
+                    );
+                }
+            }
+
+            // Outside of the data bounds
+            let epoch = Epoch::from_et_seconds(last_et_s) + 1.seconds();
+            assert_eq!(
+                dataset.evaluate(epoch, &summary),
+                Err(InterpolationError::NoInterpolationData {
+                    req: epoch,
+                    start: Epoch::from_et_seconds(first_et_s),
+                    end: Epoch::from_et_seconds(last_et_s),
+                    id: summary.target_id,
+                    center_id: summary.center_id,
+                })
+            );
+        }
     }
 }
@@ -0,0 +1,972 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+/*
+   NOTES:
+   1. The evaluation of the modified difference arrays is manually transliterated from SPICE's `spke01.f` and `spke21.f`, which only differ in the dimension of the difference arrays.
+   2. The SPICE algorithm uses one-based indexing, which is kept here (the first item of each work array is unused) for easier comparison with the original.
+*/
+
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, MathError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpolationError},
+        Vector3,
+    },
+    naif::daf::{
+        datatypes::{check_finite, check_summary_span, nth_record_data},
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
+};
+
+use super::epoch_registry::search_epoch;
+
+/// Maximum dimension of the difference arrays supported by the modified difference array evaluation.
+pub const MAX_MDA_DIM: usize = 25;
+/// Dimension of the difference arrays of Type 1 data, Type 21 data stores its own dimension.
+pub const TYPE1_MDA_DIM: usize = 15;
+/// Number of doubles in a Type 1 record.
+const TYPE1_RECORD_SIZE: usize = 4 * TYPE1_MDA_DIM + 11;
+
+/// A modified difference array record, as used in SPK Type 1 and Type 21 data.
+#[derive(Debug, PartialEq)]
+pub struct MDARecord<'a> {
+    /// Reference epoch of the difference table, in seconds past J2000 TDB
+    pub reference_epoch_et_s: f64,
+    /// Step size function vector, whose length is the dimension of the difference arrays
+    pub step_sizes: &'a [f64],
+    /// Reference position vector
+    pub ref_pos_km: Vector3,
+    /// Reference velocity vector
+    pub ref_vel_km_s: Vector3,
+    /// Modified difference arrays of the X, Y, and Z components, one after the other
+    pub difference_arrays: &'a [f64],
+    /// Maximum integration order plus one
+    pub kqmax1: usize,
+    /// Integration order of each component
+    pub kq: [usize; 3],
+}
+
+impl<'a> MDARecord<'a> {
+    /// Returns the j-th (one-based) modified difference of the provided component.
+    fn difference(&self, j: usize, component: usize) -> f64 {
+        self.difference_arrays[component * self.step_sizes.len() + j - 1]
+    }
+
+    /// Evaluates the position and velocity at the provided epoch, in seconds past J2000 TDB.
+    pub fn evaluate(&self, epoch_et_s: f64) -> Result<(Vector3, Vector3), InterpolationError> {
+        let dim = self.step_sizes.len();
+        if dim > MAX_MDA_DIM || self.difference_arrays.len() != 3 * dim {
+            return Err(InterpolationError::CorruptedData {
+                what: "MDA record size does not match its difference arrays",
+            });
+        } else if self.kqmax1 < 2 || self.kqmax1 > dim + 1 {
+            return Err(InterpolationError::CorruptedData {
+                what: "MDA maximum integration order plus one (KQMAX1) is invalid",
+            });
+        } else if self.kq.iter().any(|kq| *kq > dim) {
+            return Err(InterpolationError::CorruptedData {
+                what: "MDA integration order (KQ) exceeds the dimension of the difference arrays",
+            });
+        }
+
+        let mut fc = [0.0; MAX_MDA_DIM + 3];
+        let mut wc = [0.0; MAX_MDA_DIM + 3];
+        let mut w = [0.0; MAX_MDA_DIM + 3];
+
+        // Set up the computation of the various differences.
+        let delta = epoch_et_s - self.reference_epoch_et_s;
+        let mut tp = delta;
+        let mq2 = self.kqmax1 - 2;
+        let mut ks = self.kqmax1 - 1;
+
+        // Compute the coefficients from the step size function vector.
+        for j in 1..=mq2 {
+            let step = self.step_sizes[j - 1];
+            if step == 0.0 {
+                return Err(InterpolationError::InterpMath {
+                    source: MathError::DivisionByZero {
+                        action: "MDA step size function vector contains a zero",
+                    },
+                });
+            }
+            fc[j + 1] = tp / step;
+            wc[j] = delta / step;
+            tp = delta + step;
+        }
+
+        // Collect KQMAX1 reciprocals.
+        for (j, w_j) in w.iter_mut().enumerate().take(self.kqmax1 + 1).skip(1) {
+            *w_j = 1.0 / j as f64;
+        }
+
+        // Compute the W(K) terms needed for the position interpolation.
+        let mut jx = 0;
+        let mut ks1 = ks - 1;
+
+        while ks >= 2 {
+            jx += 1;
+            for j in 1..=jx {
+                w[j + ks] = fc[j + 1] * w[j + ks1] - wc[j] * w[j + ks];
+            }
+            ks = ks1;
+            ks1 -= 1;
+        }
+
+        // Perform the position interpolation (KS is one at this point).
+        let mut pos_km = Vector3::zeros();
+        for i in 0..3 {
+            let mut sum = 0.0;
+            for j in (1..=self.kq[i]).rev() {
+                sum += self.difference(j, i) * w[j + ks];
+            }
+            pos_km[i] = self.ref_pos_km[i] + delta * (self.ref_vel_km_s[i] + delta * sum);
+        }
+
+        // Compute the W(K) terms needed for the velocity interpolation (KS is one and KS1 is zero at this point).
+        for j in 1..=jx {
+            w[j + ks] = fc[j + 1] * w[j + ks1] - wc[j] * w[j + ks];
+        }
+        ks -= 1;
+
+        // Perform the velocity interpolation.
+        let mut vel_km_s = Vector3::zeros();
+        for i in 0..3 {
+            let mut sum = 0.0;
+            for j in (1..=self.kq[i]).rev() {
+                sum += self.difference(j, i) * w[j + ks];
+            }
+            vel_km_s[i] = self.ref_vel_km_s[i] + delta * sum;
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+}
+
+impl<'a> fmt::Display for MDARecord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reference epoch: {:E}\tKQMAX1: {}\tKQ: {:?}\tdimension: {}",
+            Epoch::from_et_seconds(self.reference_epoch_et_s),
+            self.kqmax1,
+            self.kq,
+            self.step_sizes.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for MDARecord<'a> {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        // The record is made of the reference epoch, the step size function vector (DIM), the interleaved reference position and velocity (6),
+        // the modified difference arrays (3 * DIM), the maximum integration order plus one (1), and the integration order of each component (3).
+        let dim = slice.len().saturating_sub(11) / 4;
+        let refs = &slice[dim + 1..dim + 7];
+        let kq_idx = 4 * dim + 8;
+        Self {
+            reference_epoch_et_s: slice[0],
+            step_sizes: &slice[1..dim + 1],
+            ref_pos_km: Vector3::new(refs[0], refs[2], refs[4]),
+            ref_vel_km_s: Vector3::new(refs[1], refs[3], refs[5]),
+            difference_arrays: &slice[dim + 7..4 * dim + 7],
+            kqmax1: slice[4 * dim + 7] as usize,
+            kq: [
+                slice[kq_idx] as usize,
+                slice[kq_idx + 1] as usize,
+                slice[kq_idx + 2] as usize,
+            ],
+        }
+    }
+}
+
+/// Returns the index of the record to use for the provided epoch, i.e. the first record whose final epoch is greater than or equal to it.
+///
+/// The epoch data only stores the final epoch of each record, so the start of the data is that of the summary.
+fn record_index<S: NAIFSummaryRecord>(
+    num_records: usize,
+    epoch_data: &[f64],
+    epoch_registry: &[f64],
+    epoch: Epoch,
+    summary: &S,
+) -> Result<usize, InterpolationError> {
+    if num_records == 0
+        || epoch < summary.start_epoch() - 1_i64.nanoseconds()
+        || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+    {
+        return Err(InterpolationError::NoInterpolationData {
+            req: epoch,
+            start: summary.start_epoch(),
+            end: summary.end_epoch(),
+            id: summary.id(),
+            center_id: summary.center_id(),
+        });
+    }
+
+    let idx = match search_epoch(epoch_data, epoch_registry, epoch.to_et_seconds()) {
+        Ok(idx) => idx,
+        Err(idx) => idx,
+    };
+
+    Ok(idx.min(num_records - 1))
+}
+
+#[derive(PartialEq)]
+pub struct MDASetType1<'a> {
+    /// Total number of records stored in this data
+    pub num_records: usize,
+    /// Modified difference array records
+    pub record_data: &'a [f64],
+    /// Final epoch of each of the records, ANISE expects this to be ordered chronologically!
+    pub epoch_data: &'a [f64],
+    /// Epoch registry to reduce the search space in epoch data.
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> MDASetType1<'a> {
+    /// Returns the maximum integration order of the record used at the provided epoch.
+    pub fn integration_order<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<usize, InterpolationError> {
+        let idx = record_index(
+            self.num_records,
+            self.epoch_data,
+            self.epoch_registry,
+            epoch,
+            summary,
+        )?;
+
+        let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+        Ok(record.kq.into_iter().max().unwrap_or(0))
+    }
+}
+
+impl<'a> fmt::Display for MDASetType1<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MDA Type 1 with {} records ({} epoch directories)",
+            self.num_records,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for MDASetType1<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = MDARecord<'a>;
+    const DATASET_NAME: &'static str = "MDA Type 1";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            !slice.is_empty(),
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 1_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the number of records is stored at the very end of the dataset
+        let num_records_f64 = slice[slice.len() - 1];
+        if !num_records_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be a finite value",
+                },
+            });
+        }
+        let num_records = num_records_f64 as usize;
+
+        let record_data_end_idx = TYPE1_RECORD_SIZE.saturating_mul(num_records);
+        let record_data =
+            slice
+                .get(0..record_data_end_idx)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: record_data_end_idx,
+                    size: slice.len(),
+                })?;
+        let epoch_data_end_idx = record_data_end_idx.saturating_add(num_records);
+        let epoch_data = slice.get(record_data_end_idx..epoch_data_end_idx).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: record_data_end_idx,
+                end: epoch_data_end_idx,
+                size: slice.len(),
+            },
+        )?;
+        // And the epoch directory is whatever remains minus the metadata
+        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 1).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: epoch_data_end_idx,
+                end: slice.len() - 1,
+                size: slice.len(),
+            },
+        )?;
+
+        Ok(Self {
+            num_records,
+            record_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            TYPE1_RECORD_SIZE,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        // The first record applies to any epoch before its final epoch, so only the summary bounds the start.
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "coverage without a summary",
+        })
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let idx = record_index(
+            self.num_records,
+            self.epoch_data,
+            self.epoch_registry,
+            epoch,
+            summary,
+        )?;
+
+        self.nth_record(idx)
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch.to_et_seconds())
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.record_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.record_data.len() + self.epoch_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        // The first record applies to all of the epochs before its final epoch, but no record applies after the last one.
+        if let Some(last_et_s) = self.epoch_data.last() {
+            check_summary_span(Self::DATASET_NAME, summary, f64::NEG_INFINITY, *last_et_s)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq)]
+pub struct MDASetType21<'a> {
+    /// Dimension of the difference arrays (DLSIZE, or MAXDIM in SPICE) of all of the records
+    pub dlsize: usize,
+    /// Total number of records stored in this data
+    pub num_records: usize,
+    /// Extended modified difference array records
+    pub record_data: &'a [f64],
+    /// Final epoch of each of the records, ANISE expects this to be ordered chronologically!
+    pub epoch_data: &'a [f64],
+    /// Epoch registry to reduce the search space in epoch data.
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> MDASetType21<'a> {
+    /// Returns the number of doubles in each record.
+    pub fn record_size(&self) -> usize {
+        4 * self.dlsize + 11
+    }
+
+    /// Returns the maximum integration order of the record used at the provided epoch.
+    pub fn integration_order<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<usize, InterpolationError> {
+        let idx = record_index(
+            self.num_records,
+            self.epoch_data,
+            self.epoch_registry,
+            epoch,
+            summary,
+        )?;
+
+        let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+        Ok(record.kq.into_iter().max().unwrap_or(0))
+    }
+
+    /// Ensures that the dimension of the difference arrays is supported.
+    fn check_dlsize(&self) -> Result<(), IntegrityError> {
+        if self.dlsize == 0 || self.dlsize > MAX_MDA_DIM {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "difference line size (DLSIZE)",
+                value: self.dlsize as f64,
+                reason: "must be between 1 and 25 (MAX_MDA_DIM)",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for MDASetType21<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MDA Type 21 with {} records of dimension {} ({} epoch directories)",
+            self.num_records,
+            self.dlsize,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for MDASetType21<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = MDARecord<'a>;
+    const DATASET_NAME: &'static str = "MDA Type 21";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 2_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset: DLSIZE, then the number of records.
+        let dlsize_f64 = slice[slice.len() - 2];
+        let num_records_f64 = slice[slice.len() - 1];
+        for (variable, value) in [
+            ("difference line size (DLSIZE)", dlsize_f64),
+            ("number of records", num_records_f64),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: Self::DATASET_NAME,
+                        variable,
+                        value,
+                        reason: "must be a finite positive value",
+                    },
+                });
+            }
+        }
+        let dlsize = dlsize_f64 as usize;
+        let num_records = num_records_f64 as usize;
+
+        // Reject unsupported dimensions before they are used to compute the record size.
+        if dlsize == 0 || dlsize > MAX_MDA_DIM {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "difference line size (DLSIZE)",
+                    value: dlsize_f64,
+                    reason: "must be between 1 and 25 (MAX_MDA_DIM)",
+                },
+            });
+        }
+
+        let record_data_end_idx = (4 * dlsize + 11).saturating_mul(num_records);
+        let record_data =
+            slice
+                .get(0..record_data_end_idx)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: record_data_end_idx,
+                    size: slice.len(),
+                })?;
+        let epoch_data_end_idx = record_data_end_idx.saturating_add(num_records);
+        let epoch_data = slice.get(record_data_end_idx..epoch_data_end_idx).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: record_data_end_idx,
+                end: epoch_data_end_idx,
+                size: slice.len(),
+            },
+        )?;
+        // And the epoch directory is whatever remains minus the metadata
+        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 2).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: epoch_data_end_idx,
+                end: slice.len() - 2,
+                size: slice.len(),
+            },
+        )?;
+
+        Ok(Self {
+            dlsize,
+            num_records,
+            record_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let rsize = self.record_size();
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            rsize,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        // Like Type 1, only the summary bounds the start.
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "coverage without a summary",
+        })
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        self.check_dlsize()
+            .map_err(|source| DecodingError::Integrity { source })
+            .context(InterpDecodingSnafu)?;
+
+        let idx = record_index(
+            self.num_records,
+            self.epoch_data,
+            self.epoch_registry,
+            epoch,
+            summary,
+        )?;
+
+        self.nth_record(idx)
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch.to_et_seconds())
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError> {
+        self.check_dlsize()?;
+
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.record_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.record_data.len() + self.epoch_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the record data",
+            self.record_data,
+            0,
+        )?;
+
+        // Like Type 1, only the end of the summary is bounded by the data.
+        if let Some(last_et_s) = self.epoch_data.last() {
+            check_summary_span(Self::DATASET_NAME, summary, f64::NEG_INFINITY, *last_et_s)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mda_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::{interpolation::InterpolationError, Vector3},
+        naif::{
+            daf::{NAIFDataRecord, NAIFDataSet},
+            spk::summary::SPKSummaryRecord,
+        },
+    };
+
+    use super::{MDARecord, MDASetType1, MDASetType21, TYPE1_MDA_DIM, TYPE1_RECORD_SIZE};
+
+    /// Builds a record of the provided dimension with the reference state and first two modified differences of each component.
+    fn mda_record(
+        dim: usize,
+        reference_epoch_et_s: f64,
+        pos: Vector3,
+        vel: Vector3,
+        step: f64,
+        diffs: [[f64; 2]; 3],
+    ) -> Vec<f64> {
+        let mut record = vec![reference_epoch_et_s];
+        record.extend(vec![step; dim]);
+        for i in 0..3 {
+            record.extend([pos[i], vel[i]]);
+        }
+        for diff in diffs {
+            record.extend(diff);
+            record.extend(vec![0.0; dim - 2]);
+        }
+        // KQMAX1 and KQ
+        record.extend([3.0, 2.0, 2.0, 2.0]);
+        assert_eq!(record.len(), 4 * dim + 11);
+        record
+    }
+
+    fn type1_record(
+        reference_epoch_et_s: f64,
+        pos: Vector3,
+        vel: Vector3,
+        step: f64,
+        diffs: [[f64; 2]; 3],
+    ) -> Vec<f64> {
+        mda_record(TYPE1_MDA_DIM, reference_epoch_et_s, pos, vel, step, diffs)
+    }
+
+    #[test]
+    fn mda_constant_jerk() {
+        // With KQMAX1 = 3, the first difference is the acceleration and the second is the jerk times the step size.
+        let pos = Vector3::new(7000.0, -1200.0, 300.0);
+        let vel = Vector3::new(1.0, 7.5, -0.2);
+        let acc = Vector3::new(-8e-3, 1.4e-3, -3e-4);
+        let jerk = Vector3::new(2e-6, -1e-6, 5e-7);
+        let step = -60.0;
+        let data = type1_record(
+            1000.0,
+            pos,
+            vel,
+            step,
+            [
+                [acc[0], jerk[0] * step],
+                [acc[1], jerk[1] * step],
+                [acc[2], jerk[2] * step],
+            ],
+        );
+        let record = MDARecord::from_slice_f64(&data);
+        assert_eq!(record.step_sizes.len(), TYPE1_MDA_DIM);
+        assert_eq!(record.ref_pos_km, pos);
+        assert_eq!(record.ref_vel_km_s, vel);
+        assert_eq!(record.kqmax1, 3);
+        assert_eq!(record.kq, [2, 2, 2]);
+
+        for epoch_et_s in [1000.0, 990.0, 961.3, 900.0] {
+            let dt = epoch_et_s - 1000.0;
+            let (eval_pos, eval_vel) = record.evaluate(epoch_et_s).unwrap();
+            let exp_pos = pos + vel * dt + acc * dt.powi(2) / 2.0 + jerk * dt.powi(3) / 6.0;
+            let exp_vel = vel + acc * dt + jerk * dt.powi(2) / 2.0;
+            assert!((eval_pos - exp_pos).norm() < 1e-9, "pos error at {dt}");
+            assert!((eval_vel - exp_vel).norm() < 1e-12, "vel error at {dt}");
+        }
+    }
+
+    #[test]
+    fn mda_velocity_consistency() {
+        // Higher order record with unequal steps: the velocity must be the derivative of the position.
+        let mut data = vec![500.0];
+        data.extend((1..=TYPE1_MDA_DIM).map(|j| -20.0 * j as f64 - 3.0));
+        data.extend([7000.0, 1.0, -1200.0, 7.5, 300.0, -0.2]);
+        for i in 0..3 {
+            data.extend(
+                (0..TYPE1_MDA_DIM).map(|j| (1e-3 + 1e-4 * i as f64) / 10_f64.powi(j as i32)),
+            );
+        }
+        data.extend([16.0, 15.0, 12.0, 9.0]);
+        let record = MDARecord::from_slice_f64(&data);
+
+        let h_s = 1e-3;
+        for epoch_et_s in [499.0, 480.0, 432.1, 400.0] {
+            let (_, vel) = record.evaluate(epoch_et_s).unwrap();
+            let (pos_before, _) = record.evaluate(epoch_et_s - h_s).unwrap();
+            let (pos_after, _) = record.evaluate(epoch_et_s + h_s).unwrap();
+            let numerical_vel = (pos_after - pos_before) / (2.0 * h_s);
+            assert!(
+                (vel - numerical_vel).norm() < 1e-7,
+                "velocity inconsistent at {epoch_et_s}: {vel} vs {numerical_vel}"
+            );
+        }
+
+        // Invalid orders
+        let mut invalid = data.clone();
+        invalid[TYPE1_RECORD_SIZE - 4] = 17.0;
+        assert_eq!(
+            MDARecord::from_slice_f64(&invalid).evaluate(450.0),
+            Err(InterpolationError::CorruptedData {
+                what: "MDA maximum integration order plus one (KQMAX1) is invalid",
+            })
+        );
+        let mut invalid = data;
+        invalid[TYPE1_RECORD_SIZE - 1] = 16.0;
+        assert_eq!(
+            MDARecord::from_slice_f64(&invalid).evaluate(450.0),
+            Err(InterpolationError::CorruptedData {
+                what: "MDA integration order (KQ) exceeds the dimension of the difference arrays",
+            })
+        );
+    }
+
+    #[test]
+    fn type1_evaluate() {
+        // Two records with constant acceleration each: the first covers [0, 100] and the second covers (100, 200].
+        let acc = [[1e-3, 0.0], [0.0, 0.0], [-1e-3, 0.0]];
+        let mut data = type1_record(
+            100.0,
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::zeros(),
+            -10.0,
+            acc,
+        );
+        data.extend(type1_record(
+            200.0,
+            Vector3::new(10.0, 20.0, 30.0),
+            Vector3::zeros(),
+            -10.0,
+            acc,
+        ));
+        // Epochs, no epoch registry, and the number of records
+        data.extend([100.0, 200.0, 2.0]);
+
+        let dataset = MDASetType1::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.num_records, 2);
+        assert!(dataset.epoch_registry.is_empty());
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 200.0,
+            ..Default::default()
+        };
+        dataset.check_integrity(&summary).unwrap();
+        // The first record applies before its final epoch, but no record applies after the last one.
+        let late_summary = SPKSummaryRecord {
+            start_epoch_et_s: -1e9,
+            end_epoch_et_s: 201.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            dataset.check_integrity(&late_summary),
+            Err(IntegrityError::SummaryCoverageMismatch {
+                dataset: "MDA Type 1",
+                summary_start_et_s: -1e9,
+                summary_end_et_s: 201.0,
+                data_start_et_s: f64::NEG_INFINITY,
+                data_end_et_s: 200.0,
+            })
+        );
+
+        for (epoch_et_s, ref_et_s, ref_x) in [
+            (0.0, 100.0, 1.0),
+            (50.0, 100.0, 1.0),
+            (100.0, 100.0, 1.0),
+            (100.5, 200.0, 10.0),
+            (200.0, 200.0, 10.0),
+        ] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(epoch_et_s), &summary)
+                .unwrap();
+            let dt: f64 = epoch_et_s - ref_et_s;
+            assert!((pos[0] - (ref_x + 1e-3 * dt.powi(2) / 2.0)).abs() < 1e-12);
+            assert!((vel[0] - 1e-3 * dt).abs() < 1e-12);
+            assert!((vel[2] + 1e-3 * dt).abs() < 1e-12);
+        }
+
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(200.1), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_data() {
+        match MDASetType1::from_f64_slice(&[]) {
+            Ok(_) => panic!("test failed on empty data"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::TooFewDoubles {
+                    dataset: "MDA Type 1",
+                    got: 0,
+                    need: 1,
+                }
+            ),
+        }
+
+        // Declares more records than available
+        match MDASetType1::from_f64_slice(&[0.0, 0.0, 1.0]) {
+            Ok(_) => panic!("test failed on too many records"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: TYPE1_RECORD_SIZE,
+                    size: 3
+                }
+            ),
+        }
+
+        let mut data = vec![0.0; TYPE1_RECORD_SIZE + 2];
+        data[TYPE1_RECORD_SIZE + 1] = 1.0;
+        data[3] = f64::NAN;
+        let dataset = MDASetType1::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::NaNValue {
+                dataset: "MDA Type 1",
+                variable: "one of the record data",
+                offset: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn type21_evaluate() {
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 200.0,
+            ..Default::default()
+        };
+        let diffs = [[1e-3, 2e-5], [-4e-4, 0.0], [-1e-3, -6e-5]];
+        let ref_pos = [Vector3::new(1.0, 2.0, 3.0), Vector3::new(10.0, 20.0, 30.0)];
+        let ref_vel = Vector3::new(0.1, -0.2, 0.3);
+
+        // The same dynamics stored in Type 1 and in Type 21 of a smaller dimension must evaluate identically.
+        let mut type1_data = Vec::new();
+        let mut type21_data = Vec::new();
+        for (ref_et_s, pos) in [(100.0, ref_pos[0]), (200.0, ref_pos[1])] {
+            type1_data.extend(type1_record(ref_et_s, pos, ref_vel, -10.0, diffs));
+            type21_data.extend(mda_record(5, ref_et_s, pos, ref_vel, -10.0, diffs));
+        }
+        type1_data.extend([100.0, 200.0, 2.0]);
+        type21_data.extend([100.0, 200.0, 5.0, 2.0]);
+
+        let type1 = MDASetType1::from_f64_slice(&type1_data).unwrap();
+        let type21 = MDASetType21::from_f64_slice(&type21_data).unwrap();
+        type21.check_integrity(&summary).unwrap();
+        let late_summary = SPKSummaryRecord {
+            end_epoch_et_s: 201.0,
+            ..summary
+        };
+        assert!(matches!(
+            type21.check_integrity(&late_summary),
+            Err(IntegrityError::SummaryCoverageMismatch { .. })
+        ));
+        assert_eq!(type21.dlsize, 5);
+        assert_eq!(type21.num_records, 2);
+        assert_eq!(type21.record_size(), 31);
+        assert_eq!(type21.nth_record(1).unwrap().step_sizes.len(), 5);
+        let epoch = Epoch::from_et_seconds(150.0);
+        assert_eq!(type1.integration_order(epoch, &summary), Ok(2));
+        assert_eq!(type21.integration_order(epoch, &summary), Ok(2));
+
+        for epoch_et_s in [0.0, 12.3, 99.9, 100.0, 150.0, 200.0] {
+            let epoch = Epoch::from_et_seconds(epoch_et_s);
+            let (pos1, vel1) = type1.evaluate(epoch, &summary).unwrap();
+            let (pos21, vel21) = type21.evaluate(epoch, &summary).unwrap();
+            assert!((pos1 - pos21).norm() < 1e-12, "pos differs at {epoch_et_s}");
+            assert!((vel1 - vel21).norm() < 1e-12, "vel differs at {epoch_et_s}");
+        }
+    }
+
+    #[test]
+    fn type21_invalid_dlsize() {
+        // A declared DLSIZE larger than the supported maximum is rejected before any indexing.
+        let mut data = mda_record(
+            5,
+            100.0,
+            Vector3::zeros(),
+            Vector3::zeros(),
+            -10.0,
+            [[0.0; 2]; 3],
+        );
+        data.extend([100.0, 26.0, 1.0]);
+        match MDASetType21::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on DLSIZE too large"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "MDA Type 21",
+                        variable: "difference line size (DLSIZE)",
+                        value: 26.0,
+                        reason: "must be between 1 and 25 (MAX_MDA_DIM)",
+                    },
+                }
+            ),
+        }
+
+        // A DLSIZE larger than the stored records leads to inaccessible bytes
+        let last = data.len() - 2;
+        data[last] = 6.0;
+        match MDASetType21::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on DLSIZE too large for the data"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: 35,
+                    size: 34
+                }
+            ),
+        }
+
+        // Integrity check of a data set which was not built from a slice
+        let dataset = MDASetType21 {
+            dlsize: 30,
+            num_records: 0,
+            record_data: &[],
+            epoch_data: &[],
+            epoch_registry: &[],
+        };
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InvalidValue {
+                dataset: "MDA Type 21",
+                variable: "difference line size (DLSIZE)",
+                value: 30.0,
+                reason: "must be between 1 and 25 (MAX_MDA_DIM)",
+            })
+        );
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(0.0), &SPKSummaryRecord::default())
+            .is_err());
+    }
+}
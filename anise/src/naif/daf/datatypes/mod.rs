@@ -9,12 +9,190 @@
  */
 
 pub mod chebyshev;
+pub mod chebyshev14;
+pub mod chebyshev20;
 pub mod chebyshev3;
+pub mod conic;
+mod epoch_registry;
+pub mod equinoctial;
+pub mod esoc;
+mod generic_segment;
 pub mod hermite;
+pub mod hermite_builder;
 pub mod lagrange;
+pub mod mda;
 pub mod posvel;
+pub mod tle;
+pub mod twobody;
 
 pub use chebyshev::*;
+pub use chebyshev14::*;
+pub use chebyshev20::*;
 pub use chebyshev3::*;
+pub use conic::*;
+pub use equinoctial::*;
+pub use esoc::*;
 pub use hermite::*;
+pub use hermite_builder::*;
 pub use lagrange::*;
+pub use mda::*;
+pub use tle::*;
+pub use twobody::*;
+
+use hifitime::{Duration, Epoch};
+use snafu::ResultExt;
+
+use crate::{
+    errors::{DecodingError, IntegrityError},
+    math::interpolation::{InterpDecodingSnafu, InterpolationError},
+};
+
+use super::{NAIFDataSet, NAIFSummaryRecord};
+
+/// Tolerance on the epochs of the summary beyond the span of the data, to allow for their rounding to seconds past J2000 ET.
+const SUMMARY_COVERAGE_TOLERANCE_S: f64 = 1e-6;
+
+/// Returns the data of the `n`-th record of `size` doubles, or an error if that record is not entirely in the data (including if its indexes overflow).
+pub(crate) fn nth_record_data(
+    data: &[f64],
+    n: usize,
+    size: usize,
+) -> Result<&[f64], DecodingError> {
+    let start = n.saturating_mul(size);
+    let end = start.saturating_add(size);
+    data.get(start..end)
+        .ok_or(DecodingError::InaccessibleBytes {
+            start,
+            end,
+            size: data.len(),
+        })
+}
+
+/// Returns an error if the epoch decoded from the data (in seconds past J2000 ET) is beyond the span of an [Epoch](hifitime::Epoch), i.e. about 3.3 million years around J2000.
+pub(crate) fn check_epoch_span(
+    dataset: &'static str,
+    variable: &'static str,
+    et_s: f64,
+) -> Result<(), IntegrityError> {
+    if et_s.abs() < Duration::MAX.to_seconds() {
+        Ok(())
+    } else {
+        Err(IntegrityError::InvalidValue {
+            dataset,
+            variable,
+            value: et_s,
+            reason: "must be within 32,768 centuries of J2000",
+        })
+    }
+}
+
+/// Error returned for the coverage of a data set without any records.
+pub(crate) const NO_RECORDS: InterpolationError = InterpolationError::CorruptedData {
+    what: "the data has no records",
+};
+
+/// Returns the epoch decoded from the data (in seconds past J2000 ET), or an error if it is beyond the span of an [Epoch].
+pub(crate) fn data_epoch(
+    dataset: &'static str,
+    variable: &'static str,
+    et_s: f64,
+) -> Result<Epoch, InterpolationError> {
+    check_epoch_span(dataset, variable, et_s)
+        .map_err(|source| DecodingError::Integrity { source })
+        .context(InterpDecodingSnafu)?;
+    Ok(Epoch::from_et_seconds(et_s))
+}
+
+/// Returns the coverage from the first to the last of the provided epochs (in seconds past J2000 ET) of the records of the data.
+pub(crate) fn epochs_coverage(
+    dataset: &'static str,
+    epochs_et_s: &[f64],
+) -> Result<(Epoch, Epoch), InterpolationError> {
+    match (epochs_et_s.first(), epochs_et_s.last()) {
+        (Some(first_et_s), Some(last_et_s)) => Ok((
+            data_epoch(dataset, "first epoch", *first_et_s)?,
+            data_epoch(dataset, "last epoch", *last_et_s)?,
+        )),
+        _ => Err(NO_RECORDS),
+    }
+}
+
+/// Returns the integrity error of a value which is not finite, found at the provided offset in the data of the segment.
+pub(crate) fn non_finite(
+    dataset: &'static str,
+    variable: &'static str,
+    value: f64,
+    offset: usize,
+) -> IntegrityError {
+    if value.is_nan() {
+        IntegrityError::NaNValue {
+            dataset,
+            variable,
+            offset,
+        }
+    } else {
+        IntegrityError::InfiniteValue {
+            dataset,
+            variable,
+            offset,
+        }
+    }
+}
+
+/// Returns an error for the first of the provided values which is not finite, given the offset of the first value in the data of the segment.
+pub(crate) fn check_finite(
+    dataset: &'static str,
+    variable: &'static str,
+    values: &[f64],
+    first_offset: usize,
+) -> Result<(), IntegrityError> {
+    match values.iter().position(|value| !value.is_finite()) {
+        Some(n) => Err(non_finite(dataset, variable, values[n], first_offset + n)),
+        None => Ok(()),
+    }
+}
+
+/// Returns an error if the summary covers epochs outside of the coverage of the data set, if the latter is bounded.
+pub(crate) fn check_summary_coverage<'a, D: NAIFDataSet<'a>, S: NAIFSummaryRecord>(
+    dataset: &D,
+    summary: &S,
+) -> Result<(), IntegrityError> {
+    match dataset.coverage() {
+        Ok((start, end)) => check_summary_span(
+            D::DATASET_NAME,
+            summary,
+            start.to_et_seconds(),
+            end.to_et_seconds(),
+        ),
+        // Unbounded data covers any summary, and data without valid records fails the other checks.
+        Err(_) => Ok(()),
+    }
+}
+
+/// Returns an error if the summary covers epochs (in seconds past J2000 ET) outside of the span of the data.
+///
+/// The data may span more than the summary, e.g. after a truncation. An unbounded end of the data, e.g. for data types which
+/// extrapolate before their first record, is set to an infinite number of seconds.
+pub(crate) fn check_summary_span<S: NAIFSummaryRecord>(
+    dataset: &'static str,
+    summary: &S,
+    data_start_et_s: f64,
+    data_end_et_s: f64,
+) -> Result<(), IntegrityError> {
+    let summary_start_et_s = summary.start_epoch_et_s();
+    let summary_end_et_s = summary.end_epoch_et_s();
+    // NaN summary epochs fail both comparisons, and are reported as a mismatch.
+    if summary_start_et_s >= data_start_et_s - SUMMARY_COVERAGE_TOLERANCE_S
+        && summary_end_et_s <= data_end_et_s + SUMMARY_COVERAGE_TOLERANCE_S
+    {
+        Ok(())
+    } else {
+        Err(IntegrityError::SummaryCoverageMismatch {
+            dataset,
+            summary_start_et_s,
+            summary_end_et_s,
+            data_start_et_s,
+            data_end_et_s,
+        })
+    }
+}
@@ -56,3 +56,60 @@ impl<'a> NAIFDataRecord<'a> for PositionVelocityRecord {
         }
     }
 }
+
+/// Position and velocity, each with its own time derivative, as stored in the Hermite subtype of SPK Type 18 segments.
+///
+/// The derivative of the position is an alternative velocity, and the derivative of the velocity is the acceleration.
+#[derive(Copy, Clone, Default, AsBytes, FromBytes, FromZeroes, Debug)]
+#[repr(C)]
+pub struct PositionVelocityAccelerationRecord {
+    pub x_km: f64,
+    pub y_km: f64,
+    pub z_km: f64,
+    pub dx_km_s: f64,
+    pub dy_km_s: f64,
+    pub dz_km_s: f64,
+    pub vx_km_s: f64,
+    pub vy_km_s: f64,
+    pub vz_km_s: f64,
+    pub ax_km_s2: f64,
+    pub ay_km_s2: f64,
+    pub az_km_s2: f64,
+}
+
+impl PositionVelocityAccelerationRecord {
+    /// Returns the position and the velocity (not the derivative of the position).
+    pub fn to_pos_vel(&self) -> (Vector3, Vector3) {
+        (
+            Vector3::new(self.x_km, self.y_km, self.z_km),
+            Vector3::new(self.vx_km_s, self.vy_km_s, self.vz_km_s),
+        )
+    }
+}
+
+impl fmt::Display for PositionVelocityAccelerationRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl NAIFRecord for PositionVelocityAccelerationRecord {}
+
+impl<'a> NAIFDataRecord<'a> for PositionVelocityAccelerationRecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        Self {
+            x_km: slice[0],
+            y_km: slice[1],
+            z_km: slice[2],
+            dx_km_s: slice[3],
+            dy_km_s: slice[4],
+            dz_km_s: slice[5],
+            vx_km_s: slice[6],
+            vy_km_s: slice[7],
+            vz_km_s: slice[8],
+            ax_km_s2: slice[9],
+            ay_km_s2: slice[10],
+            az_km_s2: slice[11],
+        }
+    }
+}
@@ -0,0 +1,464 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::ResultExt;
+
+use crate::{
+    errors::{DecodingError, IntegrityError},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpMathSnafu, InterpolationError},
+        rotation::{r1, r2, r3},
+        sgp4::{SGP4Constants, TLEElements, SGP4},
+        Matrix3, Vector3,
+    },
+    naif::daf::{
+        datatypes::{check_finite, nth_record_data},
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
+};
+
+use super::{epoch_registry::search_epoch, generic_segment::GenericSegment};
+
+/// Number of geophysical constants stored in a Type 10 segment.
+const TYPE10_NUM_CONSTANTS: usize = 8;
+/// Number of doubles in each Type 10 packet: the ten elements of the TLE, then the nutation angles and their rates.
+const TYPE10_PACKET_SIZE: usize = 14;
+/// Julian date of 1949 December 31 00:00:00, the reference of the SGP4 epochs.
+const JD_1950: f64 = 2433281.5;
+const ARCSEC_TO_RAD: f64 = core::f64::consts::PI / (180.0 * 3600.0);
+
+/// A two-line element set, as stored in each packet of SPK Type 10 data.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TLERecord {
+    /// Half of the first derivative of the mean motion, unused by SGP4
+    pub ndt2: f64,
+    /// One sixth of the second derivative of the mean motion, unused by SGP4
+    pub ndd6: f64,
+    pub bstar: f64,
+    pub inclination_rad: f64,
+    pub raan_rad: f64,
+    pub eccentricity: f64,
+    pub aop_rad: f64,
+    pub mean_anomaly_rad: f64,
+    pub mean_motion_rad_min: f64,
+    pub epoch_et_s: f64,
+    /// Nutation in longitude at the epoch of the elements
+    pub dpsi_rad: f64,
+    /// Nutation in obliquity at the epoch of the elements
+    pub deps_rad: f64,
+    pub dpsi_rate_rad_s: f64,
+    pub deps_rate_rad_s: f64,
+}
+
+impl TLERecord {
+    /// Returns the mean elements of this record as expected by SGP4, whose epoch is in UTC.
+    pub fn to_elements(&self) -> TLEElements {
+        TLEElements {
+            bstar: self.bstar,
+            inclination_rad: self.inclination_rad,
+            raan_rad: self.raan_rad,
+            eccentricity: self.eccentricity,
+            aop_rad: self.aop_rad,
+            mean_anomaly_rad: self.mean_anomaly_rad,
+            mean_motion_rad_min: self.mean_motion_rad_min,
+            epoch_days_1950: Epoch::from_et_seconds(self.epoch_et_s).to_jde_utc_days() - JD_1950,
+        }
+    }
+}
+
+impl fmt::Display for TLERecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for TLERecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        Self {
+            ndt2: slice[0],
+            ndd6: slice[1],
+            bstar: slice[2],
+            inclination_rad: slice[3],
+            raan_rad: slice[4],
+            eccentricity: slice[5],
+            aop_rad: slice[6],
+            mean_anomaly_rad: slice[7],
+            mean_motion_rad_min: slice[8],
+            epoch_et_s: slice[9],
+            dpsi_rad: slice[10],
+            deps_rad: slice[11],
+            dpsi_rate_rad_s: slice[12],
+            deps_rate_rad_s: slice[13],
+        }
+    }
+}
+
+/// Rotation from the True Equator Mean Equinox frame of date to J2000, using the IAU 1976 precession and the provided
+/// nutation angles, like `zzteme` in SPICE.
+fn teme_to_j2000(epoch_et_s: f64, dpsi_rad: f64, deps_rad: f64) -> Matrix3 {
+    let t = epoch_et_s / (36525.0 * 86400.0);
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    // IAU 1976 precession angles and mean obliquity of date
+    let zeta = (2306.2181 * t + 0.30188 * t2 + 0.017998 * t3) * ARCSEC_TO_RAD;
+    let theta = (2004.3109 * t - 0.42665 * t2 - 0.041833 * t3) * ARCSEC_TO_RAD;
+    let z = (2306.2181 * t + 1.09468 * t2 + 0.018203 * t3) * ARCSEC_TO_RAD;
+    let mean_obliquity = (84381.448 - 46.8150 * t - 0.00059 * t2 + 0.001813 * t3) * ARCSEC_TO_RAD;
+
+    // TEME to true of date, using the equation of the equinoxes
+    let teme_to_tod = r3(-dpsi_rad * mean_obliquity.cos());
+    let tod_to_mod = r1(-mean_obliquity) * r3(dpsi_rad) * r1(mean_obliquity + deps_rad);
+    let mod_to_j2000 = r3(zeta) * r2(-theta) * r3(z);
+
+    mod_to_j2000 * tod_to_mod * teme_to_tod
+}
+
+/// Two-line element sets propagated with SGP4, stored in a generic segment.
+#[derive(PartialEq)]
+pub struct TLESetType10<'a> {
+    /// Geophysical constants used by SGP4
+    pub constants: SGP4Constants,
+    /// Total number of element sets stored in this data
+    pub num_records: usize,
+    /// Element sets, ANISE expects these to be ordered chronologically with the epoch data.
+    pub record_data: &'a [f64],
+    /// Epoch of each of the element sets
+    pub epoch_data: &'a [f64],
+    /// Epoch registry to reduce the search space in epoch data.
+    pub epoch_registry: &'a [f64],
+    /// Offsets in the segment data of the record data, the epoch data, and the epoch registry
+    pub data_offsets: [usize; 3],
+}
+
+impl<'a> fmt::Display for TLESetType10<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TLE Type 10 with {} element sets ({} epoch directories)",
+            self.num_records,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for TLESetType10<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = TLERecord;
+    const DATASET_NAME: &'static str = "TLE Type 10";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        let segment = GenericSegment::from_f64_slice(Self::DATASET_NAME, slice)?;
+
+        if segment.constants.len() != TYPE10_NUM_CONSTANTS {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of geophysical constants",
+                    value: segment.constants.len() as f64,
+                    reason: "must be 8",
+                },
+            });
+        } else if segment.packet_size != TYPE10_PACKET_SIZE {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "packet size",
+                    value: segment.packet_size as f64,
+                    reason: "must be 14",
+                },
+            });
+        }
+
+        segment
+            .check_references(Self::DATASET_NAME)
+            .map_err(|source| DecodingError::Integrity { source })?;
+
+        Ok(Self {
+            constants: SGP4Constants::from_slice(segment.constants),
+            num_records: segment.num_packets,
+            record_data: segment.packet_data,
+            epoch_data: segment.reference_values,
+            epoch_registry: segment.reference_directory,
+            data_offsets: segment.offsets,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.record_data,
+            n,
+            TYPE10_PACKET_SIZE,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        // SGP4 propagates the element sets to any epoch, so only the summary bounds the coverage.
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "coverage without a summary",
+        })
+    }
+
+    /// Propagates the latest element set which is not after the requested epoch (or the first one before the first
+    /// element set) with SGP4, and rotates that state from TEME to J2000.
+    ///
+    /// # Note
+    /// The nutation angles are extrapolated linearly from the epoch of the element set, and the time derivative of
+    /// the TEME to J2000 rotation is neglected in the velocity (it is well below the accuracy of SGP4).
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if self.num_records == 0
+            || epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        let epoch_et_s = epoch.to_et_seconds();
+        let idx = match search_epoch(self.epoch_data, self.epoch_registry, epoch_et_s) {
+            Ok(idx) => idx,
+            Err(0) => 0,
+            Err(idx) => idx - 1,
+        };
+
+        let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+        let sgp4 = SGP4::new(&record.to_elements(), &self.constants).context(InterpMathSnafu)?;
+        let dt_s = epoch_et_s - record.epoch_et_s;
+        let (pos_teme_km, vel_teme_km_s) = sgp4.propagate(dt_s / 60.0).context(InterpMathSnafu)?;
+
+        let dcm = teme_to_j2000(
+            epoch_et_s,
+            record.dpsi_rad + record.dpsi_rate_rad_s * dt_s,
+            record.deps_rad + record.deps_rate_rad_s * dt_s,
+        );
+
+        Ok((dcm * pos_teme_km, dcm * vel_teme_km_s))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, _summary: &S) -> Result<(), IntegrityError> {
+        // SGP4 propagates the element sets before the first and after the last epochs, so any summary is covered.
+        for (value, variable) in [
+            (self.constants.ke, "square root of GM (KE)"),
+            (self.constants.radius_km, "Earth equatorial radius"),
+        ] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable,
+                    value,
+                    reason: "must be a finite and strictly positive value",
+                });
+            }
+        }
+
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.data_offsets[1],
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.data_offsets[2],
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the element set data",
+            self.record_data,
+            self.data_offsets[0],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tle_ut {
+    use core::f64::consts::TAU;
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::{
+            sgp4::{SGP4Constants, SGP4},
+            Vector3,
+        },
+        naif::{
+            daf::{datatypes::generic_segment::build_generic_segment, NAIFDataSet},
+            spk::summary::SPKSummaryRecord,
+        },
+    };
+
+    use super::{teme_to_j2000, TLERecord, TLESetType10, ARCSEC_TO_RAD};
+
+    fn constants() -> Vec<f64> {
+        let c = SGP4Constants::wgs72();
+        vec![c.j2, c.j3, c.j4, c.ke, c.qo_km, c.so_km, c.radius_km, c.ae]
+    }
+
+    /// Vanguard 1 (00005) packet, whose epoch is shifted by the provided number of days.
+    fn packet(shift_days: f64, dpsi_rad: f64) -> Vec<f64> {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(1999, 12, 31)
+            + (179.78495062 + shift_days).days();
+        vec![
+            0.0,
+            0.0,
+            0.28098e-4,
+            34.2682_f64.to_radians(),
+            348.7242_f64.to_radians(),
+            0.1859667,
+            331.7664_f64.to_radians(),
+            19.3264_f64.to_radians(),
+            10.82419157 * TAU / 1440.0,
+            epoch.to_et_seconds(),
+            dpsi_rad,
+            0.0,
+            0.0,
+            0.0,
+        ]
+    }
+
+    #[test]
+    fn teme_rotation() {
+        // No precession at J2000, and no nutation, so the frames match
+        assert!((teme_to_j2000(0.0, 0.0, 0.0) - crate::math::Matrix3::identity()).norm() < 1e-15);
+
+        // One century later, the pole of date is away from the J2000 pole by the precession angle theta
+        let dcm = teme_to_j2000(36525.0 * 86400.0, 0.0, 0.0);
+        let pole = dcm * Vector3::z();
+        let theta = (2004.3109 - 0.42665 - 0.041833) * ARCSEC_TO_RAD;
+        assert!((pole.dot(&Vector3::z()).acos() - theta).abs() < 1e-12);
+        assert!((dcm * dcm.transpose() - crate::math::Matrix3::identity()).norm() < 1e-14);
+    }
+
+    #[test]
+    fn type10_evaluate() {
+        let mut packets = packet(0.0, 0.0);
+        packets.extend(packet(1.0, 5e-5));
+        let epochs = [packets[9], packets[14 + 9]];
+        let data = build_generic_segment(&constants(), &packets, 14, &epochs, &[]);
+
+        let dataset = TLESetType10::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.num_records, 2);
+        assert_eq!(dataset.constants, SGP4Constants::wgs72());
+        assert_eq!(
+            format!("{dataset}"),
+            "TLE Type 10 with 2 element sets (0 epoch directories)"
+        );
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: epochs[0] - 3600.0,
+            end_epoch_et_s: epochs[1] + 86400.0,
+            ..Default::default()
+        };
+
+        let first = dataset.nth_record(0).unwrap();
+        let second = dataset.nth_record(1).unwrap();
+        assert!((first.to_elements().epoch_days_1950 - 18441.78495062).abs() < 1e-9);
+        let constants = SGP4Constants::wgs72();
+
+        // Before the first element set, the first one is used; then, the latest one which is not after the epoch.
+        for (offset_s, record) in [
+            (-3600.0, first),
+            (0.0, first),
+            (21600.0, first),
+            (86399.0, first),
+            (86400.0, second),
+            (90000.0, second),
+        ] {
+            let epoch_et_s = epochs[0] + offset_s;
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(epoch_et_s), &summary)
+                .unwrap();
+
+            let sgp4 = SGP4::new(&record.to_elements(), &constants).unwrap();
+            let (pos_teme, vel_teme) = sgp4
+                .propagate((epoch_et_s - record.epoch_et_s) / 60.0)
+                .unwrap();
+            let dcm = teme_to_j2000(epoch_et_s, record.dpsi_rad, record.deps_rad);
+            assert!((pos - dcm * pos_teme).norm() < 1e-9, "{offset_s}");
+            assert!((vel - dcm * vel_teme).norm() < 1e-12, "{offset_s}");
+            // The rotation to J2000 preserves the norms
+            assert!((pos.norm() - pos_teme.norm()).abs() < 1e-9);
+            assert!((pos - pos_teme).norm() > 0.1);
+        }
+
+        // At the epoch of the element set, the TEME state is the reference state of Vanguard 1
+        let (pos_teme, _) = SGP4::new(&first.to_elements(), &constants)
+            .unwrap()
+            .propagate(0.0)
+            .unwrap();
+        assert!((pos_teme - Vector3::new(7022.46529266, -1400.08296755, 0.03995155)).norm() < 1e-4);
+
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(epochs[0] - 3601.0), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_data() {
+        let packets = packet(0.0, 0.0);
+        let data = build_generic_segment(&constants()[..7], &packets, 14, &[packets[9]], &[]);
+        match TLESetType10::from_f64_slice(&data) {
+            Ok(_) => panic!("test failed on missing constants"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::Integrity {
+                    source: IntegrityError::InvalidValue {
+                        dataset: "TLE Type 10",
+                        variable: "number of geophysical constants",
+                        value: 7.0,
+                        reason: "must be 8",
+                    }
+                }
+            ),
+        }
+
+        let mut bad_packets = packets.clone();
+        bad_packets[5] = f64::NAN;
+        let data = build_generic_segment(&constants(), &bad_packets, 14, &[packets[9]], &[]);
+        let dataset = TLESetType10::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::NaNValue {
+                dataset: "TLE Type 10",
+                variable: "one of the element set data",
+                offset: constants().len() + 5,
+            })
+        );
+
+        // Elements which SGP4 cannot propagate are reported as such
+        let record = TLERecord {
+            mean_motion_rad_min: -1.0,
+            ..Default::default()
+        };
+        assert!(SGP4::new(&record.to_elements(), &SGP4Constants::wgs72()).is_err());
+    }
+}
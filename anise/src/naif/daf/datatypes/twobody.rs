@@ -0,0 +1,458 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::f64::consts::PI;
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpMathSnafu, InterpolationError},
+        propagation::propagate_two_body,
+        Vector3,
+    },
+    naif::daf::{
+        datatypes::{check_finite, nth_record_data},
+        NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord,
+    },
+};
+
+use super::{epoch_registry::search_epoch, posvel::PositionVelocityRecord};
+
+/// Discrete states between which the trajectory is propagated with two-body dynamics.
+///
+/// Its evaluation has not been validated against SPICE on a reference Type 5 kernel yet (cf. the ignored
+/// `validate_twobody_type05_kernel` integration test).
+#[derive(PartialEq)]
+pub struct TwoBodySetType5<'a> {
+    /// Gravitational parameter of the central body used to propagate the states
+    pub gm_km3_s2: f64,
+    /// Total number of states stored in this data
+    pub num_records: usize,
+    /// Discrete states, ANISE expects these to be ordered chronologically with the epoch data!
+    pub state_data: &'a [f64],
+    /// Epoch of each of the states
+    pub epoch_data: &'a [f64],
+    /// Epoch registry to reduce the search space in epoch data.
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> TwoBodySetType5<'a> {
+    /// Propagates the n-th state to the provided epoch, in seconds past J2000 TDB.
+    fn propagate_nth(
+        &self,
+        n: usize,
+        epoch_et_s: f64,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        let (pos_km, vel_km_s) = self
+            .nth_record(n)
+            .context(InterpDecodingSnafu)?
+            .to_pos_vel();
+        propagate_two_body(
+            &pos_km,
+            &vel_km_s,
+            epoch_et_s - self.epoch_data[n],
+            self.gm_km3_s2,
+        )
+        .context(InterpMathSnafu)
+    }
+}
+
+impl<'a> fmt::Display for TwoBodySetType5<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Two-body Type 5 with {} states (GM = {} km^3/s^2, {} epoch directories)",
+            self.num_records,
+            self.gm_km3_s2,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for TwoBodySetType5<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = PositionVelocityRecord;
+    const DATASET_NAME: &'static str = "Two-body Type 5";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 2_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset: the GM, then the number of states.
+        let gm_km3_s2 = slice[slice.len() - 2];
+        let num_records_f64 = slice[slice.len() - 1];
+        if !num_records_f64.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "number of records",
+                    value: num_records_f64,
+                    reason: "must be a finite value",
+                },
+            });
+        }
+        let num_records = num_records_f64 as usize;
+
+        let state_data_end_idx = 6_usize.saturating_mul(num_records);
+        let state_data =
+            slice
+                .get(0..state_data_end_idx)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: 0,
+                    end: state_data_end_idx,
+                    size: slice.len(),
+                })?;
+        let epoch_data_end_idx = state_data_end_idx.saturating_add(num_records);
+        let epoch_data = slice.get(state_data_end_idx..epoch_data_end_idx).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: state_data_end_idx,
+                end: epoch_data_end_idx,
+                size: slice.len(),
+            },
+        )?;
+        // And the epoch directory is whatever remains minus the metadata
+        let epoch_registry = slice.get(epoch_data_end_idx..slice.len() - 2).ok_or(
+            DecodingError::InaccessibleBytes {
+                start: epoch_data_end_idx,
+                end: slice.len() - 2,
+                size: slice.len(),
+            },
+        )?;
+
+        Ok(Self {
+            gm_km3_s2,
+            num_records,
+            state_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        Ok(Self::RecordKind::from_slice_f64(nth_record_data(
+            self.state_data,
+            n,
+            6,
+        )?))
+    }
+
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError> {
+        // The states are propagated to any epoch, so only the summary bounds the coverage.
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "coverage without a summary",
+        })
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if self.num_records == 0
+            || epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+                id: summary.id(),
+                center_id: summary.center_id(),
+            });
+        }
+
+        let epoch_et_s = epoch.to_et_seconds();
+        // Outside of the state epochs, or exactly on one of them, only one state is propagated (like SPICE's `spkr05`).
+        let (idx1, idx2) = match search_epoch(self.epoch_data, self.epoch_registry, epoch_et_s) {
+            Ok(idx) => (idx, idx),
+            Err(0) => (0, 0),
+            Err(idx) if idx == self.num_records => (idx - 1, idx - 1),
+            Err(idx) => (idx - 1, idx),
+        };
+
+        let (pos1_km, vel1_km_s) = self.propagate_nth(idx1, epoch_et_s)?;
+        if idx1 == idx2 {
+            return Ok((pos1_km, vel1_km_s));
+        }
+
+        // Otherwise, the two propagated states are weighted with a cosine (like SPICE's `spke05`), and the derivative
+        // of that weighting is accounted for in the velocity.
+        let (pos2_km, vel2_km_s) = self.propagate_nth(idx2, epoch_et_s)?;
+        let (t1_et_s, t2_et_s) = (self.epoch_data[idx1], self.epoch_data[idx2]);
+        if t2_et_s <= t1_et_s {
+            return Err(InterpolationError::CorruptedData {
+                what: "two-body state epochs are not strictly increasing",
+            });
+        }
+
+        let arg = (epoch_et_s - t1_et_s) * PI / (t2_et_s - t1_et_s);
+        let weight = 0.5 + 0.5 * arg.cos();
+        let dweight_dt = -0.5 * PI / (t2_et_s - t1_et_s) * arg.sin();
+
+        Ok((
+            weight * pos1_km + (1.0 - weight) * pos2_km,
+            weight * vel1_km_s + (1.0 - weight) * vel2_km_s + dweight_dt * (pos1_km - pos2_km),
+        ))
+    }
+
+    fn check_integrity<S: NAIFSummaryRecord>(&self, _summary: &S) -> Result<(), IntegrityError> {
+        // The states are propagated before the first and after the last epochs, so any summary is covered.
+        if !(self.gm_km3_s2.is_finite() && self.gm_km3_s2 > 0.0) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "gravitational parameter (GM)",
+                value: self.gm_km3_s2,
+                reason: "must be a finite and strictly positive value",
+            });
+        }
+
+        // Verify that none of the data is invalid once when we load it.
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch data",
+            self.epoch_data,
+            self.state_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the epoch registry data",
+            self.epoch_registry,
+            self.state_data.len() + self.epoch_data.len(),
+        )?;
+
+        check_finite(
+            Self::DATASET_NAME,
+            "one of the state data",
+            self.state_data,
+            0,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod twobody_ut {
+    use core::f64::consts::PI;
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        math::Vector3,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::TwoBodySetType5;
+
+    const GM_EARTH: f64 = 398_600.435_436;
+    const RADIUS_KM: f64 = 7000.0;
+
+    /// Exact state on a circular equatorial orbit.
+    fn circular(epoch_et_s: f64) -> (Vector3, Vector3) {
+        let v_km_s = (GM_EARTH / RADIUS_KM).sqrt();
+        let angle = v_km_s / RADIUS_KM * epoch_et_s;
+        (
+            RADIUS_KM * Vector3::new(angle.cos(), angle.sin(), 0.0),
+            v_km_s * Vector3::new(-angle.sin(), angle.cos(), 0.0),
+        )
+    }
+
+    fn type5_data(epochs: &[f64]) -> Vec<f64> {
+        let mut data = Vec::new();
+        for epoch in epochs {
+            let (pos, vel) = circular(*epoch);
+            data.extend(pos.iter());
+            data.extend(vel.iter());
+        }
+        data.extend(epochs);
+        data.extend([GM_EARTH, epochs.len() as f64]);
+        data
+    }
+
+    #[test]
+    fn type5_evaluate() {
+        let epochs = [0.0, 600.0, 1500.0, 2000.0, 3600.0];
+        let data = type5_data(&epochs);
+        let dataset = TwoBodySetType5::from_f64_slice(&data).unwrap();
+        dataset
+            .check_integrity(&SPKSummaryRecord::default())
+            .unwrap();
+        assert_eq!(dataset.num_records, 5);
+        assert_eq!(dataset.gm_km3_s2, GM_EARTH);
+        assert!(dataset.epoch_registry.is_empty());
+
+        // The coverage extends past the states, which are then propagated to the requested epoch.
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: -100.0,
+            end_epoch_et_s: 4000.0,
+            ..Default::default()
+        };
+
+        for epoch_et_s in [-100.0, 0.0, 123.4, 600.0, 1000.0, 1999.9, 3000.0, 4000.0] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(epoch_et_s), &summary)
+                .unwrap();
+            let (exp_pos, exp_vel) = circular(epoch_et_s);
+            assert!((pos - exp_pos).norm() < 1e-7, "pos error at {epoch_et_s}");
+            assert!((vel - exp_vel).norm() < 1e-10, "vel error at {epoch_et_s}");
+        }
+
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(4000.1), &summary)
+            .is_err());
+    }
+
+    #[test]
+    fn type5_in_spk() {
+        use crate::{
+            almanac::Almanac,
+            math::propagation::propagate_two_body,
+            naif::{spk::segment::spk_segment_ut::build_spk, SPK},
+            prelude::Frame,
+        };
+
+        // Eccentric orbit sampled irregularly, queried through the almanac like any other segment.
+        let pos0_km = Vector3::new(8000.0, 500.0, -300.0);
+        let vel0_km_s = Vector3::new(0.5, 8.0, 1.5);
+        let epochs = [0.0, 900.0, 1500.0, 3300.0, 4000.0];
+        let mut data = Vec::new();
+        for epoch in epochs {
+            let (pos, vel) = propagate_two_body(&pos0_km, &vel0_km_s, epoch, GM_EARTH).unwrap();
+            data.extend(pos.iter().chain(vel.iter()));
+        }
+        data.extend(epochs);
+        data.extend([GM_EARTH, epochs.len() as f64]);
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 4000.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 5,
+            start_idx: 385,
+            end_idx: 385 + data.len() as i32 - 1,
+        };
+        let spk = SPK::parse(build_spk(false, &[summary], &data)).unwrap();
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        for epoch_et_s in [1.0, 450.0, 1234.5, 2700.0, 3999.0] {
+            let epoch = Epoch::from_et_seconds(epoch_et_s);
+            let state = almanac
+                .translate_to_parent(Frame::from_ephem_j2000(-10), epoch)
+                .unwrap();
+            let (exp_pos, exp_vel) =
+                propagate_two_body(&pos0_km, &vel0_km_s, epoch.to_et_seconds(), GM_EARTH).unwrap();
+            assert!(
+                (state.radius_km - exp_pos).norm() < 1e-7,
+                "pos error at {epoch_et_s}"
+            );
+            assert!(
+                (state.velocity_km_s - exp_vel).norm() < 1e-10,
+                "vel error at {epoch_et_s}"
+            );
+        }
+    }
+
+    #[test]
+    fn type5_weighting() {
+        // Offset the second state so that the two propagated states differ, and check the cosine weighting.
+        let mut data = type5_data(&[0.0, 1000.0]);
+        data[6] += 1.0;
+        let dataset = TwoBodySetType5::from_f64_slice(&data).unwrap();
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 1000.0,
+            ..Default::default()
+        };
+
+        let (pos1, vel1) = dataset.propagate_nth(0, 500.0).unwrap();
+        let (pos2, vel2) = dataset.propagate_nth(1, 500.0).unwrap();
+        assert!((pos1 - pos2).norm() > 0.5);
+
+        let (pos, vel) = dataset
+            .evaluate(Epoch::from_et_seconds(500.0), &summary)
+            .unwrap();
+        assert!((pos - 0.5 * (pos1 + pos2)).norm() < 1e-9);
+        let exp_vel = 0.5 * (vel1 + vel2) - 0.5 * PI / 1000.0 * (pos1 - pos2);
+        assert!((vel - exp_vel).norm() < 1e-12);
+
+        // Each state is used as-is at its own epoch.
+        for (idx, epoch_et_s) in [(0, 0.0), (1, 1000.0)] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(epoch_et_s), &summary)
+                .unwrap();
+            let (exp_pos, exp_vel) = dataset.nth_record(idx).unwrap().to_pos_vel();
+            assert!((pos - exp_pos).norm() < 1e-9);
+            assert!((vel - exp_vel).norm() < 1e-12);
+        }
+
+        // The velocity is the derivative of the position
+        let h_s = 1e-2;
+        let (pos_m, _) = dataset
+            .evaluate(Epoch::from_et_seconds(321.0 - h_s), &summary)
+            .unwrap();
+        let (pos_p, _) = dataset
+            .evaluate(Epoch::from_et_seconds(321.0 + h_s), &summary)
+            .unwrap();
+        let (_, vel) = dataset
+            .evaluate(Epoch::from_et_seconds(321.0), &summary)
+            .unwrap();
+        assert!((vel - (pos_p - pos_m) / (2.0 * h_s)).norm() < 1e-6);
+    }
+
+    #[test]
+    fn invalid_data() {
+        match TwoBodySetType5::from_f64_slice(&[1.0]) {
+            Ok(_) => panic!("test failed on too few doubles"),
+            Err(e) => assert_eq!(
+                e,
+                DecodingError::TooFewDoubles {
+                    dataset: "Two-body Type 5",
+                    got: 1,
+                    need: 2,
+                }
+            ),
+        }
+
+        let mut data = type5_data(&[0.0, 1000.0]);
+        let gm_idx = data.len() - 2;
+        data[gm_idx] = -1.0;
+        let dataset = TwoBodySetType5::from_f64_slice(&data).unwrap();
+        assert_eq!(
+            dataset.check_integrity(&SPKSummaryRecord::default()),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Two-body Type 5",
+                variable: "gravitational parameter (GM)",
+                value: -1.0,
+                reason: "must be a finite and strictly positive value",
+            })
+        );
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 1000.0,
+            ..Default::default()
+        };
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(10.0), &summary)
+            .is_err());
+    }
+}
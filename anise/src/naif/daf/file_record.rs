@@ -27,7 +27,9 @@ pub enum FileRecordError {
     ParsingError {
         source: Utf8Error,
     },
-    #[snafu(display("endian flag is `{read}` but it should be either `BIG-IEEE` or `LTL-IEEE`"))]
+    #[snafu(display(
+        "declares the unsupported binary format `{read}` (only `BIG-IEEE` and `LTL-IEEE` are supported)"
+    ))]
     InvalidEndian {
         read: String,
     },
@@ -40,8 +42,13 @@ pub enum FileRecordError {
     NoIdentifier,
     #[snafu(display("is empty (ensure file is valid, e.g. do you need to run git-lfs)"))]
     EmptyRecord,
+    #[snafu(display("has a corrupted FTP validation string (was it transferred in ASCII mode?)"))]
+    CorruptedFTP,
 }
 
+/// FTP validation string of the file record, whose line terminators and high-bit characters are altered by text mode transfers.
+pub const FTP_STR: &[u8; 28] = b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP";
+
 #[derive(Debug, Clone, FromBytes, FromZeroes, AsBytes, PartialEq)]
 #[repr(C)]
 pub struct FileRecord {
@@ -116,18 +123,24 @@ impl FileRecord {
         }
     }
 
-    pub fn endianness(&self) -> Result<Endian, FileRecordError> {
+    /// Returns the endianness of the file as stated in its file record, regardless of the endianness of the machine.
+    pub fn file_endianness(&self) -> Result<Endian, FileRecordError> {
         let str_endianness = core::str::from_utf8(&self.endian_str).context(ParsingSnafu)?;
 
-        let file_endian = if str_endianness == "LTL-IEEE" {
-            Endian::Little
+        if str_endianness == "LTL-IEEE" {
+            Ok(Endian::Little)
         } else if str_endianness == "BIG-IEEE" {
-            Endian::Big
+            Ok(Endian::Big)
         } else {
-            return Err(FileRecordError::InvalidEndian {
+            Err(FileRecordError::InvalidEndian {
                 read: str_endianness.to_string(),
-            });
-        };
+            })
+        }
+    }
+
+    /// Returns the endianness of the file, or an error if it does not match the endianness of the machine.
+    pub fn endianness(&self) -> Result<Endian, FileRecordError> {
+        let file_endian = self.file_endianness()?;
         if file_endian != Endian::f64_native() || file_endian != Endian::u64_native() {
             Err(FileRecordError::WrongEndian)
         } else {
@@ -141,6 +154,15 @@ impl FileRecord {
             .trim())
     }
 
+    /// Checks the FTP validation string, which is null in files written before it was introduced in the DAF format.
+    pub fn check_ftp(&self) -> Result<(), FileRecordError> {
+        if &self.ftp_str == FTP_STR || self.ftp_str == [0; 28] {
+            Ok(())
+        } else {
+            Err(FileRecordError::CorruptedFTP)
+        }
+    }
+
     /// Returns whether this record was just null bytes
     pub fn is_empty(&self) -> bool {
         self == &Self::default()
@@ -25,6 +25,7 @@ pub mod mut_daf;
 pub use data_types::DataType as DafDataType;
 pub mod file_record;
 pub mod name_record;
+pub mod summaries;
 pub mod summary_record;
 // Defines the supported data types
 pub mod datatypes;
@@ -35,6 +36,7 @@ use crate::errors::DecodingError;
 use core::fmt::Debug;
 pub use file_record::FileRecord;
 pub use name_record::NameRecord;
+pub use summaries::DAFSummaries;
 pub use summary_record::SummaryRecord;
 
 use self::file_record::FileRecordError;
@@ -59,6 +61,10 @@ pub trait NAIFSummaryRecord: NAIFRecord + Copy {
     fn end_epoch_et_s(&self) -> f64;
     /// Returns whatever is the ID of this summary record.
     fn id(&self) -> i32;
+    /// Returns the ID relative to which the data of this summary record is expressed, i.e. its center or its inertial frame.
+    fn center_id(&self) -> i32;
+    /// Returns the ID of the orientation frame in which the data of this summary record is expressed.
+    fn orientation_id(&self) -> i32;
     fn is_empty(&self) -> bool {
         self.start_index() == self.end_index()
     }
@@ -93,14 +99,23 @@ pub trait NAIFDataSet<'a>: Sized + Display + PartialEq {
 
     fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError>;
 
+    /// Returns the first and last epochs at which the data of this data set can be evaluated, regardless of the epochs of its summary.
+    ///
+    /// # Errors
+    /// + [InterpolationError::UnsupportedOperation] if the data may be evaluated at any epoch (e.g. a propagated conic), such that only
+    ///   its summary bounds its coverage;
+    /// + [InterpolationError::CorruptedData] if the data has no records, or [InterpolationError::InterpDecoding] if its epochs are invalid.
+    fn coverage(&self) -> Result<(Epoch, Epoch), InterpolationError>;
+
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
         epoch: Epoch,
         summary: &S,
     ) -> Result<Self::StateKind, InterpolationError>;
 
-    /// Checks the integrity of this data set, returns an error if the data has issues.
-    fn check_integrity(&self) -> Result<(), IntegrityError>;
+    /// Checks the integrity of this data set, returns an error if the data has issues, including if the provided summary
+    /// covers epochs which the data does not.
+    fn check_integrity<S: NAIFSummaryRecord>(&self, summary: &S) -> Result<(), IntegrityError>;
 
     /// Returns a copy of Self where the data corresponds to the start and end times provided.
     /// If either is set to None, then that data will not be modified.
@@ -181,6 +196,10 @@ pub enum DAFError {
     EmptySummary { kind: &'static str, idx: usize },
     #[snafu(display("DAF/{kind}: no data record for `{name}`"))]
     NameError { kind: &'static str, name: String },
+    #[snafu(display(
+        "DAF/{kind}: summary record {rcrd} is linked more than once (corrupted file?)"
+    ))]
+    SummaryRecordCycle { kind: &'static str, rcrd: usize },
     #[snafu(display("DAF/{kind}: summary: {source}"))]
     DecodingSummary {
         kind: &'static str,
@@ -206,6 +225,13 @@ pub enum DAFError {
         #[snafu(backtrace)]
         source: DecodingError,
     },
+    #[snafu(display("DAF/{kind}: {dtype} segment: {source}"))]
+    DecodingSegment {
+        kind: &'static str,
+        dtype: DafDataType,
+        #[snafu(backtrace)]
+        source: DecodingError,
+    },
     DAFIntegrity {
         #[snafu(backtrace)]
         source: IntegrityError,
@@ -375,6 +401,18 @@ impl PartialEq for DAFError {
                     source: r_source,
                 },
             ) => l_kind == r_kind && l_idx == r_idx && l_source == r_source,
+            (
+                Self::DecodingSegment {
+                    kind: l_kind,
+                    dtype: l_dtype,
+                    source: l_source,
+                },
+                Self::DecodingSegment {
+                    kind: r_kind,
+                    dtype: r_dtype,
+                    source: r_source,
+                },
+            ) => l_kind == r_kind && l_dtype == r_dtype && l_source == r_source,
             (Self::DAFIntegrity { source: l_source }, Self::DAFIntegrity { source: r_source }) => {
                 l_source == r_source
             }
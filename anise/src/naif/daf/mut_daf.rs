@@ -28,12 +28,13 @@ use zerocopy::AsBytes;
 impl<R: NAIFSummaryRecord> MutDAF<R> {
     /// Parse the provided bytes as a SPICE Double Array File
     pub fn parse<B: Deref<Target = [u8]>>(bytes: B) -> Result<Self, DAFError> {
-        let crc32_checksum = crc32fast::hash(&bytes);
+        let crc32_checksum = Some(crc32fast::hash(&bytes));
         let mut buf = BytesMut::with_capacity(0);
         buf.extend(bytes.iter());
         let me = Self {
             bytes: buf,
             crc32_checksum,
+            endian_converted: false,
             _daf_type: PhantomData,
         };
         // Check that these calls will succeed.
@@ -0,0 +1,117 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use snafu::ResultExt;
+use zerocopy::{FromBytes, Ref};
+
+use super::{
+    DAFError, DecodingSummarySnafu, NAIFRecord, NAIFSummaryRecord, NameRecord, SummaryRecord,
+    RCRD_LEN,
+};
+use crate::{errors::DecodingError, DBL_SIZE};
+
+/// Iterator over the summaries of a DAF and their names, in file order, returned by `DAF::summaries`.
+///
+/// The summary records form a linked list: each one stores the index of the next summary record, and is immediately
+/// followed by the name record of its summaries. A malformed list, e.g. pointing outside of the file or back to a
+/// record already read, is returned as an error, after which the iterator ends.
+pub struct DAFSummaries<'a, R: NAIFSummaryRecord> {
+    bytes: &'a [u8],
+    summary_size: usize,
+    next_rcrd: usize,
+    visited: Vec<usize>,
+    current: Option<(&'a [R], &'a NameRecord)>,
+    idx: usize,
+    failed: bool,
+}
+
+impl<'a, R: NAIFSummaryRecord> DAFSummaries<'a, R> {
+    pub(crate) fn new(bytes: &'a [u8], first_rcrd: usize, summary_size: usize) -> Self {
+        Self {
+            bytes,
+            summary_size,
+            next_rcrd: first_rcrd,
+            visited: Vec::new(),
+            current: None,
+            idx: 0,
+            failed: false,
+        }
+    }
+
+    /// Loads the summaries and names of the provided summary record, DAF indexes start at 1.
+    fn load(&mut self, rcrd: usize) -> Result<(), DAFError> {
+        if self.visited.contains(&rcrd) {
+            return Err(DAFError::SummaryRecordCycle {
+                kind: R::NAME,
+                rcrd,
+            });
+        }
+        self.visited.push(rcrd);
+
+        // The summary record is followed by its name record.
+        let start = (rcrd - 1).saturating_mul(RCRD_LEN);
+        let end = start.saturating_add(2 * RCRD_LEN);
+        let rcrd_bytes = self
+            .bytes
+            .get(start..end)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start,
+                end,
+                size: self.bytes.len(),
+            })
+            .context(DecodingSummarySnafu { kind: R::NAME })?;
+
+        let control = SummaryRecord::read_from(&rcrd_bytes[..SummaryRecord::SIZE]).unwrap();
+        // Guard against summary counts which do not fit in the summary and name records.
+        let num_summaries = control
+            .num_summaries()
+            .min((RCRD_LEN - SummaryRecord::SIZE) / R::SIZE.max(1))
+            .min(RCRD_LEN / (self.summary_size * DBL_SIZE).max(1));
+        let summaries = Ref::<_, [R]>::new_slice(
+            &rcrd_bytes[SummaryRecord::SIZE..SummaryRecord::SIZE + num_summaries * R::SIZE],
+        )
+        .ok_or(DecodingError::Casting)
+        .context(DecodingSummarySnafu { kind: R::NAME })?
+        .into_slice();
+        let names = NameRecord::ref_from(&rcrd_bytes[RCRD_LEN..]).unwrap();
+
+        self.current = Some((summaries, names));
+        self.idx = 0;
+        self.next_rcrd = control.next_record();
+        Ok(())
+    }
+}
+
+impl<'a, R: NAIFSummaryRecord> Iterator for DAFSummaries<'a, R> {
+    type Item = Result<(&'a R, &'a str), DAFError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.failed {
+                return None;
+            }
+            if let Some((summaries, names)) = self.current {
+                if let Some(summary) = summaries.get(self.idx) {
+                    let name = names.nth_name(self.idx, self.summary_size);
+                    self.idx += 1;
+                    return Some(Ok((summary, name)));
+                }
+            }
+            // A next record of zero marks the end of the list.
+            if self.next_rcrd == 0 {
+                return None;
+            }
+            if let Err(e) = self.load(self.next_rcrd) {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
@@ -11,13 +11,17 @@
 pub mod daf;
 
 pub mod kpl;
+pub mod names;
 pub mod pck;
 pub mod spk;
 
 pub mod pretty_print;
 
 use self::{
-    daf::{daf::MutDAF, DAF},
+    daf::{
+        daf::{BorrowedDAF, MutDAF},
+        DAF,
+    },
     pck::BPCSummaryRecord,
     spk::summary::SPKSummaryRecord,
 };
@@ -26,10 +30,14 @@ use self::{
 pub type SPK = DAF<SPKSummaryRecord>;
 /// Spacecraft Planetary Kernel, mutable, for editing DAF/SPK files
 pub type MutSPK = MutDAF<SPKSummaryRecord>;
+/// Spacecraft Planetary Kernel borrowing the buffer of the caller, cf. `SPK::from_bytes`
+pub type BorrowedSPK<'a> = BorrowedDAF<'a, SPKSummaryRecord>;
 /// Binary Planetary Constant
 pub type BPC = DAF<BPCSummaryRecord>;
 /// Binary Planetary Constant, mutable, for editing DAF/PCK files
 pub type MutBPC = MutDAF<BPCSummaryRecord>;
+/// Binary Planetary Constant borrowing the buffer of the caller, cf. `BPC::from_bytes`
+pub type BorrowedBPC<'a> = BorrowedDAF<'a, BPCSummaryRecord>;
 
 #[macro_export]
 macro_rules! parse_bytes_as {
@@ -0,0 +1,148 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::{
+    constants::celestial_objects::{
+        celestial_name_from_id, JUPITER, MARS, NEPTUNE, PLUTO, SATURN, URANUS,
+    },
+    NaifId,
+};
+
+/// Returns the name of the common NAIF IDs: the celestial bodies known to ANISE, the planets, and some major spacecraft.
+pub const fn naif_name_from_id(id: NaifId) -> Option<&'static str> {
+    if let Some(name) = celestial_name_from_id(id) {
+        return Some(name);
+    }
+    match id {
+        MARS => Some("Mars"),
+        JUPITER => Some("Jupiter"),
+        SATURN => Some("Saturn"),
+        URANUS => Some("Uranus"),
+        NEPTUNE => Some("Neptune"),
+        PLUTO => Some("Pluto"),
+        -31 => Some("Voyager 1"),
+        -32 => Some("Voyager 2"),
+        -48 => Some("Hubble Space Telescope"),
+        -61 => Some("Juno"),
+        -64 => Some("OSIRIS-REx"),
+        -74 => Some("Mars Reconnaissance Orbiter"),
+        -77 => Some("Galileo Orbiter"),
+        -82 => Some("Cassini"),
+        -85 => Some("Lunar Reconnaissance Orbiter"),
+        -96 => Some("Parker Solar Probe"),
+        -98 => Some("New Horizons"),
+        -144 => Some("Solar Orbiter"),
+        -159 => Some("Europa Clipper"),
+        -170 => Some("James Webb Space Telescope"),
+        -202 => Some("MAVEN"),
+        -226 => Some("Rosetta"),
+        _ => None,
+    }
+}
+
+/// Table of the human readable names of NAIF IDs, used to display SPK summaries (cf. `SPKSummaryRecord::display_with`).
+///
+/// Custom names, e.g. of the spacecraft of a mission, are registered with `with_name` and take precedence over the common
+/// names of [naif_name_from_id].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NaifNames {
+    custom: HashMap<NaifId, String>,
+}
+
+impl NaifNames {
+    /// Registers the name of the provided ID, replacing any previous name of this ID.
+    pub fn with_name(mut self, id: NaifId, name: impl Into<String>) -> Self {
+        self.custom.insert(id, name.into());
+        self
+    }
+
+    /// Returns the name of the provided ID, if it is registered or is a common NAIF ID.
+    pub fn name(&self, id: NaifId) -> Option<&str> {
+        match self.custom.get(&id) {
+            Some(name) => Some(name.as_str()),
+            None => naif_name_from_id(id),
+        }
+    }
+
+    /// Returns a displayable name of this ID, e.g. `Earth (399)`, or `body -1234` for unknown IDs.
+    pub fn describe(&self, id: NaifId) -> NaifIdName<'_> {
+        NaifIdName {
+            id,
+            name: self.name(id),
+        }
+    }
+}
+
+/// Name of a NAIF ID followed by the ID itself, returned by [NaifNames::describe].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NaifIdName<'a> {
+    pub id: NaifId,
+    pub name: Option<&'a str>,
+}
+
+impl fmt::Display for NaifIdName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{name} ({})", self.id),
+            None => write!(f, "body {}", self.id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod names_ut {
+    use super::{naif_name_from_id, NaifNames};
+
+    #[test]
+    fn common_and_custom_names() {
+        assert_eq!(naif_name_from_id(399), Some("Earth"));
+        assert_eq!(naif_name_from_id(499), Some("Mars"));
+        assert_eq!(naif_name_from_id(-82), Some("Cassini"));
+        assert_eq!(naif_name_from_id(-1234), None);
+
+        let names = NaifNames::default()
+            .with_name(-1234, "My Cubesat")
+            .with_name(-82, "Cassini Orbiter");
+        assert_eq!(names.name(-1234), Some("My Cubesat"));
+        assert_eq!(names.name(-82), Some("Cassini Orbiter"));
+        assert_eq!(names.name(10), Some("Sun"));
+        assert_eq!(names.name(-5678), None);
+
+        assert_eq!(format!("{}", names.describe(-1234)), "My Cubesat (-1234)");
+        assert_eq!(
+            format!("{}", names.describe(0)),
+            "Solar System Barycenter (0)"
+        );
+        assert_eq!(format!("{}", names.describe(-5678)), "body -5678");
+    }
+
+    #[test]
+    fn summary_display() {
+        use crate::naif::spk::summary::SPKSummaryRecord;
+
+        let summary = SPKSummaryRecord {
+            target_id: -1234,
+            center_id: 301,
+            frame_id: 99,
+            data_type_i: 4,
+            ..Default::default()
+        };
+        assert!(format!("{summary}").starts_with(
+            "SPK Summary for body -1234 w.r.t. Moon (301) in orientation 99 as unknown data type 4 from"
+        ));
+
+        let names = NaifNames::default().with_name(-1234, "My Cubesat");
+        assert!(format!("{}", summary.display_with(&names))
+            .starts_with("SPK Summary for My Cubesat (-1234) w.r.t. Moon (301)"));
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::HashMap;
+
+use snafu::prelude::*;
+
+use super::text::TextPCK;
+use crate::constants::celestial_objects::*;
+use crate::NaifId;
+
+/// Gravitational parameters (km^3/s^2) used when neither an override nor a loaded kernel provides one, from `gm_de431.tpc`.
+const DEFAULT_GM_KM3_S2: [(NaifId, f64); 20] = [
+    (MERCURY, 2.203178000000002E+04),
+    (VENUS, 3.2485859200000006E+05),
+    (EARTH_MOON_BARYCENTER, 4.035032355022598E+05),
+    (MARS_BARYCENTER, 4.282837521400002E+04),
+    (JUPITER_BARYCENTER, 1.267127648000002E+08),
+    (SATURN_BARYCENTER, 3.79405852E+07),
+    (URANUS_BARYCENTER, 5.794548600000008E+06),
+    (NEPTUNE_BARYCENTER, 6.836527100580024E+06),
+    (PLUTO_BARYCENTER, 9.770000000000007E+02),
+    (SUN, 1.3271244004193938E+11),
+    (199, 2.203178000000002E+04),
+    (299, 3.2485859200000006E+05),
+    (EARTH, 3.98600435436096E+05),
+    (MOON, 4.902800066163796E+03),
+    (MARS, 4.282837362069909E+04),
+    (JUPITER, 1.266865349218008E+08),
+    (SATURN, 3.793120749865224E+07),
+    (URANUS, 5.793951322279009E+06),
+    (NEPTUNE, 6.835099502439672E+06),
+    (PLUTO, 8.696138177608748E+02),
+];
+
+#[derive(Debug, PartialEq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum GMError {
+    #[snafu(display("no gravitational parameter is known for body {id}"))]
+    UnknownGM { id: NaifId },
+    #[snafu(display("gravitational parameter of body {id} must be finite and positive but got {gm_km3_s2} km^3/s^2"))]
+    InvalidGM { id: NaifId, gm_km3_s2: f64 },
+}
+
+/// Registry of the gravitational parameters (GM) of bodies, keyed by their NAIF ID, in km^3/s^2.
+///
+/// The GM of a body is searched, in order, in the programmatic overrides (cf. [GMRegistry::set_gm]), in the
+/// `BODYnnn_GM` constants of the loaded text PCKs (cf. [GMRegistry::load_text_pck]), and finally in the built-in
+/// defaults of the DE431 planetary constants.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GMRegistry {
+    overrides: HashMap<NaifId, f64>,
+    loaded: HashMap<NaifId, f64>,
+}
+
+impl GMRegistry {
+    /// Builds a registry from the `BODYnnn_GM` constants of the provided text PCK.
+    pub fn from_text_pck(pck: &TextPCK) -> Result<Self, GMError> {
+        let mut me = Self::default();
+        me.load_text_pck(pck)?;
+        Ok(me)
+    }
+
+    /// Loads all of the `BODYnnn_GM` constants of the provided text PCK, replacing the GM of previously loaded kernels.
+    ///
+    /// # Errors
+    /// + One of the GM constants is not a single finite and positive value.
+    pub fn load_text_pck(&mut self, pck: &TextPCK) -> Result<(), GMError> {
+        let mut loaded = Vec::new();
+        for name in pck.constants.keys() {
+            let id = match name
+                .strip_prefix("BODY")
+                .and_then(|name| name.strip_suffix("_GM"))
+                .and_then(|id| id.parse::<NaifId>().ok())
+            {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let gm_km3_s2 = match pck.get_vec(name).as_deref() {
+                Some([gm_km3_s2]) => *gm_km3_s2,
+                _ => f64::NAN,
+            };
+            ensure!(
+                gm_km3_s2.is_finite() && gm_km3_s2 > 0.0,
+                InvalidGMSnafu { id, gm_km3_s2 }
+            );
+            loaded.push((id, gm_km3_s2));
+        }
+
+        self.loaded.extend(loaded);
+        Ok(())
+    }
+
+    /// Overrides the GM of the provided body, which takes precedence over any loaded or default value.
+    pub fn set_gm(&mut self, id: NaifId, gm_km3_s2: f64) -> Result<(), GMError> {
+        ensure!(
+            gm_km3_s2.is_finite() && gm_km3_s2 > 0.0,
+            InvalidGMSnafu { id, gm_km3_s2 }
+        );
+        self.overrides.insert(id, gm_km3_s2);
+        Ok(())
+    }
+
+    /// Removes the override of the GM of the provided body, returning it if it was set.
+    pub fn clear_gm(&mut self, id: NaifId) -> Option<f64> {
+        self.overrides.remove(&id)
+    }
+
+    /// Returns the GM of the provided body in km^3/s^2.
+    ///
+    /// # Errors
+    /// + No override, loaded kernel, or built-in default provides the GM of this body.
+    pub fn gm_km3_s2(&self, id: NaifId) -> Result<f64, GMError> {
+        self.overrides
+            .get(&id)
+            .or_else(|| self.loaded.get(&id))
+            .copied()
+            .or_else(|| {
+                DEFAULT_GM_KM3_S2
+                    .iter()
+                    .find(|(default_id, _)| *default_id == id)
+                    .map(|(_, gm_km3_s2)| *gm_km3_s2)
+            })
+            .context(UnknownGMSnafu { id })
+    }
+}
+
+#[cfg(test)]
+mod gm_ut {
+    use super::{GMError, GMRegistry};
+    use crate::constants::celestial_objects::{EARTH, MOON, SUN};
+    use crate::naif::pck::text::TextPCK;
+
+    #[test]
+    fn defaults_and_overrides() {
+        let mut registry = GMRegistry::default();
+        assert_eq!(registry.gm_km3_s2(EARTH), Ok(3.98600435436096E+05));
+        assert_eq!(registry.gm_km3_s2(SUN), Ok(1.3271244004193938E+11));
+        assert_eq!(registry.gm_km3_s2(-10), Err(GMError::UnknownGM { id: -10 }));
+
+        registry.set_gm(EARTH, 398_600.441_5).unwrap();
+        registry.set_gm(-10, 1e-9).unwrap();
+        assert_eq!(registry.gm_km3_s2(EARTH), Ok(398_600.441_5));
+        assert_eq!(registry.gm_km3_s2(-10), Ok(1e-9));
+
+        assert_eq!(
+            registry.set_gm(MOON, 0.0),
+            Err(GMError::InvalidGM {
+                id: MOON,
+                gm_km3_s2: 0.0
+            })
+        );
+        assert!(registry.set_gm(MOON, f64::NAN).is_err());
+        assert_eq!(registry.gm_km3_s2(MOON), Ok(4.902800066163796E+03));
+
+        assert_eq!(registry.clear_gm(EARTH), Some(398_600.441_5));
+        assert_eq!(registry.gm_km3_s2(EARTH), Ok(3.98600435436096E+05));
+    }
+
+    #[test]
+    fn from_text_pck() {
+        let pck = TextPCK::load("../data/gm_de431.tpc").unwrap();
+        let mut registry = GMRegistry::from_text_pck(&pck).unwrap();
+        // Phobos is not in the defaults
+        assert_eq!(registry.gm_km3_s2(401), Ok(7.087546066894452E-04));
+        assert_eq!(registry.gm_km3_s2(EARTH), Ok(3.98600435436096E+05));
+
+        let pck = TextPCK::parse(
+            "\\begindata\nBODY399_GM = ( 398600.0 )\nBODY399_RADII = ( 6378.0 6378.0 6357.0 )\n\\begintext\n",
+        )
+        .unwrap();
+        registry.load_text_pck(&pck).unwrap();
+        assert_eq!(registry.gm_km3_s2(EARTH), Ok(398600.0));
+        assert_eq!(registry.gm_km3_s2(401), Ok(7.087546066894452E-04));
+
+        // Overrides take precedence over the loaded kernels
+        registry.set_gm(EARTH, 398_600.441_5).unwrap();
+        assert_eq!(registry.gm_km3_s2(EARTH), Ok(398_600.441_5));
+
+        let pck = TextPCK::parse("\\begindata\nBODY10_GM = ( 1.0 2.0 )\n\\begintext\n").unwrap();
+        assert!(matches!(
+            GMRegistry::from_text_pck(&pck),
+            Err(GMError::InvalidGM { id: 10, .. })
+        ));
+    }
+}
@@ -20,6 +20,12 @@ use pyo3::prelude::*;
 
 use super::daf::DafDataType;
 
+// Parses the constants of text kernels, like text PCKs
+pub mod text;
+
+// Registry of the gravitational parameters of bodies, sourced from text PCKs
+pub mod gm;
+
 #[cfg_attr(feature = "python", pyclass)]
 #[cfg_attr(feature = "python", pyo3(module = "anise.internals"))]
 #[derive(Clone, Copy, Debug, Default, AsBytes, FromZeroes, FromBytes, PartialEq)]
@@ -86,6 +92,14 @@ impl NAIFSummaryRecord for BPCSummaryRecord {
         self.frame_id
     }
 
+    fn center_id(&self) -> i32 {
+        self.inertial_frame_id
+    }
+
+    fn orientation_id(&self) -> i32 {
+        self.inertial_frame_id
+    }
+
     fn start_epoch_et_s(&self) -> f64 {
         self.start_epoch_et_s
     }
@@ -0,0 +1,403 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use snafu::prelude::*;
+
+use crate::errors::InputOutputError;
+use crate::naif::kpl::KPLValue;
+
+#[derive(Debug, PartialEq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum TextPCKError {
+    #[snafu(display("could not read text kernel: {source}"))]
+    TextPCKIO { source: InputOutputError },
+    #[snafu(display("syntax error on line {line} of text kernel: {reason}"))]
+    Syntax { line: usize, reason: &'static str },
+}
+
+/// Constants of a NAIF text kernel (e.g. a text PCK), keyed by their variable name.
+///
+/// Only the data blocks (from `\begindata` to `\begintext`) are parsed, following the assignment syntax of the NAIF
+/// kernel required reading: scalar or parenthesized vector values, which may span several lines, assigned with `=` or
+/// appended with `+=`. Numbers are stored as [KPLValue::Float] (including Fortran `D` exponents), quoted strings as
+/// [KPLValue::String], and dates as [KPLValue::String] which keep their leading `@`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextPCK {
+    pub constants: HashMap<String, Vec<KPLValue>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Assign,
+    Append,
+    Open,
+    Close,
+}
+
+impl TextPCK {
+    /// Loads and parses the text kernel at the provided path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TextPCKError> {
+        let text = fs::read_to_string(path).map_err(|e| TextPCKError::TextPCKIO {
+            source: InputOutputError::IOError { kind: e.kind() },
+        })?;
+        Self::parse(&text)
+    }
+
+    /// Parses the provided content of a text kernel.
+    pub fn parse(text: &str) -> Result<Self, TextPCKError> {
+        let mut tokens = Vec::new();
+        let mut in_data = false;
+        for (line_no, line) in text.lines().enumerate() {
+            // The block markers must be on a line by themselves.
+            let tline = line.trim();
+            if tline == "\\begindata" {
+                in_data = true;
+            } else if tline == "\\begintext" {
+                in_data = false;
+            } else if in_data {
+                tokenize(line, line_no + 1, &mut tokens)?;
+            }
+        }
+
+        let mut constants: HashMap<String, Vec<KPLValue>> = HashMap::new();
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some((line, token)) = tokens.next() {
+            let Token::Word(name) = token else {
+                return Err(TextPCKError::Syntax {
+                    line,
+                    reason: "expected a variable name",
+                });
+            };
+
+            let append = match tokens.next() {
+                Some((_, Token::Assign)) => false,
+                Some((_, Token::Append)) => true,
+                _ => {
+                    return Err(TextPCKError::Syntax {
+                        line,
+                        reason: "expected `=` or `+=` after the variable name",
+                    })
+                }
+            };
+
+            let mut values = Vec::new();
+            match tokens.next() {
+                Some((_, Token::Open)) => loop {
+                    match tokens.next() {
+                        Some((_, Token::Close)) => break,
+                        Some((line, token)) => values.push(to_value(token, line)?),
+                        None => {
+                            return Err(TextPCKError::Syntax {
+                                line,
+                                reason: "missing closing parenthesis",
+                            })
+                        }
+                    }
+                },
+                Some((line, token)) => values.push(to_value(token, line)?),
+                None => {
+                    return Err(TextPCKError::Syntax {
+                        line,
+                        reason: "missing value after the assignment",
+                    })
+                }
+            }
+
+            let entry = constants.entry(name).or_default();
+            if !append {
+                entry.clear();
+            }
+            entry.extend(values);
+
+            // A variable holds either numbers or strings, but never both.
+            let numeric = entry
+                .iter()
+                .filter(|value| matches!(value, KPLValue::Float(_)))
+                .count();
+            if numeric != 0 && numeric != entry.len() {
+                return Err(TextPCKError::Syntax {
+                    line,
+                    reason: "variable mixes numeric and string values",
+                });
+            }
+        }
+
+        Ok(Self { constants })
+    }
+
+    /// Returns all of the values of the provided variable, if defined.
+    pub fn get(&self, name: &str) -> Option<&[KPLValue]> {
+        self.constants.get(name).map(|values| values.as_slice())
+    }
+
+    /// Returns the values of the provided variable as a vector of doubles, if it is defined and numeric.
+    pub fn get_vec(&self, name: &str) -> Option<Vec<f64>> {
+        self.get(name)?
+            .iter()
+            .map(|value| f64::try_from(value).ok())
+            .collect()
+    }
+
+    /// Returns the value of the provided variable if it is a single number.
+    pub fn get_f64(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            [KPLValue::Float(value)] => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a line of a data block into tokens, skipping the commas which may separate values.
+fn tokenize(
+    line: &str,
+    line_no: usize,
+    tokens: &mut Vec<(usize, Token)>,
+) -> Result<(), TextPCKError> {
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == ',' => {}
+            '=' => tokens.push((line_no, Token::Assign)),
+            '+' if chars.peek() == Some(&'=') => {
+                chars.next();
+                tokens.push((line_no, Token::Append));
+            }
+            '(' => tokens.push((line_no, Token::Open)),
+            ')' => tokens.push((line_no, Token::Close)),
+            '\'' => {
+                // Strings are delimited by single quotes, and two consecutive quotes encode one quote.
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            string.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => string.push(c),
+                        None => {
+                            return Err(TextPCKError::Syntax {
+                                line: line_no,
+                                reason: "unterminated string",
+                            })
+                        }
+                    }
+                }
+                tokens.push((line_no, Token::Quoted(string)));
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace()
+                        || "=(),'".contains(next)
+                        || (next == '+' && word_ends_before_append(&chars))
+                    {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push((line_no, Token::Word(word)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the upcoming `+` starts a `+=` append operator.
+fn word_ends_before_append(chars: &core::iter::Peekable<core::str::Chars<'_>>) -> bool {
+    let mut ahead = chars.clone();
+    ahead.next();
+    ahead.next() == Some('=')
+}
+
+fn to_value(token: Token, line: usize) -> Result<KPLValue, TextPCKError> {
+    match token {
+        Token::Quoted(string) => Ok(KPLValue::String(string)),
+        Token::Word(word) if word.starts_with('@') => Ok(KPLValue::String(word)),
+        Token::Word(word) => word
+            .replace(['D', 'd'], "E")
+            .parse::<f64>()
+            .map(KPLValue::Float)
+            .map_err(|_| TextPCKError::Syntax {
+                line,
+                reason: "invalid numeric value",
+            }),
+        _ => Err(TextPCKError::Syntax {
+            line,
+            reason: "unexpected token in the values",
+        }),
+    }
+}
+
+#[cfg(test)]
+mod text_pck_ut {
+    use super::{KPLValue, TextPCK, TextPCKError};
+
+    /// Excerpt of the Earth and Mars constants of `pck00010.tpc`
+    const PCK00010_EXCERPT: &str = r"KPL/PCK
+
+Earth
+
+        Old values:
+
+           Values are unchanged in the 2009 IAU report.
+
+        Current values:
+
+        \begindata
+
+        BODY399_POLE_RA        = (    0.      -0.641         0. )
+        BODY399_POLE_DEC       = (  +90.      -0.557         0. )
+        BODY399_PM             = (  190.147  +360.9856235     0. )
+        BODY399_LONG_AXIS      = (    0.                        )
+
+        \begintext
+
+Mars
+
+        \begindata
+
+        BODY499_POLE_RA          = (  317.68143   -0.1061      0.  )
+        BODY499_POLE_DEC         = (   52.88650   -0.0609      0.  )
+        BODY499_PM               = (  176.630    350.89198226  0.  )
+
+        BODY399_RADII     = ( 6378.1366   6378.1366   6356.7519 )
+        BODY499_RADII       = ( 3396.19   3396.19   3376.20 )
+
+        \begintext
+
+        BODY499_RADII = ( 1.0 2.0 3.0 )
+";
+
+    #[test]
+    fn parse_pck00010_excerpt() {
+        let pck = TextPCK::parse(PCK00010_EXCERPT).unwrap();
+        assert_eq!(pck.constants.len(), 9);
+
+        assert_eq!(
+            pck.get_vec("BODY399_RADII"),
+            Some(vec![6378.1366, 6378.1366, 6356.7519])
+        );
+        assert_eq!(
+            pck.get_vec("BODY399_PM"),
+            Some(vec![190.147, 360.9856235, 0.0])
+        );
+        assert_eq!(
+            pck.get_vec("BODY399_POLE_DEC"),
+            Some(vec![90.0, -0.557, 0.0])
+        );
+        assert_eq!(pck.get_f64("BODY399_LONG_AXIS"), Some(0.0));
+        // Assignments in comment blocks are ignored
+        assert_eq!(
+            pck.get_vec("BODY499_RADII"),
+            Some(vec![3396.19, 3396.19, 3376.20])
+        );
+        assert_eq!(pck.get_vec("BODY599_RADII"), None);
+        assert_eq!(pck.get_f64("BODY499_PM"), None);
+    }
+
+    #[test]
+    fn parse_pck00008() {
+        let pck = TextPCK::load("../data/pck00008.tpc").unwrap();
+
+        assert_eq!(
+            pck.get_vec("BODY399_RADII"),
+            Some(vec![6378.14, 6378.14, 6356.75])
+        );
+        // Multi-line vectors
+        let nut_prec_angles = pck.get_vec("BODY3_NUT_PREC_ANGLES").unwrap();
+        assert_eq!(nut_prec_angles.len(), 26);
+        assert_eq!(
+            nut_prec_angles[..4],
+            [125.045, -1935.5364525, 250.089, -3871.072905]
+        );
+        assert_eq!(nut_prec_angles[25], 473327.79642);
+
+        assert!(matches!(
+            TextPCK::load("../data/does-not-exist.tpc"),
+            Err(TextPCKError::TextPCKIO { .. })
+        ));
+    }
+
+    #[test]
+    fn assignments() {
+        let pck = TextPCK::parse(
+            r"\begindata
+            NAIF_BODY_NAME += ( 'SPACECRAFT',
+                                'O''BRIEN' )
+            NAIF_BODY_NAME += 'ROVER'
+            NAIF_BODY_CODE = ( -1000, -1001 )
+            NAIF_BODY_CODE+=-1002
+            BODY1000_GM = 1.5D-3
+            EPOCH = @2000-JAN-01/12:00
+            BODY1000_GM = 2.5d2
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            pck.get("NAIF_BODY_NAME").unwrap(),
+            [
+                KPLValue::String("SPACECRAFT".to_string()),
+                KPLValue::String("O'BRIEN".to_string()),
+                KPLValue::String("ROVER".to_string())
+            ]
+        );
+        assert_eq!(
+            pck.get_vec("NAIF_BODY_CODE"),
+            Some(vec![-1000.0, -1001.0, -1002.0])
+        );
+        // Plain assignments replace the previous values
+        assert_eq!(pck.get_f64("BODY1000_GM"), Some(250.0));
+        assert_eq!(
+            pck.get("EPOCH").unwrap(),
+            [KPLValue::String("@2000-JAN-01/12:00".to_string())]
+        );
+        assert_eq!(pck.get_vec("NAIF_BODY_NAME"), None);
+    }
+
+    #[test]
+    fn syntax_errors() {
+        for (text, line, reason) in [
+            ("\\begindata\nA = ( 1 2\n", 2, "missing closing parenthesis"),
+            (
+                "\\begindata\nA 1\n",
+                2,
+                "expected `=` or `+=` after the variable name",
+            ),
+            ("\\begindata\nA = 'unterminated\n", 2, "unterminated string"),
+            ("\\begindata\nA = ( 1 \n 2x )\n", 3, "invalid numeric value"),
+            (
+                "\\begindata\nA = 1\nA += 'B'",
+                3,
+                "variable mixes numeric and string values",
+            ),
+            ("\\begindata\n= 1\n", 2, "expected a variable name"),
+            (
+                "\\begindata\nA =\n",
+                2,
+                "missing value after the assignment",
+            ),
+        ] {
+            assert_eq!(
+                TextPCK::parse(text),
+                Err(TextPCKError::Syntax { line, reason }),
+                "{text}"
+            );
+        }
+    }
+}
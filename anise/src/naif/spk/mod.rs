@@ -10,3 +10,7 @@
 
 // Defines how to read an SPK
 pub mod summary;
+// Iterates over the segments of an SPK
+pub mod segment;
+// Dispatches the decoding and evaluation of SPK segments by data type
+pub mod segment_data;
@@ -0,0 +1,352 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use core::iter::Enumerate;
+use core::slice::Iter;
+use hifitime::{Duration, Epoch};
+
+use crate::{
+    ephemerides::EphemerisError,
+    naif::{
+        daf::{DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord},
+        SPK,
+    },
+};
+
+use super::{segment_data::SegmentData, summary::SPKSummaryRecord};
+
+/// A segment of an SPK, i.e. its summary and a reference to the SPK it is stored in.
+///
+/// The segment data is only decoded when calling `data`.
+#[derive(Copy, Clone, Debug)]
+pub struct SPKSegment<'a> {
+    /// Index of this segment in the SPK
+    pub idx: usize,
+    /// Summary of this segment
+    pub summary: &'a SPKSummaryRecord,
+    spk: &'a SPK,
+}
+
+impl<'a> SPKSegment<'a> {
+    /// Returns the NAIF ID of the target of this segment
+    pub fn target_id(&self) -> i32 {
+        self.summary.target_id
+    }
+
+    /// Returns the NAIF ID of the center of this segment
+    pub fn center_id(&self) -> i32 {
+        self.summary.center_id
+    }
+
+    /// Returns the NAIF ID of the frame of this segment
+    pub fn frame_id(&self) -> i32 {
+        self.summary.frame_id
+    }
+
+    /// Returns the data type of this segment
+    pub fn data_type(&self) -> Result<DafDataType, EphemerisError> {
+        self.summary.data_type()
+    }
+
+    /// Returns the first epoch covered by this segment
+    pub fn start_epoch(&self) -> Epoch {
+        self.summary.start_epoch()
+    }
+
+    /// Returns the last epoch covered by this segment
+    pub fn end_epoch(&self) -> Epoch {
+        self.summary.end_epoch()
+    }
+
+    /// Returns the duration covered by this segment
+    pub fn duration(&self) -> Duration {
+        self.end_epoch() - self.start_epoch()
+    }
+
+    /// Decodes the data of this segment as the provided data set.
+    pub fn data<S: NAIFDataSet<'a>>(&self) -> Result<S, DAFError> {
+        self.spk.nth_data(self.idx)
+    }
+
+    /// Decodes the data of this segment as the data type specified in its summary.
+    pub fn segment_data(&self) -> Result<SegmentData<'a>, DAFError> {
+        self.spk.nth_segment_data(self.idx)
+    }
+}
+
+impl<'a> fmt::Display for SPKSegment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The summary already displays the data type
+        write!(f, "#{}: {}", self.idx, self.summary)
+    }
+}
+
+/// Lazy iterator over the segments of an SPK, created with `SPK::segments`.
+pub struct SPKSegments<'a> {
+    spk: &'a SPK,
+    summaries: Enumerate<Iter<'a, SPKSummaryRecord>>,
+}
+
+impl<'a> Iterator for SPKSegments<'a> {
+    type Item = SPKSegment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.summaries.next().map(|(idx, summary)| SPKSegment {
+            idx,
+            summary,
+            spk: self.spk,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.summaries.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for SPKSegments<'a> {}
+
+impl SPK {
+    /// Returns an iterator over the segments of this SPK, in the order in which they are stored.
+    pub fn segments(&self) -> Result<SPKSegments<'_>, DAFError> {
+        let num_summaries = self.daf_summary()?.num_summaries();
+        let summaries = self.data_summaries()?;
+
+        Ok(SPKSegments {
+            spk: self,
+            summaries: summaries[..num_summaries.min(summaries.len())]
+                .iter()
+                .enumerate(),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod spk_segment_ut {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::naif::{
+        daf::{datatypes::HermiteSetType13, DafDataType},
+        spk::{segment_data::SegmentData, summary::SPKSummaryRecord},
+        SPK,
+    };
+
+    /// Builds a minimal DAF/SPK with one summary record, one name record, and the provided data right after.
+    pub(crate) fn build_spk(
+        big_endian: bool,
+        summaries: &[SPKSummaryRecord],
+        data: &[f64],
+    ) -> Vec<u8> {
+        let u32_bytes = |v: u32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let i32_bytes = |v: i32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let f64_bytes = |v: f64| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+
+        // File record: the summary record is the second record, and the name record the third.
+        let mut bytes = b"DAF/SPK ".to_vec();
+        bytes.extend(u32_bytes(2));
+        bytes.extend(u32_bytes(6));
+        bytes.extend([b' '; 60]);
+        bytes.extend(u32_bytes(2));
+        bytes.extend(u32_bytes(2));
+        bytes.extend(u32_bytes((3 * 128 + data.len() + 1) as u32));
+        bytes.extend(if big_endian { b"BIG-IEEE" } else { b"LTL-IEEE" });
+        bytes.resize(1024, 0);
+
+        // Summary record
+        bytes.extend(f64_bytes(0.0));
+        bytes.extend(f64_bytes(0.0));
+        bytes.extend(f64_bytes(summaries.len() as f64));
+        for summary in summaries {
+            bytes.extend(f64_bytes(summary.start_epoch_et_s));
+            bytes.extend(f64_bytes(summary.end_epoch_et_s));
+            for v in [
+                summary.target_id,
+                summary.center_id,
+                summary.frame_id,
+                summary.data_type_i,
+                summary.start_idx,
+                summary.end_idx,
+            ] {
+                bytes.extend(i32_bytes(v));
+            }
+        }
+        bytes.resize(2 * 1024, 0);
+
+        // Name record
+        bytes.resize(3 * 1024, b' ');
+
+        // Data
+        for v in data {
+            bytes.extend(f64_bytes(*v));
+        }
+        bytes.resize(bytes.len().div_ceil(1024) * 1024, 0);
+        bytes
+    }
+
+    fn summaries() -> [SPKSummaryRecord; 2] {
+        [
+            SPKSummaryRecord {
+                start_epoch_et_s: 0.0,
+                end_epoch_et_s: 86_400.0,
+                target_id: -10,
+                center_id: 399,
+                frame_id: 1,
+                data_type_i: 13,
+                start_idx: 385,
+                end_idx: 385 + 16 - 1,
+            },
+            SPKSummaryRecord {
+                start_epoch_et_s: 86_400.0,
+                end_epoch_et_s: 2.0 * 86_400.0,
+                target_id: 399,
+                center_id: 3,
+                frame_id: 1,
+                data_type_i: 2,
+                start_idx: 401,
+                end_idx: 401,
+            },
+        ]
+    }
+
+    #[test]
+    fn segments_little_endian() {
+        // Two Type 13 states, followed by their epochs, and the window size and number of records.
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([7000.0, 600.0, 0.0, 0.0, 7.5, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        let spk = SPK::parse(build_spk(false, &summaries(), &data)).unwrap();
+
+        let segments = spk.segments().unwrap();
+        assert_eq!(segments.len(), 2);
+
+        let segments = segments.collect::<Vec<_>>();
+        assert_eq!(segments[0].idx, 0);
+        assert_eq!(segments[0].target_id(), -10);
+        assert_eq!(segments[0].center_id(), 399);
+        assert_eq!(segments[0].frame_id(), 1);
+        assert_eq!(
+            segments[0].data_type(),
+            Ok(DafDataType::Type13HermiteUnequalStep)
+        );
+        assert_eq!(
+            segments[0].start_epoch(),
+            Epoch::from_et_seconds(0.0) + 1.nanoseconds()
+        );
+        assert_eq!(
+            segments[0].end_epoch(),
+            Epoch::from_et_seconds(86_400.0) - 1.nanoseconds()
+        );
+        assert_eq!(segments[0].duration(), 1.days() - 2.nanoseconds());
+
+        assert_eq!(segments[1].idx, 1);
+        assert_eq!(segments[1].target_id(), 399);
+        assert_eq!(
+            segments[1].data_type(),
+            Ok(DafDataType::Type2ChebyshevTriplet)
+        );
+        assert!(format!("{}", segments[1]).starts_with(
+            "#1: SPK Summary for Earth (399) w.r.t. Earth-Moon Barycenter (3) in J2000 (1) as Chebyshev Triplet from"
+        ));
+
+        // Data is only decoded on request
+        let hermite = segments[0].data::<HermiteSetType13>().unwrap();
+        assert_eq!(hermite.num_records, 2);
+        assert_eq!(hermite.samples, 2);
+        assert!(segments[0].segment_data().unwrap() == SegmentData::Type13(hermite));
+    }
+
+    #[test]
+    fn segments_big_endian() {
+        // Big endian files are converted to the native endianness when parsed.
+        let spk = SPK::parse(build_spk(true, &summaries(), &[0.0; 16])).unwrap();
+        let segments = spk.segments().unwrap().collect::<Vec<_>>();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].target_id(), -10);
+        assert_eq!(
+            segments[1].data_type(),
+            Ok(DafDataType::Type2ChebyshevTriplet)
+        );
+    }
+
+    /// Builds `data/tiny-type13.bsp`: a two-body low Earth orbit of the spacecraft -1000 about the Earth over ten minutes,
+    /// sampled every minute in a Type 13 segment with windows of four states.
+    fn tiny_type13_bsp() -> Vec<u8> {
+        use crate::{
+            constants::frames::EARTH_J2000,
+            math::{cartesian::CartesianState, propagation::propagate_two_body, Vector3},
+        };
+
+        let start_et_s = 757_339_200.0;
+        let pos0_km = Vector3::new(7000.0, 0.0, 0.0);
+        let vel0_km_s = Vector3::new(0.0, 7.5, 1.0);
+        let states = (0..=10).map(|n| {
+            let dt_s = 60.0 * n as f64;
+            let (pos_km, vel_km_s) =
+                propagate_two_body(&pos0_km, &vel0_km_s, dt_s, 398_600.435_436).unwrap();
+            CartesianState {
+                radius_km: pos_km,
+                velocity_km_s: vel_km_s,
+                epoch: Epoch::from_et_seconds(start_et_s + dt_s),
+                frame: EARTH_J2000,
+            }
+        });
+        let data = HermiteSetType13::builder()
+            .with_window_size(4)
+            .with_states(states)
+            .build()
+            .unwrap();
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: start_et_s,
+            end_epoch_et_s: start_et_s + 600.0,
+            target_id: -1000,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i: 13,
+            start_idx: 385,
+            end_idx: 385 + data.len() as i32 - 1,
+        };
+        build_spk(false, &[summary], &data)
+    }
+
+    #[test]
+    fn tiny_type13_kernel() {
+        // The kernel of the integration tests must be reproducible: regenerate it with `write_tiny_type13_kernel`.
+        let kernel = include_bytes!("../../../../data/tiny-type13.bsp");
+        assert!(
+            kernel[..] == tiny_type13_bsp()[..],
+            "tiny-type13.bsp is stale"
+        );
+    }
+
+    #[test]
+    #[ignore = "Overwrites data/tiny-type13.bsp"]
+    fn write_tiny_type13_kernel() {
+        std::fs::write("../data/tiny-type13.bsp", tiny_type13_bsp()).unwrap();
+    }
+}
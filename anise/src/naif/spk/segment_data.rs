@@ -0,0 +1,417 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::Epoch;
+use snafu::ResultExt;
+
+use crate::{
+    errors::IntegrityError,
+    math::{cartesian::CartesianState, interpolation::InterpolationError, Vector3},
+    naif::{
+        daf::{
+            datatypes::{
+                hermite::center_state, ChebyshevSetType14, ChebyshevSetType20, ESOCSetType18,
+                ESOCSetType19, EquinoctialSetType17, HermiteSetType12, HermiteSetType13,
+                LagrangeSetType8, LagrangeSetType9, MDASetType1, MDASetType21,
+                PrecessingConicSetType15, TLESetType10, TwoBodySetType5, Type2ChebyshevSet,
+                Type3ChebyshevSet,
+            },
+            DAFError, DafDataType, DecodingSegmentSnafu, NAIFDataSet, NAIFSummaryRecord,
+        },
+        SPK,
+    },
+};
+
+use super::summary::SPKSummaryRecord;
+
+/// The data types that may be stored in an SPK segment.
+pub type SPKDataType = DafDataType;
+
+/// The decoded data of an SPK segment, one variant per supported data type.
+///
+/// This allows evaluating any segment without knowing its data type at compile time.
+#[derive(PartialEq)]
+pub enum SegmentData<'a> {
+    Type1(MDASetType1<'a>),
+    Type2(Type2ChebyshevSet<'a>),
+    Type3(Type3ChebyshevSet<'a>),
+    Type5(TwoBodySetType5<'a>),
+    Type8(LagrangeSetType8<'a>),
+    Type9(LagrangeSetType9<'a>),
+    Type10(TLESetType10<'a>),
+    Type12(HermiteSetType12<'a>),
+    Type13(HermiteSetType13<'a>),
+    Type14(ChebyshevSetType14<'a>),
+    Type15(PrecessingConicSetType15<'a>),
+    Type17(EquinoctialSetType17<'a>),
+    Type18(ESOCSetType18<'a>),
+    Type19(ESOCSetType19<'a>),
+    Type20(ChebyshevSetType20<'a>),
+    Type21(MDASetType21<'a>),
+}
+
+/// Calls the same expression on the data set wrapped in any variant of `SegmentData`.
+macro_rules! delegate {
+    ($self:ident, $data:ident => $expr:expr) => {
+        match $self {
+            Self::Type1($data) => $expr,
+            Self::Type2($data) => $expr,
+            Self::Type3($data) => $expr,
+            Self::Type5($data) => $expr,
+            Self::Type8($data) => $expr,
+            Self::Type9($data) => $expr,
+            Self::Type10($data) => $expr,
+            Self::Type12($data) => $expr,
+            Self::Type13($data) => $expr,
+            Self::Type14($data) => $expr,
+            Self::Type15($data) => $expr,
+            Self::Type17($data) => $expr,
+            Self::Type18($data) => $expr,
+            Self::Type19($data) => $expr,
+            Self::Type20($data) => $expr,
+            Self::Type21($data) => $expr,
+        }
+    };
+}
+
+impl<'a> SegmentData<'a> {
+    /// Decodes the provided slice as the data type specified in the summary.
+    ///
+    /// # Errors
+    /// + [DAFError::Datatype] if the summary's data type is not a supported SPK data type;
+    /// + [DAFError::DecodingSegment] if the data cannot be decoded as that data type.
+    pub fn from_summary_and_slice(
+        summary: &SPKSummaryRecord,
+        slice: &'a [f64],
+    ) -> Result<Self, DAFError> {
+        let dtype = SPKDataType::try_from(summary.data_type_i)?;
+        let ctx = DecodingSegmentSnafu {
+            kind: SPKSummaryRecord::NAME,
+            dtype,
+        };
+
+        Ok(match dtype {
+            DafDataType::Type1ModifiedDifferenceArray => {
+                Self::Type1(MDASetType1::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type2ChebyshevTriplet => {
+                Self::Type2(Type2ChebyshevSet::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type3ChebyshevSextuplet => {
+                Self::Type3(Type3ChebyshevSet::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type5DiscreteStates => {
+                Self::Type5(TwoBodySetType5::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type8LagrangeEqualStep => {
+                Self::Type8(LagrangeSetType8::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type9LagrangeUnequalStep => {
+                Self::Type9(LagrangeSetType9::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type10SpaceCommandTLE => {
+                Self::Type10(TLESetType10::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type12HermiteEqualStep => {
+                Self::Type12(HermiteSetType12::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type13HermiteUnequalStep => {
+                Self::Type13(HermiteSetType13::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type14ChebyshevUnequalStep => {
+                Self::Type14(ChebyshevSetType14::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type15PrecessingConics => {
+                Self::Type15(PrecessingConicSetType15::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type17Equinoctial => {
+                Self::Type17(EquinoctialSetType17::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type18ESOCHermiteLagrange => {
+                Self::Type18(ESOCSetType18::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type19ESOCPiecewise => {
+                Self::Type19(ESOCSetType19::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type20ChebyshevDerivative => {
+                Self::Type20(ChebyshevSetType20::from_f64_slice(slice).context(ctx)?)
+            }
+            DafDataType::Type21ExtendedModifiedDifferenceArray => {
+                Self::Type21(MDASetType21::from_f64_slice(slice).context(ctx)?)
+            }
+        })
+    }
+
+    /// Returns the data type of this segment data.
+    pub fn data_type(&self) -> SPKDataType {
+        match self {
+            Self::Type1(_) => DafDataType::Type1ModifiedDifferenceArray,
+            Self::Type2(_) => DafDataType::Type2ChebyshevTriplet,
+            Self::Type3(_) => DafDataType::Type3ChebyshevSextuplet,
+            Self::Type5(_) => DafDataType::Type5DiscreteStates,
+            Self::Type8(_) => DafDataType::Type8LagrangeEqualStep,
+            Self::Type9(_) => DafDataType::Type9LagrangeUnequalStep,
+            Self::Type10(_) => DafDataType::Type10SpaceCommandTLE,
+            Self::Type12(_) => DafDataType::Type12HermiteEqualStep,
+            Self::Type13(_) => DafDataType::Type13HermiteUnequalStep,
+            Self::Type14(_) => DafDataType::Type14ChebyshevUnequalStep,
+            Self::Type15(_) => DafDataType::Type15PrecessingConics,
+            Self::Type17(_) => DafDataType::Type17Equinoctial,
+            Self::Type18(_) => DafDataType::Type18ESOCHermiteLagrange,
+            Self::Type19(_) => DafDataType::Type19ESOCPiecewise,
+            Self::Type20(_) => DafDataType::Type20ChebyshevDerivative,
+            Self::Type21(_) => DafDataType::Type21ExtendedModifiedDifferenceArray,
+        }
+    }
+
+    /// Evaluates the position (km) and velocity (km/s) at the provided epoch.
+    #[allow(clippy::useless_conversion)]
+    pub fn evaluate(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        // The Hermite data types return a CartesianState, and the other ones their position and velocity.
+        delegate!(self, data => data.evaluate(epoch, summary).map(Into::into))
+    }
+
+    /// Evaluates only the position (km) at the provided epoch.
+    ///
+    /// The Chebyshev (2 and 3) and Hermite (12 and 13) data types skip the computations only needed for the velocity;
+    /// the other data types compute both the position and velocity together, so this is equivalent to `evaluate`.
+    /// In all cases, the position is identical to that returned by `evaluate`.
+    pub fn evaluate_position(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+    ) -> Result<Vector3, InterpolationError> {
+        match self {
+            Self::Type2(data) => data.evaluate_position(epoch, summary),
+            Self::Type3(data) => data.evaluate_position(epoch, summary),
+            Self::Type12(data) => data.evaluate_position(epoch, summary),
+            Self::Type13(data) => data.evaluate_position(epoch, summary),
+            _ => Ok(self.evaluate(epoch, summary)?.0),
+        }
+    }
+
+    /// Evaluates only the velocity (km/s) at the provided epoch.
+    ///
+    /// The Chebyshev Type 3 data stores the velocity separately and skips the evaluation of the position; the other data types
+    /// need the position to compute the velocity, so this is equivalent to `evaluate`.
+    /// In all cases, the velocity is identical to that returned by `evaluate`.
+    pub fn evaluate_velocity(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+    ) -> Result<Vector3, InterpolationError> {
+        match self {
+            Self::Type3(data) => data.evaluate_velocity(epoch, summary),
+            _ => Ok(self.evaluate(epoch, summary)?.1),
+        }
+    }
+
+    /// Evaluates the position (km) and velocity (km/s) at the provided epoch, overriding the number of interpolation
+    /// samples of the Hermite data types (12 and 13) with `samples`; other data types are evaluated as with `evaluate`.
+    ///
+    /// Note that changing the window size changes the result compared to SPICE.
+    pub fn evaluate_with_samples(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        samples: usize,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        match self {
+            Self::Type12(data) => data.evaluate_with_samples(epoch, summary, samples),
+            Self::Type13(data) => data.evaluate_with_samples(epoch, summary, samples),
+            _ => self.evaluate(epoch, summary),
+        }
+    }
+
+    /// Evaluates this segment data at the provided epoch like `evaluate`, and returns the state in the center frame of the summary,
+    /// whose gravitational parameter is set to `mu_km3_s2`, if provided.
+    pub fn to_cartesian_state(
+        &self,
+        epoch: Epoch,
+        summary: &SPKSummaryRecord,
+        mu_km3_s2: Option<f64>,
+    ) -> Result<CartesianState, InterpolationError> {
+        let (pos_km, vel_km_s) = self.evaluate(epoch, summary)?;
+        Ok(center_state(pos_km, vel_km_s, epoch, summary, mu_km3_s2))
+    }
+
+    /// Returns the degree of the interpolation polynomial (or the integration order of the difference lines) used at the provided epoch.
+    ///
+    /// Windowed interpolations use fewer samples if there are not enough records. Data types which are not interpolated
+    /// (e.g. two-body propagations), or whose degree cannot be decoded at this epoch, return None.
+    pub fn degree_at(&self, epoch: Epoch, summary: &SPKSummaryRecord) -> Option<usize> {
+        match self {
+            Self::Type1(data) => data.integration_order(epoch, summary).ok(),
+            Self::Type2(data) => Some(data.degree()),
+            Self::Type3(data) => Some(data.degree()),
+            Self::Type8(data) => Some((data.degree + 1).min(data.num_records).saturating_sub(1)),
+            Self::Type9(data) => Some((data.degree + 1).min(data.num_records).saturating_sub(1)),
+            Self::Type12(data) => {
+                Some((2 * data.window_size.min(data.num_records)).saturating_sub(1))
+            }
+            Self::Type13(data) => Some((2 * data.samples.min(data.num_records)).saturating_sub(1)),
+            Self::Type14(data) => Some(data.degree),
+            Self::Type18(data) => Some(data.degree()),
+            Self::Type19(data) => data.degree_at(epoch).ok(),
+            Self::Type20(data) => Some(data.degree()),
+            Self::Type21(data) => data.integration_order(epoch, summary).ok(),
+            Self::Type5(_) | Self::Type10(_) | Self::Type15(_) | Self::Type17(_) => None,
+        }
+    }
+
+    /// Checks the integrity of the wrapped data set, including that it covers the epochs of its summary.
+    pub fn check_integrity(&self, summary: &SPKSummaryRecord) -> Result<(), IntegrityError> {
+        delegate!(self, data => data.check_integrity(summary))
+    }
+
+    /// Returns the start and end epochs covered by both this segment data and its summary.
+    ///
+    /// If the data may be evaluated at any epoch, or if its coverage cannot be decoded, then the epochs of the summary are returned.
+    /// The start epoch is after the end epoch if the summary and the data do not overlap.
+    pub fn coverage(&self, summary: &SPKSummaryRecord) -> (Epoch, Epoch) {
+        let (start, end) = (summary.start_epoch(), summary.end_epoch());
+        match delegate!(self, data => data.coverage()) {
+            Ok((data_start, data_end)) => (start.max(data_start), end.min(data_end)),
+            Err(_) => (start, end),
+        }
+    }
+}
+
+impl<'a> fmt::Display for SegmentData<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        delegate!(self, data => write!(f, "{data}"))
+    }
+}
+
+impl SPK {
+    /// Decodes the n-th segment of this SPK as the data type specified in its summary.
+    pub fn nth_segment_data(&self, idx: usize) -> Result<SegmentData<'_>, DAFError> {
+        let (summary, data) = self.nth_summary_and_slice(idx)?;
+        SegmentData::from_summary_and_slice(summary, data)
+    }
+}
+
+#[cfg(test)]
+mod segment_data_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        math::Vector3,
+        naif::{
+            daf::{DAFError, DafDataType, NAIFSummaryRecord},
+            spk::{segment::spk_segment_ut::build_spk, summary::SPKSummaryRecord},
+            SPK,
+        },
+    };
+
+    use super::SegmentData;
+
+    fn summary(data_type_i: i32, len: i32) -> SPKSummaryRecord {
+        SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 80.0,
+            target_id: -10,
+            center_id: 399,
+            frame_id: 1,
+            data_type_i,
+            start_idx: 385,
+            end_idx: 385 + len - 1,
+        }
+    }
+
+    #[test]
+    fn dispatch_by_data_type() {
+        // Two Type 13 states, followed by their epochs, and the window size and number of records.
+        let mut data = vec![7000.0, 0.0, 0.0, 0.0, 7.5, 0.0];
+        data.extend([7000.0, 600.0, 0.0, 0.0, 7.5, 0.0]);
+        data.extend([0.0, 80.0, 1.0, 2.0]);
+        let spk = SPK::parse(build_spk(false, &[summary(13, 16)], &data)).unwrap();
+
+        let segment = spk.nth_segment_data(0).unwrap();
+        assert_eq!(segment.data_type(), DafDataType::Type13HermiteUnequalStep);
+        assert!(matches!(segment, SegmentData::Type13(_)));
+        let summary = summary(13, 16);
+        assert_eq!(segment.check_integrity(&summary), Ok(()));
+        let (pos_km, vel_km_s) = segment
+            .evaluate(Epoch::from_et_seconds(0.0), &summary)
+            .unwrap();
+        assert!((pos_km - Vector3::new(7000.0, 0.0, 0.0)).norm() < 1e-9);
+        assert!((vel_km_s - Vector3::new(0.0, 7.5, 0.0)).norm() < 1e-9);
+
+        let state = segment
+            .to_cartesian_state(Epoch::from_et_seconds(0.0), &summary, Some(398_600.0))
+            .unwrap();
+        assert_eq!(state.radius_km, pos_km);
+        assert_eq!(state.velocity_km_s, vel_km_s);
+        assert_eq!(state.epoch, Epoch::from_et_seconds(0.0));
+        assert_eq!(state.frame.ephemeris_id, 399);
+        assert_eq!(state.frame.orientation_id, 1);
+        assert_eq!(state.frame.mu_km3_s2, Some(398_600.0));
+
+        assert_eq!(
+            segment.evaluate_with_samples(Epoch::from_et_seconds(40.0), &summary, 2),
+            segment.evaluate(Epoch::from_et_seconds(40.0), &summary)
+        );
+
+        assert_eq!(
+            segment.coverage(&summary),
+            (summary.start_epoch(), summary.end_epoch())
+        );
+        // A summary beyond the data is restricted to the epochs of the data
+        let wide_summary = SPKSummaryRecord {
+            start_epoch_et_s: -10.0,
+            end_epoch_et_s: 100.0,
+            ..summary
+        };
+        assert_eq!(
+            segment.coverage(&wide_summary),
+            (Epoch::from_et_seconds(0.0), Epoch::from_et_seconds(80.0))
+        );
+
+        assert_eq!(
+            spk.nth_segment_data(1).err(),
+            Some(DAFError::EmptySummary {
+                kind: "SPKSummaryRecord",
+                idx: 0
+            })
+        );
+    }
+
+    #[test]
+    fn unsupported_data_type() {
+        // Type 4 is not defined by NAIF.
+        let spk = SPK::parse(build_spk(false, &[summary(4, 16)], &[0.0; 16])).unwrap();
+        assert_eq!(
+            spk.nth_segment_data(0).err(),
+            Some(DAFError::Datatype {
+                id: 4,
+                kind: "unknown data type"
+            })
+        );
+    }
+
+    #[test]
+    fn decoding_error() {
+        // The number of records does not match the length of the segment.
+        let spk = SPK::parse(build_spk(false, &[summary(13, 4)], &[0.0, 80.0, 1.0, 2.0])).unwrap();
+        match spk.nth_segment_data(0) {
+            Err(DAFError::DecodingSegment { dtype, .. }) => {
+                assert_eq!(dtype, DafDataType::Type13HermiteUnequalStep)
+            }
+            Err(e) => panic!("wrong error {e}"),
+            Ok(_) => panic!("decoding should have failed"),
+        }
+    }
+}
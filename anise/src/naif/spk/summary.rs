@@ -16,8 +16,12 @@ use zerocopy::{AsBytes, FromBytes, FromZeroes};
 use pyo3::prelude::*;
 
 use crate::{
+    constants::orientations::orientation_name_from_id,
     ephemerides::EphemerisError,
-    naif::daf::{DafDataType, NAIFRecord, NAIFSummaryRecord},
+    naif::{
+        daf::{DafDataType, NAIFRecord, NAIFSummaryRecord},
+        names::NaifNames,
+    },
     prelude::{Frame, FrameUid},
 };
 
@@ -183,6 +187,14 @@ impl NAIFSummaryRecord for SPKSummaryRecord {
         self.target_id
     }
 
+    fn center_id(&self) -> i32 {
+        self.center_id
+    }
+
+    fn orientation_id(&self) -> i32 {
+        self.frame_id
+    }
+
     fn start_epoch_et_s(&self) -> f64 {
         self.start_epoch_et_s
     }
@@ -202,16 +214,51 @@ impl NAIFSummaryRecord for SPKSummaryRecord {
     }
 }
 
-impl fmt::Display for SPKSummaryRecord {
+impl SPKSummaryRecord {
+    /// Returns a displayable summary whose target and center are named from the provided table, e.g. to name custom spacecraft.
+    pub fn display_with<'a>(&'a self, names: &'a NaifNames) -> SPKSummaryDisplay<'a> {
+        SPKSummaryDisplay {
+            summary: self,
+            names,
+        }
+    }
+}
+
+/// Summary displayed with the names of its target and center, returned by [SPKSummaryRecord::display_with].
+pub struct SPKSummaryDisplay<'a> {
+    summary: &'a SPKSummaryRecord,
+    names: &'a NaifNames,
+}
+
+impl fmt::Display for SPKSummaryDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let summary = self.summary;
+        write!(
+            f,
+            "SPK Summary for {} w.r.t. {} in ",
+            self.names.describe(summary.target_id),
+            self.names.describe(summary.center_id),
+        )?;
+        match orientation_name_from_id(summary.frame_id) {
+            Some(name) => write!(f, "{name} ({})", summary.frame_id)?,
+            None => write!(f, "orientation {}", summary.frame_id)?,
+        }
+        match summary.data_type() {
+            Ok(dtype) => write!(f, " as {dtype}")?,
+            Err(_) => write!(f, " as unknown data type {}", summary.data_type_i)?,
+        }
         write!(
             f,
-            "SPK Summary for TGT={} CTR={} FRM={} from {:E} to {:E}",
-            self.target_id,
-            self.center_id,
-            self.frame_id,
-            self.start_epoch(),
-            self.end_epoch()
+            " from {:E} to {:E}",
+            summary.start_epoch(),
+            summary.end_epoch()
         )
     }
 }
+
+impl fmt::Display for SPKSummaryRecord {
+    /// Names the target and center from the common NAIF IDs, cf. [SPKSummaryRecord::display_with] to name other IDs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_with(&NaifNames::default()))
+    }
+}
@@ -18,7 +18,7 @@ use crate::hifitime::Epoch;
 use crate::math::cartesian::CartesianState;
 use crate::math::rotation::DCM;
 use crate::math::units::*;
-use crate::math::Vector3;
+use crate::math::{Matrix3, Vector3};
 use crate::prelude::Frame;
 
 impl Almanac {
@@ -111,6 +111,28 @@ impl Almanac {
         }
     }
 
+    /// Returns the rotation matrix from J2000 to the orientation of the provided frame at the provided epoch, along with the
+    /// angular velocity vector (in radians per second) of that frame with respect to J2000, expressed in J2000.
+    ///
+    /// This is equivalent to SPICE's `sxform` from J2000 followed by `xf2rav`. Rotations without a time derivative
+    /// (i.e. constant rotations) have a zero angular velocity.
+    pub fn orientation(
+        &self,
+        frame: Frame,
+        epoch: Epoch,
+    ) -> Result<(Matrix3, Vector3), OrientationError> {
+        let dcm = self.rotate(frame.with_orient(J2000), frame, epoch)?;
+
+        let angular_velocity_rad_s = match dcm.rot_mat_dt {
+            Some(_) => dcm
+                .angular_velocity_rad_s()
+                .context(OrientationPhysicsSnafu)?,
+            None => Vector3::zeros(),
+        };
+
+        Ok((dcm.rot_mat, angular_velocity_rad_s))
+    }
+
     /// Rotates the provided Cartesian state into the requested observer frame
     ///
     /// **WARNING:** This function only performs the translation and no rotation _whatsoever_. Use the `transform_to` function instead to include rotations.
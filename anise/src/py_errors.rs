@@ -12,6 +12,7 @@ use crate::almanac::metaload::MetaAlmanacError;
 use crate::almanac::planetary::PlanetaryDataError;
 use crate::ephemerides::EphemerisError;
 use crate::errors::{AlmanacError, DecodingError, InputOutputError, IntegrityError, PhysicsError};
+use crate::naif::pck::{gm::GMError, text::TextPCKError};
 use crate::orientations::OrientationError;
 use crate::structure::dataset::DataSetError;
 use core::convert::From;
@@ -71,3 +72,13 @@ impl From<DataSetError> for PyErr {
         PyException::new_err(err.to_string())
     }
 }
+impl From<GMError> for PyErr {
+    fn from(err: GMError) -> PyErr {
+        PyException::new_err(err.to_string())
+    }
+}
+impl From<TextPCKError> for PyErr {
+    fn from(err: TextPCKError) -> PyErr {
+        PyException::new_err(err.to_string())
+    }
+}
@@ -834,3 +834,60 @@ fn b_plane_davis(almanac: Almanac) {
     // The following is a regression test.
     assert!(dbg!(orbit.hyperbolic_anomaly_deg().unwrap() - 149.610128737).abs() < 1e-9);
 }
+
+#[test]
+fn ric_along_track_offset() {
+    let epoch = Epoch::from_gregorian_tai_at_midnight(2020, 1, 1);
+    // Circular orbit inclined by 30 degrees, at its ascending node
+    let (sin_inc, cos_inc) = 30.0_f64.to_radians().sin_cos();
+    let state = Orbit::new(
+        7000.0,
+        0.0,
+        0.0,
+        0.0,
+        7.5 * cos_inc,
+        7.5 * sin_inc,
+        epoch,
+        EARTH_J2000,
+    );
+
+    // A small offset along the velocity is purely in track
+    let along_track = state
+        .ric_vector(&(0.01 * state.velocity_km_s.normalize()))
+        .unwrap();
+    f64_eq!(
+        (along_track - Vector3::new(0.0, 0.01, 0.0)).norm(),
+        0.0,
+        "in track"
+    );
+
+    let radial = state.ric_vector(&Vector3::new(0.01, 0.0, 0.0)).unwrap();
+    f64_eq!(
+        (radial - Vector3::new(0.01, 0.0, 0.0)).norm(),
+        0.0,
+        "radial"
+    );
+
+    let cross_track = state
+        .ric_vector(&Vector3::new(0.0, -0.01 * sin_inc, 0.01 * cos_inc))
+        .unwrap();
+    f64_eq!(
+        (cross_track - Vector3::new(0.0, 0.0, 0.01)).norm(),
+        0.0,
+        "cross track"
+    );
+
+    // The RIC frame is right handed
+    let dcm = state.dcm3x3_from_ric_to_inertial().unwrap();
+    f64_eq!(dcm.rot_mat.determinant(), 1.0, "determinant");
+
+    // The difference of the offset state is also in track
+    let offset = state.with_radius_km(state.radius_km + 0.01 * state.velocity_km_s.normalize());
+    let ric_delta = offset.ric_difference(&state).unwrap();
+    f64_eq_tol!(
+        (ric_delta.radius_km - Vector3::new(0.0, 0.01, 0.0)).norm(),
+        0.0,
+        1e-6,
+        "in track difference"
+    );
+}
@@ -375,3 +375,273 @@ fn gh_283_multi_barycenter_and_los(almanac: Almanac) {
     assert_eq!(obstructions, 2841);
     assert_eq!(no_obstructions, 4171);
 }
+
+/// Checks the light time and stellar aberration corrected direction of Mars observed from a ground station by the Almanac
+/// against SPICE's `spkcpo`.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn de440s_stellar_aberration_ground_station() {
+    use anise::constants::frames::MARS_BARYCENTER_J2000;
+    use anise::constants::SPEED_OF_LIGHT_KM_S;
+    use spice::cstr;
+
+    let _ = pretty_env_logger::try_init();
+
+    let spk_path = "../data/de440s.bsp";
+    let bpc_path = "../data/earth_latest_high_prec.bpc";
+
+    let almanac = Almanac::new(spk_path)
+        .unwrap()
+        .load(bpc_path)
+        .unwrap()
+        .load("../data/pck08.pca")
+        .unwrap();
+
+    spice::furnsh(spk_path);
+    spice::furnsh(bpc_path);
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 14);
+
+    // Station fixed in the ITRF93 frame, so its inertial velocity only comes from the rotation of the Earth.
+    let itrf93 = almanac.frame_from_uid(EARTH_ITRF93).unwrap();
+    let station =
+        Orbit::try_latlongalt(40.427_222, 4.250_556, 0.834_939, 0.0, epoch, itrf93).unwrap();
+
+    let lt_pos_km = almanac
+        .transform_from_observer(MARS_BARYCENTER_J2000, station, Aberration::LT)
+        .unwrap()
+        .radius_km;
+    let app_pos_km = almanac
+        .transform_from_observer(MARS_BARYCENTER_J2000, station, Aberration::LT_S)
+        .unwrap()
+        .radius_km;
+
+    let mut spice_state = [0.0; 6];
+    let mut spice_lt_s = 0.0;
+    unsafe {
+        spice::c::spkcpo_c(
+            cstr!("MARS BARYCENTER"),
+            epoch.to_et_seconds(),
+            cstr!("J2000"),
+            cstr!("OBSERVER"),
+            cstr!("LT+S"),
+            station.radius_km.as_ptr() as *mut f64,
+            cstr!("EARTH"),
+            cstr!("ITRF93"),
+            spice_state.as_mut_ptr(),
+            &mut spice_lt_s,
+        );
+    }
+
+    spice::unload(bpc_path);
+    spice::unload(spk_path);
+
+    let spice_pos_km = Vector3::new(spice_state[0], spice_state[1], spice_state[2]);
+
+    let ra_dec_deg = |r: Vector3| {
+        (
+            r.y.atan2(r.x).to_degrees(),
+            (r.z / r.norm()).asin().to_degrees(),
+        )
+    };
+
+    let (ra_deg, dec_deg) = ra_dec_deg(app_pos_km);
+    let (spice_ra_deg, spice_dec_deg) = ra_dec_deg(spice_pos_km);
+    let (lt_ra_deg, lt_dec_deg) = ra_dec_deg(lt_pos_km);
+
+    println!("RA = {ra_deg} deg (SPICE {spice_ra_deg} deg)\tDEC = {dec_deg} deg (SPICE {spice_dec_deg} deg)");
+
+    // Stellar aberration is on the order of 20 arcseconds for an observer on Earth.
+    assert!((ra_deg - lt_ra_deg).abs() + (dec_deg - lt_dec_deg).abs() > 1e-3);
+    // Less than 0.04 milliarcseconds of difference with SPICE
+    assert!((ra_deg - spice_ra_deg).abs() < 1e-8, "RA error");
+    assert!((dec_deg - spice_dec_deg).abs() < 1e-8, "DEC error");
+    // Stellar aberration only changes the direction
+    assert!((app_pos_km.norm() - lt_pos_km.norm()).abs() < 1e-6);
+    assert!((app_pos_km.norm() - spice_pos_km.norm()).abs() < POSITION_EPSILON_KM * 1e3);
+    assert!((lt_pos_km.norm() / SPEED_OF_LIGHT_KM_S - spice_lt_s).abs() < 1e-8);
+}
+
+/// Checks the angular separation of the Sun and the Moon as seen from the Earth against SPICE's `vsep` of their `spkpos`.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn de440s_angular_separation_sun_moon() {
+    use spice::cstr;
+
+    let _ = pretty_env_logger::try_init();
+
+    let spk_path = "../data/de440s.bsp";
+
+    let almanac = Almanac::new(spk_path).unwrap();
+    spice::furnsh(spk_path);
+
+    let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+    for epoch in TimeSeries::inclusive(start, start + Unit::Day * 60, Unit::Hour * 7) {
+        for (ab_corr, spice_ab_corr) in [
+            (Aberration::NONE, cstr!("NONE")),
+            (Aberration::LT, cstr!("LT")),
+            (Aberration::LT_S, cstr!("LT+S")),
+        ] {
+            let sep_rad = almanac
+                .angular_separation_rad(SUN_J2000, MOON_J2000, EARTH_J2000, epoch, ab_corr)
+                .unwrap();
+
+            let mut sun_pos_km = [0.0; 3];
+            let mut moon_pos_km = [0.0; 3];
+            let mut lt_s = 0.0;
+            let spice_sep_rad = unsafe {
+                spice::c::spkpos_c(
+                    cstr!("SUN"),
+                    epoch.to_et_seconds(),
+                    cstr!("J2000"),
+                    spice_ab_corr,
+                    cstr!("EARTH"),
+                    sun_pos_km.as_mut_ptr(),
+                    &mut lt_s,
+                );
+                spice::c::spkpos_c(
+                    cstr!("MOON"),
+                    epoch.to_et_seconds(),
+                    cstr!("J2000"),
+                    spice_ab_corr,
+                    cstr!("EARTH"),
+                    moon_pos_km.as_mut_ptr(),
+                    &mut lt_s,
+                );
+                spice::c::vsep_c(sun_pos_km.as_mut_ptr(), moon_pos_km.as_mut_ptr())
+            };
+
+            // About one milliarcsecond of difference with SPICE at most
+            assert!(
+                (sep_rad - spice_sep_rad).abs() < 5e-9,
+                "{epoch} {ab_corr:?}: {sep_rad} rad (SPICE {spice_sep_rad} rad)"
+            );
+        }
+    }
+
+    spice::unload(spk_path);
+}
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn de440s_reframe_to_body_fixed_mars() {
+    use anise::constants::{celestial_objects::MARS_BARYCENTER, orientations::IAU_MARS};
+    use anise::naif::kpl::parser::convert_tpc;
+    use spice::cstr;
+
+    let _ = pretty_env_logger::try_init();
+
+    let spk_path = "../data/de440s.bsp";
+    let pck = "../data/pck00008.tpc";
+
+    // Load into ANISE
+    let almanac = Almanac {
+        planetary_data: convert_tpc(pck, "../data/gm_de431.tpc").unwrap(),
+        ..Default::default()
+    }
+    .with_spk(SPK::load(spk_path).unwrap())
+    .unwrap();
+
+    // Load into SPICE
+    spice::furnsh(spk_path);
+    spice::furnsh(pck);
+
+    // DE440s only includes the Mars barycenter.
+    let mars_fixed = Frame::new(MARS_BARYCENTER, IAU_MARS);
+    // A lander on the surface of Mars, at 20 deg of latitude and 135 deg of longitude.
+    let (lat_rad, lon_rad) = (20.0_f64.to_radians(), 135.0_f64.to_radians());
+    let lander_fixed_km = 3396.19
+        * Vector3::new(
+            lat_rad.cos() * lon_rad.cos(),
+            lat_rad.cos() * lon_rad.sin(),
+            lat_rad.sin(),
+        );
+
+    for epoch in [
+        Epoch::from_gregorian_utc_at_midnight(2020, 2, 7),
+        Epoch::from_gregorian_utc_at_noon(2024, 9, 22),
+    ] {
+        let et = epoch.to_et_seconds();
+
+        // The Earth as seen in IAU_MARS
+        let state = almanac
+            .reframe_to_body_fixed(Orbit::zero_at_epoch(epoch, EARTH_J2000), mars_fixed, None)
+            .unwrap();
+        let (spice_state, _) = spice::spkezr("EARTH", et, "IAU_MARS", "NONE", "MARS BARYCENTER");
+        let pos_expct_km = Vector3::new(spice_state[0], spice_state[1], spice_state[2]);
+        let vel_expct_km_s = Vector3::new(spice_state[3], spice_state[4], spice_state[5]);
+
+        assert_eq!(state.frame, mars_fixed);
+        // The IAU_MARS rotation matches that of SPICE to 1e-9, hence a relative tolerance at the Earth Mars distance.
+        let pos_err_km = (state.radius_km - pos_expct_km).norm();
+        assert!(
+            pos_err_km / pos_expct_km.norm() < 1e-8,
+            "pos = {}\nexp = {pos_expct_km}\nerr = {pos_err_km:e}",
+            state.radius_km
+        );
+        let vel_err_km_s = (state.velocity_km_s - vel_expct_km_s).norm();
+        assert!(
+            vel_err_km_s / vel_expct_km_s.norm() < 1e-8,
+            "vel = {}\nexp = {vel_expct_km_s}\nerr = {vel_err_km_s:e}",
+            state.velocity_km_s
+        );
+
+        // The inertial state of the lander with respect to the Earth, computed with SPICE
+        let mut rot_data: [[f64; 6]; 6] = [[0.0; 6]; 6];
+        unsafe {
+            spice::c::sxform_c(cstr!("IAU_MARS"), cstr!("J2000"), et, rot_data.as_mut_ptr());
+        }
+        let lander_fixed = [
+            lander_fixed_km.x,
+            lander_fixed_km.y,
+            lander_fixed_km.z,
+            0.0,
+            0.0,
+            0.0,
+        ];
+        let (mars_wrt_earth, _) = spice::spkezr("MARS BARYCENTER", et, "J2000", "NONE", "EARTH");
+        let mut lander_j2000 = [0.0; 6];
+        for (i, row) in rot_data.iter().enumerate() {
+            lander_j2000[i] = mars_wrt_earth[i]
+                + row
+                    .iter()
+                    .zip(lander_fixed.iter())
+                    .map(|(m, x)| m * x)
+                    .sum::<f64>();
+        }
+        let lander = Orbit::new(
+            lander_j2000[0],
+            lander_j2000[1],
+            lander_j2000[2],
+            lander_j2000[3],
+            lander_j2000[4],
+            lander_j2000[5],
+            epoch,
+            EARTH_J2000,
+        );
+
+        // In IAU_MARS, the lander is back at its position on the surface, and does not move.
+        let lander_mars = almanac
+            .reframe_to_body_fixed(lander, mars_fixed, None)
+            .unwrap();
+        println!("{lander_mars}");
+        assert!(
+            relative_eq!(
+                lander_mars.radius_km,
+                lander_fixed_km,
+                epsilon = POSITION_EPSILON_KM
+            ),
+            "pos = {}\nexp = {lander_fixed_km}\nerr = {:e}",
+            lander_mars.radius_km,
+            lander_fixed_km - lander_mars.radius_km
+        );
+        assert!(
+            lander_mars.velocity_km_s.norm() < 5e-7,
+            "vel = {}",
+            lander_mars.velocity_km_s
+        );
+    }
+
+    spice::unload(spk_path);
+    spice::unload(pck);
+}
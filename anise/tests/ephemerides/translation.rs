@@ -8,7 +8,9 @@
  * Documentation: https://nyxspace.com/
  */
 
-use anise::constants::frames::{EARTH_J2000, EARTH_MOON_BARYCENTER_J2000, MOON_J2000, VENUS_J2000};
+use anise::constants::frames::{
+    EARTH_J2000, EARTH_MOON_BARYCENTER_J2000, MOON_J2000, SSB_J2000, SUN_J2000, VENUS_J2000,
+};
 use anise::file2heap;
 use anise::math::Vector3;
 use anise::prelude::*;
@@ -713,3 +715,54 @@ fn type9_lagrange_query() {
         (state.velocity_km_s - expected_vel_km_s).norm()
     );
 }
+
+#[test]
+fn state_of_through_intermediate_centers() {
+    let _ = pretty_env_logger::try_init();
+
+    let spk = SPK::load("../data/de440s.bsp").unwrap();
+    let ctx = Almanac::from_spk(spk).unwrap();
+
+    let epoch = Epoch::from_gregorian_utc_at_midnight(2002, 2, 7);
+
+    // The Moon is linked to the Sun through the Earth Moon barycenter and the solar system barycenter.
+    let state = ctx
+        .state_of(MOON_J2000.ephemeris_id, SUN_J2000, epoch, Aberration::NONE)
+        .unwrap();
+
+    let moon_emb = ctx
+        .translate_geometric(MOON_J2000, EARTH_MOON_BARYCENTER_J2000, epoch)
+        .unwrap();
+    let emb_ssb = ctx
+        .translate_geometric(EARTH_MOON_BARYCENTER_J2000, SSB_J2000, epoch)
+        .unwrap();
+    let sun_ssb = ctx
+        .translate_geometric(SUN_J2000, SSB_J2000, epoch)
+        .unwrap();
+
+    let pos_expct_km = moon_emb.radius_km + emb_ssb.radius_km - sun_ssb.radius_km;
+    let vel_expct_km_s = moon_emb.velocity_km_s + emb_ssb.velocity_km_s - sun_ssb.velocity_km_s;
+
+    assert!(
+        relative_eq!(state.radius_km, pos_expct_km, epsilon = POSITION_EPSILON_KM),
+        "got {} but want {}",
+        state.radius_km,
+        pos_expct_km
+    );
+    assert!(
+        relative_eq!(
+            state.velocity_km_s,
+            vel_expct_km_s,
+            epsilon = VELOCITY_EPSILON_KM_S
+        ),
+        "got {} but want {}",
+        state.velocity_km_s,
+        vel_expct_km_s
+    );
+    assert_eq!(state.frame.ephemeris_id, SUN_J2000.ephemeris_id);
+
+    // Objects which are not in the loaded ephemerides cannot be linked to the observer.
+    assert!(ctx
+        .state_of(-999_999, SUN_J2000, epoch, Aberration::NONE)
+        .is_err());
+}
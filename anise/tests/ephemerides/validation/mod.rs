@@ -8,10 +8,17 @@
  * Documentation: https://nyxspace.com/
  */
 
+mod type01_mda;
 mod type02_chebyshev_jpl_de;
 mod type03_chebyshev_jpl_de;
+mod type05_twobody;
 mod type09_lagrange;
+mod type12_hermite;
 mod type13_hermite;
+mod type14_chebyshev;
+mod type19_esoc;
+mod type20_chebyshev;
+mod type21_mda;
 
 mod compare;
 mod validate;
@@ -0,0 +1,44 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{compare::*, validate::Validation};
+use anise::almanac::metaload::MetaFile;
+use std::env;
+
+/// Validates the two-body propagation between discrete states against SPICE with a kernel made of Type 5 segments, e.g.
+/// one of the NAIF comet or asteroid kernels, whose path or URL is provided in the `TYPE05_BSP` env var.
+///
+/// No such kernel is available in this repository and CI does not run this test: it has not been validated yet.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_twobody_type05_kernel() {
+    let uri = match env::var("TYPE05_BSP") {
+        Ok(uri) if !uri.is_empty() => uri,
+        // Skip this test if the env var is not defined.
+        _ => return,
+    };
+
+    let mut meta = MetaFile { uri, crc32: None };
+    meta.process(true).unwrap();
+
+    let file_name = "spk-type05-validation".to_string();
+    let comparator = CompareEphem::new(vec![meta.uri], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    let validator = Validation {
+        file_name,
+        ..Default::default()
+    };
+
+    validator.validate();
+}
@@ -8,6 +8,11 @@
  * Documentation: https://nyxspace.com/
  */
 
+use anise::{
+    naif::daf::datatypes::HermiteSetType13,
+    prelude::{Aberration, Almanac, Epoch, SPK},
+};
+
 use super::{compare::*, validate::Validation};
 
 #[ignore = "Requires Rust SPICE -- must be executed serially"]
@@ -59,3 +64,64 @@ fn validate_hermite_type13_with_varying_segment_sizes() {
 
     validator.validate();
 }
+
+/// Compares the interpolation with SPICE within the first and last records of the segment, where the interpolation window must be shifted.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_hermite_type13_segment_edges() {
+    let path = "../data/gmat-hermite.bsp";
+    spice::furnsh(path);
+
+    let spk = SPK::load(path).unwrap();
+    let summary = spk.data_summaries().unwrap()[0];
+    let epochs_et_s = spk
+        .nth_data::<HermiteSetType13>(0)
+        .unwrap()
+        .epoch_data
+        .to_vec();
+    let almanac = Almanac::from_spk(SPK::load(path).unwrap()).unwrap();
+
+    let num_records = epochs_et_s.len();
+    for idx in (0..5).chain(num_records - 6..num_records - 1) {
+        for fraction in [0.1, 0.25, 0.5, 0.75, 0.9] {
+            let epoch_et_s =
+                epochs_et_s[idx] + fraction * (epochs_et_s[idx + 1] - epochs_et_s[idx]);
+            let epoch = Epoch::from_et_seconds(epoch_et_s);
+
+            let state = almanac
+                .translate(
+                    summary.target_frame(),
+                    summary.center_frame(),
+                    epoch,
+                    Aberration::NONE,
+                )
+                .unwrap();
+
+            let (spice_state, _) = spice::spkezr(
+                &summary.target_id.to_string(),
+                epoch.to_et_seconds(),
+                "J2000",
+                "NONE",
+                &summary.center_id.to_string(),
+            );
+
+            let pos_err_km = (state.radius_km
+                - anise::math::Vector3::new(spice_state[0], spice_state[1], spice_state[2]))
+            .norm();
+            let vel_err_km_s = (state.velocity_km_s
+                - anise::math::Vector3::new(spice_state[3], spice_state[4], spice_state[5]))
+            .norm();
+
+            assert!(
+                pos_err_km < 1e-8,
+                "position error of {pos_err_km:e} km after record #{idx} at {epoch:E}"
+            );
+            assert!(
+                vel_err_km_s < 1e-11,
+                "velocity error of {vel_err_km_s:e} km/s after record #{idx} at {epoch:E}"
+            );
+        }
+    }
+
+    spice::unload(path);
+}
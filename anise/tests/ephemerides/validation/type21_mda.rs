@@ -0,0 +1,47 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{compare::*, validate::Validation};
+use anise::almanac::metaload::MetaFile;
+use std::env;
+
+/// Validates the extended modified difference arrays against SPICE with a small-body kernel made of Type 21 segments, e.g.
+/// as produced by JPL Horizons for an asteroid or a comet, whose path or URL is provided in the `SMALL_BODY_BSP` env var.
+///
+/// No such kernel is available in this repository and CI does not run this test: it has not been validated yet.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_mda_type21_small_body_kernel() {
+    let uri = match env::var("SMALL_BODY_BSP") {
+        Ok(uri) if !uri.is_empty() => uri,
+        // Skip this test if the env var is not defined.
+        _ => return,
+    };
+
+    let mut small_body_meta = MetaFile { uri, crc32: None };
+    small_body_meta.process(true).unwrap();
+
+    let file_name = "spk-type21-validation-small-body".to_string();
+    let comparator = CompareEphem::new(vec![small_body_meta.uri], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    // The evaluation is transliterated from SPICE's `spke21`, so only rounding differences are expected.
+    let validator = Validation {
+        file_name,
+        max_q75_err: 1e-9,
+        max_q99_err: 1e-8,
+        max_abs_err: 1e-6,
+    };
+
+    validator.validate();
+}
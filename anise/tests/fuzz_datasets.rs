@@ -0,0 +1,217 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+//! Structured fuzzing of the decoding and evaluation of every SPK data type: none of these may panic, regardless of the data.
+
+use anise::{
+    math::cartesian::CartesianState,
+    naif::{
+        daf::{datatypes::*, NAIFDataSet},
+        spk::summary::SPKSummaryRecord,
+    },
+    prelude::*,
+};
+
+/// Number of slices tried for each data type
+const NUM_CASES: usize = 20_000;
+
+/// Values which break metadata the most often: counts around the length of the slice, zero, negative, fractional, huge, and non finite.
+const SPECIAL_VALUES: [f64; 16] = [
+    0.0,
+    -0.0,
+    -1.0,
+    0.5,
+    1.5,
+    f64::MIN_POSITIVE / 2.0,
+    1e300,
+    -1e300,
+    f64::MAX,
+    f64::NAN,
+    f64::INFINITY,
+    f64::NEG_INFINITY,
+    u32::MAX as f64,
+    u64::MAX as f64,
+    i32::MAX as f64 + 1.0,
+    -(u64::MAX as f64),
+];
+
+/// Permuted congruential generator, to keep these tests deterministic without any dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let x = self.0;
+        ((x ^ (x >> 18)) >> 27).rotate_right((x >> 59) as u32)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    fn uniform(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a value likely to be interpreted as metadata of a slice of the provided length.
+    fn value(&mut self, len: usize) -> f64 {
+        match self.below(10) {
+            0..=4 => self.below(len + 4) as f64,
+            5 | 6 => SPECIAL_VALUES[self.below(SPECIAL_VALUES.len())],
+            7 => self.uniform(-10.0, 10.0),
+            _ => self.uniform(-1e5, 1e5),
+        }
+    }
+
+    /// Returns an epoch close to the provided data, where the evaluations may pass all of the checks.
+    fn epoch(&mut self, data: &[f64]) -> Epoch {
+        let et_s = match data.get(self.below(data.len() + 2)) {
+            Some(value) if value.is_finite() && value.abs() < 1e12 => {
+                value + self.uniform(-2.0, 2.0)
+            }
+            _ => self.uniform(-1e5, 1e5),
+        };
+        Epoch::from_et_seconds(et_s)
+    }
+}
+
+/// Returns the fuzzed slices: random ones, and valid Type 12 and 13 data where a few values are replaced.
+fn slices(rng: &mut Rng) -> Vec<Vec<f64>> {
+    let mut seeds = Vec::new();
+    for window_size in [2, 3, 8] {
+        let states: Vec<CartesianState> = (0..12)
+            .map(|n| {
+                CartesianState::new(
+                    7000.0 + n as f64,
+                    n as f64,
+                    10.0,
+                    0.0,
+                    7.5,
+                    n as f64 * 1e-3,
+                    Epoch::from_et_seconds(60.0 * n as f64),
+                    Frame::from_ephem_j2000(399),
+                )
+            })
+            .collect();
+        seeds.push(
+            HermiteSetType12::builder()
+                .with_window_size(window_size)
+                .with_states(states.iter().copied())
+                .build()
+                .unwrap(),
+        );
+        seeds.push(
+            HermiteSetType13::builder()
+                .with_window_size(window_size)
+                .with_states(states)
+                .build()
+                .unwrap(),
+        );
+    }
+
+    (0..NUM_CASES)
+        .map(|case| {
+            if case % 2 == 0 {
+                let len = rng.below(if case % 8 == 0 { 400 } else { 40 });
+                (0..len).map(|_| rng.value(len)).collect()
+            } else {
+                let mut data = seeds[rng.below(seeds.len())].clone();
+                for _ in 0..=rng.below(3) {
+                    // Favor the metadata at the end of the data
+                    let idx = if rng.below(2) == 0 {
+                        data.len() - 1 - rng.below(4)
+                    } else {
+                        rng.below(data.len())
+                    };
+                    data[idx] = rng.value(data.len());
+                }
+                data.truncate(data.len() - rng.below(2) * rng.below(data.len()));
+                data
+            }
+        })
+        .collect()
+}
+
+/// Decodes the data, and if it succeeds, calls every function of the data set with indexes and epochs that may be out of bounds.
+fn exercise<'a, T: NAIFDataSet<'a>>(data: &'a [f64], rng: &mut Rng) {
+    let Ok(dataset) = T::from_f64_slice(data) else {
+        return;
+    };
+
+    let _ = dataset.to_f64_daf_vec();
+
+    for n in [
+        0,
+        1,
+        2,
+        data.len() / 2,
+        data.len(),
+        usize::MAX / 2,
+        usize::MAX,
+    ] {
+        let _ = dataset.nth_record(n);
+    }
+
+    let summaries = [
+        SPKSummaryRecord::default(),
+        SPKSummaryRecord {
+            start_epoch_et_s: rng.epoch(data).to_et_seconds(),
+            end_epoch_et_s: rng.epoch(data).to_et_seconds(),
+            ..Default::default()
+        },
+    ];
+    for summary in &summaries {
+        let _ = dataset.check_integrity(summary);
+        for _ in 0..8 {
+            let _ = dataset.evaluate(rng.epoch(data), summary);
+        }
+    }
+
+    if let Ok(dataset) = T::from_f64_slice(data) {
+        let (start, end) = (rng.epoch(data), rng.epoch(data));
+        let _ = dataset.truncate(&summaries[1], Some(start.min(end)), Some(start.max(end)));
+    }
+}
+
+macro_rules! fuzz {
+    ($($name:ident => $kind:ident),+ $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                let mut rng = Rng(0x2545_f491_4f6c_dd1d ^ stringify!($kind).len() as u64);
+                for data in slices(&mut rng) {
+                    exercise::<$kind>(&data, &mut rng);
+                }
+            }
+        )+
+    };
+}
+
+fuzz!(
+    fuzz_type01 => MDASetType1,
+    fuzz_type02 => Type2ChebyshevSet,
+    fuzz_type03 => Type3ChebyshevSet,
+    fuzz_type05 => TwoBodySetType5,
+    fuzz_type08 => LagrangeSetType8,
+    fuzz_type09 => LagrangeSetType9,
+    fuzz_type10 => TLESetType10,
+    fuzz_type12 => HermiteSetType12,
+    fuzz_type13 => HermiteSetType13,
+    fuzz_type14 => ChebyshevSetType14,
+    fuzz_type15 => PrecessingConicSetType15,
+    fuzz_type17 => EquinoctialSetType17,
+    fuzz_type18 => ESOCSetType18,
+    fuzz_type19 => ESOCSetType19,
+    fuzz_type20 => ChebyshevSetType20,
+    fuzz_type21 => MDASetType21,
+);
@@ -262,3 +262,27 @@ fn test_spk_truncate_cheby() {
         "summary 301 not removed"
     );
 }
+
+#[test]
+fn test_spk_from_bytes() {
+    use anise::math::{propagation::propagate_two_body, Vector3};
+    use anise::naif::daf::datatypes::HermiteSetType13;
+
+    // Two-body LEO over ten minutes, sampled every minute, with the spacecraft -1000 centered on the Earth.
+    let spk = SPK::from_bytes(include_bytes!("../../data/tiny-type13.bsp")).unwrap();
+
+    let (summary, idx) = spk.summary_from_id(-1000).unwrap();
+    assert_eq!(summary.center_id, 399);
+    let segment = spk.nth_data::<HermiteSetType13>(idx).unwrap();
+    assert_eq!(segment.num_records, 11);
+
+    let pos0_km = Vector3::new(7000.0, 0.0, 0.0);
+    let vel0_km_s = Vector3::new(0.0, 7.5, 1.0);
+    for dt_s in [0.0, 90.0, 345.6, 600.0] {
+        let epoch = summary.start_epoch() + dt_s * Unit::Second;
+        let pos_km = segment.evaluate(epoch, summary).unwrap().radius_km;
+        let (exp_pos_km, _) =
+            propagate_two_body(&pos0_km, &vel0_km_s, dt_s, 398_600.435_436).unwrap();
+        assert!((pos_km - exp_pos_km).norm() < 1e-3, "pos error at {dt_s} s");
+    }
+}
@@ -405,6 +405,74 @@ fn validate_j2000_ecliptic() {
     }
 }
 
+/// Compares the orientation (rotation and angular velocity) of the IAU Earth frame with SPICE's `pxform`, `sxform` and `xf2rav`.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_iau_earth_orientation() {
+    let pck = "../data/pck00008.tpc";
+    spice::furnsh(pck);
+    let planetary_data = convert_tpc(pck, "../data/gm_de431.tpc").unwrap();
+
+    let almanac = Almanac {
+        planetary_data,
+        ..Default::default()
+    };
+
+    let epoch = Epoch::from_gregorian_utc_hms(2023, 6, 21, 12, 34, 56);
+
+    let (rot_mat, omega_rad_s) = almanac.orientation(IAU_EARTH_FRAME, epoch).unwrap();
+
+    let mut pxform: [[f64; 3]; 3] = [[0.0; 3]; 3];
+    let mut rot_data: [[f64; 6]; 6] = [[0.0; 6]; 6];
+    let mut xf2rav_rot: [[f64; 3]; 3] = [[0.0; 3]; 3];
+    let mut spice_omega = [0.0; 3];
+    unsafe {
+        spice::c::pxform_c(
+            cstr!("J2000"),
+            cstr!("IAU_EARTH"),
+            epoch.to_tdb_seconds(),
+            pxform.as_mut_ptr(),
+        );
+        spice::c::sxform_c(
+            cstr!("J2000"),
+            cstr!("IAU_EARTH"),
+            epoch.to_tdb_seconds(),
+            rot_data.as_mut_ptr(),
+        );
+        spice::c::xf2rav_c(
+            rot_data.as_mut_ptr(),
+            xf2rav_rot.as_mut_ptr(),
+            spice_omega.as_mut_ptr(),
+        );
+    }
+
+    let spice_mat = Matrix3::new(
+        pxform[0][0],
+        pxform[0][1],
+        pxform[0][2],
+        pxform[1][0],
+        pxform[1][1],
+        pxform[1][2],
+        pxform[2][0],
+        pxform[2][1],
+        pxform[2][2],
+    );
+    let spice_omega = Vector3::new(spice_omega[0], spice_omega[1], spice_omega[2]);
+
+    assert!(
+        (rot_mat - spice_mat).norm() < DCM_EPSILON,
+        "got: {rot_mat}want:{spice_mat}err = {:.3e}",
+        (rot_mat - spice_mat).norm()
+    );
+    assert!(
+        (omega_rad_s - spice_omega).norm() < 1e-12,
+        "got: {omega_rad_s}want:{spice_omega}err = {:.3e}",
+        (omega_rad_s - spice_omega).norm()
+    );
+    // The Earth rotates about its pole in about a sidereal day.
+    assert!((omega_rad_s.norm() - 7.292115e-5).abs() < 1e-9);
+}
+
 #[ignore = "Requires Rust SPICE -- must be executed serially"]
 #[test]
 fn validate_bpc_rotations() {